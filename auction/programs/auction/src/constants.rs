@@ -2,11 +2,52 @@ use anchor_lang::prelude::*;
 
 #[constant]
 pub const FEE_MANTISSA: u16 = 10_000; // basis for bps math (10000 -> 100.00%)
+pub const MINIMUM_CONFIG_CHANGE_DELAY: u32 = 24 * 60 * 60; // config changes are queued for at least 1 day
+
+// current account layout versions, stored in the leading `version` byte
+pub const AUCTION_CONFIG_VERSION: u8 = 2; // 2: `pause_flags` widened to u64
+pub const AUCTION_VERSION: u8 = 2; // 2: `reserved` regrown to 128 bytes
+
+// MINT EXTENSIONS (bit indexes into `AuctionConfig::allowed_mint_extensions`)
+pub const PERMANENT_DELEGATE_EXTENSION: u8 = 0; // the delegate can move tokens out of any account, escrows included
+pub const NON_TRANSFERABLE_EXTENSION: u8 = 1; // tokens can never leave the escrow again
+pub const DEFAULT_FROZEN_EXTENSION: u8 = 2; // new accounts start frozen, only the freeze authority can thaw the escrow
+pub const TRANSFER_HOOK_EXTENSION: u8 = 3; // every transfer runs an arbitrary program
+pub const PAUSABLE_EXTENSION: u8 = 4; // the pause authority can halt every transfer
+pub const MINT_EXTENSION_COUNT: u8 = 5;
+
+// PROGRAMMABLE NFTS
+pub const AUTH_RULES_PROGRAM_ID: Pubkey = pubkey!("auth9SigNpDKz4sJJ1DfCTuZrZNSAgh9sFD3rboVmgg"); // Metaplex Token Auth Rules
+
+// BUNDLES
+pub const MAX_BUNDLED_NFTS: usize = 3; // NFTs besides `prize_mint`, bounded by the accounts one transaction can carry
+pub const BUNDLE_CREATE_ACCOUNTS: usize = 7; // remaining accounts per bundled NFT at `create_auction`, see `escrow_bundle`
+pub const BUNDLE_RELEASE_ACCOUNTS: usize = 3; // and when it leaves escrow, see `release_bundle`
+
+// ROYALTIES
+pub const CREATOR_SHARE_TOTAL: u64 = 100; // Metaplex creator shares always add up to 100
+
+// SETTLEMENT
+pub const KEEPER_SETTLEMENT_DELAY: i64 = 5 * 60; // seconds after bidding closes before anyone but the admin can settle
+
+// PERMITS
+pub const PERMIT_MESSAGE_LEN: usize = 32 + 32 + 32 + 8 + 8 + 8; // program | auction id or account | user | max amount | expiry | nonce
 
 // PAUSE FUNCTION
 pub const CREATE_AUCTION_PAUSE: u8 = 0;
 pub const CANCEL_AUCTION_PAUSE: u8 = 1;
 pub const COMPLETE_AUCTION_PAUSE: u8 = 2;
-pub const PLACE_BID_PAUSE: u8 = 3; 
+pub const PLACE_BID_PAUSE: u8 = 3; // sealed bid commits too, reveals never pause so nobody loses a deposit to a pause
 pub const START_AUCTION_PAUSE: u8 = 4;
 pub const UPDATE_AUCTION_PAUSE: u8 = 5;
+pub const WITHDRAW_FEES_PAUSE: u8 = 6; // both SOL and SPL fee withdrawals
+pub const UPDATE_CONFIG_PAUSE: u8 = 7; // queuing and applying config changes
+pub const WITHDRAW_OUTBID_PAUSE: u8 = 8;
+pub const BUY_NOW_PAUSE: u8 = 9;
+pub const BUY_DUTCH_PAUSE: u8 = 10;
+// listings and offers, delisting and cancelling an offer never pause so nothing gets stuck in escrow
+pub const LIST_NFT_PAUSE: u8 = 11;
+pub const BUY_LISTING_PAUSE: u8 = 12;
+pub const MAKE_OFFER_PAUSE: u8 = 13;
+pub const ACCEPT_OFFER_PAUSE: u8 = 14;
+pub const RELIST_AUCTION_PAUSE: u8 = 15;
@@ -62,6 +62,87 @@ pub enum AuctionStateErrors {
 
     #[msg("Insufficient balance to place bid")]
     InsufficientBalance,
+
+    #[msg("Auction is frozen")]
+    AuctionFrozen,
+
+    #[msg("A royalty account is missing for a verified creator")]
+    MissingRoyaltyAccounts,
+
+    #[msg("Bid escrow of the highest bidder is missing")]
+    MissingBidEscrow,
+
+    #[msg("The highest bid stays escrowed until the auction settles")]
+    HighestBidderCannotWithdraw,
+
+    #[msg("An auction has either a public or a hidden reserve, not both")]
+    InvalidReserve,
+
+    #[msg("Revealed reserve does not match the commitment")]
+    InvalidReserveReveal,
+
+    #[msg("Buy now price must be above the base bid and any public reserve")]
+    InvalidBuyNowPrice,
+
+    #[msg("Buy now is not offered or bidding has passed its cutoff")]
+    BuyNowUnavailable,
+
+    #[msg("Accounts to refund the displaced highest bid are missing")]
+    MissingDisplacedBidAccounts,
+
+    #[msg("Sealed auctions are not enabled")]
+    SealedAuctionsDisabled,
+
+    #[msg("Auction takes sealed bids only")]
+    SealedAuction,
+
+    #[msg("Auction does not take sealed bids")]
+    NotSealedAuction,
+
+    #[msg("Sealed bids can only be revealed after bidding ends and before the reveal period is over")]
+    NotInRevealPeriod,
+
+    #[msg("Revealed bid does not match the commitment")]
+    InvalidBidReveal,
+
+    #[msg("Sealed bid is already revealed")]
+    BidAlreadyRevealed,
+
+    #[msg("Deposit does not cover the revealed bid")]
+    DepositBelowBid,
+
+    #[msg("Sealed bids stay escrowed until the reveal period is over")]
+    RevealPeriodNotOver,
+
+    #[msg("Dutch start price must be above the floor, decay in steps within the auction, and skip reserves, buy now and sealed bids")]
+    InvalidDutchSchedule,
+
+    #[msg("Dutch auctions take no bids, buy at the current price instead")]
+    DutchAuction,
+
+    #[msg("Auction is not a Dutch auction")]
+    NotDutchAuction,
+
+    #[msg("Lot amount must be 1 for an NFT prize and above zero for a fungible one")]
+    InvalidLotAmount,
+
+    #[msg("A bundle holds distinct non-programmable NFTs and is sold by bidding only")]
+    InvalidBundle,
+
+    #[msg("Bundle account or the bundled NFTs' accounts are missing")]
+    MissingBundleAccounts,
+
+    #[msg("Percentage bid increment is above 100%")]
+    InvalidBidIncrement,
+
+    #[msg("Only an auction that ended without any bid can be relisted")]
+    AuctionNotRelistable,
+
+    #[msg("Only the admin can settle before the settlement delay is over")]
+    SettlementDelayNotOver,
+
+    #[msg("Only the creator or the admin can settle without revealing the hidden reserve")]
+    HiddenReserveNotRevealed,
 }
 
 #[error_code]
@@ -97,7 +178,10 @@ pub enum KeysMismatchErrors {
     InvalidPreviousBidOwner,
 
     #[msg("Invalid highest bidder")]
-    InvalidHighestBidder
+    InvalidHighestBidder,
+
+    #[msg("Royalty account does not belong to the verified creator")]
+    InvalidRoyaltyRecipient,
 }
 
 #[error_code]
@@ -113,6 +197,138 @@ pub enum ConfigStateErrors {
 
     #[msg("Invalid Time extension")]
     InvalidTimeExtension,
+
+    #[msg("No ownership transfer is pending")]
+    NoPendingOwner,
+
+    #[msg("Signer is not the pending owner")]
+    InvalidPendingOwner,
+
+    #[msg("Config change delay is below the minimum")]
+    InvalidConfigChangeDelay,
+
+    #[msg("No config change is queued")]
+    NoPendingConfigChange,
+
+    #[msg("Queued config change is not effective yet")]
+    ConfigChangeNotReady,
+
+    #[msg("Buy now cutoff is above 100%")]
+    InvalidBuyNowCutoff,
+
+    #[msg("Sealed bid penalty is above 100%")]
+    InvalidSealedPenalty,
+
+    #[msg("Extension window, total extension or extension count is above the config bounds")]
+    InvalidExtensionPolicy,
+
+    #[msg("Keeper fee is above 100% of the commission")]
+    InvalidKeeperFee,
+
+    #[msg("Commission is above 100%")]
+    InvalidCommission,
+}
+
+#[error_code]
+pub enum PermitErrors {
+    #[msg("Permit must be verified by an Ed25519 instruction right before this one")]
+    MissingPermitInstruction,
+
+    #[msg("Ed25519 instruction must verify exactly one inline signature")]
+    InvalidPermitInstruction,
+
+    #[msg("Signed permit does not match this bid")]
+    PermitMessageMismatch,
+
+    #[msg("Permit has expired")]
+    PermitExpired,
+
+    #[msg("Bid exceeds the permit maximum")]
+    PermitAmountExceeded,
+
+    #[msg("Permit is not signed by the auction admin")]
+    InvalidPermitSigner,
+}
+
+#[error_code]
+pub enum MintPolicyErrors {
+    #[msg("Mint has a permanent delegate")]
+    PermanentDelegateNotAllowed,
+
+    #[msg("Mint is non-transferable")]
+    NonTransferableNotAllowed,
+
+    #[msg("Mint freezes new token accounts by default")]
+    DefaultFrozenNotAllowed,
+
+    #[msg("Mint has a transfer hook")]
+    TransferHookNotAllowed,
+
+    #[msg("Mint is pausable")]
+    PausableNotAllowed,
+
+    #[msg("Invalid mint extension index")]
+    InvalidMintExtension,
+
+    #[msg("Mint allowlist account does not belong to the mint")]
+    InvalidMintAllowlist,
+}
+
+#[error_code]
+pub enum NftVerificationErrors {
+    #[msg("NFT prize has no Metaplex metadata")]
+    MissingNftMetadata,
+
+    #[msg("NFT prize has no master edition")]
+    InvalidMasterEdition,
+
+    #[msg("NFT prize is not part of a verified collection")]
+    UnverifiedCollection,
+
+    #[msg("NFT prize collection is not allowlisted")]
+    CollectionNotAllowed,
+}
+
+#[error_code]
+pub enum MarketErrors {
+    #[msg("Price must be above zero")]
+    InvalidPrice,
+
+    #[msg("Listings and offers only trade non-programmable NFTs")]
+    ProgrammableNftNotSupported,
+
+    #[msg("Invalid Seller")]
+    InvalidSeller,
+
+    #[msg("Invalid Buyer")]
+    InvalidBuyer,
+
+    #[msg("Listing price is above the buyer's maximum")]
+    PriceAboveMaximum,
+
+    #[msg("Offer is below the seller's minimum")]
+    OfferBelowMinimum,
+
+    #[msg("Offer has expired")]
+    OfferExpired,
+
+    #[msg("NFT is not part of the offer's verified collection")]
+    CollectionMismatch,
+}
+
+#[error_code]
+pub enum MigrationErrors {
+    #[msg("Account is not owned by this program")]
+    InvalidAccountOwner,
+
+    #[msg("Account discriminator does not match")]
+    InvalidDiscriminator,
+
+    #[msg("Account is already on the current layout")]
+    AlreadyMigrated,
+
+    #[msg("Accounts to move a version 0 auction's live bid into its bid escrow are missing")]
+    MissingLegacyBidAccounts,
 }
 
 #[error_code]
@@ -128,4 +344,19 @@ pub enum TransferErrors {
 
     #[msg("Insufficient SOL Balance")]
     InsufficientSolBalance,
+
+    #[msg("Transfer fee could not be calculated")]
+    TransferFeeCalculationFailed,
+
+    #[msg("Escrow received less than required after the transfer fee")]
+    InsufficientAmountReceived,
+
+    #[msg("Transfer hook program or its extra accounts are missing from the remaining accounts")]
+    MissingTransferHookAccounts,
+
+    #[msg("Prize metadata account could not be read")]
+    InvalidPrizeMetadata,
+
+    #[msg("Authorization rules do not match the rule set of the programmable NFT")]
+    InvalidAuthorizationRules,
 }
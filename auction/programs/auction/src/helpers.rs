@@ -1,21 +1,75 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::program::invoke;
 use anchor_lang::system_program::{self};
+use anchor_spl::metadata::mpl_token_metadata::{
+    self,
+    accounts::{MasterEdition, Metadata},
+    instructions::TransferV1CpiBuilder,
+    types::{Key as MetadataKey, ProgrammableConfig, TokenStandard},
+};
 use anchor_spl::token_interface::{
+    close_account,
+    CloseAccount,
     Mint,
     TokenAccount,
     TokenInterface,
     TransferChecked,
     transfer_checked,
 };
-use crate::errors::TransferErrors;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        default_account_state::DefaultAccountState,
+        transfer_fee::{instruction::harvest_withheld_tokens_to_mint, TransferFeeAmount, TransferFeeConfig},
+        transfer_hook, BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+    },
+    onchain,
+    state::AccountState,
+};
+use crate::constants::{
+    BUNDLE_CREATE_ACCOUNTS, BUNDLE_RELEASE_ACCOUNTS, CREATOR_SHARE_TOTAL, DEFAULT_FROZEN_EXTENSION, FEE_MANTISSA, NON_TRANSFERABLE_EXTENSION,
+    PAUSABLE_EXTENSION, PERMANENT_DELEGATE_EXTENSION, TRANSFER_HOOK_EXTENSION,
+};
+use crate::errors::{
+    AuctionStateErrors, KeysMismatchErrors, MintPolicyErrors, NftVerificationErrors, TransferErrors,
+};
+use crate::states::{AuctionBundle, AuctionConfig, CollectionAllowlist};
+use crate::utils::{get_pct_amount, is_nft_mint};
 
 // Transfer SPL Tokens With PDA Seeds — SAFE (uses transfer_checked)
+#[allow(clippy::too_many_arguments)]
 pub fn transfer_tokens_with_seeds<'info>(
     from: &InterfaceAccount<'info, TokenAccount>,
     to: &InterfaceAccount<'info, TokenAccount>,
     authority: &AccountInfo<'info>,
     token_program: &Interface<'info, TokenInterface>,
     mint: &InterfaceAccount<'info, Mint>,
+    remaining_accounts: &[AccountInfo<'info>],
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    transfer_tokens_to_account_with_seeds(
+        from,
+        &to.to_account_info(),
+        authority,
+        token_program,
+        mint,
+        remaining_accounts,
+        signer_seeds,
+        amount,
+    )
+}
+
+// Same as `transfer_tokens_with_seeds` for a destination opened earlier in the same instruction,
+// which can't be passed in as a token account yet
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_tokens_to_account_with_seeds<'info>(
+    from: &InterfaceAccount<'info, TokenAccount>,
+    to: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    mint: &InterfaceAccount<'info, Mint>,
+    remaining_accounts: &[AccountInfo<'info>],
     signer_seeds: &[&[&[u8]]],
     amount: u64,
 ) -> Result<()> {
@@ -24,49 +78,530 @@ pub fn transfer_tokens_with_seeds<'info>(
         TransferErrors::InsufficientTokenBalance
     );
 
-    transfer_checked(
-        CpiContext::new_with_signer(
-            token_program.to_account_info(),
-            TransferChecked {
-                from: from.to_account_info(),
-                to: to.to_account_info(),
-                mint: mint.to_account_info(),
-                authority: authority.to_account_info(),
-            },
-            signer_seeds,
-        ),
+    invoke_transfer_checked(
+        from,
+        to,
+        authority,
+        token_program,
+        mint,
+        remaining_accounts,
+        signer_seeds,
         amount,
-        mint.decimals,
-    ).map_err(|_| TransferErrors::TokenTransferFailed.into())
+    )
+}
+
+// Close an emptied token account owned by a PDA, its rent goes to `destination`. A Token-2022 account
+// still holding withheld transfer fees can't be closed, they are harvested to `mint` first (writable then)
+pub fn close_token_account_with_seeds<'info>(
+    account: &InterfaceAccount<'info, TokenAccount>,
+    mint: &InterfaceAccount<'info, Mint>,
+    destination: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let account_ai = account.to_account_info();
+    if *account_ai.owner == spl_token_2022::ID {
+        let withheld = {
+            let data = account_ai.try_borrow_data()?;
+            let state = StateWithExtensions::<spl_token_2022::state::Account>::unpack(&data)?;
+            state
+                .get_extension::<TransferFeeAmount>()
+                .map_or(0, |fees| u64::from(fees.withheld_amount))
+        };
+
+        // harvesting is permissionless, no signer needed
+        if withheld > 0 {
+            invoke(
+                &harvest_withheld_tokens_to_mint(&spl_token_2022::ID, &mint.key(), &[&account_ai.key()])?,
+                &[token_program.to_account_info(), mint.to_account_info(), account_ai.clone()],
+            )?;
+        }
+    }
+
+    close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: account_ai,
+            destination: destination.clone(),
+            authority: authority.clone(),
+        },
+        signer_seeds,
+    ))
 }
 
-// Transfer SPL Tokens (normal signer authority) — SAFE
+// Transfer SPL Tokens (normal signer authority) — SAFE, returns what `to` actually received
 pub fn transfer_tokens<'info>(
     from: &InterfaceAccount<'info, TokenAccount>,
     to: &InterfaceAccount<'info, TokenAccount>,
     authority: &Signer<'info>,
     token_program: &Interface<'info, TokenInterface>,
     mint: &InterfaceAccount<'info, Mint>,
+    remaining_accounts: &[AccountInfo<'info>],
     amount: u64,
-) -> Result<()> {
+) -> Result<u64> {
     require!(
         from.amount >= amount,
         TransferErrors::InsufficientTokenBalance
     );
 
+    // the token program withholds the fee in `to`, it never counts towards the escrowed amount
+    let received = amount
+        .checked_sub(transfer_fee_amount(mint, amount)?)
+        .ok_or(TransferErrors::TransferFeeCalculationFailed)?;
+
+    invoke_transfer_checked(
+        from,
+        &to.to_account_info(),
+        &authority.to_account_info(),
+        token_program,
+        mint,
+        remaining_accounts,
+        &[],
+        amount,
+    )?;
+
+    Ok(received)
+}
+
+// Token-2022 runs the mint's transfer hook inside transfer_checked, so a hooked mint goes through the
+// hook-aware CPI that resolves the hook's extra account metas from `remaining_accounts`
+#[allow(clippy::too_many_arguments)]
+fn invoke_transfer_checked<'info>(
+    from: &InterfaceAccount<'info, TokenAccount>,
+    to: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    mint: &InterfaceAccount<'info, Mint>,
+    remaining_accounts: &[AccountInfo<'info>],
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    if let Some(hook_program_id) = transfer_hook_program_id(mint)? {
+        require!(
+            remaining_accounts.iter().any(|account| account.key == &hook_program_id),
+            TransferErrors::MissingTransferHookAccounts
+        );
+
+        return onchain::invoke_transfer_checked(
+            token_program.key,
+            from.to_account_info(),
+            mint.to_account_info(),
+            to.clone(),
+            authority.to_account_info(),
+            remaining_accounts,
+            amount,
+            mint.decimals,
+            signer_seeds,
+        )
+        .map_err(|_| TransferErrors::TokenTransferFailed.into());
+    }
+
     transfer_checked(
-        CpiContext::new(
+        CpiContext::new_with_signer(
             token_program.to_account_info(),
             TransferChecked {
                 from: from.to_account_info(),
-                to: to.to_account_info(),
+                to: to.clone(),
                 mint: mint.to_account_info(),
                 authority: authority.to_account_info(),
             },
+            signer_seeds,
         ),
         amount,
         mint.decimals,
-    ).map_err(|_| TransferErrors::TokenTransferFailed.into())
+    )
+    .map_err(|_| TransferErrors::TokenTransferFailed.into())
+}
+
+// Program a Token-2022 `TransferHook` mint calls on every transfer, none for every other mint
+fn transfer_hook_program_id<'info>(mint: &InterfaceAccount<'info, Mint>) -> Result<Option<Pubkey>> {
+    let mint_ai = mint.to_account_info();
+    if *mint_ai.owner != spl_token_2022::ID {
+        return Ok(None);
+    }
+
+    let data = mint_ai.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(transfer_hook::get_program_id(&mint_state))
+}
+
+// Fee a Token-2022 `TransferFee` mint withholds from a transfer of `amount`, zero for every other mint
+pub fn transfer_fee_amount<'info>(mint: &InterfaceAccount<'info, Mint>, amount: u64) -> Result<u64> {
+    let mint_ai = mint.to_account_info();
+    if *mint_ai.owner != spl_token_2022::ID {
+        return Ok(0);
+    }
+
+    let data = mint_ai.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    match mint_state.get_extension::<TransferFeeConfig>() {
+        Ok(fee_config) => fee_config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(TransferErrors::TransferFeeCalculationFailed.into()),
+        Err(_) => Ok(0),
+    }
+}
+
+// Token-2022 extensions that can drain or lock an auction or listing escrow pass only if the config allows them or the mint is allowlisted
+pub fn check_mint_policy<'info>(
+    mint: &InterfaceAccount<'info, Mint>,
+    allowed_extensions: u8,
+    mint_allowlist: &AccountInfo<'info>,
+) -> Result<()> {
+    let mint_ai = mint.to_account_info();
+    if *mint_ai.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+
+    // the allowlist PDA can only be created through `allow_mint`, existing is enough
+    if *mint_allowlist.owner == crate::ID && !mint_allowlist.data_is_empty() {
+        return Ok(());
+    }
+
+    let data = mint_ai.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    for extension in mint_state.get_extension_types()? {
+        let (index, error) = match extension {
+            ExtensionType::PermanentDelegate => (
+                PERMANENT_DELEGATE_EXTENSION,
+                MintPolicyErrors::PermanentDelegateNotAllowed,
+            ),
+            ExtensionType::NonTransferable => (
+                NON_TRANSFERABLE_EXTENSION,
+                MintPolicyErrors::NonTransferableNotAllowed,
+            ),
+            ExtensionType::DefaultAccountState => {
+                // an initialized default is harmless, only a frozen one locks the escrow
+                let default_state = mint_state.get_extension::<DefaultAccountState>()?;
+                if default_state.state != AccountState::Frozen as u8 {
+                    continue;
+                }
+                (
+                    DEFAULT_FROZEN_EXTENSION,
+                    MintPolicyErrors::DefaultFrozenNotAllowed,
+                )
+            }
+            ExtensionType::TransferHook => (
+                TRANSFER_HOOK_EXTENSION,
+                MintPolicyErrors::TransferHookNotAllowed,
+            ),
+            ExtensionType::Pausable => (PAUSABLE_EXTENSION, MintPolicyErrors::PausableNotAllowed),
+            _ => continue,
+        };
+
+        if allowed_extensions & (1u8 << index) == 0 {
+            return Err(error.into());
+        }
+    }
+
+    Ok(())
+}
+
+// Token Metadata accounts a programmable NFT transfer needs besides the token accounts
+pub struct ProgrammableNftAccounts<'a, 'info> {
+    pub metadata: &'a AccountInfo<'info>,
+    pub edition: &'a AccountInfo<'info>,
+    pub token_record: &'a AccountInfo<'info>,
+    pub destination_token_record: &'a AccountInfo<'info>,
+    pub authorization_rules: &'a AccountInfo<'info>,
+    pub authorization_rules_program: &'a AccountInfo<'info>,
+    pub token_metadata_program: &'a AccountInfo<'info>,
+    pub sysvar_instructions: &'a AccountInfo<'info>,
+    pub associated_token_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+fn read_prize_metadata(metadata: &AccountInfo) -> Result<Option<Metadata>> {
+    if *metadata.owner != mpl_token_metadata::ID || metadata.data_is_empty() {
+        return Ok(None);
+    }
+
+    Metadata::try_from(metadata)
+        .map(Some)
+        .map_err(|_| TransferErrors::InvalidPrizeMetadata.into())
+}
+
+// True for a Metaplex programmable NFT, false for plain NFTs, SPL tokens and mints without metadata
+pub fn is_programmable_nft(metadata: &AccountInfo) -> Result<bool> {
+    Ok(matches!(
+        read_prize_metadata(metadata)?.and_then(|metadata| metadata.token_standard),
+        Some(TokenStandard::ProgrammableNonFungible | TokenStandard::ProgrammableNonFungibleEdition)
+    ))
+}
+
+// only `allow_collection` can create a program owned `CollectionAllowlist`
+fn read_collection_allowlist(entry: &AccountInfo) -> Option<CollectionAllowlist> {
+    if *entry.owner != crate::ID {
+        return None;
+    }
+
+    let data = entry.try_borrow_data().ok()?;
+    CollectionAllowlist::try_deserialize(&mut &data[..]).ok()
+}
+
+// An NFT prize or listing needs Metaplex metadata and a master edition, returns its verified collection if any
+pub fn verify_nft_prize<'info>(
+    mint: &InterfaceAccount<'info, Mint>,
+    metadata: &AccountInfo<'info>,
+    edition: &AccountInfo<'info>,
+    collection_allowlist: Option<&AccountInfo<'info>>,
+    require_verified_collection: bool,
+) -> Result<Option<Pubkey>> {
+    let metadata = read_prize_metadata(metadata)?.ok_or(NftVerificationErrors::MissingNftMetadata)?;
+    require_keys_eq!(metadata.mint, mint.key(), NftVerificationErrors::MissingNftMetadata);
+
+    // the edition PDA is pinned by the accounts constraints, a print edition lives at the same address
+    let is_master_edition = *edition.owner == mpl_token_metadata::ID
+        && !edition.data_is_empty()
+        && MasterEdition::try_from(edition).is_ok_and(|edition| {
+            matches!(edition.key, MetadataKey::MasterEditionV1 | MetadataKey::MasterEditionV2)
+        });
+    require!(is_master_edition, NftVerificationErrors::InvalidMasterEdition);
+
+    let collection = metadata
+        .collection
+        .filter(|collection| collection.verified)
+        .map(|collection| collection.key);
+
+    if require_verified_collection {
+        let collection = collection.ok_or(NftVerificationErrors::UnverifiedCollection)?;
+
+        let allowlisted = collection_allowlist
+            .and_then(read_collection_allowlist)
+            .is_some_and(|entry| entry.collection == collection);
+        require!(allowlisted, NftVerificationErrors::CollectionNotAllowed);
+    }
+
+    Ok(collection)
+}
+
+// Verify each bundled NFT the way `create_auction` verifies the main prize and move it from the creator
+// into a token account the auction owns. `accounts` holds `[mint, metadata, edition, mint allowlist,
+// collection allowlist, creator token account, escrow token account]` per NFT. Returns the bundled mints
+pub fn escrow_bundle<'info>(
+    accounts: &'info [AccountInfo<'info>],
+    creator: &Signer<'info>,
+    auction: Pubkey,
+    prize_mint: Pubkey,
+    token_program: &Interface<'info, TokenInterface>,
+    config: &AuctionConfig,
+) -> Result<Vec<Pubkey>> {
+    let mut mints: Vec<Pubkey> = Vec::with_capacity(accounts.len() / BUNDLE_CREATE_ACCOUNTS);
+
+    for chunk in accounts.chunks_exact(BUNDLE_CREATE_ACCOUNTS) {
+        let [mint, metadata, edition, mint_allowlist, collection_allowlist, creator_ata, escrow] = chunk else {
+            return err!(AuctionStateErrors::MissingBundleAccounts);
+        };
+
+        let mint = InterfaceAccount::<Mint>::try_from(mint)?;
+        require!(is_nft_mint(&mint), AuctionStateErrors::InvalidNFT);
+        require!(
+            mint.key() != prize_mint && !mints.contains(&mint.key()),
+            AuctionStateErrors::InvalidBundle
+        );
+
+        // the main prize has these pinned by its accounts constraints, here they are derived
+        require_keys_eq!(
+            metadata.key(),
+            Metadata::find_pda(&mint.key()).0,
+            NftVerificationErrors::MissingNftMetadata
+        );
+        require_keys_eq!(
+            edition.key(),
+            MasterEdition::find_pda(&mint.key()).0,
+            NftVerificationErrors::InvalidMasterEdition
+        );
+        let (mint_allowlist_key, _) = Pubkey::find_program_address(
+            &[b"auction".as_ref(), b"mint_allowlist".as_ref(), mint.key().as_ref()],
+            &crate::ID,
+        );
+        require_keys_eq!(
+            mint_allowlist.key(),
+            mint_allowlist_key,
+            MintPolicyErrors::InvalidMintAllowlist
+        );
+
+        verify_nft_prize(
+            &mint,
+            metadata,
+            edition,
+            Some(collection_allowlist),
+            config.require_verified_collection,
+        )?;
+        check_mint_policy(&mint, config.allowed_mint_extensions, mint_allowlist)?;
+
+        // pNFTs need a Token Metadata transfer with its own accounts for each, too many for one transaction
+        require!(!is_programmable_nft(metadata)?, AuctionStateErrors::InvalidBundle);
+
+        let creator_ata = InterfaceAccount::<TokenAccount>::try_from(creator_ata)?;
+        let escrow = InterfaceAccount::<TokenAccount>::try_from(escrow)?;
+        require!(
+            creator_ata.mint == mint.key() && escrow.mint == mint.key(),
+            KeysMismatchErrors::InvalidPrizeMint
+        );
+        require_keys_eq!(creator_ata.owner, creator.key(), KeysMismatchErrors::InvalidPrizeAtaOwner);
+        require_keys_eq!(escrow.owner, auction, KeysMismatchErrors::InvalidPrizeAtaOwner);
+
+        let received = transfer_tokens(&creator_ata, &escrow, creator, token_program, &mint, &[], 1u64)?;
+        require_eq!(received, 1u64, TransferErrors::InsufficientAmountReceived);
+
+        mints.push(mint.key());
+    }
+
+    Ok(mints)
+}
+
+// Send every bundled NFT to `recipient` and close its escrow to `rent_to`, a no-op without a bundle.
+// `accounts` holds `[escrow token account, recipient token account, mint]` per NFT, in `bundle.mints` order
+pub fn release_bundle<'info>(
+    bundle: Option<&Account<'_, AuctionBundle>>,
+    accounts: &'info [AccountInfo<'info>],
+    auction: &AccountInfo<'info>,
+    recipient: Pubkey,
+    rent_to: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let Some(bundle) = bundle else {
+        return Ok(());
+    };
+    require_eq!(
+        accounts.len(),
+        bundle.mints.len() * BUNDLE_RELEASE_ACCOUNTS,
+        AuctionStateErrors::MissingBundleAccounts
+    );
+
+    for (chunk, bundled_mint) in accounts.chunks_exact(BUNDLE_RELEASE_ACCOUNTS).zip(&bundle.mints) {
+        let [escrow, destination, mint] = chunk else {
+            return err!(AuctionStateErrors::MissingBundleAccounts);
+        };
+
+        let escrow = InterfaceAccount::<TokenAccount>::try_from(escrow)?;
+        let destination = InterfaceAccount::<TokenAccount>::try_from(destination)?;
+        let mint = InterfaceAccount::<Mint>::try_from(mint)?;
+        require!(
+            mint.key() == *bundled_mint
+                && escrow.mint == *bundled_mint
+                && destination.mint == *bundled_mint,
+            KeysMismatchErrors::InvalidPrizeMint
+        );
+        require_keys_eq!(escrow.owner, auction.key(), KeysMismatchErrors::InvalidPrizeAtaOwner);
+        require_keys_eq!(destination.owner, recipient, KeysMismatchErrors::InvalidPrizeAtaOwner);
+
+        transfer_tokens_with_seeds(
+            &escrow,
+            &destination,
+            auction,
+            token_program,
+            &mint,
+            &[],
+            signer_seeds,
+            1u64,
+        )?;
+
+        close_account(CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            CloseAccount {
+                account: escrow.to_account_info(),
+                destination: rent_to.clone(),
+                authority: auction.clone(),
+            },
+            signer_seeds,
+        ))?;
+    }
+
+    Ok(())
+}
+
+// Royalty owed to each verified creator of the prize on a `sale_amount` sale, never more than `max_amount`
+// in total. Unverified creators are skipped and their share stays with the seller
+pub fn creator_royalties(
+    metadata: &AccountInfo,
+    sale_amount: u64,
+    max_amount: u64,
+) -> Result<Vec<(Pubkey, u64)>> {
+    let Some(metadata) = read_prize_metadata(metadata)? else {
+        return Ok(Vec::new());
+    };
+
+    let royalty = get_pct_amount(
+        sale_amount,
+        metadata.seller_fee_basis_points as u64,
+        FEE_MANTISSA as u64,
+    )?
+    .min(max_amount);
+
+    metadata
+        .creators
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|creator| creator.verified && creator.share > 0)
+        .map(|creator| {
+            let amount = get_pct_amount(royalty, creator.share as u64, CREATOR_SHARE_TOTAL)?;
+            Ok((creator.address, amount))
+        })
+        .collect()
+}
+
+// pNFT token accounts stay frozen between transfers, so the token program rejects a plain
+// transfer_checked and the move has to go through Token Metadata's TransferV1
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_programmable_nft<'info>(
+    from: &InterfaceAccount<'info, TokenAccount>,
+    from_owner: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    to_owner: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    payer: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    pnft: &ProgrammableNftAccounts<'_, 'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    require!(from.amount >= 1, TransferErrors::InsufficientTokenBalance);
+
+    // rule set enforced by the pNFT, if any; the rules account is only forwarded when there is one
+    let rule_set = match read_prize_metadata(pnft.metadata)?.and_then(|m| m.programmable_config) {
+        Some(ProgrammableConfig::V1 { rule_set }) => rule_set,
+        None => None,
+    };
+    if let Some(rule_set) = rule_set {
+        require_keys_eq!(
+            pnft.authorization_rules.key(),
+            rule_set,
+            TransferErrors::InvalidAuthorizationRules
+        );
+    }
+    let (authorization_rules_program, authorization_rules) = match rule_set {
+        Some(_) => (Some(pnft.authorization_rules_program), Some(pnft.authorization_rules)),
+        None => (None, None),
+    };
+
+    let from_ai = from.to_account_info();
+    let mint_ai = mint.to_account_info();
+    let token_program_ai = token_program.to_account_info();
+
+    TransferV1CpiBuilder::new(pnft.token_metadata_program)
+        .token(&from_ai)
+        .token_owner(from_owner)
+        .destination_token(to)
+        .destination_owner(to_owner)
+        .mint(&mint_ai)
+        .metadata(pnft.metadata)
+        .edition(Some(pnft.edition))
+        .token_record(Some(pnft.token_record))
+        .destination_token_record(Some(pnft.destination_token_record))
+        .authority(from_owner)
+        .payer(payer)
+        .system_program(pnft.system_program)
+        .sysvar_instructions(pnft.sysvar_instructions)
+        .spl_token_program(&token_program_ai)
+        .spl_ata_program(pnft.associated_token_program)
+        .authorization_rules_program(authorization_rules_program)
+        .authorization_rules(authorization_rules)
+        .amount(1)
+        .invoke_signed(signer_seeds)
+        .map_err(|_| TransferErrors::TokenTransferFailed.into())
 }
 
 // Transfer SOL from normal signer
@@ -93,3 +628,79 @@ pub fn transfer_sol<'info>(
         amount,
     ).map_err(|_| TransferErrors::SolTransferFailed.into())
 }
+
+// A system account can't end a transaction with lamports below rent exemption, so a payout too small
+// to open a new wallet would fail the whole instruction. True when `account` can take `amount`
+pub fn can_receive_lamports(account: &AccountInfo, amount: u64) -> Result<bool> {
+    let balance = account
+        .lamports()
+        .checked_add(amount)
+        .ok_or(AuctionStateErrors::Overflow)?;
+    Ok(amount == 0 || balance >= Rent::get()?.minimum_balance(account.data_len()))
+}
+
+// Open a PDA of this program with `space` bytes, the payer tops up whatever rent its address lacks
+pub fn create_pda_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    space: usize,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let required_lamports = Rent::get()?.minimum_balance(space);
+    let current_lamports = account.lamports();
+
+    if required_lamports > current_lamports {
+        transfer_sol(
+            payer,
+            account,
+            system_program,
+            required_lamports - current_lamports,
+        )?;
+    }
+
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            system_program::Allocate {
+                account_to_allocate: account.clone(),
+            },
+            signer_seeds,
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            system_program::Assign {
+                account_to_assign: account.clone(),
+            },
+            signer_seeds,
+        ),
+        &crate::ID,
+    )
+}
+
+// Grow a program-owned account to `new_len`, topping up rent from the payer first
+pub fn resize_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    new_len: usize,
+) -> Result<()> {
+    let required_lamports = Rent::get()?.minimum_balance(new_len);
+    let current_lamports = account.lamports();
+
+    if required_lamports > current_lamports {
+        transfer_sol(
+            payer,
+            account,
+            system_program,
+            required_lamports - current_lamports,
+        )?;
+    }
+
+    account.resize(new_len)?;
+
+    Ok(())
+}
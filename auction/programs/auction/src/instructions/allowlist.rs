@@ -0,0 +1,176 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+use crate::errors::ConfigStateErrors;
+use crate::states::{AuctionConfig, CollectionAllowlist, MintAllowlist};
+
+#[event]
+pub struct MintAllowlisted {
+    pub mint: Pubkey,
+    pub allowed_at: i64,
+}
+
+#[event]
+pub struct MintRemovedFromAllowlist {
+    pub mint: Pubkey,
+    pub removed_at: i64,
+}
+
+#[event]
+pub struct CollectionAllowlisted {
+    pub collection: Pubkey,
+    pub allowed_at: i64,
+}
+
+#[event]
+pub struct CollectionRemovedFromAllowlist {
+    pub collection: Pubkey,
+    pub removed_at: i64,
+}
+
+// The owner vouches for a mint, letting it past `check_mint_policy` whatever extensions it carries
+pub fn allow_mint(ctx: Context<AllowMint>) -> Result<()> {
+    let mint_allowlist = &mut ctx.accounts.mint_allowlist;
+    let now = Clock::get()?.unix_timestamp;
+
+    mint_allowlist.mint = ctx.accounts.mint.key();
+    mint_allowlist.allowed_at = now;
+    mint_allowlist.allowlist_bump = ctx.bumps.mint_allowlist;
+
+    emit!(MintAllowlisted {
+        mint: mint_allowlist.mint,
+        allowed_at: now,
+    });
+
+    Ok(())
+}
+
+// Live auctions, listings and offers keep their mints, only new ones are checked again
+pub fn remove_allowed_mint(ctx: Context<RemoveAllowedMint>) -> Result<()> {
+    emit!(MintRemovedFromAllowlist {
+        mint: ctx.accounts.mint_allowlist.mint,
+        removed_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// The owner vouches for a verified collection, only read while `require_verified_collection` is set
+pub fn allow_collection(ctx: Context<AllowCollection>) -> Result<()> {
+    let collection_allowlist = &mut ctx.accounts.collection_allowlist;
+    let now = Clock::get()?.unix_timestamp;
+
+    collection_allowlist.collection = ctx.accounts.collection_mint.key();
+    collection_allowlist.allowed_at = now;
+    collection_allowlist.allowlist_bump = ctx.bumps.collection_allowlist;
+
+    emit!(CollectionAllowlisted {
+        collection: collection_allowlist.collection,
+        allowed_at: now,
+    });
+
+    Ok(())
+}
+
+// Auctions already holding a prize keep it, only new auctions, listings and sales into offers are checked again
+pub fn remove_allowed_collection(ctx: Context<RemoveAllowedCollection>) -> Result<()> {
+    emit!(CollectionRemovedFromAllowlist {
+        collection: ctx.accounts.collection_allowlist.collection,
+        removed_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AllowMint<'info> {
+    #[account(
+        seeds = [b"auction"],
+        bump = auction_config.config_bump,
+        constraint = auction_owner.key() == auction_config.auction_owner @ ConfigStateErrors::InvalidAuctionOwner
+    )]
+    pub auction_config: Box<Account<'info, AuctionConfig>>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = auction_owner,
+        space = 8 + MintAllowlist::INIT_SPACE,
+        seeds = [b"auction".as_ref(), b"mint_allowlist".as_ref(), mint.key().as_ref()],
+        bump
+    )]
+    pub mint_allowlist: Box<Account<'info, MintAllowlist>>,
+
+    #[account(mut)]
+    pub auction_owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveAllowedMint<'info> {
+    #[account(
+        seeds = [b"auction"],
+        bump = auction_config.config_bump,
+        constraint = auction_owner.key() == auction_config.auction_owner @ ConfigStateErrors::InvalidAuctionOwner
+    )]
+    pub auction_config: Box<Account<'info, AuctionConfig>>,
+
+    #[account(
+        mut,
+        close = auction_owner,
+        seeds = [b"auction".as_ref(), b"mint_allowlist".as_ref(), mint_allowlist.mint.as_ref()],
+        bump = mint_allowlist.allowlist_bump
+    )]
+    pub mint_allowlist: Box<Account<'info, MintAllowlist>>,
+
+    #[account(mut)]
+    pub auction_owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AllowCollection<'info> {
+    #[account(
+        seeds = [b"auction"],
+        bump = auction_config.config_bump,
+        constraint = auction_owner.key() == auction_config.auction_owner @ ConfigStateErrors::InvalidAuctionOwner
+    )]
+    pub auction_config: Box<Account<'info, AuctionConfig>>,
+
+    pub collection_mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = auction_owner,
+        space = 8 + CollectionAllowlist::INIT_SPACE,
+        seeds = [b"auction".as_ref(), b"collection_allowlist".as_ref(), collection_mint.key().as_ref()],
+        bump
+    )]
+    pub collection_allowlist: Box<Account<'info, CollectionAllowlist>>,
+
+    #[account(mut)]
+    pub auction_owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveAllowedCollection<'info> {
+    #[account(
+        seeds = [b"auction"],
+        bump = auction_config.config_bump,
+        constraint = auction_owner.key() == auction_config.auction_owner @ ConfigStateErrors::InvalidAuctionOwner
+    )]
+    pub auction_config: Box<Account<'info, AuctionConfig>>,
+
+    #[account(
+        mut,
+        close = auction_owner,
+        seeds = [b"auction".as_ref(), b"collection_allowlist".as_ref(), collection_allowlist.collection.as_ref()],
+        bump = collection_allowlist.allowlist_bump
+    )]
+    pub collection_allowlist: Box<Account<'info, CollectionAllowlist>>,
+
+    #[account(mut)]
+    pub auction_owner: Signer<'info>,
+}
@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+
+use crate::constants::BUY_DUTCH_PAUSE;
+use crate::errors::AuctionStateErrors;
+use crate::instructions::buy_now::{sell_prize, BuyNow};
+use crate::states::*;
+use crate::utils::{dutch_price, is_paused};
+
+#[event]
+pub struct AuctionBoughtDutch {
+    pub auction_id: u32,
+    pub buyer: Pubkey,
+    pub price: u64, // `utils::dutch_price` at `bought_at`
+    pub creator_amount: u64,
+    pub fee_amount: u64,
+    pub royalty_amount: u64,
+    pub bought_at: i64,
+}
+
+// Buy the prize of a Dutch auction at its current price, settled on the spot like `buy_now` and taking
+// the same accounts. A Dutch auction nobody buys fails at `complete_auction` and the prize goes back
+pub fn buy_dutch<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BuyNow<'info>>,
+    auction_id: u32,
+    permit: Permit,
+) -> Result<()> {
+    require!(
+        !is_paused(ctx.accounts.auction_config.pause_flags, BUY_DUTCH_PAUSE),
+        AuctionStateErrors::FunctionPaused
+    );
+
+    let auction = &ctx.accounts.auction;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        auction.status == AuctionState::Active,
+        AuctionStateErrors::AuctionNotActive
+    );
+    require_gt!(auction.end_time, now, AuctionStateErrors::AuctionNotActive);
+    require_gt!(auction.dutch_start_price, 0, AuctionStateErrors::NotDutchAuction);
+
+    // the permit's `max_amount` caps what the buyer pays, the price only ever falls
+    let price = dutch_price(auction, now)?;
+    let sale = sell_prize(ctx, auction_id, permit, price, now)?;
+
+    emit!(AuctionBoughtDutch {
+        auction_id,
+        buyer: sale.buyer,
+        price,
+        creator_amount: sale.creator_amount,
+        fee_amount: sale.fee_amount,
+        royalty_amount: sale.royalty_amount,
+        bought_at: now,
+    });
+
+    Ok(())
+}
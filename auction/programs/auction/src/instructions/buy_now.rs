@@ -0,0 +1,568 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::{
+    create_idempotent, get_associated_token_address_with_program_id, AssociatedToken, Create,
+};
+use anchor_spl::metadata::mpl_token_metadata;
+use anchor_spl::token_interface::{close_account, CloseAccount, Mint, TokenAccount, TokenInterface};
+
+use crate::constants::{AUTH_RULES_PROGRAM_ID, BUY_NOW_PAUSE, FEE_MANTISSA};
+use crate::errors::{AuctionStateErrors, KeysMismatchErrors, PermitErrors};
+use crate::helpers::*;
+use crate::instructions::complete_auction::RoyaltyPaid;
+use crate::states::*;
+use crate::utils::*;
+
+#[event]
+pub struct AuctionBoughtNow {
+    pub auction_id: u32,
+    pub buyer: Pubkey,
+    pub price: u64,
+    pub creator_amount: u64,
+    pub fee_amount: u64,
+    pub royalty_amount: u64,
+    pub displaced_bidder: Option<Pubkey>, // refunded their escrowed bid in the same instruction
+    pub bought_at: i64,
+}
+
+// Buy the prize at `buy_now_price` and end the auction on the spot. The buyer pays the commission,
+// royalties and the creator directly, `remaining_accounts` is laid out as for a sale in `complete_auction`.
+// The current highest bid is refunded to its bidder on the spot, the buyer opens their bid ATA if it is gone
+pub fn buy_now<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BuyNow<'info>>,
+    auction_id: u32,
+    permit: Permit,
+) -> Result<()> {
+    require!(
+        !is_paused(ctx.accounts.auction_config.pause_flags, BUY_NOW_PAUSE),
+        AuctionStateErrors::FunctionPaused
+    );
+
+    let auction = &ctx.accounts.auction;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        auction.status == AuctionState::Active,
+        AuctionStateErrors::AuctionNotActive
+    );
+    require_gt!(auction.end_time, now, AuctionStateErrors::AuctionNotActive);
+
+    // bidding past the cutoff closes buy now for the rest of the auction
+    let price = auction.buy_now_price;
+    require_gt!(price, 0, AuctionStateErrors::BuyNowUnavailable);
+
+    let cutoff_bps = match ctx.accounts.auction_config.buy_now_cutoff_bps {
+        0 => FEE_MANTISSA,
+        bps => bps,
+    };
+    let cutoff = get_pct_amount(price, cutoff_bps as u64, FEE_MANTISSA as u64)?;
+    require!(
+        !auction.has_any_bid || auction.highest_bid_amount < cutoff,
+        AuctionStateErrors::BuyNowUnavailable
+    );
+
+    let sale = sell_prize(ctx, auction_id, permit, price, now)?;
+
+    emit!(AuctionBoughtNow {
+        auction_id,
+        buyer: sale.buyer,
+        price,
+        creator_amount: sale.creator_amount,
+        fee_amount: sale.fee_amount,
+        royalty_amount: sale.royalty_amount,
+        displaced_bidder: sale.displaced_bidder,
+        bought_at: now,
+    });
+
+    Ok(())
+}
+
+// what `sell_prize` paid out, for the caller's event
+pub struct DirectSale {
+    pub buyer: Pubkey,
+    pub creator_amount: u64,
+    pub fee_amount: u64,
+    pub royalty_amount: u64,
+    pub displaced_bidder: Option<Pubkey>,
+}
+
+// Sell the prize to the signer of `ctx` for `price` and close the auction, shared by `buy_now` and `buy_dutch`.
+// The caller has already checked the auction is live and worked out the price
+pub(crate) fn sell_prize<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BuyNow<'info>>,
+    auction_id: u32,
+    permit: Permit,
+    price: u64,
+    now: i64,
+) -> Result<DirectSale> {
+    let auction = &mut ctx.accounts.auction;
+    let buyer = &ctx.accounts.buyer;
+
+    // the admin signs off on buyers the same way as on bidders
+    let permit_signer = verify_permit(
+        &ctx.accounts.instructions_sysvar,
+        &auction_id.to_le_bytes(),
+        buyer.key(),
+        price,
+        &permit,
+    )?;
+    require_keys_eq!(
+        permit_signer,
+        ctx.accounts.auction_config.auction_admin,
+        PermitErrors::InvalidPermitSigner
+    );
+
+    let permit_nonce = &mut ctx.accounts.permit_nonce;
+    permit_nonce.user = buyer.key();
+    permit_nonce.nonce = permit.nonce;
+    permit_nonce.used_at = now;
+    permit_nonce.nonce_bump = ctx.bumps.permit_nonce;
+
+    let fee_amount = get_pct_amount(price, auction.commission_bps as u64, FEE_MANTISSA as u64)?;
+    let creator_claimable_amount = price
+        .checked_sub(fee_amount)
+        .ok_or(AuctionStateErrors::Overflow)?;
+
+    // royalties come out of the seller's side, the commission is taken on the full price
+    let royalties = if auction.fungible_prize {
+        Vec::new()
+    } else {
+        creator_royalties(&ctx.accounts.prize_metadata, price, creator_claimable_amount)?
+    };
+    let royalty_amount: u64 = royalties.iter().map(|(_, amount)| amount).sum();
+    let creator_amount = creator_claimable_amount
+        .checked_sub(royalty_amount)
+        .ok_or(AuctionStateErrors::Overflow)?;
+    require_gte!(
+        ctx.remaining_accounts.len(),
+        royalties.len(),
+        AuctionStateErrors::MissingRoyaltyAccounts
+    );
+    let (royalty_accounts, remaining_accounts) = ctx.remaining_accounts.split_at(royalties.len());
+
+    require!(
+        ctx.accounts.prize_mint.key() == auction.prize_mint
+            && ctx.accounts.buyer_prize_ata.mint == auction.prize_mint,
+        KeysMismatchErrors::InvalidPrizeMint
+    );
+    require_keys_eq!(
+        ctx.accounts.buyer_prize_ata.owner,
+        buyer.key(),
+        KeysMismatchErrors::InvalidPrizeAtaOwner
+    );
+
+    let seeds: &[&[u8]] = &[
+        b"auction",
+        &auction.auction_id.to_le_bytes(),
+        &[auction.auction_bump],
+    ];
+    let signer_seeds: &[&[&[u8]]] = &[seeds];
+
+    if is_programmable_nft(&ctx.accounts.prize_metadata)? {
+        transfer_programmable_nft(
+            &ctx.accounts.prize_escrow,
+            &auction.to_account_info(),
+            &ctx.accounts.buyer_prize_ata.to_account_info(),
+            &buyer.to_account_info(),
+            &ctx.accounts.prize_mint,
+            &buyer.to_account_info(),
+            &ctx.accounts.prize_token_program,
+            &ProgrammableNftAccounts {
+                metadata: &ctx.accounts.prize_metadata,
+                edition: &ctx.accounts.prize_edition,
+                token_record: &ctx.accounts.escrow_token_record,
+                destination_token_record: &ctx.accounts.buyer_token_record,
+                authorization_rules: &ctx.accounts.authorization_rules,
+                authorization_rules_program: &ctx.accounts.authorization_rules_program,
+                token_metadata_program: &ctx.accounts.token_metadata_program,
+                sysvar_instructions: &ctx.accounts.instructions_sysvar,
+                associated_token_program: &ctx.accounts.associated_token_program,
+                system_program: &ctx.accounts.system_program,
+            },
+            signer_seeds,
+        )?;
+    } else {
+        transfer_tokens_with_seeds(
+            &ctx.accounts.prize_escrow,
+            &ctx.accounts.buyer_prize_ata,
+            &auction.to_account_info(),
+            &ctx.accounts.prize_token_program,
+            &ctx.accounts.prize_mint,
+            remaining_accounts,
+            signer_seeds,
+            auction.lot_amount,
+        )?;
+    }
+
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.prize_token_program.to_account_info(), // token_program
+        CloseAccount {
+            account: ctx.accounts.prize_escrow.to_account_info(),
+            destination: ctx.accounts.creator.to_account_info(),
+            authority: auction.to_account_info(),
+        },
+        signer_seeds,
+    );
+    close_account(cpi_ctx)?;
+
+    // Pay out straight from the buyer: depends on SOL or SPL bidding
+    match auction.bid_mint {
+        None => {
+            transfer_sol(
+                buyer,
+                &ctx.accounts.auction_config.to_account_info(),
+                &ctx.accounts.system_program,
+                fee_amount,
+            )?;
+
+            for ((royalty_creator, amount), royalty_account) in royalties.iter().zip(royalty_accounts) {
+                require_keys_eq!(
+                    royalty_account.key(),
+                    *royalty_creator,
+                    KeysMismatchErrors::InvalidRoyaltyRecipient
+                );
+
+                transfer_sol(buyer, royalty_account, &ctx.accounts.system_program, *amount)?;
+            }
+
+            transfer_sol(
+                buyer,
+                &ctx.accounts.creator,
+                &ctx.accounts.system_program,
+                creator_amount,
+            )?;
+        }
+        Some(stored_mint) => {
+            require!(
+                ctx.accounts.bid_mint.key() == stored_mint
+                    && ctx.accounts.buyer_bid_ata.mint == stored_mint
+                    && ctx.accounts.bid_fee_treasury_ata.mint == stored_mint
+                    && ctx.accounts.creator_bid_ata.mint == stored_mint,
+                KeysMismatchErrors::InvalidBidMint
+            );
+            require!(
+                ctx.accounts.buyer_bid_ata.owner == buyer.key()
+                    && ctx.accounts.bid_fee_treasury_ata.owner == ctx.accounts.auction_config.key()
+                    && ctx.accounts.creator_bid_ata.owner == ctx.accounts.creator.key(),
+                KeysMismatchErrors::InvalidBidAtaOwner
+            );
+
+            // fee -> bid_fee_treasury_ata (owned by auction_config PDA)
+            transfer_tokens(
+                &ctx.accounts.buyer_bid_ata,
+                &ctx.accounts.bid_fee_treasury_ata,
+                buyer,
+                &ctx.accounts.bid_token_program,
+                &ctx.accounts.bid_mint,
+                remaining_accounts,
+                fee_amount,
+            )?;
+
+            // royalties -> each verified creator's bid mint account
+            for ((royalty_creator, amount), royalty_account) in royalties.iter().zip(royalty_accounts) {
+                let royalty_ata = InterfaceAccount::<TokenAccount>::try_from(royalty_account)?;
+                require!(
+                    royalty_ata.owner == *royalty_creator && royalty_ata.mint == stored_mint,
+                    KeysMismatchErrors::InvalidRoyaltyRecipient
+                );
+
+                if *amount > 0 {
+                    transfer_tokens(
+                        &ctx.accounts.buyer_bid_ata,
+                        &royalty_ata,
+                        buyer,
+                        &ctx.accounts.bid_token_program,
+                        &ctx.accounts.bid_mint,
+                        remaining_accounts,
+                        *amount,
+                    )?;
+                }
+            }
+
+            // creator_amount -> creator_ata
+            transfer_tokens(
+                &ctx.accounts.buyer_bid_ata,
+                &ctx.accounts.creator_bid_ata,
+                buyer,
+                &ctx.accounts.bid_token_program,
+                &ctx.accounts.bid_mint,
+                remaining_accounts,
+                creator_amount,
+            )?;
+        }
+    }
+
+    // the displaced highest bid goes straight back, SOL leaves with the escrow once it is closed to the bidder
+    if auction.has_any_bid {
+        let displaced_bid_escrow = ctx
+            .accounts
+            .displaced_bid_escrow
+            .as_ref()
+            .ok_or(AuctionStateErrors::MissingBidEscrow)?;
+        let displaced_bidder = ctx
+            .accounts
+            .displaced_bidder
+            .as_ref()
+            .ok_or(AuctionStateErrors::MissingDisplacedBidAccounts)?;
+
+        if let Some(stored_mint) = displaced_bid_escrow.bid_mint {
+            let (Some(escrow_ata), Some(bidder_ata)) = (
+                ctx.accounts.displaced_bid_escrow_ata.as_ref(),
+                ctx.accounts.displaced_bidder_ata.as_ref(),
+            ) else {
+                return err!(AuctionStateErrors::MissingDisplacedBidAccounts);
+            };
+            require!(
+                ctx.accounts.bid_mint.key() == stored_mint && escrow_ata.mint == stored_mint,
+                KeysMismatchErrors::InvalidBidMint
+            );
+            require_keys_eq!(
+                escrow_ata.owner,
+                displaced_bid_escrow.key(),
+                KeysMismatchErrors::InvalidBidEscrowOwner
+            );
+            require_keys_eq!(
+                bidder_ata.key(),
+                get_associated_token_address_with_program_id(
+                    &auction.highest_bidder,
+                    &stored_mint,
+                    &ctx.accounts.bid_token_program.key(),
+                ),
+                KeysMismatchErrors::InvalidBidAtaOwner
+            );
+
+            // a closed ATA must not block the sale
+            if bidder_ata.data_is_empty() {
+                create_idempotent(CpiContext::new(
+                    ctx.accounts.associated_token_program.to_account_info(),
+                    Create {
+                        payer: buyer.to_account_info(),
+                        associated_token: bidder_ata.to_account_info(),
+                        authority: displaced_bidder.to_account_info(),
+                        mint: ctx.accounts.bid_mint.to_account_info(),
+                        system_program: ctx.accounts.system_program.to_account_info(),
+                        token_program: ctx.accounts.bid_token_program.to_account_info(),
+                    },
+                ))?;
+            }
+
+            let escrow_seeds: &[&[u8]] = &[
+                b"auction",
+                &auction.auction_id.to_le_bytes(),
+                b"bid",
+                auction.highest_bidder.as_ref(),
+                &[displaced_bid_escrow.escrow_bump],
+            ];
+
+            transfer_tokens_to_account_with_seeds(
+                escrow_ata,
+                &bidder_ata.to_account_info(),
+                &displaced_bid_escrow.to_account_info(),
+                &ctx.accounts.bid_token_program,
+                &ctx.accounts.bid_mint,
+                remaining_accounts,
+                &[escrow_seeds],
+                displaced_bid_escrow.amount,
+            )?;
+            close_token_account_with_seeds(
+                escrow_ata,
+                &ctx.accounts.bid_mint,
+                &displaced_bidder.to_account_info(),
+                &displaced_bid_escrow.to_account_info(),
+                &ctx.accounts.bid_token_program,
+                &[escrow_seeds],
+            )?;
+        }
+    }
+
+    auction.status = AuctionState::CompletedSuccessfully;
+
+    for (royalty_creator, amount) in royalties {
+        emit!(RoyaltyPaid {
+            auction_id,
+            creator: royalty_creator,
+            amount,
+            paid_at: now,
+        });
+    }
+
+    Ok(DirectSale {
+        buyer: buyer.key(),
+        creator_amount,
+        fee_amount,
+        royalty_amount,
+        displaced_bidder: auction.has_any_bid.then_some(auction.highest_bidder),
+    })
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u32, permit: Permit)]
+pub struct BuyNow<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction"],
+        bump = auction_config.config_bump,
+    )]
+    pub auction_config: Box<Account<'info, AuctionConfig>>,
+
+    #[account(
+        mut,
+        close = creator,
+        seeds = [b"auction", auction_id.to_le_bytes().as_ref()],
+        bump = auction.auction_bump,
+        constraint = auction.auction_id == auction_id @AuctionStateErrors::InvalidAuctionId,
+        constraint = !auction.frozen @ AuctionStateErrors::AuctionFrozen,
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = buyer,
+        space = 8 + PermitNonce::INIT_SPACE,
+        seeds = [
+            b"auction".as_ref(),
+            b"permit".as_ref(),
+            buyer.key().as_ref(),
+            permit.nonce.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub permit_nonce: Box<Account<'info, PermitNonce>>,
+
+    #[account(
+        mut,
+        constraint = auction.creator == creator.key() @ AuctionStateErrors::InvalidCreator
+    )]
+    pub creator: AccountInfo<'info>,
+
+    pub prize_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mut)]
+    pub bid_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = prize_mint,
+        token::authority = auction,
+        token::token_program = prize_token_program
+    )]
+    pub prize_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)] // check inside
+    pub buyer_prize_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // For SPL payments (if used)
+    #[account(mut)]
+    pub buyer_bid_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // owner by config account
+    #[account(mut)]
+    pub bid_fee_treasury_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub creator_bid_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // escrowed bid of the current highest bidder, refunded to them, absent when nobody bid
+    #[account(
+        mut,
+        close = displaced_bidder,
+        seeds = [
+            b"auction".as_ref(),
+            auction_id.to_le_bytes().as_ref(),
+            b"bid".as_ref(),
+            auction.highest_bidder.as_ref(),
+        ],
+        bump = displaced_bid_escrow.escrow_bump,
+    )]
+    pub displaced_bid_escrow: Option<Box<Account<'info, BidEscrow>>>,
+
+    /// CHECK: address is pinned to the current highest bidder, only receives their refund
+    #[account(mut, address = auction.highest_bidder @ KeysMismatchErrors::InvalidHighestBidder)]
+    pub displaced_bidder: Option<UncheckedAccount<'info>>,
+
+    // For SPL refunds (if used), owner is `displaced_bid_escrow`
+    #[account(mut)]
+    pub displaced_bid_escrow_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    /// CHECK: the displaced bidder's bid_mint ATA, address checked and opened in the handler (SPL)
+    #[account(mut)]
+    pub displaced_bidder_ata: Option<UncheckedAccount<'info>>,
+
+    pub prize_token_program: Interface<'info, TokenInterface>,
+    pub bid_token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: Token Metadata PDA of the prize mint, read for royalties and to pick the pNFT transfer path
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub prize_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: master edition PDA of the prize mint, only used by pNFT transfers
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub prize_edition: UncheckedAccount<'info>,
+
+    /// CHECK: pNFT token record of `prize_escrow`, only used by pNFT transfers
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"token_record".as_ref(),
+            prize_escrow.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub escrow_token_record: UncheckedAccount<'info>,
+
+    /// CHECK: pNFT token record of `buyer_prize_ata`, only used by pNFT transfers
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"token_record".as_ref(),
+            buyer_prize_ata.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub buyer_token_record: UncheckedAccount<'info>,
+
+    /// CHECK: only forwarded when the pNFT has a rule set, checked in `transfer_programmable_nft`
+    pub authorization_rules: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to Metaplex Token Auth Rules
+    #[account(address = AUTH_RULES_PROGRAM_ID)]
+    pub authorization_rules_program: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to Token Metadata, only invoked for pNFT prizes
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to the instructions sysvar, read in `verify_permit` and by Token Metadata
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
@@ -1,9 +1,9 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{close_account, CloseAccount};
-use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use anchor_spl::metadata::mpl_token_metadata;
+use anchor_spl::token_interface::{close_account, CloseAccount, Mint, TokenAccount, TokenInterface};
 
-use crate::constants::CANCEL_AUCTION_PAUSE;
+use crate::constants::{AUTH_RULES_PROGRAM_ID, BUNDLE_RELEASE_ACCOUNTS, CANCEL_AUCTION_PAUSE};
 use crate::errors::{AuctionStateErrors, ConfigStateErrors};
 use crate::helpers::*;
 use crate::states::*;
@@ -16,7 +16,12 @@ pub struct AuctionCancelled {
     pub cancelled_time: i64,
 }
 
-pub fn cancel_auction(ctx: Context<CancelAuction>, _auction_id: u32) -> Result<()> {
+// A bundle's NFTs lead `remaining_accounts`, laid out as in `release_bundle`, followed by any transfer
+// hook accounts of the main prize
+pub fn cancel_auction<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CancelAuction<'info>>,
+    _auction_id: u32,
+) -> Result<()> {
     require!(
         !is_paused(
             ctx.accounts.auction_config.pause_flags,
@@ -33,7 +38,10 @@ pub fn cancel_auction(ctx: Context<CancelAuction>, _auction_id: u32) -> Result<(
         AuctionStateErrors::AuctionAlreadyCompleted
     );
  
-    require!(!auction.has_any_bid, AuctionStateErrors::AuctionHasBids);
+    require!(
+        !auction.has_any_bid && auction.sealed_commit_count == 0,
+        AuctionStateErrors::AuctionHasBids
+    );
 
     auction.status = AuctionState::Cancelled;
 
@@ -42,15 +50,63 @@ pub fn cancel_auction(ctx: Context<CancelAuction>, _auction_id: u32) -> Result<(
     let signer_seeds: &[&[u8]] = &[b"auction", &auction_id_bytes, &bump_bytes];
     let signer: &[&[&[u8]]] = &[signer_seeds];
 
+    let bundle_len = auction.bundle_size as usize * BUNDLE_RELEASE_ACCOUNTS;
+    require!(
+        auction.bundle_size == 0 || ctx.accounts.auction_bundle.is_some(),
+        AuctionStateErrors::MissingBundleAccounts
+    );
+    require_gte!(
+        ctx.remaining_accounts.len(),
+        bundle_len,
+        AuctionStateErrors::MissingBundleAccounts
+    );
+    let (bundle_accounts, remaining_accounts) = ctx.remaining_accounts.split_at(bundle_len);
+
     // transfer NFT back
-    transfer_tokens_with_seeds(
-        &ctx.accounts.prize_escrow,
-        &ctx.accounts.creator_prize_ata,
+    if is_programmable_nft(&ctx.accounts.prize_metadata)? {
+        transfer_programmable_nft(
+            &ctx.accounts.prize_escrow,
+            &auction.to_account_info(),
+            &ctx.accounts.creator_prize_ata.to_account_info(),
+            creator,
+            &ctx.accounts.prize_mint,
+            creator,
+            &ctx.accounts.prize_token_program,
+            &ProgrammableNftAccounts {
+                metadata: &ctx.accounts.prize_metadata,
+                edition: &ctx.accounts.prize_edition,
+                token_record: &ctx.accounts.escrow_token_record,
+                destination_token_record: &ctx.accounts.creator_token_record,
+                authorization_rules: &ctx.accounts.authorization_rules,
+                authorization_rules_program: &ctx.accounts.authorization_rules_program,
+                token_metadata_program: &ctx.accounts.token_metadata_program,
+                sysvar_instructions: &ctx.accounts.instructions_sysvar,
+                associated_token_program: &ctx.accounts.associated_token_program,
+                system_program: &ctx.accounts.system_program,
+            },
+            signer,
+        )?;
+    } else {
+        transfer_tokens_with_seeds(
+            &ctx.accounts.prize_escrow,
+            &ctx.accounts.creator_prize_ata,
+            &auction.to_account_info(),
+            &ctx.accounts.prize_token_program,
+            &ctx.accounts.prize_mint,
+            remaining_accounts,
+            signer,
+            auction.lot_amount,
+        )?;
+    }
+
+    release_bundle(
+        ctx.accounts.auction_bundle.as_deref(),
+        bundle_accounts,
         &auction.to_account_info(),
+        creator.key(),
+        &creator.to_account_info(),
         &ctx.accounts.prize_token_program,
-        &ctx.accounts.prize_mint,
         signer,
-        1u64,
     )?;
 
     let cpi_ctx = CpiContext::new_with_signer(
@@ -89,7 +145,8 @@ pub struct CancelAuction<'info> {
         close = creator,
         seeds = [b"auction", auction_id.to_le_bytes().as_ref()], 
         bump = auction.auction_bump, 
-        constraint = auction.auction_id == auction_id @AuctionStateErrors::InvalidAuctionId
+        constraint = auction.auction_id == auction_id @AuctionStateErrors::InvalidAuctionId,
+        constraint = !auction.frozen @ AuctionStateErrors::AuctionFrozen,
     )]
     pub auction: Box<Account<'info, Auction>>,
 
@@ -101,6 +158,15 @@ pub struct CancelAuction<'info> {
 
     pub auction_admin: Signer<'info>,
 
+    // only for a bundle, closed along with the auction
+    #[account(
+        mut,
+        close = creator,
+        seeds = [b"auction".as_ref(), auction_id.to_le_bytes().as_ref(), b"bundle".as_ref()],
+        bump = auction_bundle.bundle_bump,
+    )]
+    pub auction_bundle: Option<Box<Account<'info, AuctionBundle>>>,
+
     pub prize_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
@@ -122,6 +188,77 @@ pub struct CancelAuction<'info> {
 
     pub prize_token_program: Interface<'info, TokenInterface>,
 
+    /// CHECK: Token Metadata PDA of the prize mint, read to pick the pNFT transfer path
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub prize_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: master edition PDA of the prize mint, only used by pNFT transfers
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub prize_edition: UncheckedAccount<'info>,
+
+    /// CHECK: pNFT token record of `prize_escrow`, only used by pNFT transfers
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"token_record".as_ref(),
+            prize_escrow.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub escrow_token_record: UncheckedAccount<'info>,
+
+    /// CHECK: pNFT token record of `creator_prize_ata`, only used by pNFT transfers
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"token_record".as_ref(),
+            creator_prize_ata.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub creator_token_record: UncheckedAccount<'info>,
+
+    /// CHECK: only forwarded when the pNFT has a rule set, checked in `transfer_programmable_nft`
+    pub authorization_rules: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to Metaplex Token Auth Rules
+    #[account(address = AUTH_RULES_PROGRAM_ID)]
+    pub authorization_rules_program: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to Token Metadata, only invoked for pNFT prizes
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to the instructions sysvar, read by Token Metadata
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
@@ -1,10 +1,15 @@
 use anchor_lang::prelude::*;
-use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{close_account, CloseAccount};
-use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
-
-use crate::constants::{COMPLETE_AUCTION_PAUSE, FEE_MANTISSA};
-use crate::errors::{AuctionStateErrors, ConfigStateErrors, KeysMismatchErrors};
+use anchor_spl::associated_token::{
+    create_idempotent, get_associated_token_address_with_program_id, AssociatedToken, Create,
+};
+use anchor_spl::metadata::mpl_token_metadata;
+use anchor_spl::token_interface::{close_account, CloseAccount, Mint, TokenAccount, TokenInterface};
+
+use crate::constants::{
+    AUTH_RULES_PROGRAM_ID, BUNDLE_RELEASE_ACCOUNTS, COMPLETE_AUCTION_PAUSE, FEE_MANTISSA,
+    KEEPER_SETTLEMENT_DELAY,
+};
+use crate::errors::{AuctionStateErrors, KeysMismatchErrors};
 use crate::helpers::*;
 use crate::states::*;
 use crate::utils::*;
@@ -16,11 +21,39 @@ pub struct AuctionCompleted {
     pub final_price: u64,
     pub creator_amount: u64,
     pub fee_amount: u64,
+    pub royalty_amount: u64,
+    pub keeper: Pubkey,
+    pub keeper_fee: u64, // paid out of `fee_amount`
+    pub completed_at: i64,
+}
+
+#[event]
+pub struct ReserveNotMet {
+    pub auction_id: u32,
+    pub highest_bidder: Pubkey,
+    pub highest_bid_amount: u64, // refunded to `highest_bidder`
     pub completed_at: i64,
 }
 
+#[event]
+pub struct RoyaltyPaid {
+    pub auction_id: u32,
+    pub creator: Pubkey,
+    pub amount: u64,
+    pub paid_at: i64,
+}
+
 // send the prize back in another function that and then close the auction struct
-pub fn complete_auction(ctx: Context<CompleteAuction>, auction_id: u32) -> Result<()> {
+// `remaining_accounts` starts with a bundle's NFTs laid out as in `release_bundle`, going to the winner
+// on a sale and back to the creator otherwise. On a sale they are followed by one royalty account per
+// verified creator of the prize, in metadata order (the creator's wallet for SOL bids, its `bid_mint`
+// token account otherwise). Any transfer hook accounts come last.
+// `reserve_reveal` opens a hidden reserve, leaving it out fails the sale and is kept to the creator and admin
+pub fn complete_auction<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CompleteAuction<'info>>,
+    auction_id: u32,
+    reserve_reveal: Option<ReserveReveal>,
+) -> Result<()> {
     require!(
         !is_paused(
             ctx.accounts.auction_config.pause_flags,
@@ -36,7 +69,33 @@ pub fn complete_auction(ctx: Context<CompleteAuction>, auction_id: u32) -> Resul
         auction.status == AuctionState::Active,
         AuctionStateErrors::AuctionAlreadyCompleted
     );
-    require_gt!(now, auction.end_time, AuctionStateErrors::EndTimeNotReached);
+    // a sealed auction settles once its reveal period is over too
+    let closes_at = if auction.sealed {
+        auction
+            .end_time
+            .checked_add(auction.reveal_period as i64)
+            .ok_or(AuctionStateErrors::Overflow)?
+    } else {
+        auction.end_time
+    };
+    require_gt!(now, closes_at, AuctionStateErrors::EndTimeNotReached);
+
+    // the admin settles as soon as bidding closes, any keeper once the delay is over
+    if ctx.accounts.keeper.key() != ctx.accounts.auction_config.auction_admin {
+        let open_at = closes_at
+            .checked_add(KEEPER_SETTLEMENT_DELAY)
+            .ok_or(AuctionStateErrors::Overflow)?;
+        require_gt!(now, open_at, AuctionStateErrors::SettlementDelayNotOver);
+    }
+
+    // an unrevealed hidden reserve fails the sale, so only the creator or the admin may leave it out
+    if auction.has_any_bid && auction.reserve_commitment != [0u8; 32] && reserve_reveal.is_none() {
+        let keeper = ctx.accounts.keeper.key();
+        require!(
+            keeper == auction.creator || keeper == ctx.accounts.auction_config.auction_admin,
+            AuctionStateErrors::HiddenReserveNotRevealed
+        );
+    }
 
     let seeds: &[&[u8]] = &[
         b"auction",
@@ -45,19 +104,138 @@ pub fn complete_auction(ctx: Context<CompleteAuction>, auction_id: u32) -> Resul
     ];
     let signer_seeds: &[&[&[u8]]] = &[seeds];
 
-    // If no bids, return NFT to creator
-    if !auction.has_any_bid {
+    let bundle_len = auction.bundle_size as usize * BUNDLE_RELEASE_ACCOUNTS;
+    require!(
+        auction.bundle_size == 0 || ctx.accounts.auction_bundle.is_some(),
+        AuctionStateErrors::MissingBundleAccounts
+    );
+    require_gte!(
+        ctx.remaining_accounts.len(),
+        bundle_len,
+        AuctionStateErrors::MissingBundleAccounts
+    );
+    let (bundle_accounts, remaining_accounts) = ctx.remaining_accounts.split_at(bundle_len);
+
+    // a sale needs a bid that clears the reserve, a proxy bid clears it as long as its maximum does
+    let bid_cap = auction.highest_bid_max.max(auction.highest_bid_amount);
+    let is_sold = auction.has_any_bid && reserve_met(auction, bid_cap, reserve_reveal)?;
+
+    // If no sale, return NFT to creator and the highest bid to its bidder
+    if !is_sold {
         // transfer NFT back
-        transfer_tokens_with_seeds(
-            &ctx.accounts.prize_escrow,
-            &ctx.accounts.creator_prize_ata,
+        if is_programmable_nft(&ctx.accounts.prize_metadata)? {
+            transfer_programmable_nft(
+                &ctx.accounts.prize_escrow,
+                &auction.to_account_info(),
+                &ctx.accounts.creator_prize_ata.to_account_info(),
+                &ctx.accounts.creator,
+                &ctx.accounts.prize_mint,
+                &ctx.accounts.keeper,
+                &ctx.accounts.prize_token_program,
+                &ProgrammableNftAccounts {
+                    metadata: &ctx.accounts.prize_metadata,
+                    edition: &ctx.accounts.prize_edition,
+                    token_record: &ctx.accounts.escrow_token_record,
+                    destination_token_record: &ctx.accounts.creator_token_record,
+                    authorization_rules: &ctx.accounts.authorization_rules,
+                    authorization_rules_program: &ctx.accounts.authorization_rules_program,
+                    token_metadata_program: &ctx.accounts.token_metadata_program,
+                    sysvar_instructions: &ctx.accounts.instructions_sysvar,
+                    associated_token_program: &ctx.accounts.associated_token_program,
+                    system_program: &ctx.accounts.system_program,
+                },
+                signer_seeds,
+            )?;
+        } else {
+            transfer_tokens_with_seeds(
+                &ctx.accounts.prize_escrow,
+                &ctx.accounts.creator_prize_ata,
+                &auction.to_account_info(),
+                &ctx.accounts.prize_token_program,
+                &ctx.accounts.prize_mint,
+                remaining_accounts,
+                signer_seeds,
+                auction.lot_amount,
+            )?;
+        }
+
+        release_bundle(
+            ctx.accounts.auction_bundle.as_deref(),
+            bundle_accounts,
             &auction.to_account_info(),
+            ctx.accounts.creator.key(),
+            &ctx.accounts.creator,
             &ctx.accounts.prize_token_program,
-            &ctx.accounts.prize_mint,
             signer_seeds,
-            1u64,
         )?;
 
+        if auction.has_any_bid {
+            require_keys_eq!(
+                auction.highest_bidder,
+                ctx.accounts.winner.key(),
+                KeysMismatchErrors::InvalidHighestBidder
+            );
+            let winner_bid_escrow = ctx
+                .accounts
+                .winner_bid_escrow
+                .as_ref()
+                .ok_or(AuctionStateErrors::MissingBidEscrow)?;
+
+            // SOL leaves with the escrow once it is closed to the winner, SPL is sent back here
+            if let Some(stored_mint) = auction.bid_mint {
+                require!(
+                    ctx.accounts.bid_mint.key() == stored_mint
+                        && ctx.accounts.winner_bid_escrow_ata.mint == stored_mint
+                        && ctx.accounts.winner_bid_ata.mint == stored_mint,
+                    KeysMismatchErrors::InvalidBidMint
+                );
+                require_keys_eq!(
+                    ctx.accounts.winner_bid_escrow_ata.owner,
+                    winner_bid_escrow.key(),
+                    KeysMismatchErrors::InvalidBidEscrowOwner
+                );
+                require_keys_eq!(
+                    ctx.accounts.winner_bid_ata.owner,
+                    auction.highest_bidder,
+                    KeysMismatchErrors::InvalidBidAtaOwner
+                );
+
+                let escrow_seeds: &[&[u8]] = &[
+                    b"auction",
+                    &auction.auction_id.to_le_bytes(),
+                    b"bid",
+                    auction.highest_bidder.as_ref(),
+                    &[winner_bid_escrow.escrow_bump],
+                ];
+
+                transfer_tokens_with_seeds(
+                    &ctx.accounts.winner_bid_escrow_ata,
+                    &ctx.accounts.winner_bid_ata,
+                    &winner_bid_escrow.to_account_info(),
+                    &ctx.accounts.bid_token_program,
+                    &ctx.accounts.bid_mint,
+                    remaining_accounts,
+                    &[escrow_seeds],
+                    winner_bid_escrow.amount,
+                )?;
+                close_token_account_with_seeds(
+                    &ctx.accounts.winner_bid_escrow_ata,
+                    &ctx.accounts.bid_mint,
+                    &ctx.accounts.winner,
+                    &winner_bid_escrow.to_account_info(),
+                    &ctx.accounts.bid_token_program,
+                    &[escrow_seeds],
+                )?;
+            }
+
+            emit!(ReserveNotMet {
+                auction_id,
+                highest_bidder: auction.highest_bidder,
+                highest_bid_amount: auction.highest_bid_amount,
+                completed_at: now,
+            });
+        }
+
         auction.status = AuctionState::CompletedFailed;
 
         emit!(AuctionCompleted {
@@ -66,15 +244,24 @@ pub fn complete_auction(ctx: Context<CompleteAuction>, auction_id: u32) -> Resul
             final_price: 0,
             creator_amount: 0,
             fee_amount: 0,
+            royalty_amount: 0,
+            keeper: ctx.accounts.keeper.key(),
+            keeper_fee: 0,
             completed_at: now,
         });
 
         return Ok(());
     }
 
-    // There is a winner
-    let final_price = auction.highest_bid_amount;
-    let commission_bps = ctx.accounts.auction_config.commission_bps as u64;
+    // There is a winner, paying the second highest bid in a sealed auction and the highest bid otherwise,
+    // or the reserve if that is higher (only ever the case for a proxy bid)
+    let final_price = if auction.sealed {
+        auction.second_bid_amount
+    } else {
+        auction.highest_bid_amount
+    }
+    .max(reserve_floor(auction, reserve_reveal));
+    let commission_bps = auction.commission_bps as u64;
 
     let fee_amount = get_pct_amount(final_price, commission_bps, FEE_MANTISSA as u64)?;
     let creator_claimable_amount = final_price
@@ -82,12 +269,37 @@ pub fn complete_auction(ctx: Context<CompleteAuction>, auction_id: u32) -> Resul
         .ok_or(AuctionStateErrors::Overflow)?;
     let mut creator_amount = 0;
 
+    // the keeper is paid out of the commission, the creator's side never changes
+    let mut keeper_fee = get_pct_amount(
+        fee_amount,
+        ctx.accounts.auction_config.keeper_fee_bps as u64,
+        FEE_MANTISSA as u64,
+    )?;
+
+    // royalties come out of the seller's side, the commission is taken on the full price
+    let mut royalties = if auction.fungible_prize {
+        Vec::new()
+    } else {
+        creator_royalties(
+            &ctx.accounts.prize_metadata,
+            final_price,
+            creator_claimable_amount,
+        )?
+    };
+    let mut royalty_amount: u64 = royalties.iter().map(|(_, amount)| amount).sum();
+    require_gte!(
+        remaining_accounts.len(),
+        royalties.len(),
+        AuctionStateErrors::MissingRoyaltyAccounts
+    );
+    let (royalty_accounts, remaining_accounts) = remaining_accounts.split_at(royalties.len());
+
     let prize_mint_key = auction.prize_mint;
 
     // checking the keys mismatch
-    require!(
-        ctx.accounts.prize_mint.key() == prize_mint_key
-            && ctx.accounts.winner_prize_ata.mint == prize_mint_key,
+    require_keys_eq!(
+        ctx.accounts.prize_mint.key(),
+        prize_mint_key,
         KeysMismatchErrors::InvalidPrizeMint
     );
 
@@ -98,19 +310,94 @@ pub fn complete_auction(ctx: Context<CompleteAuction>, auction_id: u32) -> Resul
     );
 
     require_keys_eq!(
-        ctx.accounts.winner_prize_ata.owner,
-        ctx.accounts.winner.key(),
+        ctx.accounts.winner_prize_ata.key(),
+        get_associated_token_address_with_program_id(
+            &ctx.accounts.winner.key(),
+            &prize_mint_key,
+            &ctx.accounts.prize_token_program.key(),
+        ),
         KeysMismatchErrors::InvalidPrizeAtaOwner
     );
 
-    transfer_tokens_with_seeds(
-        &ctx.accounts.prize_escrow,
-        &ctx.accounts.winner_prize_ata,
+    // the winning bid is paid out of the winner's escrow, which is closed back to them afterwards
+    let winner_bid_escrow = ctx
+        .accounts
+        .winner_bid_escrow
+        .as_ref()
+        .ok_or(AuctionStateErrors::MissingBidEscrow)?;
+    let winner_key = ctx.accounts.winner.key();
+    let escrow_seeds: &[&[u8]] = &[
+        b"auction",
+        &auction.auction_id.to_le_bytes(),
+        b"bid",
+        winner_key.as_ref(),
+        &[winner_bid_escrow.escrow_bump],
+    ];
+    let escrow_signer_seeds: &[&[&[u8]]] = &[escrow_seeds];
+
+    // the keeper fronts the rent of whatever the winner is missing, the winner's escrow pays it back below
+    let keeper_lamports = ctx.accounts.keeper.lamports();
+
+    if is_programmable_nft(&ctx.accounts.prize_metadata)? {
+        // TransferV1 opens the winner's token account and token record itself
+        transfer_programmable_nft(
+            &ctx.accounts.prize_escrow,
+            &auction.to_account_info(),
+            &ctx.accounts.winner_prize_ata,
+            &ctx.accounts.winner,
+            &ctx.accounts.prize_mint,
+            &ctx.accounts.keeper,
+            &ctx.accounts.prize_token_program,
+            &ProgrammableNftAccounts {
+                metadata: &ctx.accounts.prize_metadata,
+                edition: &ctx.accounts.prize_edition,
+                token_record: &ctx.accounts.escrow_token_record,
+                destination_token_record: &ctx.accounts.winner_token_record,
+                authorization_rules: &ctx.accounts.authorization_rules,
+                authorization_rules_program: &ctx.accounts.authorization_rules_program,
+                token_metadata_program: &ctx.accounts.token_metadata_program,
+                sysvar_instructions: &ctx.accounts.instructions_sysvar,
+                associated_token_program: &ctx.accounts.associated_token_program,
+                system_program: &ctx.accounts.system_program,
+            },
+            signer_seeds,
+        )?;
+    } else {
+        if ctx.accounts.winner_prize_ata.data_is_empty() {
+            create_idempotent(CpiContext::new(
+                ctx.accounts.associated_token_program.to_account_info(),
+                Create {
+                    payer: ctx.accounts.keeper.to_account_info(),
+                    associated_token: ctx.accounts.winner_prize_ata.to_account_info(),
+                    authority: ctx.accounts.winner.to_account_info(),
+                    mint: ctx.accounts.prize_mint.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    token_program: ctx.accounts.prize_token_program.to_account_info(),
+                },
+            ))?;
+        }
+
+        transfer_tokens_to_account_with_seeds(
+            &ctx.accounts.prize_escrow,
+            &ctx.accounts.winner_prize_ata,
+            &auction.to_account_info(),
+            &ctx.accounts.prize_token_program,
+            &ctx.accounts.prize_mint,
+            remaining_accounts,
+            signer_seeds,
+            auction.lot_amount,
+        )?;
+    }
+    let opening_rent = keeper_lamports.saturating_sub(ctx.accounts.keeper.lamports());
+
+    release_bundle(
+        ctx.accounts.auction_bundle.as_deref(),
+        bundle_accounts,
         &auction.to_account_info(),
+        winner_key,
+        &ctx.accounts.creator,
         &ctx.accounts.prize_token_program,
-        &ctx.accounts.prize_mint,
         signer_seeds,
-        1u64,
     )?;
 
     let cpi_ctx = CpiContext::new_with_signer(
@@ -129,36 +416,87 @@ pub fn complete_auction(ctx: Context<CompleteAuction>, auction_id: u32) -> Resul
     // Distribute funds: depends on SOL or SPL bidding
     match auction.bid_mint {
         None => {
-            creator_amount = creator_claimable_amount;
+            let escrow_info = winner_bid_escrow.to_account_info();
+
+            // a royalty too small to open its creator's wallet stays with the seller
+            for ((royalty_creator, amount), royalty_account) in royalties.iter_mut().zip(royalty_accounts) {
+                require_keys_eq!(
+                    royalty_account.key(),
+                    *royalty_creator,
+                    KeysMismatchErrors::InvalidRoyaltyRecipient
+                );
+                if !can_receive_lamports(royalty_account, *amount)? {
+                    *amount = 0;
+                }
+            }
+            royalty_amount = royalties.iter().map(|(_, amount)| amount).sum();
+
+            // a sealed deposit or proxy maximum above the price stays on the escrow, closing it returns that to the winner
+            creator_amount = creator_claimable_amount
+                .checked_sub(royalty_amount)
+                .ok_or(AuctionStateErrors::Overflow)?;
 
-            **auction.to_account_info().try_borrow_mut_lamports()? -= fee_amount;
-            **ctx.accounts.auction_config.to_account_info().try_borrow_mut_lamports()? += fee_amount;
-            
-            **auction.to_account_info().try_borrow_mut_lamports()? -= creator_claimable_amount;
-            **ctx.accounts.creator.try_borrow_mut_lamports()? += creator_claimable_amount;
+            // the opened accounts' rent comes out of that refund first and the treasury's share of the fee after it
+            let refund = escrow_info
+                .lamports()
+                .checked_sub(final_price)
+                .ok_or(AuctionStateErrors::Overflow)?;
+            let rent_from_refund = opening_rent.min(refund);
+            let mut treasury_fee = fee_amount - keeper_fee;
+            let rent_from_treasury = (opening_rent - rent_from_refund).min(treasury_fee);
+            treasury_fee -= rent_from_treasury;
+
+            // a keeper that can't hold its fee leaves it to the treasury
+            let rent_back = rent_from_refund + rent_from_treasury;
+            if !can_receive_lamports(&ctx.accounts.keeper, keeper_fee + rent_back)? {
+                treasury_fee += keeper_fee;
+                keeper_fee = 0;
+            }
+
+            **escrow_info.try_borrow_mut_lamports()? -= fee_amount + rent_from_refund;
+            **ctx.accounts.auction_config.to_account_info().try_borrow_mut_lamports()? += treasury_fee;
+            **ctx.accounts.keeper.try_borrow_mut_lamports()? += keeper_fee + rent_back;
+
+            for ((_, amount), royalty_account) in royalties.iter().zip(royalty_accounts) {
+                **escrow_info.try_borrow_mut_lamports()? -= *amount;
+                **royalty_account.try_borrow_mut_lamports()? += *amount;
+            }
+
+            // the creator always ends up rent exempt, the closed auction's rent is theirs too
+            **escrow_info.try_borrow_mut_lamports()? -= creator_amount;
+            **ctx.accounts.creator.try_borrow_mut_lamports()? += creator_amount;
         }
         Some(stored_mint) => {
+            // a sealed deposit or proxy maximum above the price goes back to the winner, only dust on top of it is the creator's
+            let excess_amount = winner_bid_escrow
+                .amount
+                .checked_sub(final_price)
+                .ok_or(AuctionStateErrors::Overflow)?;
             let creator_dust_amount = ctx
                 .accounts
-                .bid_escrow
+                .winner_bid_escrow_ata
                 .amount
-                .checked_sub(fee_amount)
+                .checked_sub(excess_amount)
+                .and_then(|amount| amount.checked_sub(fee_amount))
+                .ok_or(AuctionStateErrors::Overflow)?;
+            creator_amount = creator_claimable_amount
+                .max(creator_dust_amount)
+                .checked_sub(royalty_amount)
                 .ok_or(AuctionStateErrors::Overflow)?;
-            creator_amount = creator_claimable_amount.max(creator_dust_amount);
 
-            // SPL: bid_escrow holds the funds; fee_treasury ATA (owned by config PDA) receives fee
+            // SPL: winner_bid_escrow_ata holds the funds; fee_treasury ATA (owned by config PDA) receives fee
             // validate provided accounts
             require!(
                 ctx.accounts.bid_mint.key() == stored_mint
-                    && ctx.accounts.bid_escrow.mint == stored_mint
+                    && ctx.accounts.winner_bid_escrow_ata.mint == stored_mint
                     && ctx.accounts.bid_fee_treasury_ata.mint == stored_mint
                     && ctx.accounts.creator_bid_ata.mint == stored_mint,
                 KeysMismatchErrors::InvalidBidMint
             );
 
             require_keys_eq!(
-                ctx.accounts.bid_escrow.owner,
-                auction.key(),
+                ctx.accounts.winner_bid_escrow_ata.owner,
+                winner_bid_escrow.key(),
                 KeysMismatchErrors::InvalidBidEscrowOwner
             );
 
@@ -168,38 +506,109 @@ pub fn complete_auction(ctx: Context<CompleteAuction>, auction_id: u32) -> Resul
                 KeysMismatchErrors::InvalidBidAtaOwner
             );
 
-            // fee -> bid_fee_treasury_ata (owned by auction_config PDA)
+            // keeper fee -> keeper's bid mint account, a keeper without one leaves it to the treasury
+            match ctx.accounts.keeper_bid_ata.as_ref() {
+                Some(keeper_bid_ata) if keeper_fee > 0 => {
+                    require!(
+                        keeper_bid_ata.mint == stored_mint
+                            && keeper_bid_ata.owner == ctx.accounts.keeper.key(),
+                        KeysMismatchErrors::InvalidBidAtaOwner
+                    );
+
+                    transfer_tokens_with_seeds(
+                        &ctx.accounts.winner_bid_escrow_ata,
+                        keeper_bid_ata,
+                        &winner_bid_escrow.to_account_info(),
+                        &ctx.accounts.bid_token_program,
+                        &ctx.accounts.bid_mint,
+                        remaining_accounts,
+                        escrow_signer_seeds,
+                        keeper_fee,
+                    )?;
+                }
+                _ => keeper_fee = 0,
+            }
+
+            // rest of the fee -> bid_fee_treasury_ata (owned by auction_config PDA)
             transfer_tokens_with_seeds(
-                &ctx.accounts.bid_escrow,
+                &ctx.accounts.winner_bid_escrow_ata,
                 &ctx.accounts.bid_fee_treasury_ata,
-                &auction.to_account_info(),
+                &winner_bid_escrow.to_account_info(),
                 &ctx.accounts.bid_token_program,
                 &ctx.accounts.bid_mint,
-                signer_seeds,
-                fee_amount,
+                remaining_accounts,
+                escrow_signer_seeds,
+                fee_amount - keeper_fee,
             )?;
 
+            // royalties -> each verified creator's bid mint account
+            for ((royalty_creator, amount), royalty_account) in royalties.iter().zip(royalty_accounts) {
+                let royalty_ata = InterfaceAccount::<TokenAccount>::try_from(royalty_account)?;
+                require!(
+                    royalty_ata.owner == *royalty_creator && royalty_ata.mint == stored_mint,
+                    KeysMismatchErrors::InvalidRoyaltyRecipient
+                );
+
+                if *amount > 0 {
+                    transfer_tokens_with_seeds(
+                        &ctx.accounts.winner_bid_escrow_ata,
+                        &royalty_ata,
+                        &winner_bid_escrow.to_account_info(),
+                        &ctx.accounts.bid_token_program,
+                        &ctx.accounts.bid_mint,
+                        remaining_accounts,
+                        escrow_signer_seeds,
+                        *amount,
+                    )?;
+                }
+            }
+
             // creator_amount -> creator_ata
             transfer_tokens_with_seeds(
-                &ctx.accounts.bid_escrow,
+                &ctx.accounts.winner_bid_escrow_ata,
                 &ctx.accounts.creator_bid_ata,
-                &auction.to_account_info(),
+                &winner_bid_escrow.to_account_info(),
                 &ctx.accounts.bid_token_program,
                 &ctx.accounts.bid_mint,
-                signer_seeds,
+                remaining_accounts,
+                escrow_signer_seeds,
                 creator_amount,
             )?;
 
-            let cpi_ctx = CpiContext::new_with_signer(
-                ctx.accounts.bid_token_program.to_account_info(), // token_program
-                CloseAccount {
-                    account: ctx.accounts.bid_escrow.to_account_info(),
-                    destination: ctx.accounts.creator.to_account_info(),
-                    authority: auction.to_account_info(),
-                },
-                signer_seeds,
-            );
-            close_account(cpi_ctx)?;
+            if excess_amount > 0 {
+                require!(
+                    ctx.accounts.winner_bid_ata.mint == stored_mint
+                        && ctx.accounts.winner_bid_ata.owner == ctx.accounts.winner.key(),
+                    KeysMismatchErrors::InvalidBidAtaOwner
+                );
+
+                transfer_tokens_with_seeds(
+                    &ctx.accounts.winner_bid_escrow_ata,
+                    &ctx.accounts.winner_bid_ata,
+                    &winner_bid_escrow.to_account_info(),
+                    &ctx.accounts.bid_token_program,
+                    &ctx.accounts.bid_mint,
+                    remaining_accounts,
+                    escrow_signer_seeds,
+                    excess_amount,
+                )?;
+            }
+
+            // the emptied escrow's rent joins the escrow account's own, which is closed to the winner
+            let escrow_info = winner_bid_escrow.to_account_info();
+            close_token_account_with_seeds(
+                &ctx.accounts.winner_bid_escrow_ata,
+                &ctx.accounts.bid_mint,
+                &escrow_info,
+                &escrow_info,
+                &ctx.accounts.bid_token_program,
+                escrow_signer_seeds,
+            )?;
+
+            // and pays back the rent of the accounts opened for the winner first
+            let rent_back = opening_rent.min(escrow_info.lamports());
+            **escrow_info.try_borrow_mut_lamports()? -= rent_back;
+            **ctx.accounts.keeper.try_borrow_mut_lamports()? += rent_back;
         }
     }
 
@@ -209,9 +618,21 @@ pub fn complete_auction(ctx: Context<CompleteAuction>, auction_id: u32) -> Resul
         final_price,
         creator_amount,
         fee_amount,
+        royalty_amount,
+        keeper: ctx.accounts.keeper.key(),
+        keeper_fee,
         completed_at: now,
     });
 
+    for (royalty_creator, amount) in royalties {
+        emit!(RoyaltyPaid {
+            auction_id,
+            creator: royalty_creator,
+            amount,
+            paid_at: now,
+        });
+    }
+
     Ok(())
 }
 
@@ -222,7 +643,6 @@ pub struct CompleteAuction<'info> {
         mut,
         seeds = [b"auction"], 
         bump = auction_config.config_bump, 
-        constraint = auction_config.auction_admin == auction_admin.key() @ConfigStateErrors::InvalidAuctionAdmin
     )]
     pub auction_config: Box<Account<'info, AuctionConfig>>,
 
@@ -231,12 +651,14 @@ pub struct CompleteAuction<'info> {
         close = creator,
         seeds = [b"auction", auction_id.to_le_bytes().as_ref()], 
         bump = auction.auction_bump, 
-        constraint = auction.auction_id == auction_id @AuctionStateErrors::InvalidAuctionId
+        constraint = auction.auction_id == auction_id @AuctionStateErrors::InvalidAuctionId,
+        constraint = !auction.frozen @ AuctionStateErrors::AuctionFrozen,
     )]
     pub auction: Box<Account<'info, Auction>>,
 
+    // settles the auction: the admin right after bidding closes, anyone after `KEEPER_SETTLEMENT_DELAY`
     #[account(mut)]
-    pub auction_admin: Signer<'info>,
+    pub keeper: Signer<'info>,
 
     #[account(
         mut,
@@ -249,6 +671,7 @@ pub struct CompleteAuction<'info> {
     pub winner: AccountInfo<'info>,
 
     pub prize_mint: InterfaceAccount<'info, Mint>,
+    #[account(mut)]
     pub bid_mint: InterfaceAccount<'info, Mint>,
 
     #[account(
@@ -259,9 +682,35 @@ pub struct CompleteAuction<'info> {
     )]
     pub prize_escrow: InterfaceAccount<'info, TokenAccount>,
 
-    // For SPL payouts (if used)
+    // only for a bundle, closed along with the auction
+    #[account(
+        mut,
+        seeds = [b"auction".as_ref(), auction_id.to_le_bytes().as_ref(), b"bundle".as_ref()],
+        bump = auction_bundle.bundle_bump,
+    )]
+    pub auction_bundle: Option<Box<Account<'info, AuctionBundle>>>,
+
+    // winner's escrowed bid, absent when nobody bid
+    #[account(
+        mut,
+        close = winner,
+        seeds = [
+            b"auction".as_ref(),
+            auction_id.to_le_bytes().as_ref(),
+            b"bid".as_ref(),
+            auction.highest_bidder.as_ref(),
+        ],
+        bump = winner_bid_escrow.escrow_bump,
+    )]
+    pub winner_bid_escrow: Option<Box<Account<'info, BidEscrow>>>,
+
+    // For SPL payouts (if used), owner is `winner_bid_escrow`
+    #[account(mut)]
+    pub winner_bid_escrow_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // winner's ATA for bid_mint, refunded when the reserve is not met or the escrow exceeds the price (SPL)
     #[account(mut)]
-    pub bid_escrow: InterfaceAccount<'info, TokenAccount>,
+    pub winner_bid_ata: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
@@ -271,8 +720,9 @@ pub struct CompleteAuction<'info> {
     )]
     pub creator_prize_ata: InterfaceAccount<'info, TokenAccount>,
 
-    #[account(mut)] // check inside
-    pub winner_prize_ata: InterfaceAccount<'info, TokenAccount>,
+    /// CHECK: the winner's prize ATA, address checked and opened in the handler on a sale only
+    #[account(mut)]
+    pub winner_prize_ata: UncheckedAccount<'info>,
 
     // owner by config account
     #[account(mut)]
@@ -281,9 +731,99 @@ pub struct CompleteAuction<'info> {
     #[account(mut)]
     pub creator_bid_ata: InterfaceAccount<'info, TokenAccount>,
 
+    // keeper's bid_mint account for its fee (SPL), left out to waive the fee
+    #[account(mut)]
+    pub keeper_bid_ata: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
     pub prize_token_program: Interface<'info, TokenInterface>,
     pub bid_token_program: Interface<'info, TokenInterface>,
 
+    /// CHECK: Token Metadata PDA of the prize mint, read for royalties and to pick the pNFT transfer path
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub prize_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: master edition PDA of the prize mint, only used by pNFT transfers
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub prize_edition: UncheckedAccount<'info>,
+
+    /// CHECK: pNFT token record of `prize_escrow`, only used by pNFT transfers
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"token_record".as_ref(),
+            prize_escrow.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub escrow_token_record: UncheckedAccount<'info>,
+
+    /// CHECK: pNFT token record of `creator_prize_ata`, only used by pNFT transfers
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"token_record".as_ref(),
+            creator_prize_ata.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub creator_token_record: UncheckedAccount<'info>,
+
+    /// CHECK: pNFT token record of `winner_prize_ata`, only used by pNFT transfers
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"token_record".as_ref(),
+            winner_prize_ata.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub winner_token_record: UncheckedAccount<'info>,
+
+    /// CHECK: only forwarded when the pNFT has a rule set, checked in `transfer_programmable_nft`
+    pub authorization_rules: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to Metaplex Token Auth Rules
+    #[account(address = AUTH_RULES_PROGRAM_ID)]
+    pub authorization_rules_program: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to Token Metadata, only invoked for pNFT prizes
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to the instructions sysvar, read by Token Metadata
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
@@ -1,7 +1,10 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::mpl_token_metadata;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
-use crate::constants::CREATE_AUCTION_PAUSE;
+use crate::constants::{
+    AUCTION_VERSION, AUTH_RULES_PROGRAM_ID, BUNDLE_CREATE_ACCOUNTS, CREATE_AUCTION_PAUSE, MAX_BUNDLED_NFTS,
+};
 use crate::errors::*;
 use crate::helpers::*;
 use crate::states::*;
@@ -12,20 +15,30 @@ pub struct AuctionCreated {
     pub auction_id: u32,
     pub creator: Pubkey,
     pub prize_mint: Pubkey,
+    pub collection: Option<Pubkey>, // verified collection of the prize
     pub start_time: i64,
     pub end_time: i64,
+    pub reserve_price: u64,
+    pub hidden_reserve: bool,
+    pub buy_now_price: u64,
+    pub sealed: bool,
+    pub dutch: Option<DutchSchedule>,
+    pub lot_amount: u64,
+    pub fungible_prize: bool,
+    pub bundle_mints: Vec<Pubkey>, // sold together with `prize_mint`, empty for a single prize
     pub created_at: i64,
 }
 
-pub fn create_auction(
-    ctx: Context<CreateAuction>,
-    mut start_time: i64,
-    end_time: i64,
-    start_immediately: bool,
+// A bundle's NFTs lead `remaining_accounts`, laid out as in `escrow_bundle`, followed by any transfer
+// hook accounts of the main prize
+pub fn create_auction<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateAuction<'info>>,
+    params: AuctionParams,
     is_bid_mint_sol: bool,
-    base_bid: u64, // base bid can be zero also
-    min_increment: u64,
-    time_extension: u32,
+    sealed: bool,                 // bids are committed and revealed, see `sealed_bid`
+    dutch: Option<DutchSchedule>, // sells at a falling price through `buy_dutch` instead of taking bids
+    lot_amount: u64,              // raw prize units on offer, 1 for an NFT
+    bundle_size: u8,              // NFTs sold along with the prize, 0 => a single prize
 ) -> Result<()> {
     let config = &mut ctx.accounts.auction_config;
 
@@ -38,55 +51,104 @@ pub fn create_auction(
     let creator = &ctx.accounts.creator;
     let now = Clock::get()?.unix_timestamp;
 
-    require_gt!(min_increment, 0, AuctionStateErrors::InvalidZeroAmount);
+    if sealed {
+        require_gt!(
+            config.sealed_reveal_period,
+            0,
+            AuctionStateErrors::SealedAuctionsDisabled
+        );
+    }
 
-    if start_immediately {
-        start_time = now;
+    if let Some(schedule) = dutch {
+        // there are no bids for a sealed auction to work on, the prices are checked against the schedule later
+        require!(
+            schedule.start_price > schedule.floor_price
+                && schedule.decay_interval > 0
+                && !sealed,
+            AuctionStateErrors::InvalidDutchSchedule
+        );
     }
-    let duration = end_time
-        .checked_sub(start_time)
-        .ok_or(AuctionStateErrors::StartTimeExceedEndTime)?;
 
+    // An NFT prize is verified against its metadata, a fungible lot only has to pass the mint policy
+    let fungible_prize = !is_nft_mint(&ctx.accounts.prize_mint);
+    let collection = if fungible_prize {
+        require_gt!(lot_amount, 0, AuctionStateErrors::InvalidLotAmount);
+        None
+    } else {
+        require_eq!(lot_amount, 1, AuctionStateErrors::InvalidLotAmount);
+        verify_nft_prize(
+            &ctx.accounts.prize_mint,
+            &ctx.accounts.prize_metadata,
+            &ctx.accounts.prize_edition,
+            ctx.accounts.prize_collection_allowlist.as_deref(),
+            config.require_verified_collection,
+        )?
+    };
+    check_mint_policy(
+        &ctx.accounts.prize_mint,
+        config.allowed_mint_extensions,
+        &ctx.accounts.prize_mint_allowlist,
+    )?;
+
+    // bundled NFTs leave with the main prize, Dutch sales only move the one
+    if bundle_size > 0 {
+        require!(
+            bundle_size as usize <= MAX_BUNDLED_NFTS && !fungible_prize && dutch.is_none(),
+            AuctionStateErrors::InvalidBundle
+        );
+    }
     require!(
-        duration >= config.minimum_auction_period as i64
-            && duration <= config.maximum_auction_period as i64,
-        ConfigStateErrors::InvalidAuctionPeriod
+        bundle_size > 0 || ctx.accounts.auction_bundle.is_none(),
+        AuctionStateErrors::InvalidBundle
     );
-    require!(
-        time_extension >= config.minimum_time_extension
-            && time_extension <= config.maximum_time_extension,
-        ConfigStateErrors::InvalidTimeExtension
+    let bundle_len = bundle_size as usize * BUNDLE_CREATE_ACCOUNTS;
+    require_gte!(
+        ctx.remaining_accounts.len(),
+        bundle_len,
+        AuctionStateErrors::MissingBundleAccounts
     );
+    let (bundle_accounts, remaining_accounts) = ctx.remaining_accounts.split_at(bundle_len);
 
-    require_gte!(start_time, now, AuctionStateErrors::StartTimeInPast);
-
-    // Validate NFT mint passed in accounts
-    validate_nft(&ctx.accounts.prize_mint)?;
-
-    // Initialize auction state
+    // Initialize auction state, the mode first as the params are checked against it
+    auction.version = AUCTION_VERSION;
     auction.auction_id = config.auction_count;
     auction.creator = creator.key();
     auction.prize_mint = ctx.accounts.prize_mint.key();
-    auction.start_time = start_time;
-    auction.end_time = end_time;
     auction.bid_mint = if is_bid_mint_sol {
         None
     } else {
         require!(ctx.accounts.bid_mint.decimals != 0, KeysMismatchErrors::InvalidBidMint);
+        check_mint_policy(
+            &ctx.accounts.bid_mint,
+            config.allowed_mint_extensions,
+            &ctx.accounts.bid_mint_allowlist,
+        )?;
         Some(ctx.accounts.bid_mint.key())
     };
-    auction.base_bid = base_bid;
-    auction.min_increment = min_increment;
-    auction.time_extension = time_extension;
-    auction.highest_bid_amount = base_bid;
+    auction.sealed = sealed;
+    if sealed {
+        auction.reveal_period = config.sealed_reveal_period;
+        auction.sealed_penalty_bps = config.sealed_penalty_bps;
+    }
+    if let Some(schedule) = dutch {
+        auction.dutch_start_price = schedule.start_price;
+        auction.dutch_floor_price = schedule.floor_price;
+        auction.dutch_decay_interval = schedule.decay_interval;
+    }
+    auction.lot_amount = lot_amount;
+    auction.fungible_prize = fungible_prize;
+    auction.bundle_size = bundle_size;
+    apply_auction_params(auction, config, &params, now)?;
     auction.highest_bidder = Pubkey::default();
     auction.has_any_bid = false;
-    auction.status = if start_immediately {
+    auction.status = if params.start_immediately {
         AuctionState::Active
     } else {
         AuctionState::Initialized
     };
     auction.auction_bump = ctx.bumps.auction;
+    auction.commission_bps = config.commission_bps;
+    auction.sealed_commit_count = 0;
 
     // Increment global counter
     config.auction_count = config
@@ -94,15 +156,66 @@ pub fn create_auction(
         .checked_add(1)
         .ok_or(AuctionStateErrors::Overflow)?;
 
-    // transfer NFT from creator -> prize_escrow
-    transfer_tokens(
-        &ctx.accounts.creator_prize_ata,
-        &ctx.accounts.prize_escrow,
-        creator,
-        &ctx.accounts.prize_token_program,
-        &ctx.accounts.prize_mint,
-        1u64,
-    )?;
+    // transfer the prize from creator -> prize_escrow, a fee bearing mint would short the lot
+    if is_programmable_nft(&ctx.accounts.prize_metadata)? {
+        transfer_programmable_nft(
+            &ctx.accounts.creator_prize_ata,
+            creator,
+            &ctx.accounts.prize_escrow.to_account_info(),
+            &auction.to_account_info(),
+            &ctx.accounts.prize_mint,
+            creator,
+            &ctx.accounts.prize_token_program,
+            &ProgrammableNftAccounts {
+                metadata: &ctx.accounts.prize_metadata,
+                edition: &ctx.accounts.prize_edition,
+                token_record: &ctx.accounts.creator_token_record,
+                destination_token_record: &ctx.accounts.escrow_token_record,
+                authorization_rules: &ctx.accounts.authorization_rules,
+                authorization_rules_program: &ctx.accounts.authorization_rules_program,
+                token_metadata_program: &ctx.accounts.token_metadata_program,
+                sysvar_instructions: &ctx.accounts.instructions_sysvar,
+                associated_token_program: &ctx.accounts.associated_token_program,
+                system_program: &ctx.accounts.system_program,
+            },
+            &[],
+        )?;
+    } else {
+        let received = transfer_tokens(
+            &ctx.accounts.creator_prize_ata,
+            &ctx.accounts.prize_escrow,
+            creator,
+            &ctx.accounts.prize_token_program,
+            &ctx.accounts.prize_mint,
+            remaining_accounts,
+            lot_amount,
+        )?;
+        require_eq!(received, lot_amount, TransferErrors::InsufficientAmountReceived);
+    }
+
+    let bundle_mints = if bundle_size > 0 {
+        let bundle_mints = escrow_bundle(
+            bundle_accounts,
+            creator,
+            auction.key(),
+            auction.prize_mint,
+            &ctx.accounts.prize_token_program,
+            config,
+        )?;
+
+        let auction_bundle = ctx
+            .accounts
+            .auction_bundle
+            .as_mut()
+            .ok_or(AuctionStateErrors::MissingBundleAccounts)?;
+        auction_bundle.auction_id = auction.auction_id;
+        auction_bundle.mints = bundle_mints.clone();
+        auction_bundle.bundle_bump = ctx.bumps.auction_bundle.ok_or(AuctionStateErrors::MissingBundleAccounts)?;
+
+        bundle_mints
+    } else {
+        Vec::new()
+    };
 
     if config.creation_fee_lamports > 0 {
         transfer_sol(
@@ -117,8 +230,17 @@ pub fn create_auction(
         auction_id: auction.auction_id,
         creator: creator.key(),
         prize_mint: auction.prize_mint,
+        collection,
         start_time: auction.start_time,
         end_time: auction.end_time,
+        reserve_price: auction.reserve_price,
+        hidden_reserve: auction.reserve_commitment != [0u8; 32],
+        buy_now_price: auction.buy_now_price,
+        sealed,
+        dutch,
+        lot_amount,
+        fungible_prize,
+        bundle_mints,
         created_at: now,
     });
 
@@ -150,9 +272,37 @@ pub struct CreateAuction<'info> {
 
     pub auction_admin: Signer<'info>,
 
+    // only for a bundle, lists its NFTs besides `prize_mint`
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + AuctionBundle::INIT_SPACE,
+        seeds = [
+            b"auction".as_ref(),
+            auction_config.auction_count.to_le_bytes().as_ref(),
+            b"bundle".as_ref(),
+        ],
+        bump
+    )]
+    pub auction_bundle: Option<Box<Account<'info, AuctionBundle>>>,
+
     pub prize_mint: InterfaceAccount<'info, Mint>,
     pub bid_mint: InterfaceAccount<'info, Mint>,
 
+    /// CHECK: only has to exist when the owner allowlisted the prize mint, see `check_mint_policy`
+    #[account(
+        seeds = [b"auction".as_ref(), b"mint_allowlist".as_ref(), prize_mint.key().as_ref()],
+        bump
+    )]
+    pub prize_mint_allowlist: UncheckedAccount<'info>,
+
+    /// CHECK: only has to exist when the owner allowlisted the bid mint, see `check_mint_policy`
+    #[account(
+        seeds = [b"auction".as_ref(), b"mint_allowlist".as_ref(), bid_mint.key().as_ref()],
+        bump
+    )]
+    pub bid_mint_allowlist: UncheckedAccount<'info>,
+
     #[account(
         mut, 
         associated_token::mint = prize_mint,
@@ -172,6 +322,80 @@ pub struct CreateAuction<'info> {
 
     pub prize_token_program: Interface<'info, TokenInterface>,
 
+    /// CHECK: Token Metadata PDA of the prize mint, verified for NFT prizes and read to pick the pNFT transfer path
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub prize_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: master edition PDA of the prize mint, verified for NFT prizes and used by pNFT transfers
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub prize_edition: UncheckedAccount<'info>,
+
+    /// CHECK: `CollectionAllowlist` entry of the prize's verified collection, only read when the config requires one
+    pub prize_collection_allowlist: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: pNFT token record of `creator_prize_ata`, only used by pNFT transfers
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"token_record".as_ref(),
+            creator_prize_ata.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub creator_token_record: UncheckedAccount<'info>,
+
+    /// CHECK: pNFT token record of `prize_escrow`, only used by pNFT transfers
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"token_record".as_ref(),
+            prize_escrow.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub escrow_token_record: UncheckedAccount<'info>,
+
+    /// CHECK: only forwarded when the pNFT has a rule set, checked in `transfer_programmable_nft`
+    pub authorization_rules: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to Metaplex Token Auth Rules
+    #[account(address = AUTH_RULES_PROGRAM_ID)]
+    pub authorization_rules_program: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to Token Metadata, only invoked for pNFT prizes
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to the instructions sysvar, read by Token Metadata
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
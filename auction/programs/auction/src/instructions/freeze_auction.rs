@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::errors::{AuctionStateErrors, ConfigStateErrors};
+use crate::states::{Auction, AuctionConfig};
+
+#[event]
+pub struct AuctionFrozenUpdated {
+    pub auction_id: u32,
+    pub frozen: bool,
+    pub updated_at: i64,
+}
+
+// Halts (or resumes) a single auction without touching the program wide pause flags
+pub fn set_auction_frozen(ctx: Context<SetAuctionFrozen>, auction_id: u32, frozen: bool) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+
+    auction.frozen = frozen;
+
+    emit!(AuctionFrozenUpdated {
+        auction_id,
+        frozen,
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u32)]
+pub struct SetAuctionFrozen<'info> {
+    #[account(
+        seeds = [b"auction"],
+        bump = auction_config.config_bump,
+        constraint = auction_owner.key() == auction_config.auction_owner @ConfigStateErrors::InvalidAuctionOwner
+    )]
+    pub auction_config: Box<Account<'info, AuctionConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"auction", auction_id.to_le_bytes().as_ref()],
+        bump = auction.auction_bump,
+        constraint = auction.auction_id == auction_id @ AuctionStateErrors::InvalidAuctionId,
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+
+    pub auction_owner: Signer<'info>,
+}
@@ -0,0 +1,571 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::mpl_token_metadata;
+use anchor_spl::token_interface::{close_account, CloseAccount, Mint, TokenAccount, TokenInterface};
+
+use crate::constants::{BUY_LISTING_PAUSE, FEE_MANTISSA, LIST_NFT_PAUSE};
+use crate::errors::{
+    AuctionStateErrors, ConfigStateErrors, KeysMismatchErrors, MarketErrors, PermitErrors,
+    TransferErrors,
+};
+use crate::helpers::*;
+use crate::states::*;
+use crate::utils::*;
+
+#[event]
+pub struct NftListed {
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub collection: Option<Pubkey>, // verified collection of the NFT
+    pub price_mint: Option<Pubkey>,
+    pub price: u64,
+    pub listed_at: i64,
+}
+
+#[event]
+pub struct ListingSold {
+    pub seller: Pubkey,
+    pub buyer: Pubkey,
+    pub mint: Pubkey,
+    pub price: u64,
+    pub seller_amount: u64,
+    pub fee_amount: u64,
+    pub royalty_amount: u64,
+    pub sold_at: i64,
+}
+
+#[event]
+pub struct NftDelisted {
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub delisted_at: i64,
+}
+
+// List an NFT at a fixed price in SOL or `price_mint`, moving it into a token account the listing owns.
+// The NFT is verified like an auction prize, the admin co-signs as for `create_auction`
+pub fn list_nft<'info>(
+    ctx: Context<'_, '_, '_, 'info, ListNft<'info>>,
+    price: u64,
+    is_price_mint_sol: bool,
+) -> Result<()> {
+    let config = &ctx.accounts.auction_config;
+
+    require!(
+        !is_paused(config.pause_flags, LIST_NFT_PAUSE),
+        AuctionStateErrors::FunctionPaused
+    );
+    require_gt!(price, 0, MarketErrors::InvalidPrice);
+
+    require!(is_nft_mint(&ctx.accounts.nft_mint), AuctionStateErrors::InvalidNFT);
+    let collection = verify_nft_prize(
+        &ctx.accounts.nft_mint,
+        &ctx.accounts.nft_metadata,
+        &ctx.accounts.nft_edition,
+        ctx.accounts.nft_collection_allowlist.as_deref(),
+        config.require_verified_collection,
+    )?;
+    require!(
+        !is_programmable_nft(&ctx.accounts.nft_metadata)?,
+        MarketErrors::ProgrammableNftNotSupported
+    );
+    check_mint_policy(
+        &ctx.accounts.nft_mint,
+        config.allowed_mint_extensions,
+        &ctx.accounts.nft_mint_allowlist,
+    )?;
+
+    let price_mint = if is_price_mint_sol {
+        None
+    } else {
+        require!(ctx.accounts.price_mint.decimals != 0, KeysMismatchErrors::InvalidBidMint);
+        check_mint_policy(
+            &ctx.accounts.price_mint,
+            config.allowed_mint_extensions,
+            &ctx.accounts.price_mint_allowlist,
+        )?;
+        Some(ctx.accounts.price_mint.key())
+    };
+
+    let received = transfer_tokens(
+        &ctx.accounts.seller_nft_ata,
+        &ctx.accounts.listing_escrow,
+        &ctx.accounts.seller,
+        &ctx.accounts.nft_token_program,
+        &ctx.accounts.nft_mint,
+        ctx.remaining_accounts,
+        1,
+    )?;
+    require_eq!(received, 1, TransferErrors::InsufficientAmountReceived);
+
+    let now = Clock::get()?.unix_timestamp;
+    let listing = &mut ctx.accounts.listing;
+    listing.seller = ctx.accounts.seller.key();
+    listing.mint = ctx.accounts.nft_mint.key();
+    listing.price_mint = price_mint;
+    listing.price = price;
+    listing.commission_bps = config.commission_bps;
+    listing.listed_at = now;
+    listing.listing_bump = ctx.bumps.listing;
+
+    emit!(NftListed {
+        seller: listing.seller,
+        mint: listing.mint,
+        collection,
+        price_mint,
+        price,
+        listed_at: now,
+    });
+
+    Ok(())
+}
+
+// Buy a listed NFT at its price, never above `max_price`. The buyer pays the commission, royalties and
+// the seller directly, `remaining_accounts` starts with one royalty account per verified creator as
+// in `complete_auction`, followed by any transfer hook accounts
+pub fn buy_listing<'info>(
+    ctx: Context<'_, '_, 'info, 'info, BuyListing<'info>>,
+    max_price: u64,
+    permit: Permit,
+) -> Result<()> {
+    require!(
+        !is_paused(ctx.accounts.auction_config.pause_flags, BUY_LISTING_PAUSE),
+        AuctionStateErrors::FunctionPaused
+    );
+
+    let listing = &ctx.accounts.listing;
+    let buyer = &ctx.accounts.buyer;
+    let price = listing.price;
+    require_gte!(max_price, price, MarketErrors::PriceAboveMaximum);
+
+    // the admin signs off on market buyers the same way as on bidders, bound to this listing
+    let permit_signer = verify_permit(
+        &ctx.accounts.instructions_sysvar,
+        listing.key().as_ref(),
+        buyer.key(),
+        price,
+        &permit,
+    )?;
+    require_keys_eq!(
+        permit_signer,
+        ctx.accounts.auction_config.auction_admin,
+        PermitErrors::InvalidPermitSigner
+    );
+
+    let permit_nonce = &mut ctx.accounts.permit_nonce;
+    permit_nonce.user = buyer.key();
+    permit_nonce.nonce = permit.nonce;
+    permit_nonce.used_at = Clock::get()?.unix_timestamp;
+    permit_nonce.nonce_bump = ctx.bumps.permit_nonce;
+
+    let fee_amount = get_pct_amount(price, listing.commission_bps as u64, FEE_MANTISSA as u64)?;
+    let seller_claimable_amount = price
+        .checked_sub(fee_amount)
+        .ok_or(AuctionStateErrors::Overflow)?;
+
+    // royalties come out of the seller's side, the commission is taken on the full price
+    let royalties = creator_royalties(&ctx.accounts.nft_metadata, price, seller_claimable_amount)?;
+    let royalty_amount: u64 = royalties.iter().map(|(_, amount)| amount).sum();
+    let seller_amount = seller_claimable_amount
+        .checked_sub(royalty_amount)
+        .ok_or(AuctionStateErrors::Overflow)?;
+    require_gte!(
+        ctx.remaining_accounts.len(),
+        royalties.len(),
+        AuctionStateErrors::MissingRoyaltyAccounts
+    );
+    let (royalty_accounts, remaining_accounts) = ctx.remaining_accounts.split_at(royalties.len());
+
+    require_keys_eq!(
+        ctx.accounts.buyer_nft_ata.owner,
+        buyer.key(),
+        KeysMismatchErrors::InvalidPrizeAtaOwner
+    );
+
+    let seeds: &[&[u8]] = &[
+        b"auction",
+        b"listing",
+        listing.mint.as_ref(),
+        &[listing.listing_bump],
+    ];
+    let signer_seeds: &[&[&[u8]]] = &[seeds];
+
+    transfer_tokens_with_seeds(
+        &ctx.accounts.listing_escrow,
+        &ctx.accounts.buyer_nft_ata,
+        &listing.to_account_info(),
+        &ctx.accounts.nft_token_program,
+        &ctx.accounts.nft_mint,
+        remaining_accounts,
+        signer_seeds,
+        1,
+    )?;
+
+    close_account(CpiContext::new_with_signer(
+        ctx.accounts.nft_token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.listing_escrow.to_account_info(),
+            destination: ctx.accounts.seller.to_account_info(),
+            authority: listing.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    // Pay out straight from the buyer: depends on SOL or SPL pricing
+    match listing.price_mint {
+        None => {
+            transfer_sol(
+                buyer,
+                &ctx.accounts.auction_config.to_account_info(),
+                &ctx.accounts.system_program,
+                fee_amount,
+            )?;
+
+            for ((royalty_creator, amount), royalty_account) in royalties.iter().zip(royalty_accounts) {
+                require_keys_eq!(
+                    royalty_account.key(),
+                    *royalty_creator,
+                    KeysMismatchErrors::InvalidRoyaltyRecipient
+                );
+
+                transfer_sol(buyer, royalty_account, &ctx.accounts.system_program, *amount)?;
+            }
+
+            transfer_sol(
+                buyer,
+                &ctx.accounts.seller,
+                &ctx.accounts.system_program,
+                seller_amount,
+            )?;
+        }
+        Some(stored_mint) => {
+            require!(
+                ctx.accounts.price_mint.key() == stored_mint
+                    && ctx.accounts.buyer_price_ata.mint == stored_mint
+                    && ctx.accounts.price_fee_treasury_ata.mint == stored_mint
+                    && ctx.accounts.seller_price_ata.mint == stored_mint,
+                KeysMismatchErrors::InvalidBidMint
+            );
+            require!(
+                ctx.accounts.buyer_price_ata.owner == buyer.key()
+                    && ctx.accounts.price_fee_treasury_ata.owner == ctx.accounts.auction_config.key()
+                    && ctx.accounts.seller_price_ata.owner == ctx.accounts.seller.key(),
+                KeysMismatchErrors::InvalidBidAtaOwner
+            );
+
+            transfer_tokens(
+                &ctx.accounts.buyer_price_ata,
+                &ctx.accounts.price_fee_treasury_ata,
+                buyer,
+                &ctx.accounts.price_token_program,
+                &ctx.accounts.price_mint,
+                remaining_accounts,
+                fee_amount,
+            )?;
+
+            for ((royalty_creator, amount), royalty_account) in royalties.iter().zip(royalty_accounts) {
+                let royalty_ata = InterfaceAccount::<TokenAccount>::try_from(royalty_account)?;
+                require!(
+                    royalty_ata.owner == *royalty_creator && royalty_ata.mint == stored_mint,
+                    KeysMismatchErrors::InvalidRoyaltyRecipient
+                );
+
+                if *amount > 0 {
+                    transfer_tokens(
+                        &ctx.accounts.buyer_price_ata,
+                        &royalty_ata,
+                        buyer,
+                        &ctx.accounts.price_token_program,
+                        &ctx.accounts.price_mint,
+                        remaining_accounts,
+                        *amount,
+                    )?;
+                }
+            }
+
+            transfer_tokens(
+                &ctx.accounts.buyer_price_ata,
+                &ctx.accounts.seller_price_ata,
+                buyer,
+                &ctx.accounts.price_token_program,
+                &ctx.accounts.price_mint,
+                remaining_accounts,
+                seller_amount,
+            )?;
+        }
+    }
+
+    emit!(ListingSold {
+        seller: listing.seller,
+        buyer: buyer.key(),
+        mint: listing.mint,
+        price,
+        seller_amount,
+        fee_amount,
+        royalty_amount,
+        sold_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Take a listed NFT back, never paused so a seller can always leave
+pub fn delist_nft<'info>(ctx: Context<'_, '_, '_, 'info, DelistNft<'info>>) -> Result<()> {
+    let listing = &ctx.accounts.listing;
+
+    let seeds: &[&[u8]] = &[
+        b"auction",
+        b"listing",
+        listing.mint.as_ref(),
+        &[listing.listing_bump],
+    ];
+    let signer_seeds: &[&[&[u8]]] = &[seeds];
+
+    transfer_tokens_with_seeds(
+        &ctx.accounts.listing_escrow,
+        &ctx.accounts.seller_nft_ata,
+        &listing.to_account_info(),
+        &ctx.accounts.nft_token_program,
+        &ctx.accounts.nft_mint,
+        ctx.remaining_accounts,
+        signer_seeds,
+        1,
+    )?;
+
+    close_account(CpiContext::new_with_signer(
+        ctx.accounts.nft_token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.listing_escrow.to_account_info(),
+            destination: ctx.accounts.seller.to_account_info(),
+            authority: listing.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    emit!(NftDelisted {
+        seller: listing.seller,
+        mint: listing.mint,
+        delisted_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ListNft<'info> {
+    #[account(
+        seeds = [b"auction"],
+        bump = auction_config.config_bump,
+        constraint = auction_config.auction_admin == auction_admin.key() @ ConfigStateErrors::InvalidAuctionAdmin
+    )]
+    pub auction_config: Box<Account<'info, AuctionConfig>>,
+
+    #[account(
+        init,
+        payer = seller,
+        space = 8 + Listing::INIT_SPACE,
+        seeds = [b"auction".as_ref(), b"listing".as_ref(), nft_mint.key().as_ref()],
+        bump
+    )]
+    pub listing: Box<Account<'info, Listing>>,
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    pub auction_admin: Signer<'info>,
+
+    pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub price_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: only has to exist when the owner allowlisted the NFT mint, see `check_mint_policy`
+    #[account(
+        seeds = [b"auction".as_ref(), b"mint_allowlist".as_ref(), nft_mint.key().as_ref()],
+        bump
+    )]
+    pub nft_mint_allowlist: UncheckedAccount<'info>,
+
+    /// CHECK: only has to exist when the owner allowlisted the price mint, see `check_mint_policy`
+    #[account(
+        seeds = [b"auction".as_ref(), b"mint_allowlist".as_ref(), price_mint.key().as_ref()],
+        bump
+    )]
+    pub price_mint_allowlist: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = nft_mint,
+        associated_token::authority = seller,
+        associated_token::token_program = nft_token_program
+    )]
+    pub seller_nft_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = seller,
+        associated_token::mint = nft_mint,
+        associated_token::authority = listing,
+        associated_token::token_program = nft_token_program
+    )]
+    pub listing_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Token Metadata PDA of the NFT, verified in `verify_nft_prize`
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            nft_mint.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub nft_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: master edition PDA of the NFT, verified in `verify_nft_prize`
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            nft_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub nft_edition: UncheckedAccount<'info>,
+
+    /// CHECK: `CollectionAllowlist` entry of the NFT's verified collection, only read when the config requires one
+    pub nft_collection_allowlist: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: address is pinned to Token Metadata, only used to derive the metadata PDAs
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    pub nft_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(max_price: u64, permit: Permit)]
+pub struct BuyListing<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction"],
+        bump = auction_config.config_bump,
+    )]
+    pub auction_config: Box<Account<'info, AuctionConfig>>,
+
+    #[account(
+        mut,
+        close = seller,
+        seeds = [b"auction".as_ref(), b"listing".as_ref(), nft_mint.key().as_ref()],
+        bump = listing.listing_bump,
+    )]
+    pub listing: Box<Account<'info, Listing>>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = buyer,
+        space = 8 + PermitNonce::INIT_SPACE,
+        seeds = [
+            b"auction".as_ref(),
+            b"permit".as_ref(),
+            buyer.key().as_ref(),
+            permit.nonce.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub permit_nonce: Box<Account<'info, PermitNonce>>,
+
+    #[account(
+        mut,
+        constraint = listing.seller == seller.key() @ MarketErrors::InvalidSeller
+    )]
+    pub seller: AccountInfo<'info>,
+
+    pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub price_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = listing,
+        token::token_program = nft_token_program
+    )]
+    pub listing_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)] // check inside
+    pub buyer_nft_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // For SPL prices (if used)
+    #[account(mut)]
+    pub buyer_price_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // owner by config account
+    #[account(mut)]
+    pub price_fee_treasury_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub seller_price_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Token Metadata PDA of the NFT, read for royalties
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            nft_mint.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub nft_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to Token Metadata, only used to derive the metadata PDA
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to the instructions sysvar, read in `verify_permit`
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub nft_token_program: Interface<'info, TokenInterface>,
+    pub price_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DelistNft<'info> {
+    #[account(
+        mut,
+        close = seller,
+        seeds = [b"auction".as_ref(), b"listing".as_ref(), nft_mint.key().as_ref()],
+        bump = listing.listing_bump,
+        constraint = listing.seller == seller.key() @ MarketErrors::InvalidSeller,
+    )]
+    pub listing: Box<Account<'info, Listing>>,
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = listing,
+        token::token_program = nft_token_program
+    )]
+    pub listing_escrow: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = seller,
+        token::token_program = nft_token_program
+    )]
+    pub seller_nft_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub nft_token_program: Interface<'info, TokenInterface>,
+}
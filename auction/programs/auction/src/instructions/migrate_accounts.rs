@@ -0,0 +1,476 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::constants::{AUCTION_CONFIG_VERSION, AUCTION_VERSION, MINIMUM_CONFIG_CHANGE_DELAY};
+use crate::errors::{
+    AuctionStateErrors, ConfigStateErrors, KeysMismatchErrors, MigrationErrors, TransferErrors,
+};
+use crate::helpers::{
+    close_token_account_with_seeds, create_pda_account, resize_account, transfer_fee_amount,
+    transfer_tokens_with_seeds,
+};
+use crate::states::*;
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub version: u8,
+    pub migrated_at: i64,
+}
+
+fn check_owner_and_discriminator(account: &AccountInfo, discriminator: &[u8]) -> Result<()> {
+    require_keys_eq!(
+        *account.owner,
+        crate::ID,
+        MigrationErrors::InvalidAccountOwner
+    );
+
+    let data = account.try_borrow_data()?;
+    require!(
+        data.len() >= 8 && &data[..8] == discriminator,
+        MigrationErrors::InvalidDiscriminator
+    );
+
+    Ok(())
+}
+
+// Decode a legacy account body once its owner, discriminator and legacy size are confirmed
+fn read_legacy<T: AnchorDeserialize>(
+    account: &AccountInfo,
+    discriminator: &[u8],
+    legacy_len: usize,
+) -> Result<T> {
+    check_owner_and_discriminator(account, discriminator)?;
+
+    let data = account.try_borrow_data()?;
+    // anything other than the legacy size is already versioned
+    require_eq!(data.len(), legacy_len, MigrationErrors::AlreadyMigrated);
+
+    T::deserialize(&mut &data[8..]).map_err(|_| error!(ErrorCode::AccountDidNotDeserialize))
+}
+
+// Decode a versioned account body that is still on layout `version`
+fn read_versioned<T: AnchorDeserialize>(
+    account: &AccountInfo,
+    discriminator: &[u8],
+    version: u8,
+) -> Result<T> {
+    check_owner_and_discriminator(account, discriminator)?;
+
+    let data = account.try_borrow_data()?;
+    require!(
+        data.get(8) == Some(&version),
+        MigrationErrors::AlreadyMigrated
+    );
+
+    T::deserialize(&mut &data[8..]).map_err(|_| error!(ErrorCode::AccountDidNotDeserialize))
+}
+
+fn write_migrated<T: AccountSerialize>(account: &AccountInfo, migrated: &T) -> Result<()> {
+    let mut data = account.try_borrow_mut_data()?;
+    migrated.try_serialize(&mut &mut data[..])
+}
+
+pub fn migrate_auction_config(ctx: Context<MigrateAuctionConfig>) -> Result<()> {
+    let config_ai = ctx.accounts.auction_config.to_account_info();
+
+    // version 0 is only recognisable by its size, later versions carry the `version` byte
+    let legacy: AuctionConfigV1 = if config_ai.data_len() == 8 + AuctionConfigV0::INIT_SPACE {
+        let v0: AuctionConfigV0 = read_legacy(
+            &config_ai,
+            AuctionConfig::DISCRIMINATOR,
+            8 + AuctionConfigV0::INIT_SPACE,
+        )?;
+
+        AuctionConfigV1 {
+            version: 1,
+            auction_owner: v0.auction_owner,
+            auction_admin: v0.auction_admin,
+            creation_fee_lamports: v0.creation_fee_lamports,
+            commission_bps: v0.commission_bps,
+            minimum_auction_period: v0.minimum_auction_period,
+            maximum_auction_period: v0.maximum_auction_period,
+            minimum_time_extension: v0.minimum_time_extension,
+            maximum_time_extension: v0.maximum_time_extension,
+            auction_count: v0.auction_count,
+            pause_flags: v0.pause_flags,
+            config_bump: v0.config_bump,
+            pending_owner: None,
+            config_change_delay: MINIMUM_CONFIG_CHANGE_DELAY,
+            pending_config_data: None,
+            reserved: [0u8; 52],
+        }
+    } else {
+        read_versioned(&config_ai, AuctionConfig::DISCRIMINATOR, 1)?
+    };
+
+    let migrated = AuctionConfig {
+        version: AUCTION_CONFIG_VERSION,
+        auction_owner: legacy.auction_owner,
+        auction_admin: legacy.auction_admin,
+        creation_fee_lamports: legacy.creation_fee_lamports,
+        commission_bps: legacy.commission_bps,
+        minimum_auction_period: legacy.minimum_auction_period,
+        maximum_auction_period: legacy.maximum_auction_period,
+        minimum_time_extension: legacy.minimum_time_extension,
+        maximum_time_extension: legacy.maximum_time_extension,
+        auction_count: legacy.auction_count,
+        // the old eight bits keep their indexes, the new ones start unpaused
+        pause_flags: legacy.pause_flags as u64,
+        config_bump: legacy.config_bump,
+        pending_owner: legacy.pending_owner,
+        config_change_delay: legacy.config_change_delay,
+        pending_config_data: legacy.pending_config_data,
+        allowed_mint_extensions: 0,
+        require_verified_collection: false,
+        buy_now_cutoff_bps: 0,
+        sealed_reveal_period: 0,
+        sealed_penalty_bps: 0,
+        maximum_extension_window: 0,
+        maximum_total_extension: 0,
+        maximum_extension_count: 0,
+        keeper_fee_bps: 0,
+        reserved: [0u8; 23],
+    };
+
+    require_keys_eq!(
+        migrated.auction_owner,
+        ctx.accounts.auction_owner.key(),
+        ConfigStateErrors::InvalidAuctionOwner
+    );
+
+    resize_account(
+        &config_ai,
+        &ctx.accounts.auction_owner,
+        &ctx.accounts.system_program,
+        8 + AuctionConfig::INIT_SPACE,
+    )?;
+    write_migrated(&config_ai, &migrated)?;
+
+    emit!(AccountMigrated {
+        account: config_ai.key(),
+        version: AUCTION_CONFIG_VERSION,
+        migrated_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Permissionless: the new layout only adds defaults, the payer just covers the extra rent.
+// The config has to be migrated first, its current commission becomes a version 0 auction's snapshot.
+// A version 0 auction holding a bid also takes the accounts `move_legacy_bid` needs
+pub fn migrate_auction<'info>(
+    ctx: Context<'_, '_, 'info, 'info, MigrateAuction<'info>>,
+    _auction_id: u32,
+) -> Result<()> {
+    let auction_ai = ctx.accounts.auction.to_account_info();
+    let is_legacy = auction_ai.data_len() == 8 + AuctionV0::INIT_SPACE;
+
+    // version 0 is only recognisable by its size, later versions carry the `version` byte
+    let migrated = if is_legacy {
+        let legacy: AuctionV0 = read_legacy(
+            &auction_ai,
+            Auction::DISCRIMINATOR,
+            8 + AuctionV0::INIT_SPACE,
+        )?;
+
+        Auction {
+            version: AUCTION_VERSION,
+            auction_id: legacy.auction_id,
+            creator: legacy.creator,
+            prize_mint: legacy.prize_mint,
+            start_time: legacy.start_time,
+            end_time: legacy.end_time,
+            bid_mint: legacy.bid_mint,
+            base_bid: legacy.base_bid,
+            min_increment: legacy.min_increment,
+            time_extension: legacy.time_extension,
+            highest_bid_amount: legacy.highest_bid_amount,
+            highest_bidder: legacy.highest_bidder,
+            has_any_bid: legacy.has_any_bid,
+            status: legacy.status,
+            auction_bump: legacy.auction_bump,
+            // legacy auctions charged whatever the config held, freeze that value now
+            commission_bps: ctx.accounts.auction_config.commission_bps,
+            frozen: false,
+            reserve_price: 0,
+            reserve_commitment: [0u8; 32],
+            buy_now_price: 0,
+            sealed: false,
+            reveal_period: 0,
+            second_bid_amount: 0,
+            sealed_penalty_bps: 0,
+            sealed_commit_count: 0,
+            dutch_start_price: 0,
+            dutch_floor_price: 0,
+            dutch_decay_interval: 0,
+            lot_amount: 1, // only NFTs could be auctioned before fungible lots
+            fungible_prize: false,
+            bundle_size: 0,
+            // live auctions keep extending the way they always did
+            extension_window: 0,
+            max_end_time: i64::MAX,
+            max_extensions: u16::MAX,
+            extension_count: 0,
+            highest_bid_max: legacy.highest_bid_amount,
+            min_increment_bps: 0,
+            reserved: [0u8; 128],
+        }
+    } else {
+        let legacy: AuctionV1 = read_versioned(&auction_ai, Auction::DISCRIMINATOR, 1)?;
+
+        Auction {
+            version: AUCTION_VERSION,
+            auction_id: legacy.auction_id,
+            creator: legacy.creator,
+            prize_mint: legacy.prize_mint,
+            start_time: legacy.start_time,
+            end_time: legacy.end_time,
+            bid_mint: legacy.bid_mint,
+            base_bid: legacy.base_bid,
+            min_increment: legacy.min_increment,
+            time_extension: legacy.time_extension,
+            highest_bid_amount: legacy.highest_bid_amount,
+            highest_bidder: legacy.highest_bidder,
+            has_any_bid: legacy.has_any_bid,
+            status: legacy.status,
+            auction_bump: legacy.auction_bump,
+            commission_bps: legacy.commission_bps,
+            frozen: legacy.frozen,
+            reserve_price: legacy.reserve_price,
+            reserve_commitment: legacy.reserve_commitment,
+            buy_now_price: legacy.buy_now_price,
+            sealed: legacy.sealed,
+            reveal_period: legacy.reveal_period,
+            second_bid_amount: legacy.second_bid_amount,
+            sealed_penalty_bps: legacy.sealed_penalty_bps,
+            sealed_commit_count: legacy.sealed_commit_count,
+            dutch_start_price: legacy.dutch_start_price,
+            dutch_floor_price: legacy.dutch_floor_price,
+            dutch_decay_interval: legacy.dutch_decay_interval,
+            // a zero lot predates fungible lots, and a zero cap predates anti-sniping limits
+            lot_amount: legacy.lot_amount.max(1),
+            fungible_prize: legacy.fungible_prize,
+            bundle_size: legacy.bundle_size,
+            extension_window: legacy.extension_window,
+            max_end_time: if legacy.max_end_time == 0 {
+                i64::MAX
+            } else {
+                legacy.max_end_time
+            },
+            max_extensions: if legacy.max_end_time == 0 {
+                u16::MAX
+            } else {
+                legacy.max_extensions
+            },
+            extension_count: legacy.extension_count,
+            highest_bid_max: legacy.highest_bid_max,
+            min_increment_bps: legacy.min_increment_bps,
+            reserved: [0u8; 128],
+        }
+    };
+
+    if is_legacy && migrated.has_any_bid {
+        move_legacy_bid(&ctx, &auction_ai, &migrated)?;
+    }
+
+    resize_account(
+        &auction_ai,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        8 + Auction::INIT_SPACE,
+    )?;
+    write_migrated(&auction_ai, &migrated)?;
+
+    emit!(AccountMigrated {
+        account: auction_ai.key(),
+        version: AUCTION_VERSION,
+        migrated_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Version 0 kept the highest bid on the auction itself, as lamports or in a token account it owns.
+// `complete_auction` and `withdraw_outbid` look for it in the bidder's `BidEscrow`, so it moves there
+fn move_legacy_bid<'info>(
+    ctx: &Context<'_, '_, 'info, 'info, MigrateAuction<'info>>,
+    auction_ai: &AccountInfo<'info>,
+    auction: &Auction,
+) -> Result<()> {
+    let bid_escrow = ctx
+        .accounts
+        .bid_escrow
+        .as_ref()
+        .ok_or(MigrationErrors::MissingLegacyBidAccounts)?;
+
+    let auction_id_bytes = auction.auction_id.to_le_bytes();
+    let (escrow_key, escrow_bump) = Pubkey::find_program_address(
+        &[b"auction", &auction_id_bytes, b"bid", auction.highest_bidder.as_ref()],
+        &crate::ID,
+    );
+    require_keys_eq!(bid_escrow.key(), escrow_key, KeysMismatchErrors::InvalidBidEscrow);
+
+    let escrow_seeds: &[&[u8]] = &[
+        b"auction",
+        &auction_id_bytes,
+        b"bid",
+        auction.highest_bidder.as_ref(),
+        &[escrow_bump],
+    ];
+    create_pda_account(
+        bid_escrow,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        8 + BidEscrow::INIT_SPACE,
+        &[escrow_seeds],
+    )?;
+
+    let amount = match auction.bid_mint {
+        None => {
+            let auction_lamports = auction_ai
+                .lamports()
+                .checked_sub(auction.highest_bid_amount)
+                .ok_or(AuctionStateErrors::Overflow)?;
+            **auction_ai.try_borrow_mut_lamports()? = auction_lamports;
+            **bid_escrow.try_borrow_mut_lamports()? += auction.highest_bid_amount;
+
+            auction.highest_bid_amount
+        }
+        Some(stored_mint) => {
+            let (
+                Some(bid_mint),
+                Some(legacy_vault),
+                Some(bid_escrow_ata),
+                Some(bid_token_program),
+                Some(creator),
+            ) = (
+                ctx.accounts.bid_mint.as_ref(),
+                ctx.accounts.legacy_bid_vault.as_ref(),
+                ctx.accounts.bid_escrow_ata.as_ref(),
+                ctx.accounts.bid_token_program.as_ref(),
+                ctx.accounts.creator.as_ref(),
+            ) else {
+                return err!(MigrationErrors::MissingLegacyBidAccounts);
+            };
+
+            require!(
+                bid_mint.key() == stored_mint
+                    && legacy_vault.mint == stored_mint
+                    && bid_escrow_ata.mint == stored_mint,
+                KeysMismatchErrors::InvalidBidMint
+            );
+            require_keys_eq!(
+                legacy_vault.owner,
+                auction_ai.key(),
+                KeysMismatchErrors::InvalidBidEscrowOwner
+            );
+            require_keys_eq!(
+                bid_escrow_ata.owner,
+                escrow_key,
+                KeysMismatchErrors::InvalidBidEscrowOwner
+            );
+            require_keys_eq!(creator.key(), auction.creator, AuctionStateErrors::InvalidCreator);
+
+            let auction_seeds: &[&[u8]] = &[b"auction", &auction_id_bytes, &[auction.auction_bump]];
+
+            // the vault only ever held the live bid, it closes to the creator as a settled auction's did
+            let held = legacy_vault.amount;
+            transfer_tokens_with_seeds(
+                legacy_vault,
+                bid_escrow_ata,
+                auction_ai,
+                bid_token_program,
+                bid_mint,
+                ctx.remaining_accounts,
+                &[auction_seeds],
+                held,
+            )?;
+            close_token_account_with_seeds(
+                legacy_vault,
+                bid_mint,
+                creator,
+                auction_ai,
+                bid_token_program,
+                &[auction_seeds],
+            )?;
+
+            held.checked_sub(transfer_fee_amount(bid_mint, held)?)
+                .ok_or(TransferErrors::TransferFeeCalculationFailed)?
+        }
+    };
+
+    write_migrated(
+        bid_escrow,
+        &BidEscrow {
+            auction_id: auction.auction_id,
+            bidder: auction.highest_bidder,
+            bid_mint: auction.bid_mint,
+            amount,
+            escrow_bump,
+            sealed_commitment: [0u8; 32],
+            revealed: false,
+            penalty_bps: 0,
+        },
+    )
+}
+
+#[derive(Accounts)]
+pub struct MigrateAuctionConfig<'info> {
+    /// CHECK: still on the legacy layout, owner and discriminator are checked in the handler
+    #[account(
+        mut,
+        seeds = [b"auction"],
+        bump
+    )]
+    pub auction_config: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub auction_owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u32)]
+pub struct MigrateAuction<'info> {
+    /// CHECK: still on the legacy layout, owner and discriminator are checked in the handler
+    #[account(
+        mut,
+        seeds = [b"auction", auction_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub auction: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"auction"],
+        bump = auction_config.config_bump,
+    )]
+    pub auction_config: Box<Account<'info, AuctionConfig>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: the legacy highest bidder's escrow address, checked and opened in `move_legacy_bid`
+    #[account(mut)]
+    pub bid_escrow: Option<UncheckedAccount<'info>>,
+
+    // SPL bids only, the token account the version 0 auction held the bid in
+    #[account(mut)]
+    pub legacy_bid_vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    // SPL bids only, owned by `bid_escrow`
+    #[account(mut)]
+    pub bid_escrow_ata: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub bid_mint: Option<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: SPL bids only, matched against the auction's creator, gets the emptied vault's rent
+    #[account(mut)]
+    pub creator: Option<UncheckedAccount<'info>>,
+
+    pub bid_token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -1,19 +1,39 @@
+pub mod allowlist;
+pub mod buy_dutch;
+pub mod buy_now;
 pub mod cancel_auction;
 pub mod complete_auction;
 pub mod create_auction;
+pub mod freeze_auction;
+pub mod listing;
+pub mod migrate_accounts;
+pub mod offer;
 pub mod place_bid;
 pub mod process_auction_config;
+pub mod relist_auction;
+pub mod sealed_bid;
 pub mod start_auction;
 pub mod update_auction;
+pub mod withdraw_outbid;
 pub mod withdraw_sol_fees;
 pub mod withdraw_spl_fees;
 
+pub use allowlist::*;
+pub use buy_dutch::*;
+pub use buy_now::*;
 pub use cancel_auction::*;
 pub use complete_auction::*;
 pub use create_auction::*;
+pub use freeze_auction::*;
+pub use listing::*;
+pub use migrate_accounts::*;
+pub use offer::*;
 pub use place_bid::*;
 pub use process_auction_config::*;
+pub use relist_auction::*;
+pub use sealed_bid::*;
 pub use start_auction::*;
 pub use update_auction::*;
+pub use withdraw_outbid::*;
 pub use withdraw_sol_fees::*;
 pub use withdraw_spl_fees::*;
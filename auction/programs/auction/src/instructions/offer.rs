@@ -0,0 +1,605 @@
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::mpl_token_metadata;
+use anchor_spl::token_interface::{close_account, CloseAccount, Mint, TokenAccount, TokenInterface};
+
+use crate::constants::{ACCEPT_OFFER_PAUSE, FEE_MANTISSA, MAKE_OFFER_PAUSE};
+use crate::errors::{
+    AuctionStateErrors, ConfigStateErrors, KeysMismatchErrors, MarketErrors, PermitErrors,
+};
+use crate::helpers::*;
+use crate::states::*;
+use crate::utils::*;
+
+#[event]
+pub struct OfferMade {
+    pub buyer: Pubkey,
+    pub collection: Pubkey,
+    pub price_mint: Option<Pubkey>,
+    pub amount: u64,
+    pub expiry: i64,
+    pub created_at: i64,
+}
+
+#[event]
+pub struct OfferAccepted {
+    pub buyer: Pubkey,
+    pub seller: Pubkey,
+    pub collection: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub seller_amount: u64,
+    pub fee_amount: u64,
+    pub royalty_amount: u64,
+    pub accepted_at: i64,
+}
+
+#[event]
+pub struct OfferCancelled {
+    pub buyer: Pubkey,
+    pub collection: Pubkey,
+    pub amount: u64, // refunded to the buyer
+    pub cancelled_at: i64,
+}
+
+// Offer `amount` for any NFT of `collection`, escrowed until an NFT is sold into it or the buyer cancels.
+// One standing offer per buyer and collection, the admin co-signs as for `create_auction`
+pub fn make_offer<'info>(
+    ctx: Context<'_, '_, '_, 'info, MakeOffer<'info>>,
+    collection: Pubkey,
+    amount: u64,
+    expiry: i64, // 0 => never expires
+    is_price_mint_sol: bool,
+) -> Result<()> {
+    let config = &ctx.accounts.auction_config;
+    let buyer = &ctx.accounts.buyer;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        !is_paused(config.pause_flags, MAKE_OFFER_PAUSE),
+        AuctionStateErrors::FunctionPaused
+    );
+    require_gt!(amount, 0, MarketErrors::InvalidPrice);
+    require!(expiry == 0 || expiry > now, MarketErrors::OfferExpired);
+
+    let offer = &mut ctx.accounts.offer;
+
+    // Collect the offer into its escrow (SOL or SPL)
+    let (price_mint, escrowed) = if is_price_mint_sol {
+        // SOL — lamports sit on the offer PDA itself
+        transfer_sol(buyer, &offer.to_account_info(), &ctx.accounts.system_program, amount)?;
+
+        (None, amount)
+    } else {
+        let price_mint = ctx.accounts.price_mint.key();
+        require!(ctx.accounts.price_mint.decimals != 0, KeysMismatchErrors::InvalidBidMint);
+        check_mint_policy(
+            &ctx.accounts.price_mint,
+            config.allowed_mint_extensions,
+            &ctx.accounts.price_mint_allowlist,
+        )?;
+        require!(
+            ctx.accounts.offer_escrow_ata.mint == price_mint
+                && ctx.accounts.buyer_price_ata.mint == price_mint,
+            KeysMismatchErrors::InvalidBidMint
+        );
+        require_keys_eq!(
+            ctx.accounts.offer_escrow_ata.owner,
+            offer.key(),
+            KeysMismatchErrors::InvalidBidEscrowOwner
+        );
+        require_keys_eq!(
+            ctx.accounts.buyer_price_ata.owner,
+            buyer.key(),
+            KeysMismatchErrors::InvalidBidAtaOwner
+        );
+
+        // offers compete on what the escrow holds, a Token-2022 transfer fee never counts towards one
+        let received = transfer_tokens(
+            &ctx.accounts.buyer_price_ata,
+            &ctx.accounts.offer_escrow_ata,
+            buyer,
+            &ctx.accounts.price_token_program,
+            &ctx.accounts.price_mint,
+            ctx.remaining_accounts,
+            amount,
+        )?;
+        require_gt!(received, 0, MarketErrors::InvalidPrice);
+
+        (Some(price_mint), received)
+    };
+
+    offer.buyer = buyer.key();
+    offer.collection = collection;
+    offer.price_mint = price_mint;
+    offer.amount = escrowed;
+    offer.commission_bps = config.commission_bps;
+    offer.expiry = expiry;
+    offer.created_at = now;
+    offer.offer_bump = ctx.bumps.offer;
+
+    emit!(OfferMade {
+        buyer: buyer.key(),
+        collection,
+        price_mint,
+        amount: escrowed,
+        expiry,
+        created_at: now,
+    });
+
+    Ok(())
+}
+
+// Sell an NFT of the offer's verified collection into it for no less than `min_amount`. The escrow pays
+// the commission, royalties and the seller, `remaining_accounts` is laid out as for `buy_listing`
+pub fn accept_offer<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AcceptOffer<'info>>,
+    min_amount: u64,
+    permit: Permit,
+) -> Result<()> {
+    let config = &ctx.accounts.auction_config;
+    let offer = &ctx.accounts.offer;
+    let seller = &ctx.accounts.seller;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        !is_paused(config.pause_flags, ACCEPT_OFFER_PAUSE),
+        AuctionStateErrors::FunctionPaused
+    );
+    require!(offer.expiry == 0 || now <= offer.expiry, MarketErrors::OfferExpired);
+    require_gte!(offer.amount, min_amount, MarketErrors::OfferBelowMinimum);
+
+    // the seller is the one trading here, the admin signs off on them for this offer
+    let permit_signer = verify_permit(
+        &ctx.accounts.instructions_sysvar,
+        offer.key().as_ref(),
+        seller.key(),
+        offer.amount,
+        &permit,
+    )?;
+    require_keys_eq!(
+        permit_signer,
+        config.auction_admin,
+        PermitErrors::InvalidPermitSigner
+    );
+
+    let permit_nonce = &mut ctx.accounts.permit_nonce;
+    permit_nonce.user = seller.key();
+    permit_nonce.nonce = permit.nonce;
+    permit_nonce.used_at = now;
+    permit_nonce.nonce_bump = ctx.bumps.permit_nonce;
+
+    require!(is_nft_mint(&ctx.accounts.nft_mint), AuctionStateErrors::InvalidNFT);
+    let collection = verify_nft_prize(
+        &ctx.accounts.nft_mint,
+        &ctx.accounts.nft_metadata,
+        &ctx.accounts.nft_edition,
+        ctx.accounts.nft_collection_allowlist.as_deref(),
+        config.require_verified_collection,
+    )?;
+    require!(
+        collection == Some(offer.collection),
+        MarketErrors::CollectionMismatch
+    );
+    require!(
+        !is_programmable_nft(&ctx.accounts.nft_metadata)?,
+        MarketErrors::ProgrammableNftNotSupported
+    );
+
+    let amount = offer.amount;
+    let fee_amount = get_pct_amount(amount, offer.commission_bps as u64, FEE_MANTISSA as u64)?;
+    let seller_claimable_amount = amount
+        .checked_sub(fee_amount)
+        .ok_or(AuctionStateErrors::Overflow)?;
+
+    // royalties come out of the seller's side, the commission is taken on the full price
+    let royalties = creator_royalties(&ctx.accounts.nft_metadata, amount, seller_claimable_amount)?;
+    let royalty_amount: u64 = royalties.iter().map(|(_, amount)| amount).sum();
+    let seller_amount = seller_claimable_amount
+        .checked_sub(royalty_amount)
+        .ok_or(AuctionStateErrors::Overflow)?;
+    require_gte!(
+        ctx.remaining_accounts.len(),
+        royalties.len(),
+        AuctionStateErrors::MissingRoyaltyAccounts
+    );
+    let (royalty_accounts, remaining_accounts) = ctx.remaining_accounts.split_at(royalties.len());
+
+    transfer_tokens(
+        &ctx.accounts.seller_nft_ata,
+        &ctx.accounts.buyer_nft_ata,
+        seller,
+        &ctx.accounts.nft_token_program,
+        &ctx.accounts.nft_mint,
+        remaining_accounts,
+        1,
+    )?;
+
+    // Pay out of the offer escrow: depends on SOL or SPL pricing
+    match offer.price_mint {
+        None => {
+            // the rent left on the offer goes back to the buyer when it is closed
+            **offer.to_account_info().try_borrow_mut_lamports()? -= fee_amount;
+            **ctx.accounts.auction_config.to_account_info().try_borrow_mut_lamports()? += fee_amount;
+
+            for ((royalty_creator, amount), royalty_account) in royalties.iter().zip(royalty_accounts) {
+                require_keys_eq!(
+                    royalty_account.key(),
+                    *royalty_creator,
+                    KeysMismatchErrors::InvalidRoyaltyRecipient
+                );
+
+                **offer.to_account_info().try_borrow_mut_lamports()? -= *amount;
+                **royalty_account.try_borrow_mut_lamports()? += *amount;
+            }
+
+            **offer.to_account_info().try_borrow_mut_lamports()? -= seller_amount;
+            **seller.to_account_info().try_borrow_mut_lamports()? += seller_amount;
+        }
+        Some(stored_mint) => {
+            require!(
+                ctx.accounts.price_mint.key() == stored_mint
+                    && ctx.accounts.offer_escrow_ata.mint == stored_mint
+                    && ctx.accounts.price_fee_treasury_ata.mint == stored_mint
+                    && ctx.accounts.seller_price_ata.mint == stored_mint,
+                KeysMismatchErrors::InvalidBidMint
+            );
+            require_keys_eq!(
+                ctx.accounts.offer_escrow_ata.owner,
+                offer.key(),
+                KeysMismatchErrors::InvalidBidEscrowOwner
+            );
+            require!(
+                ctx.accounts.price_fee_treasury_ata.owner == config.key()
+                    && ctx.accounts.seller_price_ata.owner == seller.key(),
+                KeysMismatchErrors::InvalidBidAtaOwner
+            );
+
+            let buyer_key = ctx.accounts.buyer.key();
+            let seeds: &[&[u8]] = &[
+                b"auction",
+                b"offer",
+                offer.collection.as_ref(),
+                buyer_key.as_ref(),
+                &[offer.offer_bump],
+            ];
+            let signer_seeds: &[&[&[u8]]] = &[seeds];
+
+            transfer_tokens_with_seeds(
+                &ctx.accounts.offer_escrow_ata,
+                &ctx.accounts.price_fee_treasury_ata,
+                &offer.to_account_info(),
+                &ctx.accounts.price_token_program,
+                &ctx.accounts.price_mint,
+                remaining_accounts,
+                signer_seeds,
+                fee_amount,
+            )?;
+
+            for ((royalty_creator, amount), royalty_account) in royalties.iter().zip(royalty_accounts) {
+                let royalty_ata = InterfaceAccount::<TokenAccount>::try_from(royalty_account)?;
+                require!(
+                    royalty_ata.owner == *royalty_creator && royalty_ata.mint == stored_mint,
+                    KeysMismatchErrors::InvalidRoyaltyRecipient
+                );
+
+                if *amount > 0 {
+                    transfer_tokens_with_seeds(
+                        &ctx.accounts.offer_escrow_ata,
+                        &royalty_ata,
+                        &offer.to_account_info(),
+                        &ctx.accounts.price_token_program,
+                        &ctx.accounts.price_mint,
+                        remaining_accounts,
+                        signer_seeds,
+                        *amount,
+                    )?;
+                }
+            }
+
+            // the seller also takes any dust sent to the escrow on top of the offer, so it can be closed
+            ctx.accounts.offer_escrow_ata.reload()?;
+            let escrow_balance = ctx.accounts.offer_escrow_ata.amount;
+            transfer_tokens_with_seeds(
+                &ctx.accounts.offer_escrow_ata,
+                &ctx.accounts.seller_price_ata,
+                &offer.to_account_info(),
+                &ctx.accounts.price_token_program,
+                &ctx.accounts.price_mint,
+                remaining_accounts,
+                signer_seeds,
+                escrow_balance,
+            )?;
+
+            close_account(CpiContext::new_with_signer(
+                ctx.accounts.price_token_program.to_account_info(),
+                CloseAccount {
+                    account: ctx.accounts.offer_escrow_ata.to_account_info(),
+                    destination: ctx.accounts.buyer.to_account_info(),
+                    authority: offer.to_account_info(),
+                },
+                signer_seeds,
+            ))?;
+        }
+    }
+
+    emit!(OfferAccepted {
+        buyer: offer.buyer,
+        seller: seller.key(),
+        collection: offer.collection,
+        mint: ctx.accounts.nft_mint.key(),
+        amount,
+        seller_amount,
+        fee_amount,
+        royalty_amount,
+        accepted_at: now,
+    });
+
+    Ok(())
+}
+
+// Withdraw a standing offer, never paused so the buyer can always take their funds back
+pub fn cancel_offer<'info>(ctx: Context<'_, '_, '_, 'info, CancelOffer<'info>>) -> Result<()> {
+    let offer = &ctx.accounts.offer;
+    let buyer = &ctx.accounts.buyer;
+
+    // SOL leaves with the closed offer account, SPL is sent back here
+    if let Some(stored_mint) = offer.price_mint {
+        require!(
+            ctx.accounts.price_mint.key() == stored_mint
+                && ctx.accounts.offer_escrow_ata.mint == stored_mint
+                && ctx.accounts.buyer_price_ata.mint == stored_mint,
+            KeysMismatchErrors::InvalidBidMint
+        );
+        require_keys_eq!(
+            ctx.accounts.offer_escrow_ata.owner,
+            offer.key(),
+            KeysMismatchErrors::InvalidBidEscrowOwner
+        );
+        require_keys_eq!(
+            ctx.accounts.buyer_price_ata.owner,
+            buyer.key(),
+            KeysMismatchErrors::InvalidBidAtaOwner
+        );
+
+        let buyer_key = buyer.key();
+        let seeds: &[&[u8]] = &[
+            b"auction",
+            b"offer",
+            offer.collection.as_ref(),
+            buyer_key.as_ref(),
+            &[offer.offer_bump],
+        ];
+        let signer_seeds: &[&[&[u8]]] = &[seeds];
+
+        transfer_tokens_with_seeds(
+            &ctx.accounts.offer_escrow_ata,
+            &ctx.accounts.buyer_price_ata,
+            &offer.to_account_info(),
+            &ctx.accounts.price_token_program,
+            &ctx.accounts.price_mint,
+            ctx.remaining_accounts,
+            signer_seeds,
+            ctx.accounts.offer_escrow_ata.amount,
+        )?;
+
+        close_account(CpiContext::new_with_signer(
+            ctx.accounts.price_token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.offer_escrow_ata.to_account_info(),
+                destination: buyer.to_account_info(),
+                authority: offer.to_account_info(),
+            },
+            signer_seeds,
+        ))?;
+    }
+
+    emit!(OfferCancelled {
+        buyer: buyer.key(),
+        collection: offer.collection,
+        amount: offer.amount,
+        cancelled_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(collection: Pubkey)]
+pub struct MakeOffer<'info> {
+    #[account(
+        seeds = [b"auction"],
+        bump = auction_config.config_bump,
+        constraint = auction_config.auction_admin == auction_admin.key() @ ConfigStateErrors::InvalidAuctionAdmin
+    )]
+    pub auction_config: Box<Account<'info, AuctionConfig>>,
+
+    #[account(
+        init,
+        payer = buyer,
+        space = 8 + Offer::INIT_SPACE,
+        seeds = [
+            b"auction".as_ref(),
+            b"offer".as_ref(),
+            collection.as_ref(),
+            buyer.key().as_ref(),
+        ],
+        bump
+    )]
+    pub offer: Box<Account<'info, Offer>>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    pub auction_admin: Signer<'info>,
+
+    pub price_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: only has to exist when the owner allowlisted the price mint, see `check_mint_policy`
+    #[account(
+        seeds = [b"auction".as_ref(), b"mint_allowlist".as_ref(), price_mint.key().as_ref()],
+        bump
+    )]
+    pub price_mint_allowlist: UncheckedAccount<'info>,
+
+    // buyer's token account for price_mint (SPL)
+    #[account(mut)]
+    pub buyer_price_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // token account that holds the offer, owner is the offer (SPL)
+    #[account(mut)]
+    pub offer_escrow_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub price_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(min_amount: u64, permit: Permit)]
+pub struct AcceptOffer<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction"],
+        bump = auction_config.config_bump,
+    )]
+    pub auction_config: Box<Account<'info, AuctionConfig>>,
+
+    #[account(
+        mut,
+        close = buyer,
+        seeds = [
+            b"auction".as_ref(),
+            b"offer".as_ref(),
+            offer.collection.as_ref(),
+            buyer.key().as_ref(),
+        ],
+        bump = offer.offer_bump,
+    )]
+    pub offer: Box<Account<'info, Offer>>,
+
+    #[account(
+        mut,
+        constraint = offer.buyer == buyer.key() @ MarketErrors::InvalidBuyer
+    )]
+    pub buyer: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    #[account(
+        init,
+        payer = seller,
+        space = 8 + PermitNonce::INIT_SPACE,
+        seeds = [
+            b"auction".as_ref(),
+            b"permit".as_ref(),
+            seller.key().as_ref(),
+            permit.nonce.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub permit_nonce: Box<Account<'info, PermitNonce>>,
+
+    pub nft_mint: Box<InterfaceAccount<'info, Mint>>,
+    pub price_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = nft_mint,
+        token::authority = seller,
+        token::token_program = nft_token_program
+    )]
+    pub seller_nft_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        init_if_needed,
+        payer = seller,
+        associated_token::mint = nft_mint,
+        associated_token::authority = buyer,
+        associated_token::token_program = nft_token_program
+    )]
+    pub buyer_nft_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // For SPL offers (if used), owner is the offer
+    #[account(mut)]
+    pub offer_escrow_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // owner by config account
+    #[account(mut)]
+    pub price_fee_treasury_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    pub seller_price_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: Token Metadata PDA of the NFT, verified in `verify_nft_prize` and read for royalties
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            nft_mint.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub nft_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: master edition PDA of the NFT, verified in `verify_nft_prize`
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            nft_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub nft_edition: UncheckedAccount<'info>,
+
+    /// CHECK: `CollectionAllowlist` entry of the NFT's verified collection, only read when the config requires one
+    pub nft_collection_allowlist: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: address is pinned to Token Metadata, only used to derive the metadata PDAs
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to the instructions sysvar, read in `verify_permit`
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub nft_token_program: Interface<'info, TokenInterface>,
+    pub price_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelOffer<'info> {
+    #[account(
+        mut,
+        close = buyer,
+        seeds = [
+            b"auction".as_ref(),
+            b"offer".as_ref(),
+            offer.collection.as_ref(),
+            buyer.key().as_ref(),
+        ],
+        bump = offer.offer_bump,
+    )]
+    pub offer: Box<Account<'info, Offer>>,
+
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    pub price_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    // For SPL offers (if used), owner is the offer
+    #[account(mut)]
+    pub offer_escrow_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // buyer's token account for price_mint (SPL)
+    #[account(mut)]
+    pub buyer_price_ata: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    pub price_token_program: Interface<'info, TokenInterface>,
+}
@@ -1,10 +1,10 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::constants::PLACE_BID_PAUSE;
-use crate::errors::{AuctionStateErrors, ConfigStateErrors, KeysMismatchErrors};
+use crate::errors::{AuctionStateErrors, KeysMismatchErrors, PermitErrors};
 use crate::helpers::*;
 use crate::states::*;
-use crate::utils::is_paused;
+use crate::utils::{is_paused, min_bid_increment, verify_permit};
 
 #[event]
 pub struct BidPlaced {
@@ -14,8 +14,26 @@ pub struct BidPlaced {
     pub bid_time: i64,
 }
 
-// Place bid: supports SOL (auction.bid_mint == None) or SPL (auction.bid_mint == Some(mint))
-pub fn place_bid(ctx: Context<PlaceBid>, _auction_id: u32, bid_amount: u64) -> Result<()> {
+#[event]
+pub struct ProxyBidOutbid {
+    pub auction_id: u32,
+    pub bidder: Pubkey,
+    pub max_amount: u64, // escrowed, back through `withdraw_outbid`
+    pub highest_bidder: Pubkey,
+    pub highest_bid_amount: u64, // where the leader's proxy bid the price up to
+    pub bid_time: i64,
+}
+
+// Place bid: supports SOL (auction.bid_mint == None) or SPL (auction.bid_mint == Some(mint)).
+// A `proxy` bid escrows `bid_amount` as the bidder's maximum and only bids as high as it takes to lead,
+// competing proxies are resolved here, the earlier one winning a tie
+pub fn place_bid<'info>(
+    ctx: Context<'_, '_, '_, 'info, PlaceBid<'info>>,
+    auction_id: u32,
+    bid_amount: u64,
+    proxy: bool,
+    permit: Permit,
+) -> Result<()> {
     require!(
         !is_paused(ctx.accounts.auction_config.pause_flags, PLACE_BID_PAUSE),
         AuctionStateErrors::FunctionPaused
@@ -30,6 +48,8 @@ pub fn place_bid(ctx: Context<PlaceBid>, _auction_id: u32, bid_amount: u64) -> R
         AuctionStateErrors::AuctionNotActive
     );
     require_gt!(auction.end_time, now, AuctionStateErrors::AuctionNotStarted);
+    require!(!auction.sealed, AuctionStateErrors::SealedAuction);
+    require_eq!(auction.dutch_start_price, 0, AuctionStateErrors::DutchAuction);
 
     // cannot be same as previous highest
     require!(
@@ -37,39 +57,68 @@ pub fn place_bid(ctx: Context<PlaceBid>, _auction_id: u32, bid_amount: u64) -> R
         AuctionStateErrors::CannotBidOwnHighBid
     );
 
-    // bid must be >= highest_bid + min_increment
+    // bid must be >= highest_bid + increment, fixed or a share of the highest bid
+    let increment = min_bid_increment(auction, auction.highest_bid_amount)?;
     let min_required = auction
         .highest_bid_amount
-        .checked_add(auction.min_increment)
+        .checked_add(increment)
         .ok_or(AuctionStateErrors::Overflow)?;
     require_gte!(bid_amount, min_required, AuctionStateErrors::BidTooLow);
 
-    // Collect new bid from bidder into auction escrow (SOL or SPL)
-    match auction.bid_mint {
+    // the admin signs the bid off-chain instead of co-signing the transaction
+    let permit_signer = verify_permit(
+        &ctx.accounts.instructions_sysvar,
+        &auction_id.to_le_bytes(),
+        bidder.key(),
+        bid_amount,
+        &permit,
+    )?;
+    require_keys_eq!(
+        permit_signer,
+        ctx.accounts.auction_config.auction_admin,
+        PermitErrors::InvalidPermitSigner
+    );
+
+    // spend the nonce, `init` already rejected a replay
+    let permit_nonce = &mut ctx.accounts.permit_nonce;
+    permit_nonce.user = bidder.key();
+    permit_nonce.nonce = permit.nonce;
+    permit_nonce.used_at = now;
+    permit_nonce.nonce_bump = ctx.bumps.permit_nonce;
+
+    // an outbid bidder coming back only pays in what their escrow does not hold yet
+    let bid_escrow = &mut ctx.accounts.bid_escrow;
+    let top_up = bid_amount
+        .checked_sub(bid_escrow.amount)
+        .ok_or(AuctionStateErrors::Overflow)?;
+
+    // Collect the top up from bidder into their bid escrow (SOL or SPL)
+    let received = match auction.bid_mint {
         None => {
-            // SOL — bidder must transfer lamports to auction PDA directly (signed by bidder)
-            // We perform a CPI that transfers lamports from bidder to auction PDA
+            // SOL — lamports sit on the bid escrow PDA itself
             transfer_sol(
                 bidder,
-                &auction.to_account_info(),
+                &bid_escrow.to_account_info(),
                 &ctx.accounts.system_program,
-                bid_amount,
+                top_up,
             )?;
+
+            top_up
         }
         Some(stored_mint) => {
-            // SPL: transfer from bidder_ata to bid_escrow (provided)
+            // SPL: transfer from bidder_ata to the bid escrow's token account (provided)
             let bid_mint = ctx.accounts.bid_mint.key();
             require_keys_eq!(bid_mint, stored_mint, KeysMismatchErrors::InvalidBidMint);
 
             // check for bid escrow
             require_keys_eq!(
-                ctx.accounts.bid_escrow.mint,
+                ctx.accounts.bid_escrow_ata.mint,
                 stored_mint,
                 KeysMismatchErrors::InvalidBidMint
             );
             require_keys_eq!(
-                ctx.accounts.bid_escrow.owner,
-                auction.key(),
+                ctx.accounts.bid_escrow_ata.owner,
+                bid_escrow.key(),
                 KeysMismatchErrors::InvalidBidEscrowOwner
             );
 
@@ -88,90 +137,101 @@ pub fn place_bid(ctx: Context<PlaceBid>, _auction_id: u32, bid_amount: u64) -> R
             // transfer tokens
             transfer_tokens(
                 &ctx.accounts.current_bidder_ata,
-                &ctx.accounts.bid_escrow,
+                &ctx.accounts.bid_escrow_ata,
                 bidder,
                 &ctx.accounts.bid_token_program,
                 &ctx.accounts.bid_mint,
-                bid_amount,
-            )?;
+                ctx.remaining_accounts,
+                top_up,
+            )?
         }
+    };
+
+    // bids compete on what the escrow holds, a Token-2022 transfer fee never counts towards a bid
+    let escrowed = bid_escrow
+        .amount
+        .checked_add(received)
+        .ok_or(AuctionStateErrors::Overflow)?;
+    require_gte!(escrowed, min_required, AuctionStateErrors::BidTooLow);
+
+    bid_escrow.auction_id = auction_id;
+    bid_escrow.bidder = bidder.key();
+    bid_escrow.bid_mint = auction.bid_mint;
+    bid_escrow.amount = escrowed;
+    bid_escrow.escrow_bump = ctx.bumps.bid_escrow;
+
+    // the previous highest bidder is not refunded here, they pull their bid back with `withdraw_outbid`
+
+    // a leader bidding by proxy holds the lead up to their maximum
+    let leader_max = auction.highest_bid_max.max(auction.highest_bid_amount);
+    let outbid_by_proxy = escrowed <= leader_max;
+
+    // Update auction state, a proxy's answer is one increment on the price it beats rather than the shown one
+    if outbid_by_proxy {
+        // the leader's proxy answers one increment above this bid, as far as their maximum goes
+        auction.highest_bid_amount = escrowed
+            .checked_add(min_bid_increment(auction, escrowed)?)
+            .ok_or(AuctionStateErrors::Overflow)?
+            .min(leader_max);
+    } else {
+        // a proxy only goes one increment past the previous leader's maximum
+        auction.highest_bid_amount = if proxy {
+            leader_max
+                .checked_add(min_bid_increment(auction, leader_max)?)
+                .ok_or(AuctionStateErrors::Overflow)?
+                .min(escrowed)
+        } else {
+            escrowed
+        };
+        auction.highest_bid_max = escrowed;
+        auction.highest_bidder = bidder.key();
     }
+    auction.has_any_bid = true;
 
-    // Handle refund of previous highest bidder
-    let refunded_amount: u64 = auction.highest_bid_amount;
-
-    let seeds: &[&[u8]] = &[
-        b"auction",
-        &auction.auction_id.to_le_bytes(),
-        &[auction.auction_bump],
-    ];
-    let signer_seeds = &[seeds];
-
-    // Refund previous highest bidder: for SPL we transfer tokens back, for SOL we transfer lamports back.
-    if auction.has_any_bid {
-        match auction.bid_mint {
-            None => {
-                // SOL refund: we require prev_bidder_account account provided and use system_program transfer from auction PDA.
-                let prev_account = &ctx.accounts.prev_bidder_account;
-
-                require_keys_eq!(
-                    prev_account.key(),
-                    auction.highest_bidder,
-                    KeysMismatchErrors::InvalidPreviousBidOwner
-                );
-                
-                **auction.to_account_info().try_borrow_mut_lamports()? -= refunded_amount;
-                **prev_account.try_borrow_mut_lamports()? += refunded_amount;
-            }
-            Some(stored_mint) => {
-                // SPL refund: transfer from bid_escrow back to prev_bidder_ata using auction PDA signer
-                require_keys_eq!(
-                    ctx.accounts.prev_bidder_ata.mint,
-                    stored_mint,
-                    KeysMismatchErrors::InvalidBidMint
-                );
-                require_keys_eq!(
-                    ctx.accounts.prev_bidder_ata.owner,
-                    auction.highest_bidder,
-                    KeysMismatchErrors::InvalidPreviousBidOwner
-                );
-
-                transfer_tokens_with_seeds(
-                    &ctx.accounts.bid_escrow,
-                    &ctx.accounts.prev_bidder_ata,
-                    &auction.to_account_info(),
-                    &ctx.accounts.bid_token_program,
-                    &ctx.accounts.bid_mint,
-                    signer_seeds,
-                    refunded_amount,
-                )?;
-            }
+    // a bid inside the trigger window pushes the end out, within the auction's caps
+    let extension_window = match auction.extension_window {
+        0 => auction.time_extension,
+        window => window,
+    };
+    if auction.end_time - now <= extension_window as i64
+        && auction.extension_count < auction.max_extensions
+    {
+        let extended_end_time = now
+            .saturating_add(auction.time_extension as i64)
+            .min(auction.max_end_time);
+        if extended_end_time > auction.end_time {
+            auction.end_time = extended_end_time;
+            auction.extension_count += 1;
         }
     }
 
-    // Update auction state
-    auction.highest_bid_amount = bid_amount;
-    auction.highest_bidder = bidder.key();
-    auction.has_any_bid = true;
-    auction.end_time = std::cmp::max(auction.end_time, now + auction.time_extension as i64);
-
-    emit!(BidPlaced {
-        auction_id: auction.auction_id,
-        bidder: bidder.key(),
-        new_bid: bid_amount,
-        bid_time: now,
-    });
+    if outbid_by_proxy {
+        emit!(ProxyBidOutbid {
+            auction_id: auction.auction_id,
+            bidder: bidder.key(),
+            max_amount: escrowed,
+            highest_bidder: auction.highest_bidder,
+            highest_bid_amount: auction.highest_bid_amount,
+            bid_time: now,
+        });
+    } else {
+        emit!(BidPlaced {
+            auction_id: auction.auction_id,
+            bidder: bidder.key(),
+            new_bid: auction.highest_bid_amount,
+            bid_time: now,
+        });
+    }
 
     Ok(())
 }
 
 #[derive(Accounts)]
-#[instruction(auction_id: u32)]
+#[instruction(auction_id: u32, bid_amount: u64, proxy: bool, permit: Permit)]
 pub struct PlaceBid<'info> {
     #[account(
         seeds = [b"auction"], 
         bump = auction_config.config_bump, 
-        constraint = auction_config.auction_admin == auction_admin.key() @ConfigStateErrors::InvalidAuctionAdmin
     )]
     pub auction_config: Box<Account<'info, AuctionConfig>>,
 
@@ -179,18 +239,31 @@ pub struct PlaceBid<'info> {
         mut, 
         seeds = [b"auction", auction_id.to_le_bytes().as_ref()], 
         bump = auction.auction_bump, 
-        constraint = auction.auction_id == auction_id @AuctionStateErrors::InvalidAuctionId
+        constraint = auction.auction_id == auction_id @AuctionStateErrors::InvalidAuctionId,
+        constraint = !auction.frozen @ AuctionStateErrors::AuctionFrozen,
     )]
     pub auction: Box<Account<'info, Auction>>,
 
     #[account(mut)]
     pub bidder: Signer<'info>,
 
-    pub auction_admin: Signer<'info>,
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + PermitNonce::INIT_SPACE,
+        seeds = [
+            b"auction".as_ref(),
+            b"permit".as_ref(),
+            bidder.key().as_ref(),
+            permit.nonce.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub permit_nonce: Box<Account<'info, PermitNonce>>,
 
-    // previous bidder system account to refund SOL (if needed)
-    #[account(mut)]
-    pub prev_bidder_account: AccountInfo<'info>,
+    /// CHECK: address is pinned to the instructions sysvar, read in `verify_permit`
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
 
     pub bid_mint: InterfaceAccount<'info, Mint>,
 
@@ -198,13 +271,24 @@ pub struct PlaceBid<'info> {
     #[account(mut)]
     pub current_bidder_ata: InterfaceAccount<'info, TokenAccount>,
 
-    // previous bidder's ATA to refund (SPL)
-    #[account(mut)]
-    pub prev_bidder_ata: InterfaceAccount<'info, TokenAccount>,
+    // bidder's escrow for this auction, reused when an outbid bidder bids again
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        space = 8 + BidEscrow::INIT_SPACE,
+        seeds = [
+            b"auction".as_ref(),
+            auction_id.to_le_bytes().as_ref(),
+            b"bid".as_ref(),
+            bidder.key().as_ref(),
+        ],
+        bump
+    )]
+    pub bid_escrow: Box<Account<'info, BidEscrow>>,
 
-    // escrow that holds bid tokens, owner is auction struct
+    // token account that holds bid tokens, owner is the bid escrow (SPL)
     #[account(mut)]
-    pub bid_escrow: InterfaceAccount<'info, TokenAccount>,
+    pub bid_escrow_ata: InterfaceAccount<'info, TokenAccount>,
 
     // programs
     pub bid_token_program: Interface<'info, TokenInterface>,
@@ -1,6 +1,101 @@
 use anchor_lang::prelude::*;
-use crate::errors::ConfigStateErrors;
-use crate::states::AuctionConfig;
+use crate::constants::{
+    AUCTION_CONFIG_VERSION, FEE_MANTISSA, MINIMUM_CONFIG_CHANGE_DELAY, MINT_EXTENSION_COUNT,
+    UPDATE_CONFIG_PAUSE,
+};
+use crate::errors::{AuctionStateErrors, ConfigStateErrors, MintPolicyErrors};
+use crate::states::{AuctionConfig, PendingAuctionConfigData};
+use crate::utils::is_paused;
+
+#[event]
+pub struct OwnershipProposed {
+    pub current_owner: Pubkey,
+    pub proposed_owner: Pubkey,
+    pub proposed_at: i64,
+}
+
+#[event]
+pub struct OwnershipTransferred {
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub transferred_at: i64,
+}
+
+#[event]
+pub struct OwnershipProposalCancelled {
+    pub owner: Pubkey,
+    pub cancelled_owner: Pubkey,
+    pub cancelled_at: i64,
+}
+
+#[event]
+pub struct ConfigChangeQueued {
+    pub creation_fee_lamports: u64,
+    pub commission_bps: u16,
+    pub minimum_auction_period: u32,
+    pub maximum_auction_period: u32,
+    pub minimum_time_extension: u32,
+    pub maximum_time_extension: u32,
+    pub config_change_delay: u32,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct ConfigChangeApplied {
+    pub creation_fee_lamports: u64,
+    pub commission_bps: u16,
+    pub minimum_auction_period: u32,
+    pub maximum_auction_period: u32,
+    pub minimum_time_extension: u32,
+    pub maximum_time_extension: u32,
+    pub config_change_delay: u32,
+    pub applied_at: i64,
+}
+
+#[event]
+pub struct ConfigChangeCancelled {
+    pub effective_at: i64,
+    pub cancelled_at: i64,
+}
+
+#[event]
+pub struct MintExtensionPolicyUpdated {
+    pub allowed_mint_extensions: u8,
+    pub updated_at: i64,
+}
+
+#[event]
+pub struct CollectionPolicyUpdated {
+    pub require_verified_collection: bool,
+    pub updated_at: i64,
+}
+
+#[event]
+pub struct BuyNowCutoffUpdated {
+    pub buy_now_cutoff_bps: u16,
+    pub updated_at: i64,
+}
+
+#[event]
+pub struct ExtensionBoundsUpdated {
+    pub maximum_extension_window: u32,
+    pub maximum_total_extension: u32,
+    pub maximum_extension_count: u16,
+    pub updated_at: i64,
+}
+
+#[event]
+pub struct KeeperFeeUpdated {
+    pub keeper_fee_bps: u16,
+    pub updated_at: i64,
+}
+
+#[event]
+pub struct SealedBidPolicyUpdated {
+    pub sealed_reveal_period: u32,
+    pub sealed_penalty_bps: u16,
+    pub updated_at: i64,
+}
 
 pub fn initialize_auction_config(
     ctx: Context<InitializeAuctionConfig>,
@@ -24,6 +119,7 @@ pub fn initialize_auction_config(
     );
 
     let cfg = &mut ctx.accounts.auction_config;
+    cfg.version = AUCTION_CONFIG_VERSION;
     cfg.auction_owner = auction_owner;
     cfg.auction_admin = auction_admin;
     cfg.creation_fee_lamports = creation_fee_lamports;
@@ -33,18 +129,66 @@ pub fn initialize_auction_config(
     cfg.minimum_time_extension = minimum_time_extension;
     cfg.maximum_time_extension = maximum_time_extension;
     cfg.auction_count = 1;
+    cfg.config_change_delay = MINIMUM_CONFIG_CHANGE_DELAY;
     cfg.config_bump = ctx.bumps.auction_config;
 
     Ok(())
 }
 
-pub fn update_auction_owner(
-    ctx: Context<UpdateAuctionConfig>,
-    new_auction_owner: Pubkey,
-) -> Result<()> {
+// Ownership moves in two steps, so a mistyped key can never take over the config
+pub fn propose_owner(ctx: Context<UpdateAuctionConfig>, new_auction_owner: Pubkey) -> Result<()> {
+    let cfg = &mut ctx.accounts.auction_config;
+
+    cfg.pending_owner = Some(new_auction_owner);
+
+    emit!(OwnershipProposed {
+        current_owner: cfg.auction_owner,
+        proposed_owner: new_auction_owner,
+        proposed_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn accept_owner(ctx: Context<AcceptAuctionOwner>) -> Result<()> {
     let cfg = &mut ctx.accounts.auction_config;
+    let new_auction_owner = ctx.accounts.new_auction_owner.key();
 
+    let pending_owner = cfg
+        .pending_owner
+        .ok_or(ConfigStateErrors::NoPendingOwner)?;
+    require_keys_eq!(
+        pending_owner,
+        new_auction_owner,
+        ConfigStateErrors::InvalidPendingOwner
+    );
+
+    let previous_owner = cfg.auction_owner;
     cfg.auction_owner = new_auction_owner;
+    cfg.pending_owner = None;
+
+    emit!(OwnershipTransferred {
+        previous_owner,
+        new_owner: new_auction_owner,
+        transferred_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn cancel_owner_proposal(ctx: Context<UpdateAuctionConfig>) -> Result<()> {
+    let cfg = &mut ctx.accounts.auction_config;
+
+    let cancelled_owner = cfg
+        .pending_owner
+        .take()
+        .ok_or(ConfigStateErrors::NoPendingOwner)?;
+
+    emit!(OwnershipProposalCancelled {
+        owner: cfg.auction_owner,
+        cancelled_owner,
+        cancelled_at: Clock::get()?.unix_timestamp,
+    });
 
     Ok(())
 }
@@ -60,7 +204,8 @@ pub fn update_auction_admin(
     Ok(())
 }
 
-pub fn update_config_data(
+// Commission, fee and period changes are queued, so creators always get `config_change_delay` of notice
+pub fn queue_config_data(
     ctx: Context<UpdateAuctionConfig>,
     creation_fee_lamports: u64,
     commission_bps: u16,
@@ -68,7 +213,12 @@ pub fn update_config_data(
     maximum_auction_period: u32,
     minimum_time_extension: u32,
     maximum_time_extension: u32,
+    config_change_delay: u32,
 ) -> Result<()> {
+    require!(
+        !is_paused(ctx.accounts.auction_config.pause_flags, UPDATE_CONFIG_PAUSE),
+        AuctionStateErrors::FunctionPaused
+    );
     require!(
         minimum_auction_period > 0 && maximum_auction_period > minimum_auction_period,
         ConfigStateErrors::InvalidAuctionPeriod
@@ -78,22 +228,112 @@ pub fn update_config_data(
         minimum_time_extension,
         ConfigStateErrors::InvalidTimeExtension
     );
+    require_gte!(
+        FEE_MANTISSA,
+        commission_bps,
+        ConfigStateErrors::InvalidCommission
+    );
+    require_gte!(
+        config_change_delay,
+        MINIMUM_CONFIG_CHANGE_DELAY,
+        ConfigStateErrors::InvalidConfigChangeDelay
+    );
 
     let cfg = &mut ctx.accounts.auction_config;
 
-    cfg.creation_fee_lamports = creation_fee_lamports;
-    cfg.commission_bps = commission_bps;
-    cfg.minimum_auction_period = minimum_auction_period;
-    cfg.maximum_auction_period = maximum_auction_period;
-    cfg.minimum_time_extension = minimum_time_extension;
-    cfg.maximum_time_extension = maximum_time_extension;
+    // a zeroed delay (freshly carved out of `reserved`) still waits the minimum
+    let current_delay = cfg.config_change_delay.max(MINIMUM_CONFIG_CHANGE_DELAY);
+    let effective_at = Clock::get()?
+        .unix_timestamp
+        .checked_add(current_delay as i64)
+        .ok_or(AuctionStateErrors::Overflow)?;
+
+    // queuing again replaces the previous change and restarts the delay
+    cfg.pending_config_data = Some(PendingAuctionConfigData {
+        creation_fee_lamports,
+        commission_bps,
+        minimum_auction_period,
+        maximum_auction_period,
+        minimum_time_extension,
+        maximum_time_extension,
+        config_change_delay,
+        effective_at,
+    });
+
+    emit!(ConfigChangeQueued {
+        creation_fee_lamports,
+        commission_bps,
+        minimum_auction_period,
+        maximum_auction_period,
+        minimum_time_extension,
+        maximum_time_extension,
+        config_change_delay,
+        effective_at,
+    });
+
+    Ok(())
+}
+
+pub fn apply_config_data(ctx: Context<UpdateAuctionConfig>) -> Result<()> {
+    require!(
+        !is_paused(ctx.accounts.auction_config.pause_flags, UPDATE_CONFIG_PAUSE),
+        AuctionStateErrors::FunctionPaused
+    );
+
+    let cfg = &mut ctx.accounts.auction_config;
+    let now = Clock::get()?.unix_timestamp;
+
+    let pending = cfg
+        .pending_config_data
+        .ok_or(ConfigStateErrors::NoPendingConfigChange)?;
+    require_gte!(
+        now,
+        pending.effective_at,
+        ConfigStateErrors::ConfigChangeNotReady
+    );
+
+    cfg.creation_fee_lamports = pending.creation_fee_lamports;
+    cfg.commission_bps = pending.commission_bps;
+    cfg.minimum_auction_period = pending.minimum_auction_period;
+    cfg.maximum_auction_period = pending.maximum_auction_period;
+    cfg.minimum_time_extension = pending.minimum_time_extension;
+    cfg.maximum_time_extension = pending.maximum_time_extension;
+    cfg.config_change_delay = pending.config_change_delay;
+    cfg.pending_config_data = None;
+
+    emit!(ConfigChangeApplied {
+        creation_fee_lamports: pending.creation_fee_lamports,
+        commission_bps: pending.commission_bps,
+        minimum_auction_period: pending.minimum_auction_period,
+        maximum_auction_period: pending.maximum_auction_period,
+        minimum_time_extension: pending.minimum_time_extension,
+        maximum_time_extension: pending.maximum_time_extension,
+        config_change_delay: pending.config_change_delay,
+        applied_at: now,
+    });
+
+    Ok(())
+}
+
+pub fn cancel_config_data(ctx: Context<UpdateAuctionConfig>) -> Result<()> {
+    let cfg = &mut ctx.accounts.auction_config;
+
+    let pending = cfg
+        .pending_config_data
+        .take()
+        .ok_or(ConfigStateErrors::NoPendingConfigChange)?;
+
+    emit!(ConfigChangeCancelled {
+        effective_at: pending.effective_at,
+        cancelled_at: Clock::get()?.unix_timestamp,
+    });
 
     Ok(())
 }
 
 pub fn update_pause_and_unpause(
     ctx: Context<UpdateAuctionConfig>,
-    new_pause_flags: u8,
+    new_pause_flags: u64,
 ) -> Result<()> {
     let cfg = &mut ctx.accounts.auction_config;
 
@@ -102,6 +342,130 @@ pub fn update_pause_and_unpause(
     Ok(())
 }
 
+// Only checked when an auction is created, live auctions keep whatever mints they were created with
+pub fn set_allowed_mint_extensions(
+    ctx: Context<UpdateAuctionConfig>,
+    allowed_mint_extensions: u8,
+) -> Result<()> {
+    require!(
+        allowed_mint_extensions >> MINT_EXTENSION_COUNT == 0,
+        MintPolicyErrors::InvalidMintExtension
+    );
+
+    let cfg = &mut ctx.accounts.auction_config;
+
+    cfg.allowed_mint_extensions = allowed_mint_extensions;
+
+    emit!(MintExtensionPolicyUpdated {
+        allowed_mint_extensions,
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Only checked for auctions created from now on, live auctions keep the prize they were created with
+pub fn set_collection_policy(
+    ctx: Context<UpdateAuctionConfig>,
+    require_verified_collection: bool,
+) -> Result<()> {
+    let cfg = &mut ctx.accounts.auction_config;
+
+    cfg.require_verified_collection = require_verified_collection;
+
+    emit!(CollectionPolicyUpdated {
+        require_verified_collection,
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Read by `buy_now` on every call, so it also applies to live auctions
+pub fn set_buy_now_cutoff(ctx: Context<UpdateAuctionConfig>, buy_now_cutoff_bps: u16) -> Result<()> {
+    require!(
+        buy_now_cutoff_bps <= FEE_MANTISSA,
+        ConfigStateErrors::InvalidBuyNowCutoff
+    );
+
+    let cfg = &mut ctx.accounts.auction_config;
+
+    cfg.buy_now_cutoff_bps = buy_now_cutoff_bps;
+
+    emit!(BuyNowCutoffUpdated {
+        buy_now_cutoff_bps,
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Only read when an auction is created, live sealed auctions keep their reveal period and penalty
+pub fn set_sealed_bid_policy(
+    ctx: Context<UpdateAuctionConfig>,
+    sealed_reveal_period: u32,
+    sealed_penalty_bps: u16,
+) -> Result<()> {
+    require!(
+        sealed_penalty_bps <= FEE_MANTISSA,
+        ConfigStateErrors::InvalidSealedPenalty
+    );
+
+    let cfg = &mut ctx.accounts.auction_config;
+
+    cfg.sealed_reveal_period = sealed_reveal_period;
+    cfg.sealed_penalty_bps = sealed_penalty_bps;
+
+    emit!(SealedBidPolicyUpdated {
+        sealed_reveal_period,
+        sealed_penalty_bps,
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Bounds the anti-sniping settings of auctions created or updated from now on
+pub fn set_extension_bounds(
+    ctx: Context<UpdateAuctionConfig>,
+    maximum_extension_window: u32,
+    maximum_total_extension: u32,
+    maximum_extension_count: u16,
+) -> Result<()> {
+    let cfg = &mut ctx.accounts.auction_config;
+
+    cfg.maximum_extension_window = maximum_extension_window;
+    cfg.maximum_total_extension = maximum_total_extension;
+    cfg.maximum_extension_count = maximum_extension_count;
+
+    emit!(ExtensionBoundsUpdated {
+        maximum_extension_window,
+        maximum_total_extension,
+        maximum_extension_count,
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Share of the commission paid to the keeper that settles an auction, applies to settlements from now on
+pub fn set_keeper_fee(ctx: Context<UpdateAuctionConfig>, keeper_fee_bps: u16) -> Result<()> {
+    require!(
+        keeper_fee_bps <= FEE_MANTISSA,
+        ConfigStateErrors::InvalidKeeperFee
+    );
+
+    let cfg = &mut ctx.accounts.auction_config;
+    cfg.keeper_fee_bps = keeper_fee_bps;
+
+    emit!(KeeperFeeUpdated {
+        keeper_fee_bps,
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
 #[derive(Accounts)]
 pub struct InitializeAuctionConfig<'info> {
     #[account(
@@ -132,3 +496,15 @@ pub struct UpdateAuctionConfig<'info> {
     #[account(mut)]
     pub auction_owner: Signer<'info>,
 }
+
+#[derive(Accounts)]
+pub struct AcceptAuctionOwner<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction"],
+        bump = auction_config.config_bump,
+    )]
+    pub auction_config: Box<Account<'info, AuctionConfig>>,
+
+    pub new_auction_owner: Signer<'info>,
+}
@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use crate::constants::RELIST_AUCTION_PAUSE;
+use crate::errors::{AuctionStateErrors, ConfigStateErrors};
+use crate::states::*;
+use crate::utils::{apply_auction_params, is_paused};
+
+#[event]
+pub struct AuctionRelisted {
+    pub auction_id: u32,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub base_bid: u64,
+    pub reserve_price: u64,
+    pub hidden_reserve: bool,
+    pub buy_now_price: u64,
+    pub relisted_at: i64,
+}
+
+// Run an auction that ended without a bid again, the prize never leaves escrow and no creation fee is due.
+// `complete_auction` closes a failed auction, so this is called in its place once bidding is over.
+// Times, prices and extensions are set as at `create_auction`, the commission is taken from the config again
+pub fn relist_auction(
+    ctx: Context<RelistAuction>,
+    _auction_id: u32,
+    params: AuctionParams,
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let config = &ctx.accounts.auction_config;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        !is_paused(config.pause_flags, RELIST_AUCTION_PAUSE),
+        AuctionStateErrors::FunctionPaused
+    );
+
+    // a sealed commitment counts as a bid, its deposit is only released once the reveals are over
+    require!(
+        matches!(auction.status, AuctionState::Initialized | AuctionState::Active)
+            && !auction.has_any_bid
+            && auction.sealed_commit_count == 0,
+        AuctionStateErrors::AuctionNotRelistable
+    );
+    let closes_at = if auction.sealed {
+        auction
+            .end_time
+            .checked_add(auction.reveal_period as i64)
+            .ok_or(AuctionStateErrors::Overflow)?
+    } else {
+        auction.end_time
+    };
+    require_gt!(now, closes_at, AuctionStateErrors::EndTimeNotReached);
+
+    // the auction's mode is kept, so the params are checked as for it at `create_auction`
+    apply_auction_params(auction, config, &params, now)?;
+    auction.commission_bps = config.commission_bps;
+    // no bid was ever placed, so the bid state only needs to follow the new base bid
+    auction.highest_bid_max = 0;
+    auction.status = if params.start_immediately {
+        AuctionState::Active
+    } else {
+        AuctionState::Initialized
+    };
+
+    emit!(AuctionRelisted {
+        auction_id: auction.auction_id,
+        start_time: auction.start_time,
+        end_time: auction.end_time,
+        base_bid: auction.base_bid,
+        reserve_price: auction.reserve_price,
+        hidden_reserve: auction.reserve_commitment != [0u8; 32],
+        buy_now_price: auction.buy_now_price,
+        relisted_at: now,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u32)]
+pub struct RelistAuction<'info> {
+    #[account(
+        seeds = [b"auction"],
+        bump = auction_config.config_bump,
+        constraint = auction_config.auction_admin == auction_admin.key() @ ConfigStateErrors::InvalidAuctionAdmin
+    )]
+    pub auction_config: Box<Account<'info, AuctionConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"auction", auction_id.to_le_bytes().as_ref()],
+        bump = auction.auction_bump,
+        constraint = auction.auction_id == auction_id @ AuctionStateErrors::InvalidAuctionId,
+        constraint = !auction.frozen @ AuctionStateErrors::AuctionFrozen,
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+
+    #[account(
+        constraint = auction.creator == creator.key() @ AuctionStateErrors::InvalidCreator
+    )]
+    pub creator: Signer<'info>,
+
+    pub auction_admin: Signer<'info>,
+}
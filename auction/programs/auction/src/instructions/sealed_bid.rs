@@ -0,0 +1,298 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::constants::PLACE_BID_PAUSE;
+use crate::errors::{AuctionStateErrors, KeysMismatchErrors, PermitErrors};
+use crate::helpers::*;
+use crate::states::*;
+use crate::utils::{is_paused, sealed_bid_commitment, verify_permit};
+
+#[event]
+pub struct SealedBidCommitted {
+    pub auction_id: u32,
+    pub bidder: Pubkey,
+    pub deposit: u64, // total locked behind the commitment
+    pub committed_at: i64,
+}
+
+#[event]
+pub struct SealedBidRevealed {
+    pub auction_id: u32,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub revealed_at: i64,
+}
+
+// Commit to a sealed bid and lock `deposit` more into the bidder's escrow. Committing again before
+// `end_time` replaces the commitment, the deposit has to cover the bid once it is revealed
+pub fn commit_sealed_bid<'info>(
+    ctx: Context<'_, '_, '_, 'info, CommitSealedBid<'info>>,
+    auction_id: u32,
+    commitment: [u8; 32],
+    deposit: u64,
+    permit: Permit,
+) -> Result<()> {
+    require!(
+        !is_paused(ctx.accounts.auction_config.pause_flags, PLACE_BID_PAUSE),
+        AuctionStateErrors::FunctionPaused
+    );
+
+    let auction = &mut ctx.accounts.auction;
+    let bidder = &ctx.accounts.bidder;
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        auction.status == AuctionState::Active,
+        AuctionStateErrors::AuctionNotActive
+    );
+    require_gt!(auction.end_time, now, AuctionStateErrors::AuctionNotActive);
+    require!(auction.sealed, AuctionStateErrors::NotSealedAuction);
+    require!(commitment != [0u8; 32], AuctionStateErrors::InvalidBidReveal);
+
+    // the admin signs the deposit off-chain instead of co-signing the transaction
+    let permit_signer = verify_permit(
+        &ctx.accounts.instructions_sysvar,
+        &auction_id.to_le_bytes(),
+        bidder.key(),
+        deposit,
+        &permit,
+    )?;
+    require_keys_eq!(
+        permit_signer,
+        ctx.accounts.auction_config.auction_admin,
+        PermitErrors::InvalidPermitSigner
+    );
+
+    // spend the nonce, `init` already rejected a replay
+    let permit_nonce = &mut ctx.accounts.permit_nonce;
+    permit_nonce.user = bidder.key();
+    permit_nonce.nonce = permit.nonce;
+    permit_nonce.used_at = now;
+    permit_nonce.nonce_bump = ctx.bumps.permit_nonce;
+
+    let bid_escrow = &mut ctx.accounts.bid_escrow;
+
+    // Collect the deposit from bidder into their bid escrow (SOL or SPL)
+    let received = match auction.bid_mint {
+        None => {
+            transfer_sol(
+                bidder,
+                &bid_escrow.to_account_info(),
+                &ctx.accounts.system_program,
+                deposit,
+            )?;
+
+            deposit
+        }
+        Some(stored_mint) => {
+            require!(
+                ctx.accounts.bid_mint.key() == stored_mint
+                    && ctx.accounts.bid_escrow_ata.mint == stored_mint
+                    && ctx.accounts.current_bidder_ata.mint == stored_mint,
+                KeysMismatchErrors::InvalidBidMint
+            );
+            require_keys_eq!(
+                ctx.accounts.bid_escrow_ata.owner,
+                bid_escrow.key(),
+                KeysMismatchErrors::InvalidBidEscrowOwner
+            );
+            require_keys_eq!(
+                ctx.accounts.current_bidder_ata.owner,
+                bidder.key(),
+                KeysMismatchErrors::InvalidBidAtaOwner
+            );
+
+            transfer_tokens(
+                &ctx.accounts.current_bidder_ata,
+                &ctx.accounts.bid_escrow_ata,
+                bidder,
+                &ctx.accounts.bid_token_program,
+                &ctx.accounts.bid_mint,
+                ctx.remaining_accounts,
+                deposit,
+            )?
+        }
+    };
+
+    if bid_escrow.sealed_commitment == [0u8; 32] {
+        auction.sealed_commit_count = auction
+            .sealed_commit_count
+            .checked_add(1)
+            .ok_or(AuctionStateErrors::Overflow)?;
+    }
+
+    bid_escrow.auction_id = auction_id;
+    bid_escrow.bidder = bidder.key();
+    bid_escrow.bid_mint = auction.bid_mint;
+    bid_escrow.amount = bid_escrow
+        .amount
+        .checked_add(received)
+        .ok_or(AuctionStateErrors::Overflow)?;
+    bid_escrow.escrow_bump = ctx.bumps.bid_escrow;
+    bid_escrow.sealed_commitment = commitment;
+    bid_escrow.penalty_bps = auction.sealed_penalty_bps;
+
+    emit!(SealedBidCommitted {
+        auction_id,
+        bidder: bidder.key(),
+        deposit: bid_escrow.amount,
+        committed_at: now,
+    });
+
+    Ok(())
+}
+
+// Open a sealed bid during the reveal period, the highest revealed bid wins at the second highest
+pub fn reveal_sealed_bid(
+    ctx: Context<RevealSealedBid>,
+    auction_id: u32,
+    amount: u64,
+    salt: [u8; 32],
+) -> Result<()> {
+    let auction = &mut ctx.accounts.auction;
+    let bid_escrow = &mut ctx.accounts.bid_escrow;
+    let bidder = ctx.accounts.bidder.key();
+    let now = Clock::get()?.unix_timestamp;
+
+    require!(
+        auction.status == AuctionState::Active,
+        AuctionStateErrors::AuctionNotActive
+    );
+    require!(auction.sealed, AuctionStateErrors::NotSealedAuction);
+
+    let reveal_end_time = auction
+        .end_time
+        .checked_add(auction.reveal_period as i64)
+        .ok_or(AuctionStateErrors::Overflow)?;
+    require!(
+        now > auction.end_time && now <= reveal_end_time,
+        AuctionStateErrors::NotInRevealPeriod
+    );
+
+    require!(!bid_escrow.revealed, AuctionStateErrors::BidAlreadyRevealed);
+    require!(
+        bid_escrow.sealed_commitment != [0u8; 32]
+            && sealed_bid_commitment(auction_id, &bidder, amount, &salt) == bid_escrow.sealed_commitment,
+        AuctionStateErrors::InvalidBidReveal
+    );
+    require_gte!(bid_escrow.amount, amount, AuctionStateErrors::DepositBelowBid);
+    require_gte!(amount, auction.base_bid, AuctionStateErrors::BidTooLow);
+
+    // ties go to whoever revealed first, and set the price at the tied amount
+    if !auction.has_any_bid || amount > auction.highest_bid_amount {
+        if auction.has_any_bid {
+            auction.second_bid_amount = auction.highest_bid_amount;
+        }
+        auction.highest_bid_amount = amount;
+        auction.highest_bidder = bidder;
+        auction.has_any_bid = true;
+    } else if amount > auction.second_bid_amount {
+        auction.second_bid_amount = amount;
+    }
+
+    bid_escrow.revealed = true;
+
+    emit!(SealedBidRevealed {
+        auction_id,
+        bidder,
+        amount,
+        revealed_at: now,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u32, commitment: [u8; 32], deposit: u64, permit: Permit)]
+pub struct CommitSealedBid<'info> {
+    #[account(
+        seeds = [b"auction"],
+        bump = auction_config.config_bump,
+    )]
+    pub auction_config: Box<Account<'info, AuctionConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"auction", auction_id.to_le_bytes().as_ref()],
+        bump = auction.auction_bump,
+        constraint = auction.auction_id == auction_id @AuctionStateErrors::InvalidAuctionId,
+        constraint = !auction.frozen @ AuctionStateErrors::AuctionFrozen,
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + PermitNonce::INIT_SPACE,
+        seeds = [
+            b"auction".as_ref(),
+            b"permit".as_ref(),
+            bidder.key().as_ref(),
+            permit.nonce.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub permit_nonce: Box<Account<'info, PermitNonce>>,
+
+    /// CHECK: address is pinned to the instructions sysvar, read in `verify_permit`
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub bid_mint: InterfaceAccount<'info, Mint>,
+
+    // bidder's ATA for bid_mint
+    #[account(mut)]
+    pub current_bidder_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // bidder's escrow for this auction, holds the deposit behind the commitment
+    #[account(
+        init_if_needed,
+        payer = bidder,
+        space = 8 + BidEscrow::INIT_SPACE,
+        seeds = [
+            b"auction".as_ref(),
+            auction_id.to_le_bytes().as_ref(),
+            b"bid".as_ref(),
+            bidder.key().as_ref(),
+        ],
+        bump
+    )]
+    pub bid_escrow: Box<Account<'info, BidEscrow>>,
+
+    // token account that holds bid tokens, owner is the bid escrow (SPL)
+    #[account(mut)]
+    pub bid_escrow_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // programs
+    pub bid_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u32)]
+pub struct RevealSealedBid<'info> {
+    // no freeze check, a bidder kept from revealing would forfeit the sealed penalty
+    #[account(
+        mut,
+        seeds = [b"auction", auction_id.to_le_bytes().as_ref()],
+        bump = auction.auction_bump,
+        constraint = auction.auction_id == auction_id @AuctionStateErrors::InvalidAuctionId,
+    )]
+    pub auction: Box<Account<'info, Auction>>,
+
+    pub bidder: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"auction".as_ref(),
+            auction_id.to_le_bytes().as_ref(),
+            b"bid".as_ref(),
+            bidder.key().as_ref(),
+        ],
+        bump = bid_escrow.escrow_bump,
+    )]
+    pub bid_escrow: Box<Account<'info, BidEscrow>>,
+}
@@ -54,7 +54,8 @@ pub struct StartAuction<'info> {
         mut, 
         seeds = [b"auction", auction_id.to_le_bytes().as_ref()], 
         bump = auction.auction_bump, 
-        constraint = auction.auction_id == auction_id @AuctionStateErrors::InvalidAuctionId
+        constraint = auction.auction_id == auction_id @AuctionStateErrors::InvalidAuctionId,
+        constraint = !auction.frozen @ AuctionStateErrors::AuctionFrozen,
     )]
     pub auction: Box<Account<'info, Auction>>,
 
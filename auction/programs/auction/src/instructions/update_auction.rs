@@ -2,7 +2,7 @@ use anchor_lang::prelude::*;
 use crate::constants::UPDATE_AUCTION_PAUSE;
 use crate::errors::{AuctionStateErrors, ConfigStateErrors};
 use crate::states::*;
-use crate::utils::is_paused;
+use crate::utils::{apply_auction_params, is_paused};
 
 #[event]
 pub struct AuctionUpdated {
@@ -11,19 +11,18 @@ pub struct AuctionUpdated {
     pub end_time: i64,
     pub base_bid: u64,
     pub min_increment: u64,
+    pub min_increment_bps: u16,
     pub time_extension: u32,
+    pub reserve_price: u64,
+    pub hidden_reserve: bool,
+    pub buy_now_price: u64,
     pub updated_at: i64,
 }
 
 pub fn update_auction(
     ctx: Context<UpdateAuction>,
     _auction_id: u32,
-    mut start_time: i64,
-    end_time: i64,
-    start_immediately: bool, // If true, set start_time = now (but we still require status==Initialized & no bids)
-    base_bid: u64,
-    min_increment: u64,
-    time_extension: u32,
+    params: AuctionParams, // start_immediately => start_time = now (but we still require status==Initialized & no bids)
 ) -> Result<()> {
     let auction = &mut ctx.accounts.auction;
     let config = &ctx.accounts.auction_config;
@@ -51,41 +50,8 @@ pub fn update_auction(
     // No bids must be present to allow update
     require!(!auction.has_any_bid, AuctionStateErrors::AuctionHasBids);
 
-    // If start_immediately asked, set start_time to now
-    if start_immediately {
-        start_time = now;
-    }
-
-    // Ensure provided start_time is not in the past
-    require_gte!(start_time, now, AuctionStateErrors::StartTimeInPast);
-
-    // duration checks (end_time must be >= start_time)
-    let duration = end_time
-        .checked_sub(start_time)
-        .ok_or(AuctionStateErrors::StartTimeExceedEndTime)?;
-
-    require!(
-        duration >= config.minimum_auction_period as i64
-            && duration <= config.maximum_auction_period as i64,
-        ConfigStateErrors::InvalidAuctionPeriod
-    );
-    require!(
-        time_extension >= config.minimum_time_extension
-            && time_extension <= config.maximum_time_extension,
-        ConfigStateErrors::InvalidTimeExtension
-    );
-
-    // min_increment must be > 0
-    require_gt!(min_increment, 0, AuctionStateErrors::InvalidZeroAmount);
-
-    // Apply updates
-    auction.start_time = start_time;
-    auction.end_time = end_time;
-    auction.base_bid = base_bid;
-    auction.min_increment = min_increment;
-    auction.time_extension = time_extension;
-    // highest_bid_amount should reflect base_bid after update (since there are no bids)
-    auction.highest_bid_amount = base_bid;
+    // Apply updates, checked against the config and the auction's mode as at creation
+    apply_auction_params(auction, config, &params, now)?;
 
     emit!(AuctionUpdated {
         auction_id: auction.auction_id,
@@ -93,7 +59,11 @@ pub fn update_auction(
         end_time: auction.end_time,
         base_bid: auction.base_bid,
         min_increment: auction.min_increment,
+        min_increment_bps: auction.min_increment_bps,
         time_extension: auction.time_extension,
+        reserve_price: auction.reserve_price,
+        hidden_reserve: auction.reserve_commitment != [0u8; 32],
+        buy_now_price: auction.buy_now_price,
         updated_at: now,
     });
 
@@ -115,6 +85,7 @@ pub struct UpdateAuction<'info> {
         seeds = [b"auction", auction_id.to_le_bytes().as_ref()],
         bump = auction.auction_bump,
         constraint = auction.auction_id == auction_id @ AuctionStateErrors::InvalidAuctionId,
+        constraint = !auction.frozen @ AuctionStateErrors::AuctionFrozen,
     )]
     pub auction: Box<Account<'info, Auction>>,
 
@@ -0,0 +1,200 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use crate::constants::{FEE_MANTISSA, WITHDRAW_OUTBID_PAUSE};
+use crate::errors::{AuctionStateErrors, KeysMismatchErrors};
+use crate::helpers::{close_token_account_with_seeds, transfer_tokens_with_seeds};
+use crate::states::{Auction, AuctionConfig, BidEscrow};
+use crate::utils::{get_pct_amount, is_paused};
+
+#[event]
+pub struct OutbidWithdrawn {
+    pub auction_id: u32,
+    pub bidder: Pubkey,
+    pub amount: u64,
+    pub forfeited: u64, // penalty of an unrevealed sealed bid, sent to the fee treasury
+    pub withdrawn_at: i64,
+}
+
+// An outbid bidder takes their escrowed bid back, SOL leaves with the closed escrow account.
+// A sealed bid that was never revealed forfeits its penalty share of the deposit
+pub fn withdraw_outbid<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawOutbid<'info>>,
+    auction_id: u32,
+) -> Result<()> {
+    require!(
+        !is_paused(ctx.accounts.auction_config.pause_flags, WITHDRAW_OUTBID_PAUSE),
+        AuctionStateErrors::FunctionPaused
+    );
+
+    let bidder = &ctx.accounts.bidder;
+    let bid_escrow = &ctx.accounts.bid_escrow;
+
+    // a settled auction is closed, its winning escrow is already spent by then
+    let auction_info = ctx.accounts.auction.to_account_info();
+    if auction_info.owner == &crate::ID && !auction_info.data_is_empty() {
+        let auction = Auction::try_deserialize(&mut &auction_info.try_borrow_data()?[..])?;
+
+        require!(!auction.frozen, AuctionStateErrors::AuctionFrozen);
+        require_keys_neq!(
+            auction.highest_bidder,
+            bidder.key(),
+            AuctionStateErrors::HighestBidderCannotWithdraw
+        );
+
+        // nobody knows who lost a sealed auction before the reveals are in
+        if auction.sealed {
+            let reveal_end_time = auction
+                .end_time
+                .checked_add(auction.reveal_period as i64)
+                .ok_or(AuctionStateErrors::Overflow)?;
+            require_gt!(
+                Clock::get()?.unix_timestamp,
+                reveal_end_time,
+                AuctionStateErrors::RevealPeriodNotOver
+            );
+        }
+    }
+
+    let forfeited = if bid_escrow.sealed_commitment != [0u8; 32] && !bid_escrow.revealed {
+        get_pct_amount(bid_escrow.amount, bid_escrow.penalty_bps as u64, FEE_MANTISSA as u64)?
+    } else {
+        0
+    };
+    let amount = bid_escrow
+        .amount
+        .checked_sub(forfeited)
+        .ok_or(AuctionStateErrors::Overflow)?;
+
+    if let Some(stored_mint) = bid_escrow.bid_mint {
+        require!(
+            ctx.accounts.bid_mint.key() == stored_mint
+                && ctx.accounts.bid_escrow_ata.mint == stored_mint
+                && ctx.accounts.bidder_ata.mint == stored_mint,
+            KeysMismatchErrors::InvalidBidMint
+        );
+        require_keys_eq!(
+            ctx.accounts.bid_escrow_ata.owner,
+            bid_escrow.key(),
+            KeysMismatchErrors::InvalidBidEscrowOwner
+        );
+        require_keys_eq!(
+            ctx.accounts.bidder_ata.owner,
+            bidder.key(),
+            KeysMismatchErrors::InvalidBidAtaOwner
+        );
+
+        let bidder_key = bidder.key();
+        let seeds: &[&[u8]] = &[
+            b"auction",
+            &auction_id.to_le_bytes(),
+            b"bid",
+            bidder_key.as_ref(),
+            &[bid_escrow.escrow_bump],
+        ];
+
+        if forfeited > 0 {
+            require!(
+                ctx.accounts.bid_fee_treasury_ata.mint == stored_mint
+                    && ctx.accounts.bid_fee_treasury_ata.owner == ctx.accounts.auction_config.key(),
+                KeysMismatchErrors::InvalidBidAtaOwner
+            );
+
+            transfer_tokens_with_seeds(
+                &ctx.accounts.bid_escrow_ata,
+                &ctx.accounts.bid_fee_treasury_ata,
+                &bid_escrow.to_account_info(),
+                &ctx.accounts.bid_token_program,
+                &ctx.accounts.bid_mint,
+                ctx.remaining_accounts,
+                &[seeds],
+                forfeited,
+            )?;
+        }
+
+        transfer_tokens_with_seeds(
+            &ctx.accounts.bid_escrow_ata,
+            &ctx.accounts.bidder_ata,
+            &bid_escrow.to_account_info(),
+            &ctx.accounts.bid_token_program,
+            &ctx.accounts.bid_mint,
+            ctx.remaining_accounts,
+            &[seeds],
+            amount,
+        )?;
+        // closed along with the escrow, a later bid on this auction opens it again
+        close_token_account_with_seeds(
+            &ctx.accounts.bid_escrow_ata,
+            &ctx.accounts.bid_mint,
+            &bidder.to_account_info(),
+            &bid_escrow.to_account_info(),
+            &ctx.accounts.bid_token_program,
+            &[seeds],
+        )?;
+    } else if forfeited > 0 {
+        **bid_escrow.to_account_info().try_borrow_mut_lamports()? -= forfeited;
+        **ctx.accounts.auction_config.to_account_info().try_borrow_mut_lamports()? += forfeited;
+    }
+
+    emit!(OutbidWithdrawn {
+        auction_id,
+        bidder: bidder.key(),
+        amount,
+        forfeited,
+        withdrawn_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(auction_id: u32)]
+pub struct WithdrawOutbid<'info> {
+    #[account(
+        mut,
+        seeds = [b"auction"],
+        bump = auction_config.config_bump,
+    )]
+    pub auction_config: Box<Account<'info, AuctionConfig>>,
+
+    /// CHECK: seeds pin the auction, it is already closed once settled and only read while it still exists
+    #[account(
+        seeds = [b"auction", auction_id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub auction: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+
+    #[account(
+        mut,
+        close = bidder,
+        seeds = [
+            b"auction".as_ref(),
+            auction_id.to_le_bytes().as_ref(),
+            b"bid".as_ref(),
+            bidder.key().as_ref(),
+        ],
+        bump = bid_escrow.escrow_bump,
+        constraint = bid_escrow.auction_id == auction_id @AuctionStateErrors::InvalidAuctionId,
+    )]
+    pub bid_escrow: Box<Account<'info, BidEscrow>>,
+
+    #[account(mut)]
+    pub bid_mint: InterfaceAccount<'info, Mint>,
+
+    // token account that holds bid tokens, owner is the bid escrow (SPL)
+    #[account(mut)]
+    pub bid_escrow_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // bidder's ATA for bid_mint, receives the withdrawn bid (SPL)
+    #[account(mut)]
+    pub bidder_ata: InterfaceAccount<'info, TokenAccount>,
+
+    // owner by config account, receives a forfeited sealed bid penalty (SPL, only checked when there is one)
+    #[account(mut)]
+    pub bid_fee_treasury_ata: InterfaceAccount<'info, TokenAccount>,
+
+    pub bid_token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
-use crate::errors::{ConfigStateErrors, TransferErrors};
+use crate::constants::WITHDRAW_FEES_PAUSE;
+use crate::errors::{AuctionStateErrors, ConfigStateErrors, TransferErrors};
 use crate::states::AuctionConfig;
+use crate::utils::is_paused;
 
 #[event]
 pub struct FeesWithdrawn {
@@ -9,6 +11,11 @@ pub struct FeesWithdrawn {
 }
 
 pub fn withdraw_sol_fees(ctx: Context<WithdrawSolFees>, amount: u64) -> Result<()> {
+    require!(
+        !is_paused(ctx.accounts.auction_config.pause_flags, WITHDRAW_FEES_PAUSE),
+        AuctionStateErrors::FunctionPaused
+    );
+
     let config = &ctx.accounts.auction_config;
     let pda_ai = config.to_account_info();
 
@@ -1,8 +1,10 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
-use crate::errors::ConfigStateErrors;
+use crate::constants::WITHDRAW_FEES_PAUSE;
+use crate::errors::{AuctionStateErrors, ConfigStateErrors};
 use crate::helpers::transfer_tokens_with_seeds;
 use crate::states::AuctionConfig;
+use crate::utils::is_paused;
 
 #[event]
 pub struct SplFeesWithdrawn {
@@ -11,7 +13,15 @@ pub struct SplFeesWithdrawn {
     pub receiver: Pubkey,
 }
 
-pub fn withdraw_spl_fees(ctx: Context<WithdrawSplFees>, amount: u64) -> Result<()> {
+pub fn withdraw_spl_fees<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawSplFees<'info>>,
+    amount: u64,
+) -> Result<()> {
+    require!(
+        !is_paused(ctx.accounts.auction_config.pause_flags, WITHDRAW_FEES_PAUSE),
+        AuctionStateErrors::FunctionPaused
+    );
+
     let config = &ctx.accounts.auction_config;
     let signer_seeds: &[&[&[u8]]] = &[&[b"auction", &[config.config_bump]]];
 
@@ -21,6 +31,7 @@ pub fn withdraw_spl_fees(ctx: Context<WithdrawSplFees>, amount: u64) -> Result<(
         &ctx.accounts.auction_config.to_account_info(),
         &ctx.accounts.token_program,
         &ctx.accounts.fee_mint,
+        ctx.remaining_accounts,
         signer_seeds,
         amount,
     )?;
@@ -5,7 +5,7 @@ pub mod instructions;
 pub mod states;
 pub mod utils;
 
-use {anchor_lang::prelude::*, instructions::*};
+use {anchor_lang::prelude::*, instructions::*, states::{AuctionParams, DutchSchedule, Permit, ReserveReveal}};
 
 declare_id!("HDW5Huoqz7Us3yiMgxAEkekHUfGvggUPLEz7WZ8uSTBY");
 
@@ -41,11 +41,16 @@ pub mod auction {
         )
     }
 
-    pub fn update_auction_owner(
-        ctx: Context<UpdateAuctionConfig>,
-        new_auction_owner: Pubkey,
-    ) -> Result<()> {
-        process_auction_config::update_auction_owner(ctx, new_auction_owner)
+    pub fn propose_owner(ctx: Context<UpdateAuctionConfig>, new_auction_owner: Pubkey) -> Result<()> {
+        process_auction_config::propose_owner(ctx, new_auction_owner)
+    }
+
+    pub fn accept_owner(ctx: Context<AcceptAuctionOwner>) -> Result<()> {
+        process_auction_config::accept_owner(ctx)
+    }
+
+    pub fn cancel_owner_proposal(ctx: Context<UpdateAuctionConfig>) -> Result<()> {
+        process_auction_config::cancel_owner_proposal(ctx)
     }
 
     pub fn update_auction_admin(
@@ -57,12 +62,75 @@ pub mod auction {
 
     pub fn update_pause_and_unpause(
         ctx: Context<UpdateAuctionConfig>,
-        new_pause_flags: u8,
+        new_pause_flags: u64,
     ) -> Result<()> {
         process_auction_config::update_pause_and_unpause(ctx, new_pause_flags)
     }
 
-    pub fn update_config_data(
+    pub fn set_allowed_mint_extensions(
+        ctx: Context<UpdateAuctionConfig>,
+        allowed_mint_extensions: u8,
+    ) -> Result<()> {
+        process_auction_config::set_allowed_mint_extensions(ctx, allowed_mint_extensions)
+    }
+
+    pub fn allow_mint(ctx: Context<AllowMint>) -> Result<()> {
+        allowlist::allow_mint(ctx)
+    }
+
+    pub fn remove_allowed_mint(ctx: Context<RemoveAllowedMint>) -> Result<()> {
+        allowlist::remove_allowed_mint(ctx)
+    }
+
+    pub fn set_collection_policy(
+        ctx: Context<UpdateAuctionConfig>,
+        require_verified_collection: bool,
+    ) -> Result<()> {
+        process_auction_config::set_collection_policy(ctx, require_verified_collection)
+    }
+
+    pub fn set_sealed_bid_policy(
+        ctx: Context<UpdateAuctionConfig>,
+        sealed_reveal_period: u32,
+        sealed_penalty_bps: u16,
+    ) -> Result<()> {
+        process_auction_config::set_sealed_bid_policy(ctx, sealed_reveal_period, sealed_penalty_bps)
+    }
+
+    pub fn set_extension_bounds(
+        ctx: Context<UpdateAuctionConfig>,
+        maximum_extension_window: u32,
+        maximum_total_extension: u32,
+        maximum_extension_count: u16,
+    ) -> Result<()> {
+        process_auction_config::set_extension_bounds(
+            ctx,
+            maximum_extension_window,
+            maximum_total_extension,
+            maximum_extension_count,
+        )
+    }
+
+    pub fn set_keeper_fee(ctx: Context<UpdateAuctionConfig>, keeper_fee_bps: u16) -> Result<()> {
+        process_auction_config::set_keeper_fee(ctx, keeper_fee_bps)
+    }
+
+    pub fn set_buy_now_cutoff(
+        ctx: Context<UpdateAuctionConfig>,
+        buy_now_cutoff_bps: u16,
+    ) -> Result<()> {
+        process_auction_config::set_buy_now_cutoff(ctx, buy_now_cutoff_bps)
+    }
+
+    pub fn allow_collection(ctx: Context<AllowCollection>) -> Result<()> {
+        allowlist::allow_collection(ctx)
+    }
+
+    pub fn remove_allowed_collection(ctx: Context<RemoveAllowedCollection>) -> Result<()> {
+        allowlist::remove_allowed_collection(ctx)
+    }
+
+    pub fn queue_config_data(
         ctx: Context<UpdateAuctionConfig>,
         creation_fee_lamports: u64,
         commission_bps: u16,
@@ -70,8 +138,9 @@ pub mod auction {
         maximum_auction_period: u32,
         minimum_time_extension: u32,
         maximum_time_extension: u32,
+        config_change_delay: u32,
     ) -> Result<()> {
-        process_auction_config::update_config_data(
+        process_auction_config::queue_config_data(
             ctx,
             creation_fee_lamports,
             commission_bps,
@@ -79,28 +148,47 @@ pub mod auction {
             maximum_auction_period,
             minimum_time_extension,
             maximum_time_extension,
+            config_change_delay,
         )
     }
 
-    pub fn create_auction(
-        ctx: Context<CreateAuction>,
-        start_time: i64,
-        end_time: i64,
-        start_immediately: bool,
+    pub fn apply_config_data(ctx: Context<UpdateAuctionConfig>) -> Result<()> {
+        process_auction_config::apply_config_data(ctx)
+    }
+
+    pub fn cancel_config_data(ctx: Context<UpdateAuctionConfig>) -> Result<()> {
+        process_auction_config::cancel_config_data(ctx)
+    }
+
+    // Migration
+    pub fn migrate_auction_config(ctx: Context<MigrateAuctionConfig>) -> Result<()> {
+        migrate_accounts::migrate_auction_config(ctx)
+    }
+
+    pub fn migrate_auction<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MigrateAuction<'info>>,
+        auction_id: u32,
+    ) -> Result<()> {
+        migrate_accounts::migrate_auction(ctx, auction_id)
+    }
+
+    pub fn create_auction<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateAuction<'info>>,
+        params: AuctionParams,
         is_bid_mint_sol: bool,
-        base_bid: u64, // base bid can be zero also
-        min_increment: u64,
-        time_extension: u32,
+        sealed: bool,
+        dutch: Option<DutchSchedule>,
+        lot_amount: u64,
+        bundle_size: u8,
     ) -> Result<()> {
         create_auction::create_auction(
             ctx,
-            start_time,
-            end_time,
-            start_immediately,
+            params,
             is_bid_mint_sol,
-            base_bid,
-            min_increment,
-            time_extension,
+            sealed,
+            dutch,
+            lot_amount,
+            bundle_size,
         )
     }
 
@@ -111,42 +199,144 @@ pub mod auction {
     pub fn update_auction(
         ctx: Context<UpdateAuction>,
         auction_id: u32,
-        start_time: i64,
-        end_time: i64,
-        start_immediately: bool, // If true, set start_time = now (but we still require status==Initialized & no bids)
-        base_bid: u64,
-        min_increment: u64,
-        time_extension: u32,
-    ) -> Result<()> {
-        update_auction::update_auction(
-            ctx,
-            auction_id,
-            start_time,
-            end_time,
-            start_immediately,
-            base_bid,
-            min_increment,
-            time_extension,
-        )
+        params: AuctionParams,
+    ) -> Result<()> {
+        update_auction::update_auction(ctx, auction_id, params)
     }
 
-    pub fn cancel_auction(ctx: Context<CancelAuction>, auction_id: u32) -> Result<()> {
+    pub fn relist_auction(
+        ctx: Context<RelistAuction>,
+        auction_id: u32,
+        params: AuctionParams,
+    ) -> Result<()> {
+        relist_auction::relist_auction(ctx, auction_id, params)
+    }
+
+    pub fn set_auction_frozen(
+        ctx: Context<SetAuctionFrozen>,
+        auction_id: u32,
+        frozen: bool,
+    ) -> Result<()> {
+        freeze_auction::set_auction_frozen(ctx, auction_id, frozen)
+    }
+
+    pub fn cancel_auction<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelAuction<'info>>,
+        auction_id: u32,
+    ) -> Result<()> {
         cancel_auction::cancel_auction(ctx, auction_id)
     }
 
-    pub fn place_bid(ctx: Context<PlaceBid>, auction_id: u32, bid_amount: u64) -> Result<()> {
-        place_bid::place_bid(ctx, auction_id, bid_amount)
+    pub fn place_bid<'info>(
+        ctx: Context<'_, '_, '_, 'info, PlaceBid<'info>>,
+        auction_id: u32,
+        bid_amount: u64,
+        proxy: bool,
+        permit: Permit,
+    ) -> Result<()> {
+        place_bid::place_bid(ctx, auction_id, bid_amount, proxy, permit)
+    }
+
+    pub fn complete_auction<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CompleteAuction<'info>>,
+        auction_id: u32,
+        reserve_reveal: Option<ReserveReveal>,
+    ) -> Result<()> {
+        complete_auction::complete_auction(ctx, auction_id, reserve_reveal)
+    }
+
+    pub fn commit_sealed_bid<'info>(
+        ctx: Context<'_, '_, '_, 'info, CommitSealedBid<'info>>,
+        auction_id: u32,
+        commitment: [u8; 32],
+        deposit: u64,
+        permit: Permit,
+    ) -> Result<()> {
+        sealed_bid::commit_sealed_bid(ctx, auction_id, commitment, deposit, permit)
     }
 
-    pub fn complete_auction(ctx: Context<CompleteAuction>, auction_id: u32) -> Result<()> {
-        complete_auction::complete_auction(ctx, auction_id)
+    pub fn reveal_sealed_bid(
+        ctx: Context<RevealSealedBid>,
+        auction_id: u32,
+        amount: u64,
+        salt: [u8; 32],
+    ) -> Result<()> {
+        sealed_bid::reveal_sealed_bid(ctx, auction_id, amount, salt)
+    }
+
+    pub fn buy_now<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BuyNow<'info>>,
+        auction_id: u32,
+        permit: Permit,
+    ) -> Result<()> {
+        buy_now::buy_now(ctx, auction_id, permit)
+    }
+
+    pub fn buy_dutch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BuyNow<'info>>,
+        auction_id: u32,
+        permit: Permit,
+    ) -> Result<()> {
+        buy_dutch::buy_dutch(ctx, auction_id, permit)
+    }
+
+    pub fn withdraw_outbid<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawOutbid<'info>>,
+        auction_id: u32,
+    ) -> Result<()> {
+        withdraw_outbid::withdraw_outbid(ctx, auction_id)
+    }
+
+    pub fn list_nft<'info>(
+        ctx: Context<'_, '_, '_, 'info, ListNft<'info>>,
+        price: u64,
+        is_price_mint_sol: bool,
+    ) -> Result<()> {
+        listing::list_nft(ctx, price, is_price_mint_sol)
+    }
+
+    pub fn buy_listing<'info>(
+        ctx: Context<'_, '_, 'info, 'info, BuyListing<'info>>,
+        max_price: u64,
+        permit: Permit,
+    ) -> Result<()> {
+        listing::buy_listing(ctx, max_price, permit)
+    }
+
+    pub fn delist_nft<'info>(ctx: Context<'_, '_, '_, 'info, DelistNft<'info>>) -> Result<()> {
+        listing::delist_nft(ctx)
+    }
+
+    pub fn make_offer<'info>(
+        ctx: Context<'_, '_, '_, 'info, MakeOffer<'info>>,
+        collection: Pubkey,
+        amount: u64,
+        expiry: i64,
+        is_price_mint_sol: bool,
+    ) -> Result<()> {
+        offer::make_offer(ctx, collection, amount, expiry, is_price_mint_sol)
+    }
+
+    pub fn accept_offer<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AcceptOffer<'info>>,
+        min_amount: u64,
+        permit: Permit,
+    ) -> Result<()> {
+        offer::accept_offer(ctx, min_amount, permit)
+    }
+
+    pub fn cancel_offer<'info>(ctx: Context<'_, '_, '_, 'info, CancelOffer<'info>>) -> Result<()> {
+        offer::cancel_offer(ctx)
     }
 
     pub fn withdraw_sol_fees(ctx: Context<WithdrawSolFees>, amount: u64) -> Result<()> {
         withdraw_sol_fees::withdraw_sol_fees(ctx, amount)
     }
 
-    pub fn withdraw_spl_fees(ctx: Context<WithdrawSplFees>, amount: u64) -> Result<()> {
+    pub fn withdraw_spl_fees<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawSplFees<'info>>,
+        amount: u64,
+    ) -> Result<()> {
         withdraw_spl_fees::withdraw_spl_fees(ctx, amount)
     }
 }
@@ -1,8 +1,11 @@
 use anchor_lang::prelude::*;
+use crate::constants::MAX_BUNDLED_NFTS;
 
 #[account]
 #[derive(InitSpace)]
 pub struct AuctionConfig {
+    pub version: u8, // account layout version, bumped whenever the layout changes
+
     pub auction_owner: Pubkey,
     pub auction_admin: Pubkey,
 
@@ -16,14 +19,51 @@ pub struct AuctionConfig {
     pub maximum_time_extension: u32,
 
     pub auction_count: u32,
-    pub pause_flags: u8,
+    pub pause_flags: u64, // pause the function using bit masking, see the `*_PAUSE` indexes
 
     pub config_bump: u8,
+
+    pub pending_owner: Option<Pubkey>, // proposed owner, only takes over once it calls `accept_owner`
+
+    pub config_change_delay: u32, // seconds a queued config change waits, never below `MINIMUM_CONFIG_CHANGE_DELAY`
+    pub pending_config_data: Option<PendingAuctionConfigData>, // queued change, applied only after its `effective_at`
+
+    pub allowed_mint_extensions: u8, // risky Token-2022 extensions accepted on any mint, see the `*_EXTENSION` indexes
+
+    pub require_verified_collection: bool, // NFT prizes must belong to a verified collection on the allowlist
+
+    pub buy_now_cutoff_bps: u16, // buy now closes once the highest bid reaches this share of its price, 0 => only at the price itself
+
+    pub sealed_reveal_period: u32, // seconds after `end_time` to reveal sealed bids, 0 => sealed auctions disabled
+    pub sealed_penalty_bps: u16, // share of the deposit an unrevealed sealed bid forfeits to the fee treasury
+
+    // bounds on each auction's `ExtensionPolicy`, 0 => that setting is uncapped
+    pub maximum_extension_window: u32,
+    pub maximum_total_extension: u32,
+    pub maximum_extension_count: u16,
+
+    pub keeper_fee_bps: u16, // share of the commission paid to whoever settles an auction through `complete_auction`
+
+    pub reserved: [u8; 23], // zeroed space for future fields, new fields must decode `0` as their default
+}
+
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct PendingAuctionConfigData {
+    pub creation_fee_lamports: u64,
+    pub commission_bps: u16,
+    pub minimum_auction_period: u32,
+    pub maximum_auction_period: u32,
+    pub minimum_time_extension: u32,
+    pub maximum_time_extension: u32,
+    pub config_change_delay: u32,
+    pub effective_at: i64, // unix timestamp from which the change can be applied
 }
 
 #[account]
 #[derive(InitSpace)]
 pub struct Auction {
+    pub version: u8, // layout version, see `AUCTION_VERSION`
+
     pub auction_id: u32,
     pub creator: Pubkey,
 
@@ -34,10 +74,10 @@ pub struct Auction {
     pub end_time: i64,
 
     pub bid_mint: Option<Pubkey>, // None => SOL
-    // check bid_escrow.owner == auction and bid_escrow.mint == bid_mint, while checking for bid escrow
+    // bids are held per bidder in `BidEscrow`, check its token account's owner and mint == bid_mint for SPL
 
     pub base_bid: u64,
-    pub min_increment: u64,     // min bidding increment
+    pub min_increment: u64,     // min bidding increment, the floor under a percentage increment
     pub time_extension: u32, 
  
     pub highest_bid_amount: u64,
@@ -46,6 +86,188 @@ pub struct Auction {
 
     pub status: AuctionState,
     pub auction_bump: u8,
+
+    pub commission_bps: u16, // config commission in effect at `create_auction`, later config changes never reach a live auction
+
+    pub frozen: bool, // halts this auction alone, every instruction on it fails until it is unfrozen
+
+    pub reserve_price: u64, // the prize only sells at or above this, 0 => no public reserve
+    pub reserve_commitment: [u8; 32], // `utils::reserve_commitment` of a hidden reserve, zeroed when there is none
+
+    pub buy_now_price: u64, // instant sale price through `buy_now`, 0 => not offered
+
+    // sealed-bid (Vickrey) mode: bids are committed until `end_time`, revealed for `reveal_period` seconds after,
+    // and the highest revealed bid wins at `second_bid_amount`
+    pub sealed: bool,
+    pub reveal_period: u32,
+    pub second_bid_amount: u64, // second highest revealed bid, `base_bid` until two bids are revealed
+    pub sealed_penalty_bps: u16, // config penalty in effect at `create_auction`
+    pub sealed_commit_count: u32, // committed sealed bids, revealed or not
+
+    // Dutch mode: no bids, the price falls from `dutch_start_price` to `dutch_floor_price` in equal steps
+    // every `dutch_decay_interval` seconds between `start_time` and `end_time`, see `utils::dutch_price`
+    pub dutch_start_price: u64, // 0 => not a Dutch auction
+    pub dutch_floor_price: u64,
+    pub dutch_decay_interval: u32,
+
+    // a fungible lot auctions `lot_amount` raw units of an SPL token, an NFT prize is always a lot of 1.
+    // Metaplex royalties are only paid on NFT prizes
+    pub lot_amount: u64,
+    pub fungible_prize: bool,
+
+    pub bundle_size: u8, // NFTs in the `AuctionBundle` besides `prize_mint`, 0 => a single prize
+
+    // anti-sniping: a bid within `extension_window` seconds of `end_time` pushes it out by `time_extension`,
+    // at most `max_extensions` times and never past `max_end_time`
+    pub extension_window: u32, // 0 => `time_extension`
+    pub max_end_time: i64,
+    pub max_extensions: u16,
+    pub extension_count: u16,
+    // the leader's escrowed maximum, above `highest_bid_amount` while they bid by proxy. Open auctions only
+    pub highest_bid_max: u64,
+    pub min_increment_bps: u16, // bids must also raise `highest_bid_amount` by this share of it, 0 => fixed increment
+
+    pub reserved: [u8; 128], // same rules as `AuctionConfig::reserved`
+}
+
+// seeds = "auction" + "permit" + `user address` + `nonce`
+// existing at all marks the nonce as spent, so a permit can never be replayed
+#[account]
+#[derive(InitSpace)]
+pub struct PermitNonce {
+    pub user: Pubkey,
+    pub nonce: u64,
+    pub used_at: i64,
+    pub nonce_bump: u8,
+}
+
+// seeds = "auction" + `auction id` + "bid" + `bidder address`
+// holds what the bidder has bid: SOL as lamports on this account, SPL in a `bid_mint` token account it owns.
+// A proxy bid escrows the bidder's maximum here, only the winning price is spent and the rest refunded.
+// The highest bid is spent by `complete_auction`, an outbid bidder pulls theirs back with `withdraw_outbid`
+#[account]
+#[derive(InitSpace)]
+pub struct BidEscrow {
+    pub auction_id: u32,
+    pub bidder: Pubkey,
+    pub bid_mint: Option<Pubkey>, // copied from the auction, which is closed before losing bids are withdrawn
+    pub amount: u64,              // escrowed bid, net of any Token-2022 transfer fee
+    pub escrow_bump: u8,
+
+    // sealed auctions only, `amount` is then the deposit locked behind the commitment
+    pub sealed_commitment: [u8; 32], // `utils::sealed_bid_commitment`, zeroed for open bids
+    pub revealed: bool,
+    pub penalty_bps: u16, // forfeited on withdrawal if the bid was never revealed
+}
+
+// seeds = "auction" + `auction id` + "bundle"
+// the NFTs sold together with `Auction::prize_mint`, each held in a token account the auction owns.
+// They go wherever the main prize goes, royalties follow the main prize's metadata alone
+#[account]
+#[derive(InitSpace)]
+pub struct AuctionBundle {
+    pub auction_id: u32,
+    #[max_len(MAX_BUNDLED_NFTS)]
+    pub mints: Vec<Pubkey>,
+    pub bundle_bump: u8,
+}
+
+// seeds = "auction" + "listing" + `mint address`
+// a fixed-price sale of the NFT held in the listing's token account, closed once it sells or is delisted
+#[account]
+#[derive(InitSpace)]
+pub struct Listing {
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub price_mint: Option<Pubkey>, // None => SOL
+    pub price: u64,
+    pub commission_bps: u16, // config commission in effect at `list_nft`
+    pub listed_at: i64,
+    pub listing_bump: u8,
+}
+
+// seeds = "auction" + "offer" + `collection mint address` + `buyer address`
+// a standing offer for any NFT of a verified collection: SOL as lamports on this account, SPL in a
+// `price_mint` token account it owns. Closed once an NFT is sold into it or the buyer cancels
+#[account]
+#[derive(InitSpace)]
+pub struct Offer {
+    pub buyer: Pubkey,
+    pub collection: Pubkey,
+    pub price_mint: Option<Pubkey>, // None => SOL
+    pub amount: u64,         // escrowed, net of any Token-2022 transfer fee
+    pub commission_bps: u16, // config commission in effect at `make_offer`
+    pub expiry: i64,         // unix timestamp after which the offer can't be accepted, 0 => never expires
+    pub created_at: i64,
+    pub offer_bump: u8,
+}
+
+// seeds = "auction" + "mint_allowlist" + `mint address`
+// existing at all means the owner vetted the mint, it skips the extension policy at creation
+#[account]
+#[derive(InitSpace)]
+pub struct MintAllowlist {
+    pub mint: Pubkey,
+    pub allowed_at: i64,
+    pub allowlist_bump: u8,
+}
+
+// seeds = "auction" + "collection_allowlist" + `collection mint address`
+// existing lets NFTs of that verified collection in, only checked when the config requires a collection
+#[account]
+#[derive(InitSpace)]
+pub struct CollectionAllowlist {
+    pub collection: Pubkey,
+    pub allowed_at: i64,
+    pub allowlist_bump: u8,
+}
+
+// signed off-chain by the auction admin over `utils::permit_message`, verified via the Ed25519 program
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct Permit {
+    pub max_amount: u64, // upper bound on the bid or price paid with this permit
+    pub expiry: i64,     // unix timestamp after which the permit is rejected
+    pub nonce: u64,      // unique per user, spent through `PermitNonce`
+}
+
+// opens `Auction::reserve_commitment` at `complete_auction`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct ReserveReveal {
+    pub price: u64,
+    pub salt: [u8; 32], // keeps a low reserve from being brute forced out of the commitment
+}
+
+// schedule, increments and prices of an auction, passed to `create_auction`, `update_auction` and
+// `relist_auction`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct AuctionParams {
+    pub start_time: i64,
+    pub end_time: i64,
+    pub start_immediately: bool,       // `start_time` is taken as now
+    pub base_bid: u64,                 // base bid can be zero also
+    pub min_increment: u64,            // fixed increment, or the floor under `min_increment_bps`
+    pub min_increment_bps: u16,        // increment as a share of the highest bid, 0 => `min_increment` alone
+    pub time_extension: u32,
+    pub reserve_price: u64,            // 0 => no public reserve
+    pub reserve_commitment: [u8; 32],  // zeroed => no hidden reserve
+    pub buy_now_price: u64,            // 0 => no buy now
+    pub extension_policy: ExtensionPolicy,
+}
+
+// anti-sniping settings of an auction, part of its `AuctionParams`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct ExtensionPolicy {
+    pub window: u32, // seconds before the end in which a bid extends it, 0 => `time_extension`
+    pub max_total_extension: u32, // seconds the end can move past the scheduled `end_time` in total, 0 => the config bound
+    pub max_extensions: u16,      // 0 => the config bound
+}
+
+// price schedule of a Dutch auction, passed to `create_auction`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct DutchSchedule {
+    pub start_price: u64,
+    pub floor_price: u64,
+    pub decay_interval: u32, // seconds between price drops
 }
 
 #[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
@@ -58,3 +280,103 @@ pub enum AuctionState {
     CompletedSuccessfully = 4,
     CompletedFailed = 5,
 }
+
+// Legacy layouts, as deployed before the `version` byte and reserved space were added.
+// Only read by the `migrate_*` instructions, never written.
+#[derive(InitSpace, AnchorDeserialize)]
+pub struct AuctionConfigV0 {
+    pub auction_owner: Pubkey,
+    pub auction_admin: Pubkey,
+    pub creation_fee_lamports: u64,
+    pub commission_bps: u16,
+    pub minimum_auction_period: u32,
+    pub maximum_auction_period: u32,
+    pub minimum_time_extension: u32,
+    pub maximum_time_extension: u32,
+    pub auction_count: u32,
+    pub pause_flags: u8,
+    pub config_bump: u8,
+}
+
+// Version 1 config, before `pause_flags` was widened to u64. Same total size as version 2.
+#[derive(InitSpace, AnchorDeserialize)]
+pub struct AuctionConfigV1 {
+    pub version: u8,
+    pub auction_owner: Pubkey,
+    pub auction_admin: Pubkey,
+    pub creation_fee_lamports: u64,
+    pub commission_bps: u16,
+    pub minimum_auction_period: u32,
+    pub maximum_auction_period: u32,
+    pub minimum_time_extension: u32,
+    pub maximum_time_extension: u32,
+    pub auction_count: u32,
+    pub pause_flags: u8,
+    pub config_bump: u8,
+    pub pending_owner: Option<Pubkey>,
+    pub config_change_delay: u32,
+    pub pending_config_data: Option<PendingAuctionConfigData>,
+    pub reserved: [u8; 52],
+}
+
+#[derive(InitSpace, AnchorDeserialize)]
+pub struct AuctionV0 {
+    pub auction_id: u32,
+    pub creator: Pubkey,
+    pub prize_mint: Pubkey,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub bid_mint: Option<Pubkey>,
+    pub base_bid: u64,
+    pub min_increment: u64,
+    pub time_extension: u32,
+    pub highest_bid_amount: u64,
+    pub highest_bidder: Pubkey,
+    pub has_any_bid: bool,
+    pub status: AuctionState,
+    pub auction_bump: u8,
+}
+
+// Version 1 auction, its 128 reserved bytes filled up to the last 2. Fields carved out after the auction
+// was created decode as 0
+#[derive(InitSpace, AnchorDeserialize)]
+pub struct AuctionV1 {
+    pub version: u8,
+    pub auction_id: u32,
+    pub creator: Pubkey,
+    pub prize_mint: Pubkey,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub bid_mint: Option<Pubkey>,
+    pub base_bid: u64,
+    pub min_increment: u64,
+    pub time_extension: u32,
+    pub highest_bid_amount: u64,
+    pub highest_bidder: Pubkey,
+    pub has_any_bid: bool,
+    pub status: AuctionState,
+    pub auction_bump: u8,
+    pub commission_bps: u16,
+    pub frozen: bool,
+    pub reserve_price: u64,
+    pub reserve_commitment: [u8; 32],
+    pub buy_now_price: u64,
+    pub sealed: bool,
+    pub reveal_period: u32,
+    pub second_bid_amount: u64,
+    pub sealed_penalty_bps: u16,
+    pub sealed_commit_count: u32,
+    pub dutch_start_price: u64,
+    pub dutch_floor_price: u64,
+    pub dutch_decay_interval: u32,
+    pub lot_amount: u64,
+    pub fungible_prize: bool,
+    pub bundle_size: u8,
+    pub extension_window: u32,
+    pub max_end_time: i64,
+    pub max_extensions: u16,
+    pub extension_count: u16,
+    pub highest_bid_max: u64,
+    pub min_increment_bps: u16,
+    pub reserved: [u8; 2],
+}
@@ -1,6 +1,10 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::Mint;
-use crate::errors::AuctionStateErrors;
+use solana_sha256_hasher::hashv;
+use solana_instructions_sysvar::{load_current_index_checked, load_instruction_at_checked};
+use crate::constants::{FEE_MANTISSA, PERMIT_MESSAGE_LEN};
+use crate::errors::{AuctionStateErrors, ConfigStateErrors, PermitErrors};
+use crate::states::{Auction, AuctionConfig, AuctionParams, ExtensionPolicy, Permit, ReserveReveal};
 
 pub fn get_pct_amount(amount: u64, pct: u64, base: u64) -> Result<u64> {
     let mul = amount
@@ -10,16 +14,279 @@ pub fn get_pct_amount(amount: u64, pct: u64, base: u64) -> Result<u64> {
     Ok(div)
 }
 
-pub fn validate_nft(mint: &InterfaceAccount<Mint>) -> Result<()> {
+// a single-supply, 0-decimal mint is auctioned as an NFT, anything else as a fungible lot
+pub fn is_nft_mint(mint: &InterfaceAccount<Mint>) -> bool {
+    mint.decimals == 0 && mint.supply == 1
+}
+
+// weather the fucntion is paused or not
+pub fn is_paused(pause_flags: u64, index: u8) -> bool {
+    let mask = 1u64 << index; // set the bit at `index`
+    (pause_flags & mask) != 0 // check if it's active
+}
+
+// sha256 of `price (le) | salt`, what the creator commits to for a hidden reserve
+pub fn reserve_commitment(reveal: &ReserveReveal) -> [u8; 32] {
+    hashv(&[reveal.price.to_le_bytes().as_ref(), reveal.salt.as_ref()]).to_bytes()
+}
+
+// sha256 of `auction id (le) | bidder | amount (le) | salt`, what a bidder commits to in a sealed auction
+pub fn sealed_bid_commitment(auction_id: u32, bidder: &Pubkey, amount: u64, salt: &[u8; 32]) -> [u8; 32] {
+    hashv(&[
+        auction_id.to_le_bytes().as_ref(),
+        bidder.as_ref(),
+        amount.to_le_bytes().as_ref(),
+        salt.as_ref(),
+    ])
+    .to_bytes()
+}
+
+// The lowest price a sale can go through at, a hidden reserve only once revealed
+pub fn reserve_floor(auction: &Auction, reveal: Option<ReserveReveal>) -> u64 {
+    if auction.reserve_commitment == [0u8; 32] {
+        auction.reserve_price
+    } else {
+        reveal.map_or(0, |reveal| reveal.price)
+    }
+}
+
+// Smallest raise a bid must put on `price`: `min_increment_bps` of it but at least
+// `min_increment`, and never below one unit so a bid always moves the price
+pub fn min_bid_increment(auction: &Auction, price: u64) -> Result<u64> {
+    let pct_increment = get_pct_amount(
+        price,
+        auction.min_increment_bps as u64,
+        FEE_MANTISSA as u64,
+    )?;
+
+    Ok(pct_increment.max(auction.min_increment).max(1))
+}
+
+// Check an auction's schedule and prices against the config and the auction's mode, which has to be
+// set already, and apply them. An auction without bids tracks the base bid as its highest
+pub fn apply_auction_params(
+    auction: &mut Auction,
+    config: &AuctionConfig,
+    params: &AuctionParams,
+    now: i64,
+) -> Result<()> {
+    let start_time = if params.start_immediately {
+        now
+    } else {
+        params.start_time
+    };
+    require_gte!(start_time, now, AuctionStateErrors::StartTimeInPast);
+
+    let duration = params
+        .end_time
+        .checked_sub(start_time)
+        .ok_or(AuctionStateErrors::StartTimeExceedEndTime)?;
+    require!(
+        duration >= config.minimum_auction_period as i64
+            && duration <= config.maximum_auction_period as i64,
+        ConfigStateErrors::InvalidAuctionPeriod
+    );
+    require!(
+        params.time_extension >= config.minimum_time_extension
+            && params.time_extension <= config.maximum_time_extension,
+        ConfigStateErrors::InvalidTimeExtension
+    );
+
+    // some increment must be set, a percentage one at most 100%
+    require!(
+        params.min_increment > 0 || params.min_increment_bps > 0,
+        AuctionStateErrors::InvalidZeroAmount
+    );
+    require_gte!(
+        FEE_MANTISSA,
+        params.min_increment_bps,
+        AuctionStateErrors::InvalidBidIncrement
+    );
+
+    require!(
+        params.reserve_price == 0 || params.reserve_commitment == [0u8; 32],
+        AuctionStateErrors::InvalidReserve
+    );
+    // buy now closes on the visible highest bid, a sealed auction has none
+    require!(
+        params.buy_now_price == 0
+            || (params.buy_now_price > params.base_bid
+                && params.buy_now_price >= params.reserve_price
+                && !auction.sealed),
+        AuctionStateErrors::InvalidBuyNowPrice
+    );
+    // bundled NFTs leave with the main prize, buy now only moves the one
+    require!(
+        auction.bundle_size == 0 || params.buy_now_price == 0,
+        AuctionStateErrors::InvalidBundle
+    );
+    // the floor is the reserve, and a Dutch price still has to take at least one step before the end
+    if auction.dutch_start_price > 0 {
+        require!(
+            duration >= auction.dutch_decay_interval as i64
+                && params.reserve_price == 0
+                && params.reserve_commitment == [0u8; 32]
+                && params.buy_now_price == 0,
+            AuctionStateErrors::InvalidDutchSchedule
+        );
+    }
+
+    auction.start_time = start_time;
+    auction.end_time = params.end_time;
+    auction.base_bid = params.base_bid;
+    auction.min_increment = params.min_increment;
+    auction.min_increment_bps = params.min_increment_bps;
+    auction.time_extension = params.time_extension;
+    auction.reserve_price = params.reserve_price;
+    auction.reserve_commitment = params.reserve_commitment;
+    auction.buy_now_price = params.buy_now_price;
+    auction.highest_bid_amount = params.base_bid;
+    auction.second_bid_amount = params.base_bid;
+    apply_extension_policy(auction, config, &params.extension_policy)
+}
+
+// Check an auction's anti-sniping settings against the config and apply them, capping the end
+// relative to `auction.end_time`. A 0 config bound leaves that setting uncapped, a 0 policy cap
+// takes the config bound
+pub fn apply_extension_policy(
+    auction: &mut Auction,
+    config: &AuctionConfig,
+    policy: &ExtensionPolicy,
+) -> Result<()> {
+    let within = |value: u32, bound: u32| bound == 0 || value <= bound;
     require!(
-        mint.decimals == 0 && mint.supply == 1,
-        AuctionStateErrors::InvalidNFT
+        within(policy.window, config.maximum_extension_window)
+            && within(policy.max_total_extension, config.maximum_total_extension)
+            && within(policy.max_extensions as u32, config.maximum_extension_count as u32),
+        ConfigStateErrors::InvalidExtensionPolicy
     );
+
+    auction.extension_window = policy.window;
+    auction.max_end_time = match (policy.max_total_extension, config.maximum_total_extension) {
+        (0, 0) => i64::MAX,
+        (0, cap) | (cap, _) => auction
+            .end_time
+            .checked_add(cap as i64)
+            .ok_or(AuctionStateErrors::Overflow)?,
+    };
+    auction.max_extensions = match (policy.max_extensions, config.maximum_extension_count) {
+        (0, 0) => u16::MAX,
+        (0, cap) | (cap, _) => cap,
+    };
+    auction.extension_count = 0;
+
     Ok(())
 }
 
-// weather the fucntion is paused or not
-pub fn is_paused(pause_flags: u8, index: u8) -> bool {
-    let mask = 1u8 << index; // set the bit at `index`
-    (pause_flags & mask) != 0 // check if it's active
+// Current price of a Dutch auction: the start price until the first interval passes, then one equal step
+// down per `dutch_decay_interval`, landing on the floor at the last full interval before `end_time`
+pub fn dutch_price(auction: &Auction, now: i64) -> Result<u64> {
+    let interval = auction.dutch_decay_interval as i64;
+    let elapsed = now.saturating_sub(auction.start_time).max(0);
+    let total_steps = (auction.end_time - auction.start_time) / interval;
+    let steps = (elapsed / interval).min(total_steps);
+
+    let drop = (auction.dutch_start_price - auction.dutch_floor_price) as u128 * steps as u128
+        / total_steps as u128;
+    auction
+        .dutch_start_price
+        .checked_sub(drop as u64)
+        .ok_or(AuctionStateErrors::Overflow.into())
+}
+
+// Whether `final_price` clears the auction's reserve. A hidden reserve only counts once revealed,
+// one nobody reveals is treated as not met
+pub fn reserve_met(auction: &Auction, final_price: u64, reveal: Option<ReserveReveal>) -> Result<bool> {
+    if auction.reserve_commitment == [0u8; 32] {
+        return Ok(final_price >= auction.reserve_price);
+    }
+
+    match reveal {
+        Some(reveal) => {
+            require!(
+                reserve_commitment(&reveal) == auction.reserve_commitment,
+                AuctionStateErrors::InvalidReserveReveal
+            );
+            Ok(final_price >= reveal.price)
+        }
+        None => Ok(false),
+    }
+}
+
+// Bytes the auction admin signs off-chain to authorize `user` on `subject`: the little-endian id of an
+// auction, or the address of the listing or offer a market sale fills
+pub fn permit_message(subject: &[u8], user: Pubkey, permit: &Permit) -> Vec<u8> {
+    let mut message = Vec::with_capacity(PERMIT_MESSAGE_LEN);
+    message.extend_from_slice(crate::ID.as_ref());
+    message.extend_from_slice(subject);
+    message.extend_from_slice(user.as_ref());
+    message.extend_from_slice(&permit.max_amount.to_le_bytes());
+    message.extend_from_slice(&permit.expiry.to_le_bytes());
+    message.extend_from_slice(&permit.nonce.to_le_bytes());
+    message
+}
+
+// Check the permit against the Ed25519 instruction placed right before the current one
+// and return the key that signed it. The Ed25519 program has already verified the signature
+// by the time we run, so we only need to bind its key and message to this bid.
+pub fn verify_permit(
+    instructions_sysvar: &AccountInfo,
+    subject: &[u8],
+    user: Pubkey,
+    amount: u64,
+    permit: &Permit,
+) -> Result<Pubkey> {
+    require_gte!(
+        permit.expiry,
+        Clock::get()?.unix_timestamp,
+        PermitErrors::PermitExpired
+    );
+    require_gte!(
+        permit.max_amount,
+        amount,
+        PermitErrors::PermitAmountExceeded
+    );
+
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require_gt!(current_index, 0, PermitErrors::MissingPermitInstruction);
+    let ed25519_ix = load_instruction_at_checked(current_index as usize - 1, instructions_sysvar)?;
+    require_keys_eq!(
+        ed25519_ix.program_id,
+        solana_sdk_ids::ed25519_program::ID,
+        PermitErrors::MissingPermitInstruction
+    );
+
+    // layout: [num signatures, padding, offsets (7 x u16), ...inline data]
+    let data = &ed25519_ix.data;
+    require!(
+        data.len() >= 16 && data[0] == 1,
+        PermitErrors::InvalidPermitInstruction
+    );
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]) as usize;
+
+    // signature, key and message must all live in the Ed25519 instruction itself
+    require!(
+        read_u16(4) == u16::MAX as usize
+            && read_u16(8) == u16::MAX as usize
+            && read_u16(14) == u16::MAX as usize,
+        PermitErrors::InvalidPermitInstruction
+    );
+
+    let public_key_offset = read_u16(6);
+    let message_offset = read_u16(10);
+    let message_size = read_u16(12);
+
+    let signer = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(PermitErrors::InvalidPermitInstruction)?;
+    let message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(PermitErrors::InvalidPermitInstruction)?;
+
+    require!(
+        message == permit_message(subject, user, permit).as_slice(),
+        PermitErrors::PermitMessageMismatch
+    );
+
+    Pubkey::try_from(signer).map_err(|_| error!(PermitErrors::InvalidPermitInstruction))
 }
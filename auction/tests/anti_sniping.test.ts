@@ -0,0 +1,198 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { Keypair, SystemProgram } from "@solana/web3.js";
+
+import {
+    createAuctionConfig,
+    createAuction,
+    placeBid,
+    setExtensionBounds,
+    createNftMint,
+    createAta,
+    mintTokens,
+    getCurrentTimestamp,
+    warpForward,
+    ExtensionPolicy,
+    noExtensions,
+} from "./helpers";
+
+import {
+    auction_owner,
+    auction_admin,
+    auction_1_creator,
+    creation_fee_lamports,
+    commission_bps,
+    minimum_auction_period,
+    maximum_auction_period,
+    minimum_time_extension,
+    maximum_time_extension,
+    setProgram,
+    setProvider,
+    auctionConfigPda,
+    auctionPda,
+} from "./values";
+
+import type { Auction } from "../target/types/auction";
+
+describe("Anti-sniping extensions", () => {
+    let context: any;
+    let provider: BankrunProvider;
+    let program: anchor.Program<Auction>;
+
+    // bids in the last 15 minutes extend, at most twice and by 30 minutes in total
+    const maxWindow = 20 * 60;
+    const policy = { window: 15 * 60, maxTotalExtension: 30 * 60, maxExtensions: 2 };
+
+    before(async () => {
+        context = await startAnchor("", [], []);
+        provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+
+        program = anchor.workspace.Auction as anchor.Program<Auction>;
+        setProgram(program);
+
+        for (const kp of [auction_owner, auction_admin, auction_1_creator]) {
+            await context.setAccount(kp.publicKey, {
+                lamports: 20_000_000_000,
+                owner: SystemProgram.programId,
+                executable: false,
+                data: Buffer.alloc(0),
+            });
+        }
+
+        await createAuctionConfig(program, auction_owner, auction_admin.publicKey, {
+            creationFeeLamports: creation_fee_lamports,
+            commision_bps: commission_bps,
+            minPeriod: minimum_auction_period,
+            maxPeriod: maximum_auction_period,
+            minTimeExtension: minimum_time_extension,
+            maxTimeExtension: maximum_time_extension,
+        });
+        await setExtensionBounds(program, auction_owner, maxWindow, 60 * 60, 3);
+    });
+
+    async function fundedKeypair() {
+        const kp = Keypair.generate();
+        await context.setAccount(kp.publicKey, {
+            lamports: 20_000_000_000,
+            owner: SystemProgram.programId,
+            executable: false,
+            data: Buffer.alloc(0),
+        });
+        return kp;
+    }
+
+    // live SOL auction running for exactly `minimum_auction_period`, each bid from a fresh bidder
+    async function liveAuction(extensionPolicy: ExtensionPolicy) {
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        const prizeMint = await createNftMint();
+        const creatorPrizeAta = await createAta(prizeMint, auction_1_creator.publicKey);
+        const prizeEscrow = await createAta(prizeMint, auctionPda(cfg.auctionCount));
+        await mintTokens(prizeMint, creatorPrizeAta, 1);
+
+        const start = await getCurrentTimestamp();
+        const { auctionId } = await createAuction(program, {
+            creator: auction_1_creator,
+            auctionAdmin: auction_admin,
+            prizeMint,
+            bidMint: null,
+            creatorPrizeAta,
+            prizeEscrow,
+            startTime: start,
+            endTime: start + minimum_auction_period,
+            startImmediately: true,
+            baseBid: 1_000_000_000,
+            minIncrement: 500_000_000,
+            timeExtension: minimum_time_extension,
+            extensionPolicy,
+        });
+
+        // SOL bids never touch the token accounts, the prize escrow stands in for them
+        let bidAmount = 1_000_000_000;
+        const bid = async () => {
+            bidAmount += 500_000_000;
+            await placeBid(program, {
+                auctionId,
+                bidder: await fundedKeypair(),
+                auctionAdmin: auction_admin,
+                bidAmount,
+                bidMint: prizeMint,
+                bidderAta: prizeEscrow,
+                bidEscrowAta: prizeEscrow,
+            });
+            return program.account.auction.fetch(auctionPda(auctionId));
+        };
+
+        return { start, bid };
+    }
+
+    it("rejects settings above the config bounds", async () => {
+        await assert.rejects(liveAuction({ ...policy, window: maxWindow + 1 }), /InvalidExtensionPolicy/);
+        await assert.rejects(liveAuction({ ...policy, maxExtensions: 4 }), /InvalidExtensionPolicy/);
+    });
+
+    it("extends only for bids inside the trigger window", async () => {
+        const { start, bid } = await liveAuction(policy);
+
+        let auction = await bid();
+        assert.equal(auction.endTime.toNumber(), start + minimum_auction_period);
+        assert.equal(auction.extensionCount, 0);
+
+        // 12 minutes left, inside the window: the auction now ends `time_extension` after the bid
+        await warpForward(minimum_auction_period - 12 * 60);
+        auction = await bid();
+        assert.equal(auction.endTime.toNumber(), (await getCurrentTimestamp()) + minimum_time_extension);
+        assert.equal(auction.extensionCount, 1);
+    });
+
+    it("stops extending after the maximum number of extensions", async () => {
+        const { bid } = await liveAuction(policy);
+
+        await warpForward(minimum_auction_period - 60);
+        await bid();
+        await warpForward(minimum_time_extension - 60);
+        const extended = await bid();
+        assert.equal(extended.extensionCount, 2);
+
+        await warpForward(minimum_time_extension - 60);
+        const last = await bid();
+        assert.equal(last.extensionCount, 2);
+        assert.equal(last.endTime.toNumber(), extended.endTime.toNumber());
+    });
+
+    it("never extends past the total extension cap", async () => {
+        const maxTotalExtension = 5 * 60;
+        const { start, bid } = await liveAuction({ ...policy, maxTotalExtension });
+
+        await warpForward(minimum_auction_period - 60);
+        const extended = await bid();
+        assert.equal(extended.endTime.toNumber(), start + minimum_auction_period + maxTotalExtension);
+        assert.equal(extended.maxEndTime.toNumber(), extended.endTime.toNumber());
+        assert.equal(extended.extensionCount, 1);
+
+        // at the cap a bid in the window leaves the end where it is
+        await warpForward(maxTotalExtension);
+        const last = await bid();
+        assert.equal(last.endTime.toNumber(), extended.endTime.toNumber());
+        assert.equal(last.extensionCount, 1);
+    });
+
+    it("still extends under the default, uncapped config", async () => {
+        await setExtensionBounds(program, auction_owner, 0, 0, 0);
+        const { start, bid } = await liveAuction(noExtensions);
+
+        let auction = await bid();
+        assert.equal(auction.maxEndTime.toString(), "9223372036854775807");
+        assert.equal(auction.maxExtensions, 65535);
+
+        // 2 minutes left: the bid pushes the end out by `time_extension`
+        await warpForward(minimum_auction_period - 2 * 60);
+        auction = await bid();
+        assert.equal(auction.endTime.toNumber(), (await getCurrentTimestamp()) + minimum_time_extension);
+        assert.ok(auction.endTime.toNumber() > start + minimum_auction_period);
+        assert.equal(auction.extensionCount, 1);
+    });
+});
@@ -1,3 +1,4 @@
+import assert from "assert";
 import * as anchor from "@coral-xyz/anchor";
 import { startAnchor } from "solana-bankrun";
 import { BankrunProvider } from "anchor-bankrun";
@@ -15,10 +16,13 @@ import {
     maximum_auction_period,
     minimum_time_extension,
     maximum_time_extension,
+    minimum_config_change_delay,
+    UPDATE_CONFIG_PAUSE,
 } from "./values";
 
 import {
     createAuctionConfig,
+    warpForward,
 } from "./helpers";
 
 describe("Auction Config Tests", () => {
@@ -126,56 +130,131 @@ describe("Auction Config Tests", () => {
     //     }
     // });
 
-    // it("Update Auction Owner", async () => {
-    //     const newOwner = Keypair.generate();
-    //     await context.setAccount(newOwner.publicKey, {
-    //         lamports: 5_000_000_000,
-    //         owner: SystemProgram.programId,
-    //         executable: false,
-    //         data: Buffer.alloc(0),
-    //     });
-
-    //     await program.methods
-    //         .updateAuctionOwner(newOwner.publicKey)
-    //         .accounts({
-    //             auctionConfig: auctionConfigPda(),
-    //             auctionOwner: auction_owner.publicKey,
-    //         })
-    //         .signers([auction_owner])
-    //         .rpc();
-
-    //     const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
-    //     console.log("New Owner Set:", cfg.auctionOwner.toBase58());
-
-    //     if (cfg.auctionOwner.toBase58() !== newOwner.publicKey.toBase58()) {
-    //         throw new Error("Owner update failed");
-    //     }
-    // });
-
-    // it("Should fail: Unauthorized owner update", async () => {
-    //     const fake = Keypair.generate();
-    //     await context.setAccount(fake.publicKey, {
-    //         lamports: 5_000_000_000,
-    //         owner: SystemProgram.programId,
-    //         executable: false,
-    //         data: Buffer.alloc(0),
-    //     });
+    it("Two-step owner transfer (propose, cancel, accept)", async () => {
+        const newOwner = Keypair.generate();
+
+        await program.methods
+            .proposeOwner(newOwner.publicKey)
+            .accounts({
+                auctionConfig: auctionConfigPda(),
+                auctionOwner: auction_owner.publicKey,
+            })
+            .signers([auction_owner])
+            .rpc();
+
+        let cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        assert.equal(cfg.auctionOwner.toBase58(), auction_owner.publicKey.toBase58());
+        assert.equal(cfg.pendingOwner.toBase58(), newOwner.publicKey.toBase58());
+
+        await program.methods
+            .cancelOwnerProposal()
+            .accounts({
+                auctionConfig: auctionConfigPda(),
+                auctionOwner: auction_owner.publicKey,
+            })
+            .signers([auction_owner])
+            .rpc();
+
+        cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        assert.equal(cfg.pendingOwner, null);
+
+        // nothing pending anymore
+        await assert.rejects(
+            program.methods
+                .acceptOwner()
+                .accounts({
+                    auctionConfig: auctionConfigPda(),
+                    newAuctionOwner: newOwner.publicKey,
+                })
+                .signers([newOwner])
+                .rpc()
+        );
+
+        await program.methods
+            .proposeOwner(newOwner.publicKey)
+            .accounts({
+                auctionConfig: auctionConfigPda(),
+                auctionOwner: auction_owner.publicKey,
+            })
+            .signers([auction_owner])
+            .rpc();
+
+        await program.methods
+            .acceptOwner()
+            .accounts({
+                auctionConfig: auctionConfigPda(),
+                newAuctionOwner: newOwner.publicKey,
+            })
+            .signers([newOwner])
+            .rpc();
+
+        cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        assert.equal(cfg.auctionOwner.toBase58(), newOwner.publicKey.toBase58());
+        assert.equal(cfg.pendingOwner, null);
+
+        // give ownership back to the shared test owner
+        await program.methods
+            .proposeOwner(auction_owner.publicKey)
+            .accounts({
+                auctionConfig: auctionConfigPda(),
+                auctionOwner: newOwner.publicKey,
+            })
+            .signers([newOwner])
+            .rpc();
+        await program.methods
+            .acceptOwner()
+            .accounts({
+                auctionConfig: auctionConfigPda(),
+                newAuctionOwner: auction_owner.publicKey,
+            })
+            .signers([auction_owner])
+            .rpc();
+    });
 
-    //     try {
-    //         await program.methods
-    //             .updateAuctionOwner(fake.publicKey)
-    //             .accounts({
-    //                 auctionConfig: auctionConfigPda(),
-    //                 auctionOwner: fake.publicKey, // WRONG OWNER
-    //             })
-    //             .signers([fake])
-    //             .rpc();
-
-    //         throw new Error("Unauthorized update should have failed.");
-    //     } catch (err) {
-    //         console.log("Unauthorized owner update rejected:", err.error?.errorMessage);
-    //     }
-    // });
+    it("Should fail: Unauthorized owner proposal and wrong acceptor", async () => {
+        const fake = Keypair.generate();
+
+        await assert.rejects(
+            program.methods
+                .proposeOwner(fake.publicKey)
+                .accounts({
+                    auctionConfig: auctionConfigPda(),
+                    auctionOwner: fake.publicKey, // WRONG OWNER
+                })
+                .signers([fake])
+                .rpc()
+        );
+
+        const newOwner = Keypair.generate();
+        await program.methods
+            .proposeOwner(newOwner.publicKey)
+            .accounts({
+                auctionConfig: auctionConfigPda(),
+                auctionOwner: auction_owner.publicKey,
+            })
+            .signers([auction_owner])
+            .rpc();
+
+        await assert.rejects(
+            program.methods
+                .acceptOwner()
+                .accounts({
+                    auctionConfig: auctionConfigPda(),
+                    newAuctionOwner: fake.publicKey, // NOT THE PENDING OWNER
+                })
+                .signers([fake])
+                .rpc()
+        );
+
+        await program.methods
+            .cancelOwnerProposal()
+            .accounts({
+                auctionConfig: auctionConfigPda(),
+                auctionOwner: auction_owner.publicKey,
+            })
+            .signers([auction_owner])
+            .rpc();
+    });
 
     // it("Update Auction Admin", async () => {
     //     const newAdmin = Keypair.generate();
@@ -203,57 +282,141 @@ describe("Auction Config Tests", () => {
     //     }
     // });
 
-    // it("Update Config Data (Valid)", async () => {
-    //     const newFee = 50_000_000;
-    //     const newComm = 250; // 2.5%
-
-    //     await program.methods
-    //         .updateConfigData(
-    //             new anchor.BN(newFee),
-    //             newComm,
-    //             1200,
-    //             3600,
-    //             100,
-    //             500,
-    //         )
-    //         .accounts({
-    //             auctionConfig: auctionConfigPda(),
-    //             auctionOwner: auction_owner.publicKey,
-    //         })
-    //         .signers([auction_owner])
-    //         .rpc();
-
-    //     const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+    it("Queued config data applies only after the delay", async () => {
+        const newFee = 50_000_000;
+        const newComm = 250; // 2.5%
+
+        const queue = (comm: number, minPeriod: number, maxPeriod: number, delay: number) =>
+            program.methods
+                .queueConfigData(
+                    new anchor.BN(newFee),
+                    comm,
+                    minPeriod,
+                    maxPeriod,
+                    100,
+                    500,
+                    delay,
+                )
+                .accounts({
+                    auctionConfig: auctionConfigPda(),
+                    auctionOwner: auction_owner.publicKey,
+                })
+                .signers([auction_owner])
+                .rpc();
+
+        const apply = () =>
+            program.methods
+                .applyConfigData()
+                .accounts({
+                    auctionConfig: auctionConfigPda(),
+                    auctionOwner: auction_owner.publicKey,
+                })
+                .signers([auction_owner])
+                .rpc();
+
+        // invalid values and a too short delay never get queued
+        await assert.rejects(queue(newComm, 500, 200, minimum_config_change_delay));
+        await assert.rejects(queue(10_001, 1200, 3600, minimum_config_change_delay), /InvalidCommission/);
+        await assert.rejects(queue(newComm, 1200, 3600, minimum_config_change_delay - 1));
+
+        await queue(newComm, 1200, 3600, minimum_config_change_delay);
+        await assert.rejects(apply());
+
+        let cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        assert.equal(cfg.commissionBps, commission_bps);
+        assert.equal(cfg.pendingConfigData.commissionBps, newComm);
+
+        await warpForward(minimum_config_change_delay);
+        await apply();
+
+        cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        assert.equal(cfg.creationFeeLamports.toNumber(), newFee);
+        assert.equal(cfg.commissionBps, newComm);
+        assert.equal(cfg.minimumAuctionPeriod, 1200);
+        assert.equal(cfg.maximumAuctionPeriod, 3600);
+        assert.equal(cfg.pendingConfigData, null);
+    });
 
-    //     console.log("Updated Fee:", cfg.creationFeeLamports.toNumber());
-    //     console.log("Updated Commission:", cfg.commissionBps);
+    it("Cancelled config data is never applied", async () => {
+        await program.methods
+            .queueConfigData(
+                new anchor.BN(creation_fee_lamports),
+                5_000,
+                minimum_auction_period,
+                maximum_auction_period,
+                minimum_time_extension,
+                maximum_time_extension,
+                minimum_config_change_delay,
+            )
+            .accounts({
+                auctionConfig: auctionConfigPda(),
+                auctionOwner: auction_owner.publicKey,
+            })
+            .signers([auction_owner])
+            .rpc();
+
+        await program.methods
+            .cancelConfigData()
+            .accounts({
+                auctionConfig: auctionConfigPda(),
+                auctionOwner: auction_owner.publicKey,
+            })
+            .signers([auction_owner])
+            .rpc();
+
+        await warpForward(minimum_config_change_delay);
+        await assert.rejects(
+            program.methods
+                .applyConfigData()
+                .accounts({
+                    auctionConfig: auctionConfigPda(),
+                    auctionOwner: auction_owner.publicKey,
+                })
+                .signers([auction_owner])
+                .rpc()
+        );
 
-    //     if (cfg.commissionBps !== newComm) throw new Error("Commission update failed");
-    // });
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        assert.equal(cfg.commissionBps, 250);
+    });
 
-    // it("Should fail: Invalid config update values", async () => {
-    //     try {
-    //         await program.methods
-    //             .updateConfigData(
-    //                 new anchor.BN(100),
-    //                 100,
-    //                 500,
-    //                 200, // INVALID max < min
-    //                 10,
-    //                 20
-    //             )
-    //             .accounts({
-    //                 auctionConfig: auctionConfigPda(),
-    //                 auctionOwner: auction_owner.publicKey,
-    //             })
-    //             .signers([auction_owner])
-    //             .rpc();
-
-    //         throw new Error("Invalid config update should fail");
-    //     } catch (err) {
-    //         console.log("Invalid config update rejected:", err.error?.errorMessage);
-    //     }
-    // });
+    it("Config changes are blocked while UPDATE_CONFIG_PAUSE is set", async () => {
+        const setFlags = (flags: anchor.BN) =>
+            program.methods
+                .updatePauseAndUnpause(flags)
+                .accounts({
+                    auctionConfig: auctionConfigPda(),
+                    auctionOwner: auction_owner.publicKey,
+                })
+                .signers([auction_owner])
+                .rpc();
+
+        await setFlags(new anchor.BN(1).shln(UPDATE_CONFIG_PAUSE));
+        await assert.rejects(
+            program.methods
+                .queueConfigData(
+                    new anchor.BN(creation_fee_lamports),
+                    commission_bps,
+                    minimum_auction_period,
+                    maximum_auction_period,
+                    minimum_time_extension,
+                    maximum_time_extension,
+                    minimum_config_change_delay,
+                )
+                .accounts({
+                    auctionConfig: auctionConfigPda(),
+                    auctionOwner: auction_owner.publicKey,
+                })
+                .signers([auction_owner])
+                .rpc(),
+            /FunctionPaused/
+        );
+
+        await setFlags(new anchor.BN(0));
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        assert.equal(cfg.pauseFlags.toNumber(), 0);
+        assert.equal(cfg.pendingConfigData, null);
+    });
 
     // it("Update Pause Flags", async () => {
     //     await program.methods
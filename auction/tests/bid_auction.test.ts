@@ -9,7 +9,9 @@ import {
     startAuction,
     placeBid,
     createSplMint,
+    createNftMint,
     createAta,
+    createTransferFeeMint,
     mintTokens,
     getSolBalance,
     getTokenBalance,
@@ -18,6 +20,8 @@ import {
     deriveAuctionPda,
     cancelAuction,
     completeAuction,
+    withdrawOutbid,
+    signPermit,
 } from "./helpers";
 
 import {
@@ -35,11 +39,12 @@ import {
     setProvider,
     auctionConfigPda,
     auctionPda,
+    bidEscrowPda,
 } from "./values";
 
 import type { Auction } from "../target/types/auction";
 import assert from "assert";
-import { TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID } from "@solana/spl-token";
 
 describe("Auction Bidding – SOL & SPL", () => {
     let context: any;
@@ -60,7 +65,6 @@ describe("Auction Bidding – SOL & SPL", () => {
     let splCreatorPrizeAta: PublicKey;
     let splPrizeEscrow: PublicKey;
     let splBidMint: PublicKey;
-    let splBidEscrow: PublicKey;
 
     before(async () => {
         context = await startAnchor("", [], []);
@@ -93,7 +97,7 @@ describe("Auction Bidding – SOL & SPL", () => {
 
 
         // 2. SETUP AUCTION A (SOL BID AUCTION)
-        solPrizeMint = await createSplMint(0); // NFT (decimals=0)
+        solPrizeMint = await createNftMint();
         solCreatorPrizeAta = await createAta(solPrizeMint, auction_1_creator.publicKey);
         solPrizeEscrow = await createAta(solPrizeMint, auctionPda(1)); // temporarily but replaced when auction created
 
@@ -122,7 +126,7 @@ describe("Auction Bidding – SOL & SPL", () => {
         solAuctionPda = await deriveAuctionPda(program, solAuctionId);
 
         // 3. SETUP AUCTION B (SPL BID AUCTION)
-        splPrizeMint = await createSplMint(0); // NFT
+        splPrizeMint = await createNftMint();
         splCreatorPrizeAta = await createAta(splPrizeMint, auction_2_creator.publicKey);
         splPrizeEscrow = await createAta(splPrizeMint, auctionPda(2));
         await mintTokens(splPrizeMint, splCreatorPrizeAta, 1);
@@ -130,9 +134,6 @@ describe("Auction Bidding – SOL & SPL", () => {
         // SPL mint used for bidding
         splBidMint = await createSplMint(9);
 
-        // Create SPL bid escrow ATA owned by auction PDA (created AFTER auction init)
-        splBidEscrow = await createAta(splBidMint, auctionPda(2));
-
         const splStart = await getCurrentTimestamp();
         const splEnd = splStart + minimum_auction_period + 100;
 
@@ -193,9 +194,7 @@ describe("Auction Bidding – SOL & SPL", () => {
     //         bidAmount: 2_000_000_000,
     //         bidMint: solPrizeMint,
     //         bidderAta: solPrizeEscrow,
-    //         prevBidder: PublicKey.unique(),
-    //         prevBidderAta: solPrizeEscrow,
-    //         bidEscrow: solPrizeEscrow,
+    //         bidEscrowAta: solPrizeEscrow,
     //     });
 
     //     const solAfterFirstBidder1 = await getSolBalance(bidder.publicKey);
@@ -214,9 +213,7 @@ describe("Auction Bidding – SOL & SPL", () => {
     //         bidAmount: 3_000_000_000,
     //         bidMint: solPrizeMint,
     //         bidderAta: solPrizeEscrow,
-    //         prevBidder: bidder.publicKey,
-    //         prevBidderAta: solPrizeEscrow,
-    //         bidEscrow: solPrizeEscrow,
+    //         bidEscrowAta: solPrizeEscrow,
     //     });
 
     //     const solAfterSecondBidder1 = await getSolBalance(bidder.publicKey);
@@ -257,13 +254,13 @@ describe("Auction Bidding – SOL & SPL", () => {
     //     // COMPLETE AUCTION
     //     await completeAuction(program, {
     //         auctionId: solAuctionId,
-    //         auctionAdmin: auction_admin,
+    //         keeper: auction_admin,
     //         creator: auction_1_creator.publicKey,
     //         winner: winner.publicKey,
     //         prizeMint: solPrizeMint,
     //         bidMint: solPrizeMint,
     //         prizeEscrow: solPrizeEscrow,
-    //         bidEscrow: solPrizeEscrow,
+    //         winnerBidEscrowAta: solPrizeEscrow,
     //         creatorPrizeAta: solCreatorPrizeAta,
     //         winnerPrizeAta: winnerPrizeAta,
     //         bidFeeTreasuryAta: solPrizeEscrow,
@@ -317,11 +314,18 @@ describe("Auction Bidding – SOL & SPL", () => {
         await mintTokens(splBidMint, bidder2Ata, 200_000_000_000);
         await mintTokens(splBidMint, bidder3Ata, 200_000_000_000);
 
+        // every bidder's tokens sit in a token account owned by their own bid escrow
+        const bidder1Escrow = await createAta(splBidMint, bidEscrowPda(splAuctionId, bidder1.publicKey));
+        const bidder2Escrow = await createAta(splBidMint, bidEscrowPda(splAuctionId, bidder2.publicKey));
+        const bidder3Escrow = await createAta(splBidMint, bidEscrowPda(splAuctionId, bidder3.publicKey));
+
         const balances = async () => ({
             b1: await getTokenBalance(bidder1Ata),
             b2: await getTokenBalance(bidder2Ata),
             b3: await getTokenBalance(bidder3Ata),
-            escrow: await getTokenBalance(splBidEscrow)
+            escrow1: await getTokenBalance(bidder1Escrow),
+            escrow2: await getTokenBalance(bidder2Escrow),
+            escrow3: await getTokenBalance(bidder3Escrow),
         });
 
         console.log("\n---- INITIAL BALANCES ----");
@@ -337,9 +341,7 @@ describe("Auction Bidding – SOL & SPL", () => {
             bidAmount: 20_000_000_000, // 20 tokens
             bidMint: splBidMint,
             bidderAta: bidder1Ata,
-            prevBidder: PublicKey.unique(),
-            prevBidderAta: bidder1Ata,
-            bidEscrow: splBidEscrow,
+            bidEscrowAta: bidder1Escrow,
         });
 
         console.log(await balances());
@@ -354,9 +356,7 @@ describe("Auction Bidding – SOL & SPL", () => {
             bidAmount: 25_000_000_000,
             bidMint: splBidMint,
             bidderAta: bidder2Ata,
-            prevBidder: bidder1.publicKey,
-            prevBidderAta: bidder1Ata,
-            bidEscrow: splBidEscrow,
+            bidEscrowAta: bidder2Escrow,
         });
 
         console.log(await balances());
@@ -371,9 +371,7 @@ describe("Auction Bidding – SOL & SPL", () => {
             bidAmount: 30_000_000_000,
             bidMint: splBidMint,
             bidderAta: bidder1Ata,
-            prevBidder: bidder2.publicKey,
-            prevBidderAta: bidder2Ata,
-            bidEscrow: splBidEscrow,
+            bidEscrowAta: bidder1Escrow,
         });
 
         console.log(await balances());
@@ -388,9 +386,7 @@ describe("Auction Bidding – SOL & SPL", () => {
             bidAmount: 40_000_000_000,
             bidMint: splBidMint,
             bidderAta: bidder3Ata,
-            prevBidder: bidder1.publicKey,
-            prevBidderAta: bidder1Ata,
-            bidEscrow: splBidEscrow,
+            bidEscrowAta: bidder3Escrow,
         });
 
         console.log(await balances());
@@ -404,12 +400,44 @@ describe("Auction Bidding – SOL & SPL", () => {
                 bidAmount: 40_000_000_001,
                 bidMint: splBidMint,
                 bidderAta: bidder1Ata,
-                prevBidder: bidder3.publicKey,
-                prevBidderAta: bidder3Ata,
-                bidEscrow: splBidEscrow,
+                bidEscrowAta: bidder1Escrow,
             })
         );
 
+        console.log("\n---- BID #1 try with permits the admin did not sign for this bid ----");
+        const bidAttempt = {
+            auctionId: splAuctionId,
+            bidder: bidder1,
+            auctionAdmin: auction_admin,
+            bidAmount: 45_000_000_000,
+            bidMint: splBidMint,
+            bidderAta: bidder1Ata,
+            bidEscrowAta: bidder1Escrow,
+        };
+
+        const forged = await signPermit(program, splAuctionId, bidder1.publicKey, 45_000_000_000, Keypair.generate());
+        await assert.rejects(placeBid(program, { ...bidAttempt, signed: forged }), /InvalidPermitSigner/);
+
+        const capped = await signPermit(program, splAuctionId, bidder1.publicKey, 44_000_000_000, auction_admin);
+        await assert.rejects(placeBid(program, { ...bidAttempt, signed: capped }), /PermitAmountExceeded/);
+
+        const expired = await signPermit(program, splAuctionId, bidder1.publicKey, 45_000_000_000, auction_admin, {
+            expiry: (await getCurrentTimestamp()) - 1,
+        });
+        await assert.rejects(placeBid(program, { ...bidAttempt, signed: expired }), /PermitExpired/);
+
+        console.log("\n---- Outbid Bidder-2 withdraws while the auction runs ----");
+        const withdrawal = (bidder: Keypair, bidEscrowAta: PublicKey, bidderAta: PublicKey) =>
+            withdrawOutbid(program, { auctionId: splAuctionId, bidder, bidMint: splBidMint, bidEscrowAta, bidderAta });
+
+        await assert.rejects(withdrawal(bidder3, bidder3Escrow, bidder3Ata), /HighestBidderCannotWithdraw/);
+
+        await withdrawal(bidder2, bidder2Escrow, bidder2Ata);
+        assert.strictEqual(await getTokenBalance(bidder2Ata), 200_000_000_000);
+        // the emptied escrow token account is closed with the escrow
+        assert.equal(await context.banksClient.getAccount(bidder2Escrow), null);
+        console.log(await balances());
+
         // WINNER IS BIDDER-3
         console.log("\n---- WINNER IS BIDDER-3 ----");
 
@@ -420,7 +448,7 @@ describe("Auction Bidding – SOL & SPL", () => {
         const creatorBidAta = await createAta(splBidMint, auction_2_creator.publicKey);
 
         const before = {
-            auction: await getTokenBalance(splBidEscrow),
+            auction: await getTokenBalance(bidder3Escrow),
             config: await getTokenBalance(bidFeeTreasuryAta),
             creatorPrize: await getTokenBalance(splCreatorPrizeAta),
             creatorBid: await getTokenBalance(creatorBidAta),
@@ -433,14 +461,14 @@ describe("Auction Bidding – SOL & SPL", () => {
 
         await completeAuction(program, {
             auctionId: splAuctionId,
-            auctionAdmin: auction_admin,
+            keeper: auction_admin,
             creator: auction_2_creator.publicKey,
             winner: bidder3.publicKey,
 
             prizeMint: splPrizeMint,
             bidMint: splBidMint,
             prizeEscrow: splPrizeEscrow,
-            bidEscrow: splBidEscrow,
+            winnerBidEscrowAta: bidder3Escrow,
 
             creatorPrizeAta: splCreatorPrizeAta,
             winnerPrizeAta: winnerPrizeAta,
@@ -450,7 +478,7 @@ describe("Auction Bidding – SOL & SPL", () => {
         });
 
         const after = {
-            auction: await getTokenBalance(splBidEscrow),
+            auction: await getTokenBalance(bidder3Escrow),
             config: await getTokenBalance(bidFeeTreasuryAta),
             creatorPrize: await getTokenBalance(splCreatorPrizeAta),
             creatorBid: await getTokenBalance(creatorBidAta),
@@ -460,14 +488,15 @@ describe("Auction Bidding – SOL & SPL", () => {
 
         console.log("\n---- AFTER COMPLETE ----");
         console.log(after);
+        assert.equal(await context.banksClient.getAccount(bidder3Escrow), null);
 
         console.log("\n---- EXPECTED MOVEMENTS ----");
         console.log("Winner paid: 40 tokens");
-        console.log("Previous bidders refunded properly:");
-        console.log("- Bidder-1 refunded after Bidder-2 and Bidder-3");
-        console.log("- Bidder-2 refunded after Bidder-1's second bid");
+        console.log("Outbid bidders withdraw from their own escrow:");
+        console.log("- Bidder-1 topped up 20 -> 30, withdraws after completion");
+        console.log("- Bidder-2 withdrew 25 before completion");
         console.log("NFT moved to Bidder-3");
-        console.log("Auction escrow closed");
+        console.log("Bidder-3 escrow spent and closed");
 
         console.log("\n---- BID #1 try after completion fails ----");
 
@@ -479,12 +508,16 @@ describe("Auction Bidding – SOL & SPL", () => {
                 bidAmount: 50_000_000_000,
                 bidMint: splBidMint,
                 bidderAta: bidder1Ata,
-                prevBidder: bidder3.publicKey,
-                prevBidderAta: bidder3Ata,
-                bidEscrow: splBidEscrow,
+                bidEscrowAta: bidder1Escrow,
             })
         );
 
+        console.log("\n---- Bidder-1 withdraws the outbid 30 tokens after completion ----");
+        await withdrawal(bidder1, bidder1Escrow, bidder1Ata);
+        assert.strictEqual(await getTokenBalance(bidder1Ata), 200_000_000_000);
+        assert.equal(await context.banksClient.getAccount(bidder1Escrow), null);
+        await assert.rejects(withdrawal(bidder1, bidder1Escrow, bidder1Ata));
+
         console.log("------- After completion Config owner claims the fees ---------");
 
         const configPda = auctionConfigPda();
@@ -531,4 +564,82 @@ describe("Auction Bidding – SOL & SPL", () => {
 
     });
 
+    it("records an SPL bid net of the Token-2022 transfer fee", async () => {
+        const feeBps = 100; // 1%
+        const bidMint = await createTransferFeeMint(feeBps, BigInt(1_000_000_000));
+
+        const prizeMint = await createNftMint();
+        const creatorPrizeAta = await createAta(prizeMint, auction_1_creator.publicKey);
+        await mintTokens(prizeMint, creatorPrizeAta, 1);
+
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        const auctionId = cfg.auctionCount;
+        const prizeEscrow = await createAta(prizeMint, auctionPda(auctionId));
+
+        const start = await getCurrentTimestamp();
+        await createAuction(program, {
+            creator: auction_1_creator,
+            auctionAdmin: auction_admin,
+            prizeMint,
+            bidMint,
+            creatorPrizeAta,
+            prizeEscrow,
+            startTime: start,
+            endTime: start + minimum_auction_period + 100,
+            startImmediately: true,
+            baseBid: 1_000_000_000,
+            minIncrement: 500_000_000,
+            timeExtension: minimum_time_extension,
+        });
+
+        const feeBidder = async () => {
+            const bidder = Keypair.generate();
+            await context.setAccount(bidder.publicKey, {
+                lamports: 10_000_000_000,
+                owner: SystemProgram.programId,
+                executable: false,
+                data: Buffer.alloc(0),
+            });
+            const bidderAta = await createAta(bidMint, bidder.publicKey, TOKEN_2022_PROGRAM_ID);
+            await mintTokens(bidMint, bidderAta, 100_000_000_000, TOKEN_2022_PROGRAM_ID);
+            const bidEscrow = await createAta(bidMint, bidEscrowPda(auctionId, bidder.publicKey), TOKEN_2022_PROGRAM_ID);
+            return { bidder, bidderAta, bidEscrow };
+        };
+        const first = await feeBidder();
+        const second = await feeBidder();
+
+        const bid = (b: Awaited<ReturnType<typeof feeBidder>>, bidAmount: number) =>
+            placeBid(program, {
+                auctionId,
+                bidder: b.bidder,
+                auctionAdmin: auction_admin,
+                bidAmount,
+                bidMint,
+                bidderAta: b.bidderAta,
+                bidEscrowAta: b.bidEscrow,
+                bidTokenProgram: TOKEN_2022_PROGRAM_ID,
+            });
+
+        const bidAmount = 10_000_000_000;
+        const net = bidAmount - (bidAmount * feeBps) / 10_000;
+        await bid(first, bidAmount);
+
+        // the escrow only holds what arrived, so that is what the bid is worth
+        const auction = await program.account.auction.fetch(auctionPda(auctionId));
+        assert.strictEqual(auction.highestBidAmount.toNumber(), net);
+        assert.strictEqual(await getTokenBalance(first.bidEscrow), net);
+
+        // the fee withheld on the outbid escrow is harvested to the mint, so the escrow still closes
+        await bid(second, 2 * bidAmount);
+        await withdrawOutbid(program, {
+            auctionId,
+            bidder: first.bidder,
+            bidMint,
+            bidEscrowAta: first.bidEscrow,
+            bidderAta: first.bidderAta,
+            bidTokenProgram: TOKEN_2022_PROGRAM_ID,
+        });
+        assert.strictEqual(await context.banksClient.getAccount(first.bidEscrow), null);
+    });
+
 });
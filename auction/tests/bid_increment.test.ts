@@ -0,0 +1,182 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { Keypair, SystemProgram } from "@solana/web3.js";
+
+import {
+    createAuctionConfig,
+    createAuction,
+    updateAuction,
+    placeBid,
+    createNftMint,
+    createAta,
+    mintTokens,
+    getCurrentTimestamp,
+} from "./helpers";
+
+import {
+    auction_owner,
+    auction_admin,
+    auction_1_creator,
+    creation_fee_lamports,
+    commission_bps,
+    minimum_auction_period,
+    maximum_auction_period,
+    minimum_time_extension,
+    maximum_time_extension,
+    setProgram,
+    setProvider,
+    auctionConfigPda,
+    auctionPda,
+} from "./values";
+
+import type { Auction } from "../target/types/auction";
+
+describe("Percentage bid increments", () => {
+    let context: any;
+    let provider: BankrunProvider;
+    let program: anchor.Program<Auction>;
+
+    // 5% of the highest bid, never less than 0.1 SOL
+    const minIncrementBps = 500;
+    const minIncrement = 100_000_000;
+
+    before(async () => {
+        context = await startAnchor("", [], []);
+        provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+
+        program = anchor.workspace.Auction as anchor.Program<Auction>;
+        setProgram(program);
+
+        for (const kp of [auction_owner, auction_admin, auction_1_creator]) {
+            await context.setAccount(kp.publicKey, {
+                lamports: 20_000_000_000,
+                owner: SystemProgram.programId,
+                executable: false,
+                data: Buffer.alloc(0),
+            });
+        }
+
+        await createAuctionConfig(program, auction_owner, auction_admin.publicKey, {
+            creationFeeLamports: creation_fee_lamports,
+            commision_bps: commission_bps,
+            minPeriod: minimum_auction_period,
+            maxPeriod: maximum_auction_period,
+            minTimeExtension: minimum_time_extension,
+            maxTimeExtension: maximum_time_extension,
+        });
+    });
+
+    async function fundedKeypair() {
+        const kp = Keypair.generate();
+        await context.setAccount(kp.publicKey, {
+            lamports: 20_000_000_000,
+            owner: SystemProgram.programId,
+            executable: false,
+            data: Buffer.alloc(0),
+        });
+        return kp;
+    }
+
+    // SOL auction opening at 1 SOL, live unless `startIn` pushes its start out
+    async function increments(increment: { minIncrement: number; minIncrementBps: number }, startIn = 0) {
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        const prizeMint = await createNftMint();
+        const creatorPrizeAta = await createAta(prizeMint, auction_1_creator.publicKey);
+        const prizeEscrow = await createAta(prizeMint, auctionPda(cfg.auctionCount));
+        await mintTokens(prizeMint, creatorPrizeAta, 1);
+
+        const start = (await getCurrentTimestamp()) + startIn;
+        const { auctionId } = await createAuction(program, {
+            creator: auction_1_creator,
+            auctionAdmin: auction_admin,
+            prizeMint,
+            bidMint: null,
+            creatorPrizeAta,
+            prizeEscrow,
+            startTime: start,
+            endTime: start + minimum_auction_period + 100,
+            startImmediately: startIn === 0,
+            baseBid: 1_000_000_000,
+            timeExtension: minimum_time_extension,
+            ...increment,
+        });
+
+        // SOL bids never touch the token accounts, the prize escrow stands in for them
+        const bid = async (bidAmount: number) => {
+            await placeBid(program, {
+                auctionId,
+                bidder: await fundedKeypair(),
+                auctionAdmin: auction_admin,
+                bidAmount,
+                bidMint: prizeMint,
+                bidderAta: prizeEscrow,
+                bidEscrowAta: prizeEscrow,
+            });
+            return program.account.auction.fetch(auctionPda(auctionId));
+        };
+
+        return { auctionId, start, bid };
+    }
+
+    it("rejects a missing or over 100% increment", async () => {
+        await assert.rejects(increments({ minIncrement: 0, minIncrementBps: 0 }), /InvalidZeroAmount/);
+        await assert.rejects(increments({ minIncrement, minIncrementBps: 10_001 }), /InvalidBidIncrement/);
+    });
+
+    it("requires the floor on small bids and the percentage on large ones", async () => {
+        const { bid } = await increments({ minIncrement, minIncrementBps });
+
+        // 5% of 1 SOL is below the 0.1 SOL floor
+        await assert.rejects(bid(1_050_000_000), /BidTooLow/);
+        await bid(1_100_000_000);
+
+        let auction = await bid(10_000_000_000);
+        assert.equal(auction.highestBidAmount.toNumber(), 10_000_000_000);
+
+        // at 10 SOL the 5% increment takes over
+        await assert.rejects(bid(10_200_000_000), /BidTooLow/);
+        auction = await bid(10_500_000_000);
+        assert.equal(auction.highestBidAmount.toNumber(), 10_500_000_000);
+    });
+
+    it("lets the creator switch to a percentage increment before the auction starts", async () => {
+        const { auctionId, start } = await increments({ minIncrement, minIncrementBps: 0 }, 600);
+
+        await assert.rejects(
+            updateAuction(program, {
+                auctionId,
+                creator: auction_1_creator,
+                auctionAdmin: auction_admin,
+                startTime: start,
+                endTime: start + minimum_auction_period + 100,
+                startImmediately: false,
+                baseBid: 1_000_000_000,
+                minIncrement: 0,
+                minIncrementBps: 10_001,
+                timeExtension: minimum_time_extension,
+            }),
+            /InvalidBidIncrement/
+        );
+
+        await updateAuction(program, {
+            auctionId,
+            creator: auction_1_creator,
+            auctionAdmin: auction_admin,
+            startTime: start,
+            endTime: start + minimum_auction_period + 100,
+            startImmediately: false,
+            baseBid: 1_000_000_000,
+            minIncrement: 0,
+            minIncrementBps: 1_000,
+            timeExtension: minimum_time_extension,
+        });
+
+        const auction = await program.account.auction.fetch(auctionPda(auctionId));
+        assert.equal(auction.minIncrement.toNumber(), 0);
+        assert.equal(auction.minIncrementBps, 1_000);
+    });
+});
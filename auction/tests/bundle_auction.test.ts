@@ -0,0 +1,212 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+
+import {
+    createAuctionConfig,
+    createAuction,
+    cancelAuction,
+    placeBid,
+    completeAuction,
+    createNftMint,
+    createAta,
+    mintTokens,
+    getTokenBalance,
+    warpForward,
+    getCurrentTimestamp,
+} from "./helpers";
+
+import {
+    auction_owner,
+    auction_admin,
+    auction_1_creator,
+    creation_fee_lamports,
+    commission_bps,
+    minimum_auction_period,
+    maximum_auction_period,
+    minimum_time_extension,
+    maximum_time_extension,
+    setProgram,
+    setProvider,
+    auctionConfigPda,
+    auctionPda,
+    auctionBundlePda,
+} from "./values";
+
+import type { Auction } from "../target/types/auction";
+
+describe("Bundle auctions", () => {
+    let context: any;
+    let provider: BankrunProvider;
+    let program: anchor.Program<Auction>;
+
+    before(async () => {
+        context = await startAnchor("", [], []);
+        provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+
+        program = anchor.workspace.Auction as anchor.Program<Auction>;
+        setProgram(program);
+
+        for (const kp of [auction_owner, auction_admin, auction_1_creator]) {
+            await context.setAccount(kp.publicKey, {
+                lamports: 20_000_000_000,
+                owner: SystemProgram.programId,
+                executable: false,
+                data: Buffer.alloc(0),
+            });
+        }
+
+        await createAuctionConfig(program, auction_owner, auction_admin.publicKey, {
+            creationFeeLamports: creation_fee_lamports,
+            commision_bps: commission_bps,
+            minPeriod: minimum_auction_period,
+            maxPeriod: maximum_auction_period,
+            minTimeExtension: minimum_time_extension,
+            maxTimeExtension: maximum_time_extension,
+        });
+    });
+
+    // NFT held by the creator, with its escrow under the next auction's PDA
+    async function creatorNft() {
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        const mint = await createNftMint();
+        const creatorAta = await createAta(mint, auction_1_creator.publicKey);
+        const escrow = await createAta(mint, auctionPda(cfg.auctionCount));
+        await mintTokens(mint, creatorAta, 1);
+        return { mint, creatorAta, escrow };
+    }
+
+    // live SOL auction of a main prize and `extra` bundled NFTs
+    async function bundleAuction(extra: number, opts: { duplicate?: boolean; buyNowPrice?: number } = {}) {
+        const prize = await creatorNft();
+        const bundle = [];
+        for (let i = 0; i < extra; i++) {
+            bundle.push(await creatorNft());
+        }
+        if (opts.duplicate) {
+            bundle.push(bundle[0]);
+        }
+
+        const start = await getCurrentTimestamp();
+        const { auctionId } = await createAuction(program, {
+            creator: auction_1_creator,
+            auctionAdmin: auction_admin,
+            prizeMint: prize.mint,
+            bidMint: null,
+            creatorPrizeAta: prize.creatorAta,
+            prizeEscrow: prize.escrow,
+            startTime: start,
+            endTime: start + minimum_auction_period + 100,
+            startImmediately: true,
+            baseBid: 1_000_000_000,
+            minIncrement: 500_000_000,
+            timeExtension: minimum_time_extension,
+            buyNowPrice: opts.buyNowPrice,
+            bundle,
+        });
+
+        return { auctionId, prize, bundle };
+    }
+
+    const released = async (bundle: { mint: PublicKey; escrow: PublicKey }[], owner: PublicKey) =>
+        Promise.all(
+            bundle.map(async (nft) => ({
+                escrow: nft.escrow,
+                mint: nft.mint,
+                destination: await createAta(nft.mint, owner),
+            }))
+        );
+
+    it("rejects a bundle that repeats an NFT or offers buy now", async () => {
+        await assert.rejects(bundleAuction(1, { duplicate: true }), /InvalidBundle/);
+        await assert.rejects(bundleAuction(2, { buyNowPrice: 10_000_000_000 }), /InvalidBundle/);
+    });
+
+    it("sends the whole bundle to the winner", async () => {
+        const { auctionId, prize, bundle } = await bundleAuction(2);
+
+        const entry = await program.account.auctionBundle.fetch(auctionBundlePda(auctionId));
+        assert.deepEqual(
+            entry.mints.map((mint) => mint.toString()),
+            bundle.map((nft) => nft.mint.toString())
+        );
+        for (const nft of bundle) {
+            assert.equal(await getTokenBalance(nft.escrow), 1);
+        }
+
+        const bidder = Keypair.generate();
+        await context.setAccount(bidder.publicKey, {
+            lamports: 20_000_000_000,
+            owner: SystemProgram.programId,
+            executable: false,
+            data: Buffer.alloc(0),
+        });
+
+        // SOL bids never touch the token accounts, the prize escrow stands in for them
+        await placeBid(program, {
+            auctionId,
+            bidder,
+            auctionAdmin: auction_admin,
+            bidAmount: 2_000_000_000,
+            bidMint: prize.mint,
+            bidderAta: prize.escrow,
+            bidEscrowAta: prize.escrow,
+        });
+        await warpForward(minimum_auction_period + 200);
+
+        const winnerBundle = await released(bundle, bidder.publicKey);
+        const winnerPrizeAta = await createAta(prize.mint, bidder.publicKey);
+        const complete = (nfts: typeof winnerBundle) =>
+            completeAuction(program, {
+                auctionId,
+                keeper: auction_admin,
+                creator: auction_1_creator.publicKey,
+                winner: bidder.publicKey,
+                prizeMint: prize.mint,
+                bidMint: prize.mint,
+                prizeEscrow: prize.escrow,
+                winnerBidEscrowAta: prize.escrow,
+                creatorPrizeAta: prize.creatorAta,
+                winnerPrizeAta,
+                bidFeeTreasuryAta: prize.creatorAta,
+                creatorBidAta: prize.creatorAta,
+                bundle: nfts,
+            });
+
+        // leaving a bundled NFT behind fails the whole settlement
+        await assert.rejects(complete(winnerBundle.slice(1)), /MissingBundleAccounts/);
+
+        await complete(winnerBundle);
+
+        assert.equal(await getTokenBalance(winnerPrizeAta), 1);
+        for (const nft of winnerBundle) {
+            assert.equal(await getTokenBalance(nft.destination), 1);
+            assert.equal(await context.banksClient.getAccount(nft.escrow), null);
+        }
+        assert.equal(await context.banksClient.getAccount(auctionBundlePda(auctionId)), null);
+    });
+
+    it("returns the whole bundle to the creator on cancel", async () => {
+        const { auctionId, prize, bundle } = await bundleAuction(2);
+
+        await cancelAuction(program, {
+            auctionId,
+            creator: auction_1_creator,
+            auctionAdmin: auction_admin,
+            prizeMint: prize.mint,
+            creatorPrizeAta: prize.creatorAta,
+            prizeEscrow: prize.escrow,
+            bundle: bundle.map((nft) => ({ escrow: nft.escrow, mint: nft.mint, destination: nft.creatorAta })),
+        });
+
+        assert.equal(await getTokenBalance(prize.creatorAta), 1);
+        for (const nft of bundle) {
+            assert.equal(await getTokenBalance(nft.creatorAta), 1);
+        }
+        assert.equal(await context.banksClient.getAccount(auctionBundlePda(auctionId)), null);
+    });
+});
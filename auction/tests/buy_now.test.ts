@@ -0,0 +1,249 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { Keypair, SystemProgram } from "@solana/web3.js";
+
+import {
+    createAuctionConfig,
+    createAuction,
+    updateAuction,
+    placeBid,
+    buyNow,
+    withdrawOutbid,
+    setBuyNowCutoff,
+    createNftMint,
+    createAta,
+    mintTokens,
+    getSolBalance,
+    getTokenBalance,
+    getCurrentTimestamp,
+} from "./helpers";
+
+import {
+    auction_owner,
+    auction_admin,
+    auction_1_creator,
+    creation_fee_lamports,
+    commission_bps,
+    minimum_auction_period,
+    maximum_auction_period,
+    minimum_time_extension,
+    maximum_time_extension,
+    setProgram,
+    setProvider,
+    auctionConfigPda,
+    auctionPda,
+    bidEscrowPda,
+} from "./values";
+
+import type { Auction } from "../target/types/auction";
+
+describe("Buy now", () => {
+    let context: any;
+    let provider: BankrunProvider;
+    let program: anchor.Program<Auction>;
+
+    const buyNowPrice = 10_000_000_000; // 10 SOL
+
+    before(async () => {
+        context = await startAnchor("", [], []);
+        provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+
+        program = anchor.workspace.Auction as anchor.Program<Auction>;
+        setProgram(program);
+
+        for (const kp of [auction_owner, auction_admin, auction_1_creator]) {
+            await context.setAccount(kp.publicKey, {
+                lamports: 20_000_000_000,
+                owner: SystemProgram.programId,
+                executable: false,
+                data: Buffer.alloc(0),
+            });
+        }
+
+        await createAuctionConfig(program, auction_owner, auction_admin.publicKey, {
+            creationFeeLamports: creation_fee_lamports,
+            commision_bps: commission_bps,
+            minPeriod: minimum_auction_period,
+            maxPeriod: maximum_auction_period,
+            minTimeExtension: minimum_time_extension,
+            maxTimeExtension: maximum_time_extension,
+        });
+    });
+
+    async function fundedKeypair() {
+        const kp = Keypair.generate();
+        await context.setAccount(kp.publicKey, {
+            lamports: 20_000_000_000,
+            owner: SystemProgram.programId,
+            executable: false,
+            data: Buffer.alloc(0),
+        });
+        return kp;
+    }
+
+    // live SOL auction offering buy now at `price`
+    async function buyNowAuction(price = buyNowPrice) {
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        const prizeMint = await createNftMint();
+        const creatorPrizeAta = await createAta(prizeMint, auction_1_creator.publicKey);
+        const prizeEscrow = await createAta(prizeMint, auctionPda(cfg.auctionCount));
+        await mintTokens(prizeMint, creatorPrizeAta, 1);
+
+        const start = await getCurrentTimestamp();
+        const { auctionId } = await createAuction(program, {
+            creator: auction_1_creator,
+            auctionAdmin: auction_admin,
+            prizeMint,
+            bidMint: null,
+            creatorPrizeAta,
+            prizeEscrow,
+            startTime: start,
+            endTime: start + minimum_auction_period + 100,
+            startImmediately: true,
+            baseBid: 1_000_000_000,
+            minIncrement: 500_000_000,
+            timeExtension: minimum_time_extension,
+            buyNowPrice: price,
+        });
+
+        // SOL payments never touch the token accounts, the prize escrow stands in for them
+        const bid = (bidder: Keypair, bidAmount: number) =>
+            placeBid(program, {
+                auctionId,
+                bidder,
+                auctionAdmin: auction_admin,
+                bidAmount,
+                bidMint: prizeMint,
+                bidderAta: prizeEscrow,
+                bidEscrowAta: prizeEscrow,
+            });
+
+        const buy = async (buyer: Keypair) => {
+            const buyerPrizeAta = await createAta(prizeMint, buyer.publicKey);
+            await buyNow(program, {
+                auctionId,
+                buyer,
+                auctionAdmin: auction_admin,
+                price,
+                creator: auction_1_creator.publicKey,
+                prizeMint,
+                prizeEscrow,
+                buyerPrizeAta,
+                bidMint: prizeMint,
+                buyerBidAta: prizeEscrow,
+                bidFeeTreasuryAta: prizeEscrow,
+                creatorBidAta: prizeEscrow,
+            });
+            return buyerPrizeAta;
+        };
+
+        const withdraw = (bidder: Keypair) =>
+            withdrawOutbid(program, {
+                auctionId,
+                bidder,
+                bidMint: prizeMint,
+                bidEscrowAta: prizeEscrow,
+                bidderAta: prizeEscrow,
+            });
+
+        return { auctionId, bid, buy, withdraw };
+    }
+
+    it("rejects a buy now price at or below the base bid", async () => {
+        await assert.rejects(buyNowAuction(1_000_000_000), /InvalidBuyNowPrice/);
+    });
+
+    it("takes a buy now price through update_auction before the start", async () => {
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        const prizeMint = await createNftMint();
+        const creatorPrizeAta = await createAta(prizeMint, auction_1_creator.publicKey);
+        const prizeEscrow = await createAta(prizeMint, auctionPda(cfg.auctionCount));
+        await mintTokens(prizeMint, creatorPrizeAta, 1);
+
+        const start = (await getCurrentTimestamp()) + 60;
+        const schedule = {
+            startTime: start,
+            endTime: start + minimum_auction_period + 100,
+            startImmediately: false,
+            baseBid: 1_000_000_000,
+            minIncrement: 500_000_000,
+            timeExtension: minimum_time_extension,
+        };
+        const { auctionId } = await createAuction(program, {
+            creator: auction_1_creator,
+            auctionAdmin: auction_admin,
+            prizeMint,
+            bidMint: null,
+            creatorPrizeAta,
+            prizeEscrow,
+            ...schedule,
+        });
+        const update = (price: number) =>
+            updateAuction(program, {
+                auctionId,
+                creator: auction_1_creator,
+                auctionAdmin: auction_admin,
+                ...schedule,
+                buyNowPrice: price,
+            });
+
+        await assert.rejects(update(schedule.baseBid), /InvalidBuyNowPrice/);
+        await update(buyNowPrice);
+        const auction = await program.account.auction.fetch(auctionPda(auctionId));
+        assert.equal(Number(auction.buyNowPrice), buyNowPrice);
+    });
+
+    it("sells the prize at once and pays the creator net of commission", async () => {
+        const { auctionId, buy } = await buyNowAuction();
+        const buyer = await fundedKeypair();
+
+        const creatorBefore = Number(await getSolBalance(auction_1_creator.publicKey));
+        const configBefore = Number(await getSolBalance(auctionConfigPda()));
+        const buyerPrizeAta = await buy(buyer);
+
+        const fee = (buyNowPrice * commission_bps) / 10_000;
+        assert.equal(await getTokenBalance(buyerPrizeAta), 1);
+        assert.equal(Number(await getSolBalance(auctionConfigPda())) - configBefore, fee);
+        assert.equal(await context.banksClient.getAccount(auctionPda(auctionId)), null);
+
+        // the creator also gets the closed accounts' rent back
+        assert.ok(Number(await getSolBalance(auction_1_creator.publicKey)) - creatorBefore >= buyNowPrice - fee);
+    });
+
+    it("refunds the displaced highest bidder within the purchase", async () => {
+        const { auctionId, bid, buy, withdraw } = await buyNowAuction();
+        const bidder = await fundedKeypair();
+        const buyer = await fundedKeypair();
+
+        await bid(bidder, 3_000_000_000);
+        await assert.rejects(withdraw(bidder), /HighestBidderCannotWithdraw/);
+
+        const bidderBefore = Number(await getSolBalance(bidder.publicKey));
+        await buy(buyer);
+
+        // the bid and the escrow's rent come back without the bidder doing anything
+        assert.ok(Number(await getSolBalance(bidder.publicKey)) - bidderBefore > 3_000_000_000);
+        assert.equal(await context.banksClient.getAccount(bidEscrowPda(auctionId, bidder.publicKey)), null);
+    });
+
+    it("closes buy now once bidding passes the configured cutoff", async () => {
+        await assert.rejects(setBuyNowCutoff(program, auction_owner, 10_001), /InvalidBuyNowCutoff/);
+        await assert.rejects(setBuyNowCutoff(program, auction_1_creator, 5_000), /InvalidAuctionOwner/);
+
+        await setBuyNowCutoff(program, auction_owner, 5_000); // 50% of the buy now price
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        assert.equal(cfg.buyNowCutoffBps, 5_000);
+
+        const { bid, buy } = await buyNowAuction();
+        await bid(await fundedKeypair(), 4_000_000_000);
+        await bid(await fundedKeypair(), 5_000_000_000);
+
+        await assert.rejects(buy(await fundedKeypair()), /BuyNowUnavailable/);
+
+        await setBuyNowCutoff(program, auction_owner, 0);
+    });
+});
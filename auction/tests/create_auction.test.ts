@@ -1,3 +1,4 @@
+import assert from "assert";
 import * as anchor from "@coral-xyz/anchor";
 import { startAnchor } from "solana-bankrun";
 import { BankrunProvider } from "anchor-bankrun";
@@ -17,6 +18,8 @@ import {
     maximum_time_extension,
     auction_1_creator,
     auction_2_creator,
+    auctionPda,
+    collectionAllowlistPda,
 } from "./values";
 
 import {
@@ -32,6 +35,16 @@ import {
     getTokenBalance,
     getSolBalance,
     cancelAuction,
+    setAuctionFrozen,
+    createAuction,
+    createPermanentDelegateMint,
+    allowMint,
+    removeAllowedMint,
+    pnftAccounts,
+    setNftMetadata,
+    setCollectionPolicy,
+    allowCollection,
+    removeAllowedCollection,
 } from "./helpers";
 
 import { Auction } from "../target/types/auction";
@@ -98,19 +111,31 @@ describe("Create Auction Tests", () => {
         // Run create_auction
         await program.methods
             .createAuction(
-                new anchor.BN(startTime),
-                new anchor.BN(endTime),
-                false,             // startImmediately
+                {
+                    startTime: new anchor.BN(startTime),
+                    endTime: new anchor.BN(endTime),
+                    startImmediately: false,
+                    baseBid: new anchor.BN(0),
+                    minIncrement: new anchor.BN(10_000_000), // 0.01 sol
+                    minIncrementBps: 0,                      // fixed increment
+                    timeExtension: minimum_time_extension,
+                    reservePrice: new anchor.BN(0),          // no reserve
+                    reserveCommitment: new Array(32).fill(0),
+                    buyNowPrice: new anchor.BN(0),           // no buy now
+                    extensionPolicy: { window: 0, maxTotalExtension: 0, maxExtensions: 0 }, // never extended
+                },
                 true,              // bid mint = SOL
-                new anchor.BN(0),  // base bid
-                new anchor.BN(10_000_000), // min increment, 0.01 sol
-                minimum_time_extension
+                false,             // open bids
+                null,              // not a Dutch auction
+                new anchor.BN(1),  // lot of one NFT
+                0                  // no bundle
             )
             .accounts({
                 auctionConfig: auctionConfigPda(),
                 auction: auctionPda, // PDA
                 creator: auction_1_creator.publicKey,
                 auctionAdmin: auction_admin.publicKey,
+                auctionBundle: null,
                 prizeMint: nftMint,
                 bidMint: nftMint,  // ignored because is_bid_mint_sol = true
                 creatorPrizeAta: creatorAta,
@@ -118,6 +143,7 @@ describe("Create Auction Tests", () => {
                 prizeTokenProgram: TOKEN_PROGRAM_ID,
                 associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
                 systemProgram: SystemProgram.programId,
+                ...pnftAccounts(),
             })
             .signers([auction_1_creator, auction_admin])
             .rpc();
@@ -128,6 +154,8 @@ describe("Create Auction Tests", () => {
 
         if (auction.prizeMint.toBase58() !== nftMint.toBase58())
             throw new Error("NFT mint mismatch");
+        if (auction.commissionBps !== commission_bps)
+            throw new Error("Commission snapshot mismatch");
     });
 
     it("Update Auction Successfully (valid update before start + no bids)", async () => {
@@ -163,18 +191,30 @@ describe("Create Auction Tests", () => {
         const newMinIncrement = 20000;
         const newTimeExtension = minimum_time_extension + 30;
 
+        const update = () =>
+            updateAuction(program, {
+                auctionId,
+                creator: auction_1_creator,  // fix as per your setup
+                auctionAdmin: auction_admin,
+                startTime: newStart,
+                endTime: newEnd,
+                startImmediately: false,
+                baseBid: newBaseBid,
+                minIncrement: newMinIncrement,
+                timeExtension: newTimeExtension,
+            });
+
+        // a frozen auction rejects everything until the owner unfreezes it
+        await setAuctionFrozen(program, { auctionId, frozen: true, auctionOwner: auction_owner });
+        await assert.rejects(update(), /AuctionFrozen/);
+        await assert.rejects(
+            setAuctionFrozen(program, { auctionId, frozen: false, auctionOwner: auction_1_creator }),
+            /InvalidAuctionOwner/
+        );
+        await setAuctionFrozen(program, { auctionId, frozen: false, auctionOwner: auction_owner });
+
         // 4) Perform the update
-        await updateAuction(program, {
-            auctionId,
-            creator: auction_1_creator,  // fix as per your setup
-            auctionAdmin: auction_admin,
-            startTime: newStart,
-            endTime: newEnd,
-            startImmediately: false,
-            baseBid: newBaseBid,
-            minIncrement: newMinIncrement,
-            timeExtension: newTimeExtension,
-        });
+        await update();
 
         // 5) Read updated auction
         const auctionAfter = await program.account.auction.fetch(auctionPda);
@@ -311,4 +351,100 @@ describe("Create Auction Tests", () => {
         console.log("Receiver SOL After   :", receiverAfter);
         console.log("Receiver SOL Gained  :", receiverAfter - receiverBefore);
     });
+
+    it("checks a permanent-delegate bid mint on its own and keeps live auctions once it leaves the allowlist", async () => {
+        const bidMint = await createPermanentDelegateMint(Keypair.generate().publicKey);
+
+        const create = async () => {
+            const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+            const prizeMint = await createNftMint();
+            const creatorPrizeAta = await createAta(prizeMint, auction_2_creator.publicKey);
+            await mintTokens(prizeMint, creatorPrizeAta, 1);
+            const start = await getCurrentTimestamp();
+
+            await createAuction(program, {
+                creator: auction_2_creator,
+                auctionAdmin: auction_admin,
+                prizeMint,
+                bidMint,
+                creatorPrizeAta,
+                prizeEscrow: await createAta(prizeMint, auctionPda(cfg.auctionCount)),
+                startTime: start,
+                endTime: start + minimum_auction_period + 100,
+                startImmediately: true,
+                baseBid: 1_000_000_000,
+                minIncrement: 100_000_000,
+                timeExtension: minimum_time_extension,
+            });
+            return cfg.auctionCount;
+        };
+
+        // the prize is a clean NFT, only the bid mint trips the policy
+        await assert.rejects(create(), /PermanentDelegateNotAllowed/);
+
+        await assert.rejects(allowMint(program, auction_2_creator, bidMint), /InvalidAuctionOwner/);
+        await allowMint(program, auction_owner, bidMint);
+        const auctionId = await create();
+
+        await removeAllowedMint(program, auction_owner, bidMint);
+        await assert.rejects(create(), /PermanentDelegateNotAllowed/);
+
+        // the running auction keeps its bid mint
+        const auction = await program.account.auction.fetch(auctionPda(auctionId));
+        assert.equal(auction.bidMint.toString(), bidMint.toString());
+        assert.ok("active" in auction.status);
+    });
+
+    it("only accepts NFT prizes backed by Metaplex metadata and, when required, an allowlisted collection", async () => {
+        const collection = await createNftMint();
+
+        // a fresh 0-decimal, supply-1 prize each time, `metadata` undefined leaves it bare
+        const create = async (metadata?: Parameters<typeof setNftMetadata>[1]) => {
+            const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+            const prizeMint = await createSplMint(0);
+            if (metadata) await setNftMetadata(prizeMint, metadata);
+            const creatorPrizeAta = await createAta(prizeMint, auction_2_creator.publicKey);
+            await mintTokens(prizeMint, creatorPrizeAta, 1);
+            const start = await getCurrentTimestamp();
+
+            await createAuction(program, {
+                creator: auction_2_creator,
+                auctionAdmin: auction_admin,
+                prizeMint,
+                bidMint: null,
+                creatorPrizeAta,
+                prizeEscrow: await createAta(prizeMint, auctionPda(cfg.auctionCount)),
+                startTime: start,
+                endTime: start + minimum_auction_period + 100,
+                startImmediately: true,
+                baseBid: 1_000_000_000,
+                minIncrement: 100_000_000,
+                timeExtension: minimum_time_extension,
+                prizeCollectionAllowlist: collectionAllowlistPda(collection),
+            });
+        };
+
+        await assert.rejects(create(), /MissingNftMetadata/);
+        await assert.rejects(create({ masterEdition: false }), /InvalidMasterEdition/);
+        await create({});
+
+        await assert.rejects(setCollectionPolicy(program, auction_2_creator, true), /InvalidAuctionOwner/);
+        await setCollectionPolicy(program, auction_owner, true);
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        assert.equal(cfg.requireVerifiedCollection, true);
+
+        await assert.rejects(create({}), /UnverifiedCollection/);
+        await assert.rejects(create({ collection, verifiedCollection: false }), /UnverifiedCollection/);
+        await assert.rejects(create({ collection }), /CollectionNotAllowed/);
+
+        await assert.rejects(allowCollection(program, auction_2_creator, collection), /InvalidAuctionOwner/);
+        await allowCollection(program, auction_owner, collection);
+        await create({ collection });
+
+        await removeAllowedCollection(program, auction_owner, collection);
+        await assert.rejects(create({ collection }), /CollectionNotAllowed/);
+
+        await setCollectionPolicy(program, auction_owner, false);
+        await create({ collection, verifiedCollection: false });
+    });
 });
@@ -0,0 +1,214 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { Keypair, SystemProgram } from "@solana/web3.js";
+
+import {
+    createAuctionConfig,
+    createAuction,
+    placeBid,
+    buyDutch,
+    completeAuction,
+    createNftMint,
+    createAta,
+    mintTokens,
+    getSolBalance,
+    getTokenBalance,
+    getCurrentTimestamp,
+    warpForward,
+} from "./helpers";
+
+import {
+    auction_owner,
+    auction_admin,
+    auction_1_creator,
+    creation_fee_lamports,
+    commission_bps,
+    minimum_auction_period,
+    maximum_auction_period,
+    minimum_time_extension,
+    maximum_time_extension,
+    setProgram,
+    setProvider,
+    auctionConfigPda,
+    auctionPda,
+} from "./values";
+
+import type { Auction } from "../target/types/auction";
+
+describe("Dutch auctions", () => {
+    let context: any;
+    let provider: BankrunProvider;
+    let program: anchor.Program<Auction>;
+
+    // 10 SOL falling to 2 SOL in four 2 SOL steps over the hour
+    const decayInterval = minimum_auction_period / 4;
+    const schedule = { startPrice: 10_000_000_000, floorPrice: 2_000_000_000, decayInterval };
+
+    before(async () => {
+        context = await startAnchor("", [], []);
+        provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+
+        program = anchor.workspace.Auction as anchor.Program<Auction>;
+        setProgram(program);
+
+        for (const kp of [auction_owner, auction_admin, auction_1_creator]) {
+            await context.setAccount(kp.publicKey, {
+                lamports: 20_000_000_000,
+                owner: SystemProgram.programId,
+                executable: false,
+                data: Buffer.alloc(0),
+            });
+        }
+
+        await createAuctionConfig(program, auction_owner, auction_admin.publicKey, {
+            creationFeeLamports: creation_fee_lamports,
+            commision_bps: commission_bps,
+            minPeriod: minimum_auction_period,
+            maxPeriod: maximum_auction_period,
+            minTimeExtension: minimum_time_extension,
+            maxTimeExtension: maximum_time_extension,
+        });
+    });
+
+    async function fundedKeypair() {
+        const kp = Keypair.generate();
+        await context.setAccount(kp.publicKey, {
+            lamports: 20_000_000_000,
+            owner: SystemProgram.programId,
+            executable: false,
+            data: Buffer.alloc(0),
+        });
+        return kp;
+    }
+
+    // live Dutch SOL auction running for exactly `minimum_auction_period`
+    async function dutchAuction(dutch = schedule, extra: { buyNowPrice?: number } = {}) {
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        const prizeMint = await createNftMint();
+        const creatorPrizeAta = await createAta(prizeMint, auction_1_creator.publicKey);
+        const prizeEscrow = await createAta(prizeMint, auctionPda(cfg.auctionCount));
+        await mintTokens(prizeMint, creatorPrizeAta, 1);
+
+        const start = await getCurrentTimestamp();
+        const { auctionId } = await createAuction(program, {
+            creator: auction_1_creator,
+            auctionAdmin: auction_admin,
+            prizeMint,
+            bidMint: null,
+            creatorPrizeAta,
+            prizeEscrow,
+            startTime: start,
+            endTime: start + minimum_auction_period,
+            startImmediately: true,
+            baseBid: 0,
+            minIncrement: 1,
+            timeExtension: minimum_time_extension,
+            dutch,
+            ...extra,
+        });
+
+        // SOL payments never touch the token accounts, the prize escrow stands in for them
+        const buy = async (buyer: Keypair) => {
+            const buyerPrizeAta = await createAta(prizeMint, buyer.publicKey);
+            await buyDutch(program, {
+                auctionId,
+                buyer,
+                auctionAdmin: auction_admin,
+                maxPrice: dutch.startPrice,
+                creator: auction_1_creator.publicKey,
+                prizeMint,
+                prizeEscrow,
+                buyerPrizeAta,
+                bidMint: prizeMint,
+                buyerBidAta: prizeEscrow,
+                bidFeeTreasuryAta: prizeEscrow,
+                creatorBidAta: prizeEscrow,
+            });
+            return buyerPrizeAta;
+        };
+
+        const bid = (bidder: Keypair) =>
+            placeBid(program, {
+                auctionId,
+                bidder,
+                auctionAdmin: auction_admin,
+                bidAmount: dutch.startPrice,
+                bidMint: prizeMint,
+                bidderAta: prizeEscrow,
+                bidEscrowAta: prizeEscrow,
+            });
+
+        const complete = async () => {
+            const nobody = Keypair.generate().publicKey;
+            await completeAuction(program, {
+                auctionId,
+                keeper: auction_admin,
+                creator: auction_1_creator.publicKey,
+                winner: nobody,
+                prizeMint,
+                bidMint: prizeMint,
+                prizeEscrow,
+                winnerBidEscrowAta: prizeEscrow,
+                creatorPrizeAta,
+                winnerPrizeAta: await createAta(prizeMint, nobody),
+                bidFeeTreasuryAta: creatorPrizeAta,
+                creatorBidAta: creatorPrizeAta,
+            });
+        };
+
+        return { auctionId, creatorPrizeAta, buy, bid, complete };
+    }
+
+    it("rejects a schedule that does not fall or mixes in other sale modes", async () => {
+        await assert.rejects(
+            dutchAuction({ ...schedule, floorPrice: schedule.startPrice }),
+            /InvalidDutchSchedule/
+        );
+        await assert.rejects(
+            dutchAuction({ ...schedule, decayInterval: minimum_auction_period + 1 }),
+            /InvalidDutchSchedule/
+        );
+        await assert.rejects(dutchAuction(schedule, { buyNowPrice: 20_000_000_000 }), /InvalidDutchSchedule/);
+    });
+
+    it("takes no bids", async () => {
+        const { bid } = await dutchAuction();
+        await assert.rejects(bid(await fundedKeypair()), /DutchAuction/);
+    });
+
+    it("sells at the decayed price and pays the commission on it", async () => {
+        const { auctionId, buy } = await dutchAuction();
+        const buyer = await fundedKeypair();
+
+        // one interval in, the price has dropped a single step
+        await warpForward(decayInterval + 10);
+        const price = 8_000_000_000;
+
+        const configBefore = Number(await getSolBalance(auctionConfigPda()));
+        const buyerBefore = Number(await getSolBalance(buyer.publicKey));
+        const buyerPrizeAta = await buy(buyer);
+
+        const fee = (price * commission_bps) / 10_000;
+        assert.equal(await getTokenBalance(buyerPrizeAta), 1);
+        assert.equal(Number(await getSolBalance(auctionConfigPda())) - configBefore, fee);
+        assert.equal(await context.banksClient.getAccount(auctionPda(auctionId)), null);
+
+        // the buyer also paid rent for the permit nonce and prize account
+        const spent = buyerBefore - Number(await getSolBalance(buyer.publicKey));
+        assert.ok(spent >= price && spent < price + 100_000_000);
+    });
+
+    it("returns the prize to the creator when nobody buys", async () => {
+        const { creatorPrizeAta, buy, complete } = await dutchAuction();
+
+        await warpForward(minimum_auction_period + 10);
+        await assert.rejects(buy(await fundedKeypair()), /AuctionNotActive/);
+
+        await complete();
+        assert.equal(await getTokenBalance(creatorPrizeAta), 1);
+    });
+});
@@ -0,0 +1,171 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+
+import {
+    createAuctionConfig,
+    createAuction,
+    cancelAuction,
+    placeBid,
+    completeAuction,
+    createSplMint,
+    createNftMint,
+    createAta,
+    mintTokens,
+    getTokenBalance,
+    warpForward,
+    getCurrentTimestamp,
+} from "./helpers";
+
+import {
+    auction_owner,
+    auction_admin,
+    auction_1_creator,
+    creation_fee_lamports,
+    commission_bps,
+    minimum_auction_period,
+    maximum_auction_period,
+    minimum_time_extension,
+    maximum_time_extension,
+    setProgram,
+    setProvider,
+    auctionConfigPda,
+    auctionPda,
+} from "./values";
+
+import type { Auction } from "../target/types/auction";
+
+describe("Fungible lots", () => {
+    let context: any;
+    let provider: BankrunProvider;
+    let program: anchor.Program<Auction>;
+
+    const lot = 1_000_000 * 10 ** 6; // a million tokens of a 6 decimal mint
+
+    before(async () => {
+        context = await startAnchor("", [], []);
+        provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+
+        program = anchor.workspace.Auction as anchor.Program<Auction>;
+        setProgram(program);
+
+        for (const kp of [auction_owner, auction_admin, auction_1_creator]) {
+            await context.setAccount(kp.publicKey, {
+                lamports: 20_000_000_000,
+                owner: SystemProgram.programId,
+                executable: false,
+                data: Buffer.alloc(0),
+            });
+        }
+
+        await createAuctionConfig(program, auction_owner, auction_admin.publicKey, {
+            creationFeeLamports: creation_fee_lamports,
+            commision_bps: commission_bps,
+            minPeriod: minimum_auction_period,
+            maxPeriod: maximum_auction_period,
+            minTimeExtension: minimum_time_extension,
+            maxTimeExtension: maximum_time_extension,
+        });
+    });
+
+    // live SOL auction of `lotAmount` units of `prizeMint`, the creator of a fungible lot is minted two lots
+    async function lotAuction(lotAmount: number, prizeMint?: PublicKey) {
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        const mint = prizeMint ?? (await createSplMint(6));
+        const creatorPrizeAta = await createAta(mint, auction_1_creator.publicKey);
+        const prizeEscrow = await createAta(mint, auctionPda(cfg.auctionCount));
+        await mintTokens(mint, creatorPrizeAta, prizeMint ? 1 : 2 * lot);
+
+        const start = await getCurrentTimestamp();
+        const { auctionId } = await createAuction(program, {
+            creator: auction_1_creator,
+            auctionAdmin: auction_admin,
+            prizeMint: mint,
+            bidMint: null,
+            creatorPrizeAta,
+            prizeEscrow,
+            startTime: start,
+            endTime: start + minimum_auction_period + 100,
+            startImmediately: true,
+            baseBid: 1_000_000_000,
+            minIncrement: 500_000_000,
+            timeExtension: minimum_time_extension,
+            lotAmount,
+        });
+
+        return { auctionId, prizeMint: mint, creatorPrizeAta, prizeEscrow };
+    }
+
+    it("only auctions a lot of one for an NFT and a non-empty lot otherwise", async () => {
+        await assert.rejects(lotAuction(0), /InvalidLotAmount/);
+        await assert.rejects(lotAuction(2, await createNftMint()), /InvalidLotAmount/);
+    });
+
+    it("escrows the whole lot and hands it to the winner", async () => {
+        const { auctionId, prizeMint, creatorPrizeAta, prizeEscrow } = await lotAuction(lot);
+
+        const auction = await program.account.auction.fetch(auctionPda(auctionId));
+        assert.equal(auction.lotAmount.toNumber(), lot);
+        assert.equal(auction.fungiblePrize, true);
+        assert.equal(await getTokenBalance(prizeEscrow), lot);
+        assert.equal(await getTokenBalance(creatorPrizeAta), lot);
+
+        const bidder = Keypair.generate();
+        await context.setAccount(bidder.publicKey, {
+            lamports: 20_000_000_000,
+            owner: SystemProgram.programId,
+            executable: false,
+            data: Buffer.alloc(0),
+        });
+
+        // SOL bids never touch the token accounts, the prize escrow stands in for them
+        await placeBid(program, {
+            auctionId,
+            bidder,
+            auctionAdmin: auction_admin,
+            bidAmount: 2_000_000_000,
+            bidMint: prizeMint,
+            bidderAta: prizeEscrow,
+            bidEscrowAta: prizeEscrow,
+        });
+        await warpForward(minimum_auction_period + 200);
+
+        const winnerPrizeAta = await createAta(prizeMint, bidder.publicKey);
+        await completeAuction(program, {
+            auctionId,
+            keeper: auction_admin,
+            creator: auction_1_creator.publicKey,
+            winner: bidder.publicKey,
+            prizeMint,
+            bidMint: prizeMint,
+            prizeEscrow,
+            winnerBidEscrowAta: prizeEscrow,
+            creatorPrizeAta,
+            winnerPrizeAta,
+            bidFeeTreasuryAta: creatorPrizeAta,
+            creatorBidAta: creatorPrizeAta,
+        });
+
+        assert.equal(await getTokenBalance(winnerPrizeAta), lot);
+        assert.equal(await getTokenBalance(creatorPrizeAta), lot);
+    });
+
+    it("returns the whole lot when the auction is cancelled", async () => {
+        const { auctionId, prizeMint, creatorPrizeAta, prizeEscrow } = await lotAuction(lot);
+
+        await cancelAuction(program, {
+            auctionId,
+            creator: auction_1_creator,
+            auctionAdmin: auction_admin,
+            prizeMint,
+            creatorPrizeAta,
+            prizeEscrow,
+        });
+
+        assert.equal(await getTokenBalance(creatorPrizeAta), 2 * lot);
+    });
+});
@@ -1,16 +1,31 @@
 import * as anchor from "@coral-xyz/anchor";
-import { Keypair, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import {
+    AccountMeta,
+    Ed25519Program,
+    Keypair,
+    PublicKey,
+    SYSVAR_INSTRUCTIONS_PUBKEY,
+    SystemProgram,
+    Transaction,
+    TransactionInstruction,
+} from "@solana/web3.js";
 import {
     createInitializeMintInstruction,
     createAssociatedTokenAccountIdempotentInstruction,
     createTransferCheckedInstruction,
     TOKEN_PROGRAM_ID,
+    TOKEN_2022_PROGRAM_ID,
+    ExtensionType,
+    getMintLen,
+    createInitializeTransferFeeConfigInstruction,
+    createInitializePermanentDelegateInstruction,
+    createInitializeTransferHookInstruction,
     getAssociatedTokenAddressSync,
     createMintToInstruction,
     AccountLayout,
     ASSOCIATED_TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
-import { getProvider, auctionConfigPda } from "./values";
+import { getProvider, auctionConfigPda, mintAllowlistPda, TOKEN_METADATA_PROGRAM_ID, AUTH_RULES_PROGRAM_ID, collectionAllowlistPda, nftMetadataPda, nftEditionPda, tokenRecordPda, bidEscrowPda, auctionBundlePda, listingPda, offerPda } from "./values";
 import { Clock } from "solana-bankrun";
 import { Auction } from "../target/types/auction";
 
@@ -36,19 +51,305 @@ export async function createSplMint(decimals = 9): Promise<PublicKey> {
 }
 
 export async function createNftMint(): Promise<PublicKey> {
-    return createSplMint(0);
+    const mint = await createSplMint(0);
+    await setNftMetadata(mint);
+    return mint;
+}
+
+// Writes a Token Metadata `Metadata` and `MasterEdition` for `mint` straight into the bank, the
+// Token Metadata program itself is not loaded in these tests
+export async function setNftMetadata(
+    mint: PublicKey,
+    opts: {
+        collection?: PublicKey;
+        verifiedCollection?: boolean;
+        masterEdition?: boolean;
+        sellerFeeBasisPoints?: number;
+        creators?: { address: PublicKey; verified: boolean; share: number }[];
+    } = {}
+) {
+    const { collection, verifiedCollection = true, masterEdition = true, sellerFeeBasisPoints = 0, creators } = opts;
+    const context = getProvider().context;
+
+    const u16Le = (value: number) => {
+        const buf = Buffer.alloc(2);
+        buf.writeUInt16LE(value);
+        return buf;
+    };
+    const u32Le = (value: number) => {
+        const buf = Buffer.alloc(4);
+        buf.writeUInt32LE(value);
+        return buf;
+    };
+    const borshString = (value: string) => Buffer.concat([u32Le(value.length), Buffer.from(value)]);
+
+    const metadata = Buffer.concat([
+        Buffer.from([4]), // Key::MetadataV1
+        getProvider().wallet.publicKey.toBuffer(), // update authority
+        mint.toBuffer(),
+        borshString("Prize"),
+        borshString("PRZ"),
+        borshString(""),
+        u16Le(sellerFeeBasisPoints),
+        creators
+            ? Buffer.concat([
+                Buffer.from([1]),
+                u32Le(creators.length),
+                ...creators.map((c) => Buffer.concat([c.address.toBuffer(), Buffer.from([c.verified ? 1 : 0, c.share])])),
+            ])
+            : Buffer.from([0]),
+        Buffer.from([0, 1]), // primary sale happened, is mutable
+        Buffer.from([0]), // edition nonce
+        Buffer.from([1, 0]), // TokenStandard::NonFungible
+        collection
+            ? Buffer.concat([Buffer.from([1, verifiedCollection ? 1 : 0]), collection.toBuffer()])
+            : Buffer.from([0]),
+        Buffer.from([0, 0, 0]), // uses, collection details, programmable config
+    ]);
+    await context.setAccount(nftMetadataPda(mint), {
+        lamports: 1_000_000_000,
+        data: metadata,
+        owner: TOKEN_METADATA_PROGRAM_ID,
+        executable: false,
+    });
+
+    if (masterEdition) {
+        // Key::MasterEditionV2, supply 0, max supply Some(0)
+        const edition = Buffer.concat([Buffer.from([6]), Buffer.alloc(8), Buffer.from([1]), Buffer.alloc(8)]);
+        await context.setAccount(nftEditionPda(mint), {
+            lamports: 1_000_000_000,
+            data: edition,
+            owner: TOKEN_METADATA_PROGRAM_ID,
+            executable: false,
+        });
+    }
+}
+
+// Mints a programmable NFT to `owner` through Token Metadata's CreateV1 and MintV1, unlike
+// `setNftMetadata` this needs the real program loaded from tests/fixtures/mpl_token_metadata.so
+export async function createPnftMint(owner: PublicKey): Promise<PublicKey> {
+    const provider = getProvider();
+    const payer = provider.wallet.publicKey;
+    const mint = Keypair.generate();
+    const metadata = nftMetadataPda(mint.publicKey);
+    const edition = nftEditionPda(mint.publicKey);
+    const ownerAta = getAssociatedTokenAddressSync(mint.publicKey, owner, true);
+
+    const borshString = (value: string) => {
+        const len = Buffer.alloc(4);
+        len.writeUInt32LE(value.length);
+        return Buffer.concat([len, Buffer.from(value)]);
+    };
+    const account = (pubkey: PublicKey, isWritable = false, isSigner = false) => ({ pubkey, isSigner, isWritable });
+    // absent optional accounts are passed as the Token Metadata program id
+    const none = account(TOKEN_METADATA_PROGRAM_ID);
+
+    const create = new TransactionInstruction({
+        programId: TOKEN_METADATA_PROGRAM_ID,
+        keys: [
+            account(metadata, true),
+            account(edition, true),
+            account(mint.publicKey, true, true),
+            account(payer, false, true), // mint authority
+            account(payer, true, true),
+            account(payer, false, true), // update authority
+            account(SystemProgram.programId),
+            account(SYSVAR_INSTRUCTIONS_PUBKEY),
+            account(TOKEN_PROGRAM_ID),
+        ],
+        data: Buffer.concat([
+            Buffer.from([42, 0]), // CreateV1
+            borshString("Prize"),
+            borshString("PRZ"),
+            borshString(""),
+            Buffer.from([0, 0]), // seller fee bps
+            Buffer.from([0]), // no creators
+            Buffer.from([0, 1]), // primary sale happened, is mutable
+            Buffer.from([4]), // TokenStandard::ProgrammableNonFungible
+            Buffer.from([0, 0, 0, 0]), // collection, uses, collection details, rule set
+            Buffer.from([1, 0]), // decimals
+            Buffer.from([1, 0]), // PrintSupply::Zero
+        ]),
+    });
+
+    const mintTo = new TransactionInstruction({
+        programId: TOKEN_METADATA_PROGRAM_ID,
+        keys: [
+            account(ownerAta, true),
+            account(owner),
+            account(metadata),
+            account(edition, true),
+            account(tokenRecordPda(mint.publicKey, ownerAta), true),
+            account(mint.publicKey, true),
+            account(payer, false, true),
+            none, // delegate record
+            account(payer, true, true),
+            account(SystemProgram.programId),
+            account(SYSVAR_INSTRUCTIONS_PUBKEY),
+            account(TOKEN_PROGRAM_ID),
+            account(ASSOCIATED_TOKEN_PROGRAM_ID),
+            none, // authorization rules program
+            none, // authorization rules
+        ],
+        data: Buffer.concat([
+            Buffer.from([43, 0]), // MintV1
+            new anchor.BN(1).toArrayLike(Buffer, "le", 8),
+            Buffer.from([0]), // no authorization data
+        ]),
+    });
+
+    await provider.sendAndConfirm(new Transaction().add(create), [mint]);
+    await provider.sendAndConfirm(new Transaction().add(mintTo));
+    return mint.publicKey;
+}
+
+// Token-2022 mint that withholds `feeBps` of every transfer, capped at `maxFee`
+export async function createTransferFeeMint(feeBps: number, maxFee: bigint, decimals = 9): Promise<PublicKey> {
+    const provider = getProvider();
+    const mint = Keypair.generate();
+
+    const space = getMintLen([ExtensionType.TransferFeeConfig]);
+    const lamports = await provider.connection.getMinimumBalanceForRentExemption(space);
+    const tx = new Transaction().add(
+        SystemProgram.createAccount({
+            fromPubkey: provider.wallet.publicKey,
+            newAccountPubkey: mint.publicKey,
+            space,
+            lamports,
+            programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+            mint.publicKey,
+            provider.wallet.publicKey,
+            provider.wallet.publicKey,
+            feeBps,
+            maxFee,
+            TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(mint.publicKey, decimals, provider.wallet.publicKey, null, TOKEN_2022_PROGRAM_ID)
+    );
+
+    await provider.sendAndConfirm(tx, [mint]);
+    return mint.publicKey;
+}
+
+// Token-2022 mint whose `delegate` can move tokens out of any account, escrows included
+export async function createPermanentDelegateMint(delegate: PublicKey, decimals = 9): Promise<PublicKey> {
+    const provider = getProvider();
+    const mint = Keypair.generate();
+
+    const space = getMintLen([ExtensionType.PermanentDelegate]);
+    const lamports = await provider.connection.getMinimumBalanceForRentExemption(space);
+    const tx = new Transaction().add(
+        SystemProgram.createAccount({
+            fromPubkey: provider.wallet.publicKey,
+            newAccountPubkey: mint.publicKey,
+            space,
+            lamports,
+            programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializePermanentDelegateInstruction(mint.publicKey, delegate, TOKEN_2022_PROGRAM_ID),
+        createInitializeMintInstruction(mint.publicKey, decimals, provider.wallet.publicKey, null, TOKEN_2022_PROGRAM_ID)
+    );
+
+    await provider.sendAndConfirm(tx, [mint]);
+    return mint.publicKey;
+}
+
+export async function createTransferHookMint(hookProgramId: PublicKey, decimals = 9): Promise<PublicKey> {
+    const provider = getProvider();
+    const mint = Keypair.generate();
+
+    const space = getMintLen([ExtensionType.TransferHook]);
+    const lamports = await provider.connection.getMinimumBalanceForRentExemption(space);
+    const tx = new Transaction().add(
+        SystemProgram.createAccount({
+            fromPubkey: provider.wallet.publicKey,
+            newAccountPubkey: mint.publicKey,
+            space,
+            lamports,
+            programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferHookInstruction(
+            mint.publicKey,
+            provider.wallet.publicKey,
+            hookProgramId,
+            TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(mint.publicKey, decimals, provider.wallet.publicKey, null, TOKEN_2022_PROGRAM_ID)
+    );
+
+    await provider.sendAndConfirm(tx, [mint]);
+    return mint.publicKey;
+}
+
+// Hook program, its validation account and the counter PDA it lists, in the order the hook resolves them
+export function transferHookAccounts(hookProgramId: PublicKey, mint: PublicKey): AccountMeta[] {
+    const [extraAccountMetaList] = PublicKey.findProgramAddressSync(
+        [Buffer.from("extra-account-metas"), mint.toBuffer()],
+        hookProgramId
+    );
+    const [counter] = PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), mint.toBuffer()],
+        hookProgramId
+    );
+
+    return [
+        { pubkey: hookProgramId, isSigner: false, isWritable: false },
+        { pubkey: extraAccountMetaList, isSigner: false, isWritable: false },
+        { pubkey: counter, isSigner: false, isWritable: true },
+    ];
+}
+
+// Token Metadata accounts every prize transfer carries; with no rule set the program id stands in for `authorizationRules`
+export function pnftAccounts(authorizationRules: PublicKey = TOKEN_METADATA_PROGRAM_ID) {
+    return {
+        authorizationRules,
+        authorizationRulesProgram: AUTH_RULES_PROGRAM_ID,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+    };
+}
+
+// `create_auction` remaining accounts for a bundle, the layout `escrow_bundle` reads
+export function bundleCreateAccounts(
+    bundle: { mint: PublicKey; creatorAta: PublicKey; escrow: PublicKey; collectionAllowlist?: PublicKey }[]
+): AccountMeta[] {
+    return bundle.flatMap((nft) => [
+        { pubkey: nft.mint, isSigner: false, isWritable: false },
+        { pubkey: nftMetadataPda(nft.mint), isSigner: false, isWritable: false },
+        { pubkey: nftEditionPda(nft.mint), isSigner: false, isWritable: false },
+        { pubkey: mintAllowlistPda(nft.mint), isSigner: false, isWritable: false },
+        { pubkey: nft.collectionAllowlist ?? SystemProgram.programId, isSigner: false, isWritable: false },
+        { pubkey: nft.creatorAta, isSigner: false, isWritable: true },
+        { pubkey: nft.escrow, isSigner: false, isWritable: true },
+    ]);
 }
 
-export async function createAta(mint: PublicKey, owner: PublicKey): Promise<PublicKey> {
+// `cancel_auction` and `complete_auction` remaining accounts for a bundle, the layout `release_bundle` reads
+export function bundleReleaseAccounts(bundle: { escrow: PublicKey; destination: PublicKey; mint: PublicKey }[]): AccountMeta[] {
+    return bundle.flatMap((nft) => [
+        { pubkey: nft.escrow, isSigner: false, isWritable: true },
+        { pubkey: nft.destination, isSigner: false, isWritable: true },
+        { pubkey: nft.mint, isSigner: false, isWritable: false },
+    ]);
+}
+
+export async function createAta(
+    mint: PublicKey,
+    owner: PublicKey,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID
+): Promise<PublicKey> {
     const provider = getProvider();
-    const ata = getAssociatedTokenAddressSync(mint, owner, true);
+    const ata = getAssociatedTokenAddressSync(mint, owner, true, tokenProgram);
 
     const tx = new Transaction().add(
         createAssociatedTokenAccountIdempotentInstruction(
             provider.wallet.publicKey, // payer
             ata,
             owner,
-            mint
+            mint,
+            tokenProgram
         )
     );
 
@@ -56,14 +357,21 @@ export async function createAta(mint: PublicKey, owner: PublicKey): Promise<Publ
     return ata;
 }
 
-export async function mintTokens(mint: PublicKey, to: PublicKey, amount: number) {
+export async function mintTokens(
+    mint: PublicKey,
+    to: PublicKey,
+    amount: number,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID
+) {
     const provider = getProvider();
     const tx = new Transaction().add(
         createMintToInstruction(
             mint,
             to,
             provider.wallet.publicKey, // mint authority
-            amount
+            amount,
+            [],
+            tokenProgram
         )
     );
     await provider.sendAndConfirm(tx);
@@ -172,6 +480,40 @@ export async function createAuctionConfig(
         .rpc();
 }
 
+// Anti-sniping settings of an auction, 0 caps => the config bounds, uncapped under a default config
+export type ExtensionPolicy = { window: number; maxTotalExtension: number; maxExtensions: number };
+
+export const noExtensions: ExtensionPolicy = { window: 0, maxTotalExtension: 0, maxExtensions: 0 };
+
+// Schedule and prices of an auction as `create_auction`, `update_auction` and `relist_auction` take them
+export function auctionParams(params: {
+    startTime: number;
+    endTime: number;
+    startImmediately: boolean;
+    baseBid: number;
+    minIncrement: number;
+    minIncrementBps?: number;
+    timeExtension: number;
+    reservePrice?: number;
+    reserveCommitment?: number[];
+    buyNowPrice?: number;
+    extensionPolicy?: ExtensionPolicy;
+}) {
+    return {
+        startTime: new anchor.BN(params.startTime),
+        endTime: new anchor.BN(params.endTime),
+        startImmediately: params.startImmediately,
+        baseBid: new anchor.BN(params.baseBid),
+        minIncrement: new anchor.BN(params.minIncrement),
+        minIncrementBps: params.minIncrementBps ?? 0,
+        timeExtension: params.timeExtension,
+        reservePrice: new anchor.BN(params.reservePrice ?? 0),
+        reserveCommitment: params.reserveCommitment ?? new Array(32).fill(0),
+        buyNowPrice: new anchor.BN(params.buyNowPrice ?? 0),
+        extensionPolicy: params.extensionPolicy ?? noExtensions,
+    };
+}
+
 export async function createAuction(
     program: anchor.Program<Auction>,
     params: {
@@ -186,7 +528,20 @@ export async function createAuction(
         startImmediately: boolean;
         baseBid: number;
         minIncrement: number;
+        minIncrementBps?: number; // increment as a share of the highest bid, `minIncrement` is then its floor
         timeExtension: number;
+        prizeCollectionAllowlist?: PublicKey;
+        reservePrice?: number;
+        reserveCommitment?: number[]; // `reserveCommitment(price, salt)` of a hidden reserve
+        buyNowPrice?: number;
+        sealed?: boolean;
+        dutch?: { startPrice: number; floorPrice: number; decayInterval: number };
+        lotAmount?: number; // raw units of a fungible prize, 1 for an NFT
+        // NFTs sold along with the prize, each escrow owned by the auction PDA
+        bundle?: { mint: PublicKey; creatorAta: PublicKey; escrow: PublicKey; collectionAllowlist?: PublicKey }[];
+        extensionPolicy?: ExtensionPolicy;
+        prizeTokenProgram?: PublicKey;
+        remainingAccounts?: AccountMeta[]; // transfer hook accounts of the prize mint, after the bundle's
     }
 ) {
     const cfg = await program.account.auctionConfig.fetch(
@@ -198,13 +553,18 @@ export async function createAuction(
 
     await program.methods
         .createAuction(
-            new anchor.BN(params.startTime),
-            new anchor.BN(params.endTime),
-            params.startImmediately,
+            auctionParams(params),
             params.bidMint === null,         // is_bid_mint_sol
-            new anchor.BN(params.baseBid),
-            new anchor.BN(params.minIncrement),
-            params.timeExtension
+            params.sealed ?? false,
+            params.dutch
+                ? {
+                      startPrice: new anchor.BN(params.dutch.startPrice),
+                      floorPrice: new anchor.BN(params.dutch.floorPrice),
+                      decayInterval: params.dutch.decayInterval,
+                  }
+                : null,
+            new anchor.BN(params.lotAmount ?? 1),
+            params.bundle?.length ?? 0
         )
         .accounts({
             auctionConfig: auctionConfigPda(),
@@ -215,10 +575,14 @@ export async function createAuction(
             bidMint: params.bidMint ?? params.prizeMint,
             creatorPrizeAta: params.creatorPrizeAta,
             prizeEscrow: params.prizeEscrow,
-            prizeTokenProgram: TOKEN_PROGRAM_ID,
+            prizeCollectionAllowlist: params.prizeCollectionAllowlist ?? null,
+            auctionBundle: params.bundle?.length ? auctionBundlePda(auctionId) : null,
+            prizeTokenProgram: params.prizeTokenProgram ?? TOKEN_PROGRAM_ID,
             associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
+            ...pnftAccounts(),
         })
+        .remainingAccounts([...bundleCreateAccounts(params.bundle ?? []), ...(params.remainingAccounts ?? [])])
         .signers([params.creator, params.auctionAdmin])
         .rpc();
 
@@ -234,6 +598,9 @@ export async function cancelAuction(
         prizeMint: PublicKey;
         prizeEscrow: PublicKey;
         creatorPrizeAta: PublicKey;
+        bundle?: { escrow: PublicKey; destination: PublicKey; mint: PublicKey }[]; // destinations owned by the creator
+        prizeTokenProgram?: PublicKey;
+        remainingAccounts?: AccountMeta[]; // transfer hook accounts of the prize mint, after the bundle's
     }
 ) {
     const auctionPda = deriveAuctionPda(program, params.auctionId);
@@ -246,15 +613,18 @@ export async function cancelAuction(
             auction: auctionPda,
             creator: params.creator.publicKey,
             auctionAdmin: params.auctionAdmin.publicKey,
+            auctionBundle: params.bundle?.length ? auctionBundlePda(params.auctionId) : null,
 
             prizeMint: params.prizeMint,
             prizeEscrow: params.prizeEscrow,
             creatorPrizeAta: params.creatorPrizeAta,
 
-            prizeTokenProgram: TOKEN_PROGRAM_ID,
+            prizeTokenProgram: params.prizeTokenProgram ?? TOKEN_PROGRAM_ID,
             associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
+            ...pnftAccounts(),
         })
+        .remainingAccounts([...bundleReleaseAccounts(params.bundle ?? []), ...(params.remainingAccounts ?? [])])
         .signers([params.creator, params.auctionAdmin]) // both signatures required
         .rpc();
 
@@ -283,6 +653,162 @@ export async function startAuction(
     return { auctionPda };
 }
 
+export async function setAllowedMintExtensions(
+    program: anchor.Program<Auction>,
+    auctionOwner: Keypair,
+    allowedMintExtensions: number
+) {
+    await program.methods
+        .setAllowedMintExtensions(allowedMintExtensions)
+        .accounts({
+            auctionConfig: auctionConfigPda(),
+            auctionOwner: auctionOwner.publicKey,
+        })
+        .signers([auctionOwner])
+        .rpc();
+}
+
+export async function allowMint(program: anchor.Program<Auction>, auctionOwner: Keypair, mint: PublicKey) {
+    await program.methods
+        .allowMint()
+        .accounts({
+            auctionConfig: auctionConfigPda(),
+            mint,
+            mintAllowlist: mintAllowlistPda(mint),
+            auctionOwner: auctionOwner.publicKey,
+            systemProgram: SystemProgram.programId,
+        })
+        .signers([auctionOwner])
+        .rpc();
+}
+
+export async function removeAllowedMint(program: anchor.Program<Auction>, auctionOwner: Keypair, mint: PublicKey) {
+    await program.methods
+        .removeAllowedMint()
+        .accounts({
+            auctionConfig: auctionConfigPda(),
+            mintAllowlist: mintAllowlistPda(mint),
+            auctionOwner: auctionOwner.publicKey,
+        })
+        .signers([auctionOwner])
+        .rpc();
+}
+
+export async function setCollectionPolicy(
+    program: anchor.Program<Auction>,
+    auctionOwner: Keypair,
+    requireVerifiedCollection: boolean
+) {
+    await program.methods
+        .setCollectionPolicy(requireVerifiedCollection)
+        .accounts({
+            auctionConfig: auctionConfigPda(),
+            auctionOwner: auctionOwner.publicKey,
+        })
+        .signers([auctionOwner])
+        .rpc();
+}
+
+export async function setSealedBidPolicy(
+    program: anchor.Program<Auction>,
+    auctionOwner: Keypair,
+    revealPeriod: number,
+    penaltyBps: number
+) {
+    await program.methods
+        .setSealedBidPolicy(revealPeriod, penaltyBps)
+        .accounts({
+            auctionConfig: auctionConfigPda(),
+            auctionOwner: auctionOwner.publicKey,
+        })
+        .signers([auctionOwner])
+        .rpc();
+}
+
+export async function setExtensionBounds(
+    program: anchor.Program<Auction>,
+    auctionOwner: Keypair,
+    maxWindow: number,
+    maxTotalExtension: number,
+    maxExtensionCount: number
+) {
+    await program.methods
+        .setExtensionBounds(maxWindow, maxTotalExtension, maxExtensionCount)
+        .accounts({
+            auctionConfig: auctionConfigPda(),
+            auctionOwner: auctionOwner.publicKey,
+        })
+        .signers([auctionOwner])
+        .rpc();
+}
+
+export async function setKeeperFee(program: anchor.Program<Auction>, auctionOwner: Keypair, keeperFeeBps: number) {
+    await program.methods
+        .setKeeperFee(keeperFeeBps)
+        .accounts({
+            auctionConfig: auctionConfigPda(),
+            auctionOwner: auctionOwner.publicKey,
+        })
+        .signers([auctionOwner])
+        .rpc();
+}
+
+export async function setBuyNowCutoff(program: anchor.Program<Auction>, auctionOwner: Keypair, buyNowCutoffBps: number) {
+    await program.methods
+        .setBuyNowCutoff(buyNowCutoffBps)
+        .accounts({
+            auctionConfig: auctionConfigPda(),
+            auctionOwner: auctionOwner.publicKey,
+        })
+        .signers([auctionOwner])
+        .rpc();
+}
+
+export async function allowCollection(program: anchor.Program<Auction>, auctionOwner: Keypair, collection: PublicKey) {
+    await program.methods
+        .allowCollection()
+        .accounts({
+            auctionConfig: auctionConfigPda(),
+            collectionMint: collection,
+            collectionAllowlist: collectionAllowlistPda(collection),
+            auctionOwner: auctionOwner.publicKey,
+            systemProgram: SystemProgram.programId,
+        })
+        .signers([auctionOwner])
+        .rpc();
+}
+
+export async function removeAllowedCollection(program: anchor.Program<Auction>, auctionOwner: Keypair, collection: PublicKey) {
+    await program.methods
+        .removeAllowedCollection()
+        .accounts({
+            auctionConfig: auctionConfigPda(),
+            collectionAllowlist: collectionAllowlistPda(collection),
+            auctionOwner: auctionOwner.publicKey,
+        })
+        .signers([auctionOwner])
+        .rpc();
+}
+
+export async function setAuctionFrozen(
+    program: anchor.Program<Auction>,
+    params: {
+        auctionId: number;
+        frozen: boolean;
+        auctionOwner: Keypair;
+    }
+) {
+    await program.methods
+        .setAuctionFrozen(params.auctionId, params.frozen)
+        .accounts({
+            auctionConfig: auctionConfigPda(),
+            auction: deriveAuctionPda(program, params.auctionId),
+            auctionOwner: params.auctionOwner.publicKey,
+        })
+        .signers([params.auctionOwner])
+        .rpc();
+}
+
 export async function updateAuction(
     program: anchor.Program<Auction>,
     params: {
@@ -294,21 +820,18 @@ export async function updateAuction(
         startImmediately: boolean;
         baseBid: number;
         minIncrement: number;
+        minIncrementBps?: number; // increment as a share of the highest bid, `minIncrement` is then its floor
         timeExtension: number;
+        reservePrice?: number;
+        reserveCommitment?: number[];
+        buyNowPrice?: number;
+        extensionPolicy?: ExtensionPolicy;
     }
 ) {
     const auctionPda = deriveAuctionPda(program, params.auctionId);
 
     await program.methods
-        .updateAuction(
-            params.auctionId,
-            new anchor.BN(params.startTime),
-            new anchor.BN(params.endTime),
-            params.startImmediately,
-            new anchor.BN(params.baseBid),
-            new anchor.BN(params.minIncrement),
-            params.timeExtension
-        )
+        .updateAuction(params.auctionId, auctionParams(params))
         .accounts({
             auctionConfig: auctionConfigPda(),
             auction: auctionPda,
@@ -321,6 +844,102 @@ export async function updateAuction(
     return { auctionPda };
 }
 
+export async function relistAuction(
+    program: anchor.Program<Auction>,
+    params: {
+        auctionId: number;
+        creator: Keypair;
+        auctionAdmin: Keypair;
+        startTime: number;
+        endTime: number;
+        startImmediately: boolean;
+        baseBid: number;
+        minIncrement: number;
+        minIncrementBps?: number;
+        timeExtension: number;
+        reservePrice?: number;
+        reserveCommitment?: number[];
+        buyNowPrice?: number;
+        extensionPolicy?: ExtensionPolicy;
+    }
+) {
+    const auctionPda = deriveAuctionPda(program, params.auctionId);
+
+    await program.methods
+        .relistAuction(params.auctionId, auctionParams(params))
+        .accounts({
+            auctionConfig: auctionConfigPda(),
+            auction: auctionPda,
+            creator: params.creator.publicKey,
+            auctionAdmin: params.auctionAdmin.publicKey,
+        })
+        .signers([params.creator, params.auctionAdmin])
+        .rpc();
+
+    return { auctionPda };
+}
+
+// === PERMITS ===
+
+let nextPermitNonce = 1;
+
+// Mirrors `utils::permit_message` on chain, `subject` is an auction id or a listing / offer address
+export function permitMessage(
+    programId: PublicKey,
+    subject: number | PublicKey,
+    user: PublicKey,
+    permit: { maxAmount: anchor.BN; expiry: anchor.BN; nonce: anchor.BN }
+): Buffer {
+    return Buffer.concat([
+        programId.toBuffer(),
+        typeof subject === "number" ? new anchor.BN(subject).toArrayLike(Buffer, "le", 4) : subject.toBuffer(),
+        user.toBuffer(),
+        permit.maxAmount.toArrayLike(Buffer, "le", 8),
+        permit.expiry.toTwos(64).toArrayLike(Buffer, "le", 8),
+        permit.nonce.toArrayLike(Buffer, "le", 8),
+    ]);
+}
+
+// Signs a permit off-chain and returns it with the Ed25519 instruction that must precede the call
+export async function signPermit(
+    program: anchor.Program<Auction>,
+    subject: number | PublicKey,
+    user: PublicKey,
+    maxAmount: number,
+    signer: Keypair,
+    overrides: { expiry?: number; nonce?: number } = {}
+): Promise<{
+    permit: { maxAmount: anchor.BN; expiry: anchor.BN; nonce: anchor.BN };
+    ed25519Ix: TransactionInstruction;
+    permitNonce: PublicKey;
+}> {
+    const expiry = overrides.expiry ?? (await getCurrentTimestamp()) + 60 * 60;
+    const nonce = overrides.nonce ?? nextPermitNonce++;
+
+    const permit = {
+        maxAmount: new anchor.BN(maxAmount),
+        expiry: new anchor.BN(expiry),
+        nonce: new anchor.BN(nonce),
+    };
+
+    const ed25519Ix = Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: signer.secretKey,
+        message: permitMessage(program.programId, subject, user, permit),
+    });
+
+    const permitNonce = PublicKey.findProgramAddressSync(
+        [
+            Buffer.from("auction"),
+            Buffer.from("permit"),
+            user.toBuffer(),
+            permit.nonce.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+    )[0];
+
+    return { permit, ed25519Ix, permitNonce };
+}
+
 export async function placeBid(
     program: anchor.Program<Auction>,
     params: {
@@ -328,36 +947,51 @@ export async function placeBid(
         bidder: Keypair;
         auctionAdmin: Keypair;
         bidAmount: number;
+        proxy?: boolean; // `bidAmount` is then the escrowed maximum
 
         // For SPL bids
         bidMint: PublicKey;
         bidderAta: PublicKey;
-        prevBidder: PublicKey;
-        prevBidderAta: PublicKey;
-        bidEscrow: PublicKey;
+        bidEscrowAta: PublicKey; // `bidEscrowPda` token account, any token account for SOL bids
+        bidTokenProgram?: PublicKey;
+
+        // pre-signed permit, otherwise `auctionAdmin` signs one for exactly `bidAmount`
+        signed?: Awaited<ReturnType<typeof signPermit>>;
     }
 ) {
     const auctionPda = deriveAuctionPda(program, params.auctionId);
 
+    // the admin only signs the permit, the bidder submits on their own
+    const signed =
+        params.signed ??
+        (await signPermit(
+            program,
+            params.auctionId,
+            params.bidder.publicKey,
+            params.bidAmount,
+            params.auctionAdmin
+        ));
+
     await program.methods
-        .placeBid(params.auctionId, new anchor.BN(params.bidAmount))
+        .placeBid(params.auctionId, new anchor.BN(params.bidAmount), params.proxy ?? false, signed.permit)
         .accounts({
             auctionConfig: auctionConfigPda(),
             auction: auctionPda,
             bidder: params.bidder.publicKey,
-            auctionAdmin: params.auctionAdmin.publicKey,
+            permitNonce: signed.permitNonce,
+            instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
 
-            prevBidderAccount: params.prevBidder,
             bidMint: params.bidMint,
 
             currentBidderAta: params.bidderAta,
-            prevBidderAta: params.prevBidderAta,
-            bidEscrow: params.bidEscrow,
+            bidEscrow: bidEscrowPda(params.auctionId, params.bidder.publicKey),
+            bidEscrowAta: params.bidEscrowAta,
 
-            bidTokenProgram: TOKEN_PROGRAM_ID,
+            bidTokenProgram: params.bidTokenProgram ?? TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
         })
-        .signers([params.bidder, params.auctionAdmin])
+        .preInstructions([signed.ed25519Ix])
+        .signers([params.bidder])
         .rpc();
 
     return { auctionPda };
@@ -367,7 +1001,7 @@ export async function completeAuction(
     program: anchor.Program<Auction>,
     params: {
         auctionId: number;
-        auctionAdmin: Keypair;
+        keeper: Keypair; // the admin right after the auction ends, anyone once the settlement delay is over
         creator: PublicKey;
         winner: PublicKey;
 
@@ -375,44 +1009,526 @@ export async function completeAuction(
         bidMint: PublicKey;
 
         prizeEscrow: PublicKey;
-        bidEscrow: PublicKey;
+        winnerBidEscrowAta: PublicKey;
+        winnerBidAta?: PublicKey; // refunded when the reserve is not met, SPL only
 
         creatorPrizeAta: PublicKey;
-        winnerPrizeAta: PublicKey;
+        winnerPrizeAta: PublicKey; // opened for the winner on a sale if missing, its rent comes out of their escrow
 
         bidFeeTreasuryAta: PublicKey;
         creatorBidAta: PublicKey;
+        keeperBidAta?: PublicKey; // receives the keeper fee, SPL only
+
+        // NFTs sold along with the prize, destinations owned by the winner on a sale and by the creator otherwise
+        bundle?: { escrow: PublicKey; destination: PublicKey; mint: PublicKey }[];
+
+        // on a sale one per verified prize creator, then any transfer hook accounts
+        remainingAccounts?: AccountMeta[];
+
+        // opens a hidden reserve
+        reserveReveal?: { price: number; salt: number[] };
     }
 ) {
     const auctionPda = deriveAuctionPda(program, params.auctionId);
 
+    // only an auction with bids has a winner escrow to pay out of
+    const auction = await program.account.auction.fetch(await auctionPda);
+    const winnerBidEscrow = auction.hasAnyBid ? bidEscrowPda(params.auctionId, params.winner) : null;
+
     await program.methods
-        .completeAuction(params.auctionId)
+        .completeAuction(
+            params.auctionId,
+            params.reserveReveal
+                ? { price: new anchor.BN(params.reserveReveal.price), salt: params.reserveReveal.salt }
+                : null
+        )
         .accounts({
             auctionConfig: auctionConfigPda(),
             auction: auctionPda,
-            auctionAdmin: params.auctionAdmin.publicKey,
+            keeper: params.keeper.publicKey,
             creator: params.creator,
             winner: params.winner,
             prizeMint: params.prizeMint,
             bidMint: params.bidMint,
             prizeEscrow: params.prizeEscrow,
-            bidEscrow: params.bidEscrow,
+            auctionBundle: params.bundle?.length ? auctionBundlePda(params.auctionId) : null,
+            winnerBidEscrow,
+            winnerBidEscrowAta: params.winnerBidEscrowAta,
+            winnerBidAta: params.winnerBidAta ?? params.winnerBidEscrowAta,
             creatorPrizeAta: params.creatorPrizeAta,
             winnerPrizeAta: params.winnerPrizeAta,
             bidFeeTreasuryAta: params.bidFeeTreasuryAta,
             creatorBidAta: params.creatorBidAta,
+            keeperBidAta: params.keeperBidAta ?? null,
             prizeTokenProgram: TOKEN_PROGRAM_ID,
             bidTokenProgram: TOKEN_PROGRAM_ID,
             associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
+            ...pnftAccounts(),
         })
-        .signers([params.auctionAdmin])
+        .remainingAccounts([...bundleReleaseAccounts(params.bundle ?? []), ...(params.remainingAccounts ?? [])])
+        .signers([params.keeper])
         .rpc();
 
     return { auctionPda };
 }
 
+export async function commitSealedBid(
+    program: anchor.Program<Auction>,
+    params: {
+        auctionId: number;
+        bidder: Keypair;
+        auctionAdmin: Keypair;
+        commitment: number[]; // `sealedBidCommitment(auctionId, bidder, amount, salt)`
+        deposit: number;
+
+        // For SPL bids, any token accounts for SOL
+        bidMint: PublicKey;
+        bidderAta: PublicKey;
+        bidEscrowAta: PublicKey;
+        bidTokenProgram?: PublicKey;
+    }
+) {
+    const signed = await signPermit(program, params.auctionId, params.bidder.publicKey, params.deposit, params.auctionAdmin);
+
+    await program.methods
+        .commitSealedBid(params.auctionId, params.commitment, new anchor.BN(params.deposit), signed.permit)
+        .accounts({
+            auctionConfig: auctionConfigPda(),
+            auction: await deriveAuctionPda(program, params.auctionId),
+            bidder: params.bidder.publicKey,
+            permitNonce: signed.permitNonce,
+            instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+            bidMint: params.bidMint,
+            currentBidderAta: params.bidderAta,
+            bidEscrow: bidEscrowPda(params.auctionId, params.bidder.publicKey),
+            bidEscrowAta: params.bidEscrowAta,
+            bidTokenProgram: params.bidTokenProgram ?? TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+        })
+        .preInstructions([signed.ed25519Ix])
+        .signers([params.bidder])
+        .rpc();
+}
+
+export async function revealSealedBid(
+    program: anchor.Program<Auction>,
+    auctionId: number,
+    bidder: Keypair,
+    amount: number,
+    salt: number[]
+) {
+    await program.methods
+        .revealSealedBid(auctionId, new anchor.BN(amount), salt)
+        .accounts({
+            auction: await deriveAuctionPda(program, auctionId),
+            bidder: bidder.publicKey,
+            bidEscrow: bidEscrowPda(auctionId, bidder.publicKey),
+        })
+        .signers([bidder])
+        .rpc();
+}
+
+export async function buyNow(
+    program: anchor.Program<Auction>,
+    params: {
+        auctionId: number;
+        buyer: Keypair;
+        auctionAdmin: Keypair;
+        price: number;
+        creator: PublicKey;
+
+        prizeMint: PublicKey;
+        prizeEscrow: PublicKey;
+        buyerPrizeAta: PublicKey;
+
+        // For SPL payments, any token accounts for SOL
+        bidMint: PublicKey;
+        buyerBidAta: PublicKey;
+        bidFeeTreasuryAta: PublicKey;
+        creatorBidAta: PublicKey;
+        bidTokenProgram?: PublicKey;
+        displacedBidEscrowAta?: PublicKey; // SPL escrow of the current highest bid, refunded to its bidder
+
+        // one per verified prize creator, then any transfer hook accounts
+        remainingAccounts?: AccountMeta[];
+    }
+) {
+    const signed = await signPermit(program, params.auctionId, params.buyer.publicKey, params.price, params.auctionAdmin);
+    const auctionPda = await deriveAuctionPda(program, params.auctionId);
+
+    // the current highest bid is refunded within the purchase
+    const auction = await program.account.auction.fetch(auctionPda);
+    const displacedBidder = auction.hasAnyBid ? auction.highestBidder : null;
+    const displacedSpl = displacedBidder !== null && params.displacedBidEscrowAta !== undefined;
+
+    await program.methods
+        .buyNow(params.auctionId, signed.permit)
+        .accounts({
+            auctionConfig: auctionConfigPda(),
+            auction: auctionPda,
+            buyer: params.buyer.publicKey,
+            permitNonce: signed.permitNonce,
+            creator: params.creator,
+            prizeMint: params.prizeMint,
+            bidMint: params.bidMint,
+            prizeEscrow: params.prizeEscrow,
+            buyerPrizeAta: params.buyerPrizeAta,
+            buyerBidAta: params.buyerBidAta,
+            bidFeeTreasuryAta: params.bidFeeTreasuryAta,
+            creatorBidAta: params.creatorBidAta,
+            prizeTokenProgram: TOKEN_PROGRAM_ID,
+            bidTokenProgram: params.bidTokenProgram ?? TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            displacedBidEscrow: displacedBidder && bidEscrowPda(params.auctionId, displacedBidder),
+            displacedBidder,
+            displacedBidEscrowAta: displacedSpl ? params.displacedBidEscrowAta : null,
+            displacedBidderAta: displacedSpl
+                ? getAssociatedTokenAddressSync(params.bidMint, displacedBidder, false, params.bidTokenProgram ?? TOKEN_PROGRAM_ID)
+                : null,
+            ...pnftAccounts(),
+        })
+        .remainingAccounts(params.remainingAccounts ?? [])
+        .preInstructions([signed.ed25519Ix])
+        .signers([params.buyer])
+        .rpc();
+}
+
+export async function buyDutch(
+    program: anchor.Program<Auction>,
+    params: {
+        auctionId: number;
+        buyer: Keypair;
+        auctionAdmin: Keypair;
+        maxPrice: number; // permit cap, the current Dutch price is charged
+        creator: PublicKey;
+
+        prizeMint: PublicKey;
+        prizeEscrow: PublicKey;
+        buyerPrizeAta: PublicKey;
+
+        // For SPL payments, any token accounts for SOL
+        bidMint: PublicKey;
+        buyerBidAta: PublicKey;
+        bidFeeTreasuryAta: PublicKey;
+        creatorBidAta: PublicKey;
+        bidTokenProgram?: PublicKey;
+
+        // one per verified prize creator, then any transfer hook accounts
+        remainingAccounts?: AccountMeta[];
+    }
+) {
+    const signed = await signPermit(program, params.auctionId, params.buyer.publicKey, params.maxPrice, params.auctionAdmin);
+
+    await program.methods
+        .buyDutch(params.auctionId, signed.permit)
+        .accounts({
+            auctionConfig: auctionConfigPda(),
+            auction: await deriveAuctionPda(program, params.auctionId),
+            buyer: params.buyer.publicKey,
+            permitNonce: signed.permitNonce,
+            creator: params.creator,
+            prizeMint: params.prizeMint,
+            bidMint: params.bidMint,
+            prizeEscrow: params.prizeEscrow,
+            buyerPrizeAta: params.buyerPrizeAta,
+            buyerBidAta: params.buyerBidAta,
+            bidFeeTreasuryAta: params.bidFeeTreasuryAta,
+            creatorBidAta: params.creatorBidAta,
+            prizeTokenProgram: TOKEN_PROGRAM_ID,
+            bidTokenProgram: params.bidTokenProgram ?? TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+            // Dutch auctions take no bids, nobody is displaced
+            displacedBidEscrow: null,
+            displacedBidder: null,
+            displacedBidEscrowAta: null,
+            displacedBidderAta: null,
+            ...pnftAccounts(),
+        })
+        .remainingAccounts(params.remainingAccounts ?? [])
+        .preInstructions([signed.ed25519Ix])
+        .signers([params.buyer])
+        .rpc();
+}
+
+export async function withdrawOutbid(
+    program: anchor.Program<Auction>,
+    params: {
+        auctionId: number;
+        bidder: Keypair;
+
+        // For SPL bids, any token accounts for SOL
+        bidMint: PublicKey;
+        bidEscrowAta: PublicKey;
+        bidderAta: PublicKey;
+        bidFeeTreasuryAta?: PublicKey; // receives a forfeited sealed bid penalty
+        bidTokenProgram?: PublicKey;
+    }
+) {
+    const bidEscrow = bidEscrowPda(params.auctionId, params.bidder.publicKey);
+
+    await program.methods
+        .withdrawOutbid(params.auctionId)
+        .accounts({
+            auctionConfig: auctionConfigPda(),
+            auction: await deriveAuctionPda(program, params.auctionId),
+            bidder: params.bidder.publicKey,
+            bidEscrow,
+            bidMint: params.bidMint,
+            bidEscrowAta: params.bidEscrowAta,
+            bidderAta: params.bidderAta,
+            bidFeeTreasuryAta: params.bidFeeTreasuryAta ?? params.bidEscrowAta,
+            bidTokenProgram: params.bidTokenProgram ?? TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+        })
+        .signers([params.bidder])
+        .rpc();
+
+    return { bidEscrow };
+}
+
+export async function listNft(
+    program: anchor.Program<Auction>,
+    params: {
+        seller: Keypair;
+        auctionAdmin: Keypair;
+        nftMint: PublicKey;
+        sellerNftAta: PublicKey;
+        price: number;
+        priceMint: PublicKey | null; // null => SOL
+        nftCollectionAllowlist?: PublicKey;
+    }
+) {
+    const listing = listingPda(params.nftMint);
+    // the price mint account is only read for SPL prices
+    const priceMint = params.priceMint ?? params.nftMint;
+
+    await program.methods
+        .listNft(new anchor.BN(params.price), params.priceMint === null)
+        .accounts({
+            auctionConfig: auctionConfigPda(),
+            listing,
+            seller: params.seller.publicKey,
+            auctionAdmin: params.auctionAdmin.publicKey,
+            nftMint: params.nftMint,
+            priceMint,
+            nftMintAllowlist: mintAllowlistPda(params.nftMint),
+            priceMintAllowlist: mintAllowlistPda(priceMint),
+            sellerNftAta: params.sellerNftAta,
+            listingEscrow: getAssociatedTokenAddressSync(params.nftMint, listing, true),
+            nftMetadata: nftMetadataPda(params.nftMint),
+            nftEdition: nftEditionPda(params.nftMint),
+            nftCollectionAllowlist: params.nftCollectionAllowlist ?? null,
+            tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+            nftTokenProgram: TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+        })
+        .signers([params.seller, params.auctionAdmin])
+        .rpc();
+
+    return { listing };
+}
+
+export async function buyListing(
+    program: anchor.Program<Auction>,
+    params: {
+        buyer: Keypair;
+        auctionAdmin: Keypair; // signs the buyer's permit
+        seller: PublicKey;
+        nftMint: PublicKey;
+        buyerNftAta: PublicKey;
+        maxPrice: number;
+
+        // For SPL prices, any token accounts for SOL
+        priceMint: PublicKey;
+        buyerPriceAta: PublicKey;
+        priceFeeTreasuryAta: PublicKey;
+        sellerPriceAta: PublicKey;
+        priceTokenProgram?: PublicKey;
+
+        // one per verified NFT creator, then any transfer hook accounts
+        remainingAccounts?: AccountMeta[];
+    }
+) {
+    const listing = listingPda(params.nftMint);
+    const signed = await signPermit(program, listing, params.buyer.publicKey, params.maxPrice, params.auctionAdmin);
+
+    await program.methods
+        .buyListing(new anchor.BN(params.maxPrice), signed.permit)
+        .accounts({
+            auctionConfig: auctionConfigPda(),
+            listing,
+            buyer: params.buyer.publicKey,
+            permitNonce: signed.permitNonce,
+            instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+            seller: params.seller,
+            nftMint: params.nftMint,
+            priceMint: params.priceMint,
+            listingEscrow: getAssociatedTokenAddressSync(params.nftMint, listing, true),
+            buyerNftAta: params.buyerNftAta,
+            buyerPriceAta: params.buyerPriceAta,
+            priceFeeTreasuryAta: params.priceFeeTreasuryAta,
+            sellerPriceAta: params.sellerPriceAta,
+            nftMetadata: nftMetadataPda(params.nftMint),
+            tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+            nftTokenProgram: TOKEN_PROGRAM_ID,
+            priceTokenProgram: params.priceTokenProgram ?? TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(params.remainingAccounts ?? [])
+        .preInstructions([signed.ed25519Ix])
+        .signers([params.buyer])
+        .rpc();
+}
+
+export async function delistNft(
+    program: anchor.Program<Auction>,
+    params: { seller: Keypair; nftMint: PublicKey; sellerNftAta: PublicKey }
+) {
+    const listing = listingPda(params.nftMint);
+
+    await program.methods
+        .delistNft()
+        .accounts({
+            listing,
+            seller: params.seller.publicKey,
+            nftMint: params.nftMint,
+            listingEscrow: getAssociatedTokenAddressSync(params.nftMint, listing, true),
+            sellerNftAta: params.sellerNftAta,
+            nftTokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([params.seller])
+        .rpc();
+}
+
+export async function makeOffer(
+    program: anchor.Program<Auction>,
+    params: {
+        buyer: Keypair;
+        auctionAdmin: Keypair;
+        collection: PublicKey;
+        amount: number;
+        expiry?: number; // 0 => never expires
+
+        // For SPL offers, any token accounts for SOL
+        priceMint: PublicKey;
+        isPriceMintSol: boolean;
+        buyerPriceAta: PublicKey;
+        offerEscrowAta: PublicKey;
+        priceTokenProgram?: PublicKey;
+    }
+) {
+    const offer = offerPda(params.collection, params.buyer.publicKey);
+
+    await program.methods
+        .makeOffer(params.collection, new anchor.BN(params.amount), new anchor.BN(params.expiry ?? 0), params.isPriceMintSol)
+        .accounts({
+            auctionConfig: auctionConfigPda(),
+            offer,
+            buyer: params.buyer.publicKey,
+            auctionAdmin: params.auctionAdmin.publicKey,
+            priceMint: params.priceMint,
+            priceMintAllowlist: mintAllowlistPda(params.priceMint),
+            buyerPriceAta: params.buyerPriceAta,
+            offerEscrowAta: params.offerEscrowAta,
+            priceTokenProgram: params.priceTokenProgram ?? TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+        })
+        .signers([params.buyer, params.auctionAdmin])
+        .rpc();
+
+    return { offer };
+}
+
+export async function acceptOffer(
+    program: anchor.Program<Auction>,
+    params: {
+        seller: Keypair;
+        auctionAdmin: Keypair; // signs the seller's permit
+        buyer: PublicKey;
+        collection: PublicKey;
+        minAmount: number;
+        nftMint: PublicKey;
+        sellerNftAta: PublicKey;
+        nftCollectionAllowlist?: PublicKey;
+
+        // For SPL offers, any token accounts for SOL
+        priceMint: PublicKey;
+        offerEscrowAta: PublicKey;
+        priceFeeTreasuryAta: PublicKey;
+        sellerPriceAta: PublicKey;
+        priceTokenProgram?: PublicKey;
+
+        // one per verified NFT creator, then any transfer hook accounts
+        remainingAccounts?: AccountMeta[];
+    }
+) {
+    const offer = offerPda(params.collection, params.buyer);
+    // the permit has to cover what the offer escrows, which may be more than the seller asks for
+    const { amount } = await program.account.offer.fetch(offer);
+    const signed = await signPermit(program, offer, params.seller.publicKey, amount.toNumber(), params.auctionAdmin);
+
+    await program.methods
+        .acceptOffer(new anchor.BN(params.minAmount), signed.permit)
+        .accounts({
+            auctionConfig: auctionConfigPda(),
+            offer,
+            buyer: params.buyer,
+            seller: params.seller.publicKey,
+            permitNonce: signed.permitNonce,
+            instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+            nftMint: params.nftMint,
+            priceMint: params.priceMint,
+            sellerNftAta: params.sellerNftAta,
+            buyerNftAta: getAssociatedTokenAddressSync(params.nftMint, params.buyer, true),
+            offerEscrowAta: params.offerEscrowAta,
+            priceFeeTreasuryAta: params.priceFeeTreasuryAta,
+            sellerPriceAta: params.sellerPriceAta,
+            nftMetadata: nftMetadataPda(params.nftMint),
+            nftEdition: nftEditionPda(params.nftMint),
+            nftCollectionAllowlist: params.nftCollectionAllowlist ?? null,
+            tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+            nftTokenProgram: TOKEN_PROGRAM_ID,
+            priceTokenProgram: params.priceTokenProgram ?? TOKEN_PROGRAM_ID,
+            associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+            systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(params.remainingAccounts ?? [])
+        .preInstructions([signed.ed25519Ix])
+        .signers([params.seller])
+        .rpc();
+}
+
+export async function cancelOffer(
+    program: anchor.Program<Auction>,
+    params: {
+        buyer: Keypair;
+        collection: PublicKey;
+
+        // For SPL offers, any token accounts for SOL
+        priceMint: PublicKey;
+        offerEscrowAta: PublicKey;
+        buyerPriceAta: PublicKey;
+        priceTokenProgram?: PublicKey;
+    }
+) {
+    await program.methods
+        .cancelOffer()
+        .accounts({
+            offer: offerPda(params.collection, params.buyer.publicKey),
+            buyer: params.buyer.publicKey,
+            priceMint: params.priceMint,
+            offerEscrowAta: params.offerEscrowAta,
+            buyerPriceAta: params.buyerPriceAta,
+            priceTokenProgram: params.priceTokenProgram ?? TOKEN_PROGRAM_ID,
+        })
+        .signers([params.buyer])
+        .rpc();
+}
+
 export async function deriveAuctionPda(program: anchor.Program<Auction>, auctionId: number) {
     const [pda] = PublicKey.findProgramAddressSync(
         [
@@ -0,0 +1,204 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { Keypair, SystemProgram } from "@solana/web3.js";
+import { getAssociatedTokenAddressSync } from "@solana/spl-token";
+
+import {
+    createAuctionConfig,
+    createAuction,
+    placeBid,
+    completeAuction,
+    setKeeperFee,
+    createNftMint,
+    createAta,
+    mintTokens,
+    getSolBalance,
+    getTokenBalance,
+    getCurrentTimestamp,
+    warpForward,
+} from "./helpers";
+
+import {
+    auction_owner,
+    auction_admin,
+    auction_1_creator,
+    creation_fee_lamports,
+    commission_bps,
+    minimum_auction_period,
+    maximum_auction_period,
+    minimum_time_extension,
+    maximum_time_extension,
+    setProgram,
+    setProvider,
+    auctionConfigPda,
+    auctionPda,
+    bidEscrowPda,
+    reserveCommitment,
+} from "./values";
+
+import type { Auction } from "../target/types/auction";
+
+describe("Keeper settlement", () => {
+    let context: any;
+    let provider: BankrunProvider;
+    let program: anchor.Program<Auction>;
+
+    // KEEPER_SETTLEMENT_DELAY on chain
+    const settlementDelay = 5 * 60;
+    // 20% of the commission goes to the keeper
+    const keeperFeeBps = 2_000;
+    const bid = 10_000_000_000;
+    const fee = (bid * commission_bps) / 10_000;
+    const keeperFee = (fee * keeperFeeBps) / 10_000;
+
+    before(async () => {
+        context = await startAnchor("", [], []);
+        provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+
+        program = anchor.workspace.Auction as anchor.Program<Auction>;
+        setProgram(program);
+
+        for (const kp of [auction_owner, auction_admin, auction_1_creator]) {
+            await context.setAccount(kp.publicKey, {
+                lamports: 20_000_000_000,
+                owner: SystemProgram.programId,
+                executable: false,
+                data: Buffer.alloc(0),
+            });
+        }
+
+        await createAuctionConfig(program, auction_owner, auction_admin.publicKey, {
+            creationFeeLamports: creation_fee_lamports,
+            commision_bps: commission_bps,
+            minPeriod: minimum_auction_period,
+            maxPeriod: maximum_auction_period,
+            minTimeExtension: minimum_time_extension,
+            maxTimeExtension: maximum_time_extension,
+        });
+    });
+
+    async function fundedKeypair() {
+        const kp = Keypair.generate();
+        await context.setAccount(kp.publicKey, {
+            lamports: 20_000_000_000,
+            owner: SystemProgram.programId,
+            executable: false,
+            data: Buffer.alloc(0),
+        });
+        return kp;
+    }
+
+    // SOL auction with one bid, ended by the time this returns. The winner never opens a prize account
+    async function endedAuction(hiddenReserve?: number[]) {
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        const prizeMint = await createNftMint();
+        const creatorPrizeAta = await createAta(prizeMint, auction_1_creator.publicKey);
+        const prizeEscrow = await createAta(prizeMint, auctionPda(cfg.auctionCount));
+        await mintTokens(prizeMint, creatorPrizeAta, 1);
+
+        const start = await getCurrentTimestamp();
+        const { auctionId } = await createAuction(program, {
+            creator: auction_1_creator,
+            auctionAdmin: auction_admin,
+            prizeMint,
+            bidMint: null,
+            creatorPrizeAta,
+            prizeEscrow,
+            startTime: start,
+            endTime: start + minimum_auction_period,
+            startImmediately: true,
+            baseBid: 1_000_000_000,
+            minIncrement: 100_000_000,
+            timeExtension: minimum_time_extension,
+            reserveCommitment: hiddenReserve,
+        });
+
+        // SOL bids never touch the token accounts, the prize escrow stands in for them
+        const winner = await fundedKeypair();
+        await placeBid(program, {
+            auctionId,
+            bidder: winner,
+            auctionAdmin: auction_admin,
+            bidAmount: bid,
+            bidMint: prizeMint,
+            bidderAta: prizeEscrow,
+            bidEscrowAta: prizeEscrow,
+        });
+        await warpForward(minimum_auction_period + 1);
+
+        const winnerPrizeAta = getAssociatedTokenAddressSync(prizeMint, winner.publicKey);
+        const settle = (keeper: Keypair, reserveReveal?: { price: number; salt: number[] }) =>
+            completeAuction(program, {
+                auctionId,
+                keeper,
+                creator: auction_1_creator.publicKey,
+                winner: winner.publicKey,
+                prizeMint,
+                bidMint: prizeMint,
+                prizeEscrow,
+                winnerBidEscrowAta: prizeEscrow,
+                creatorPrizeAta,
+                winnerPrizeAta,
+                bidFeeTreasuryAta: prizeEscrow,
+                creatorBidAta: prizeEscrow,
+                reserveReveal,
+            });
+
+        return { auctionId, winner, winnerPrizeAta, settle };
+    }
+
+    it("rejects a keeper fee above the whole commission", async () => {
+        await assert.rejects(setKeeperFee(program, auction_owner, 10_001), /InvalidKeeperFee/);
+        await setKeeperFee(program, auction_owner, keeperFeeBps);
+
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        assert.equal(cfg.keeperFeeBps, keeperFeeBps);
+    });
+
+    it("lets anyone settle after the delay and pays them out of the commission", async () => {
+        const { auctionId, winner, winnerPrizeAta, settle } = await endedAuction();
+        const keeper = await fundedKeypair();
+
+        await assert.rejects(settle(keeper), /SettlementDelayNotOver/);
+        await warpForward(settlementDelay);
+
+        const keeperBefore = await getSolBalance(keeper.publicKey);
+        const configBefore = await getSolBalance(auctionConfigPda());
+        const escrow = await context.banksClient.getAccount(bidEscrowPda(auctionId, winner.publicKey));
+        const ataRent = Number((await context.banksClient.getRent()).minimumBalance(165n));
+        await settle(keeper);
+
+        // the keeper opened the winner's prize account and got its rent back, out of the winner's
+        // escrow refund first and the treasury's share of the commission for the rest
+        assert.equal(await getTokenBalance(winnerPrizeAta), 1);
+        const rentFromTreasury = Math.max(0, ataRent - (Number(escrow.lamports) - bid));
+        assert.equal((await getSolBalance(auctionConfigPda())) - configBefore, fee - keeperFee - rentFromTreasury);
+        assert.equal((await getSolBalance(keeper.publicKey)) - keeperBefore, keeperFee);
+        assert.equal(await context.banksClient.getAccount(auctionPda(auctionId)), null);
+    });
+
+    it("still lets the admin settle as soon as the auction ends", async () => {
+        const { winnerPrizeAta, settle } = await endedAuction();
+
+        await settle(auction_admin);
+        assert.equal(await getTokenBalance(winnerPrizeAta), 1);
+    });
+
+    it("keeps a keeper from failing a hidden-reserve sale by leaving the reserve unrevealed", async () => {
+        const reserve = bid / 2;
+        const salt = new Array(32).fill(7);
+        const { winnerPrizeAta, settle } = await endedAuction(reserveCommitment(reserve, salt));
+        const keeper = await fundedKeypair();
+        await warpForward(settlementDelay);
+
+        await assert.rejects(settle(keeper), /HiddenReserveNotRevealed/);
+
+        // with the reveal the bid clears the reserve and the winner gets the prize
+        await settle(keeper, { price: reserve, salt });
+        assert.equal(await getTokenBalance(winnerPrizeAta), 1);
+    });
+});
@@ -0,0 +1,253 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+
+import {
+    createAuctionConfig,
+    listNft,
+    buyListing,
+    delistNft,
+    makeOffer,
+    acceptOffer,
+    cancelOffer,
+    createSplMint,
+    createNftMint,
+    setNftMetadata,
+    createAta,
+    mintTokens,
+    getSolBalance,
+    getTokenBalance,
+} from "./helpers";
+
+import {
+    auction_owner,
+    auction_admin,
+    creation_fee_lamports,
+    commission_bps,
+    minimum_auction_period,
+    maximum_auction_period,
+    minimum_time_extension,
+    maximum_time_extension,
+    setProgram,
+    setProvider,
+    auctionConfigPda,
+    listingPda,
+    offerPda,
+} from "./values";
+
+import type { Auction } from "../target/types/auction";
+
+describe("Listings and collection offers", () => {
+    let context: any;
+    let provider: BankrunProvider;
+    let program: anchor.Program<Auction>;
+
+    const price = 2_000_000_000;
+    const fee = (price * commission_bps) / 10_000;
+
+    before(async () => {
+        context = await startAnchor("", [], []);
+        provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+
+        program = anchor.workspace.Auction as anchor.Program<Auction>;
+        setProgram(program);
+
+        for (const kp of [auction_owner, auction_admin]) {
+            await context.setAccount(kp.publicKey, {
+                lamports: 20_000_000_000,
+                owner: SystemProgram.programId,
+                executable: false,
+                data: Buffer.alloc(0),
+            });
+        }
+
+        await createAuctionConfig(program, auction_owner, auction_admin.publicKey, {
+            creationFeeLamports: creation_fee_lamports,
+            commision_bps: commission_bps,
+            minPeriod: minimum_auction_period,
+            maxPeriod: maximum_auction_period,
+            minTimeExtension: minimum_time_extension,
+            maxTimeExtension: maximum_time_extension,
+        });
+    });
+
+    async function fundedKeypair() {
+        const kp = Keypair.generate();
+        await context.setAccount(kp.publicKey, {
+            lamports: 20_000_000_000,
+            owner: SystemProgram.programId,
+            executable: false,
+            data: Buffer.alloc(0),
+        });
+        return kp;
+    }
+
+    // NFT held by `owner`, in `collection` when given
+    async function ownedNft(owner: PublicKey, collection?: PublicKey) {
+        const mint = collection ? await createSplMint(0) : await createNftMint();
+        if (collection) {
+            await setNftMetadata(mint, { collection });
+        }
+        const ata = await createAta(mint, owner);
+        await mintTokens(mint, ata, 1);
+        return { mint, ata };
+    }
+
+    async function listedNft() {
+        const seller = await fundedKeypair();
+        const nft = await ownedNft(seller.publicKey);
+        await listNft(program, {
+            seller,
+            auctionAdmin: auction_admin,
+            nftMint: nft.mint,
+            sellerNftAta: nft.ata,
+            price,
+            priceMint: null,
+        });
+        return { seller, ...nft };
+    }
+
+    it("sells a listed NFT for its price less the commission", async () => {
+        const { seller, mint } = await listedNft();
+        const listing = await program.account.listing.fetch(listingPda(mint));
+        assert.equal(listing.price.toNumber(), price);
+        assert.equal(listing.commissionBps, commission_bps);
+
+        const buyer = await fundedKeypair();
+        const buyerNftAta = await createAta(mint, buyer.publicKey);
+        const accounts = {
+            buyer,
+            auctionAdmin: auction_admin,
+            seller: seller.publicKey,
+            nftMint: mint,
+            buyerNftAta,
+            // SOL prices never touch the token accounts, the buyer's NFT account stands in for them
+            priceMint: mint,
+            buyerPriceAta: buyerNftAta,
+            priceFeeTreasuryAta: buyerNftAta,
+            sellerPriceAta: buyerNftAta,
+        };
+
+        await assert.rejects(buyListing(program, { ...accounts, maxPrice: price - 1 }), /PriceAboveMaximum/);
+        // the admin signs off on market buyers as on bidders
+        await assert.rejects(
+            buyListing(program, { ...accounts, auctionAdmin: buyer, maxPrice: price }),
+            /InvalidPermitSigner/
+        );
+
+        const sellerBefore = await getSolBalance(seller.publicKey);
+        const configBefore = await getSolBalance(auctionConfigPda());
+        await buyListing(program, { ...accounts, maxPrice: price });
+
+        assert.equal(await getTokenBalance(buyerNftAta), 1);
+        assert.equal((await getSolBalance(auctionConfigPda())) - configBefore, fee);
+        // the seller also gets the rent of the closed listing and its token account
+        assert.ok((await getSolBalance(seller.publicKey)) - sellerBefore >= price - fee);
+        assert.equal(await context.banksClient.getAccount(listingPda(mint)), null);
+    });
+
+    it("returns a delisted NFT to its seller", async () => {
+        const { seller, mint, ata } = await listedNft();
+        assert.equal(await getTokenBalance(ata), 0);
+
+        const stranger = await fundedKeypair();
+        await assert.rejects(
+            delistNft(program, { seller: stranger, nftMint: mint, sellerNftAta: await createAta(mint, stranger.publicKey) }),
+            /InvalidSeller/
+        );
+
+        await delistNft(program, { seller, nftMint: mint, sellerNftAta: ata });
+        assert.equal(await getTokenBalance(ata), 1);
+        assert.equal(await context.banksClient.getAccount(listingPda(mint)), null);
+    });
+
+    it("sells an NFT of the collection into a standing offer", async () => {
+        const collection = Keypair.generate().publicKey;
+        const buyer = await fundedKeypair();
+        // SOL offers never touch the token accounts, a throwaway one stands in for them
+        const { mint: standInMint, ata: standIn } = await ownedNft(buyer.publicKey);
+
+        await makeOffer(program, {
+            buyer,
+            auctionAdmin: auction_admin,
+            collection,
+            amount: price,
+            priceMint: standInMint,
+            isPriceMintSol: true,
+            buyerPriceAta: standIn,
+            offerEscrowAta: standIn,
+        });
+        const offer = await program.account.offer.fetch(offerPda(collection, buyer.publicKey));
+        assert.equal(offer.amount.toNumber(), price);
+        assert.ok(offer.priceMint === null);
+
+        const seller = await fundedKeypair();
+        const accept = async (nft: { mint: PublicKey; ata: PublicKey }, minAmount = price, auctionAdmin = auction_admin) =>
+            acceptOffer(program, {
+                seller,
+                auctionAdmin,
+                buyer: buyer.publicKey,
+                collection,
+                minAmount,
+                nftMint: nft.mint,
+                sellerNftAta: nft.ata,
+                priceMint: standInMint,
+                offerEscrowAta: standIn,
+                priceFeeTreasuryAta: standIn,
+                sellerPriceAta: standIn,
+            });
+
+        // an NFT of another collection can't fill it, nor can a seller asking for more
+        await assert.rejects(accept(await ownedNft(seller.publicKey, Keypair.generate().publicKey)), /CollectionMismatch/);
+        const nft = await ownedNft(seller.publicKey, collection);
+        await assert.rejects(accept(nft, price + 1), /OfferBelowMinimum/);
+        // and neither can a seller the admin has not signed off on
+        await assert.rejects(accept(nft, price, seller), /InvalidPermitSigner/);
+
+        const sellerBefore = await getSolBalance(seller.publicKey);
+        const configBefore = await getSolBalance(auctionConfigPda());
+        await accept(nft);
+
+        assert.equal(await getTokenBalance(nft.ata), 0);
+        assert.equal(await getSolBalance(auctionConfigPda()) - configBefore, fee);
+        // less the rent of the buyer's new NFT account, paid by the seller
+        assert.ok((await getSolBalance(seller.publicKey)) - sellerBefore >= price - fee - 3_000_000);
+        assert.equal(await context.banksClient.getAccount(offerPda(collection, buyer.publicKey)), null);
+    });
+
+    it("refunds a cancelled offer", async () => {
+        const collection = Keypair.generate().publicKey;
+        const buyer = await fundedKeypair();
+        // SOL offers never touch the token accounts, a throwaway one stands in for them
+        const { mint: standInMint, ata: standIn } = await ownedNft(buyer.publicKey);
+
+        const before = await getSolBalance(buyer.publicKey);
+        await makeOffer(program, {
+            buyer,
+            auctionAdmin: auction_admin,
+            collection,
+            amount: price,
+            priceMint: standInMint,
+            isPriceMintSol: true,
+            buyerPriceAta: standIn,
+            offerEscrowAta: standIn,
+        });
+        assert.ok(before - (await getSolBalance(buyer.publicKey)) >= price);
+
+        await cancelOffer(program, {
+            buyer,
+            collection,
+            priceMint: standInMint,
+            offerEscrowAta: standIn,
+            buyerPriceAta: standIn,
+        });
+
+        // only the transaction fees are lost
+        assert.ok(before - (await getSolBalance(buyer.publicKey)) < 1_000_000);
+        assert.equal(await context.banksClient.getAccount(offerPda(collection, buyer.publicKey)), null);
+    });
+});
@@ -0,0 +1,461 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { SystemProgram, Keypair, PublicKey } from "@solana/web3.js";
+import { getAssociatedTokenAddressSync } from "@solana/spl-token";
+
+import {
+    auction_owner,
+    auction_admin,
+    auction_1_creator,
+    setProgram,
+    setProvider,
+    auctionConfigPda,
+    auctionPda,
+    creation_fee_lamports,
+    commission_bps,
+    minimum_auction_period,
+    maximum_auction_period,
+    minimum_time_extension,
+    maximum_time_extension,
+    minimum_config_change_delay,
+    bidEscrowPda,
+} from "./values";
+import {
+    createNftMint,
+    createAta,
+    mintTokens,
+    completeAuction,
+    getSolBalance,
+    getTokenBalance,
+    getCurrentTimestamp,
+    warpForward,
+} from "./helpers";
+
+// Sizes of the accounts as deployed before the `version` byte and reserved space
+const LEGACY_CONFIG_LEN = 8 + 32 + 32 + 8 + 2 + 4 + 4 + 4 + 4 + 4 + 1 + 1;
+const LEGACY_AUCTION_LEN =
+    8 + 4 + 32 + 32 + 8 + 8 + 33 + 8 + 8 + 4 + 8 + 32 + 1 + 1 + 1;
+
+// A version 0 auction with a live SOL bid also takes the bidder's escrow, the SPL accounts stay empty
+function migrateAuctionAccounts(auctionId: number, bidEscrow: PublicKey | null = null) {
+    return {
+        auction: auctionPda(auctionId),
+        auctionConfig: auctionConfigPda(),
+        payer: auction_owner.publicKey,
+        bidEscrow,
+        legacyBidVault: null,
+        bidEscrowAta: null,
+        bidMint: null,
+        creator: null,
+        bidTokenProgram: null,
+        systemProgram: SystemProgram.programId,
+    };
+}
+
+function discriminator(program: anchor.Program<any>, name: string): Buffer {
+    const account = program.idl.accounts.find((a: any) => a.name === name);
+    return Buffer.from(account.discriminator);
+}
+
+function legacyConfigSnapshot(program: anchor.Program<any>, bump: number): Buffer {
+    const buf = Buffer.alloc(LEGACY_CONFIG_LEN);
+    let o = 0;
+    o += discriminator(program, "auctionConfig").copy(buf, o);
+    o += auction_owner.publicKey.toBuffer().copy(buf, o);
+    o += auction_admin.publicKey.toBuffer().copy(buf, o);
+    o = buf.writeBigUInt64LE(BigInt(creation_fee_lamports), o);
+    o = buf.writeUInt16LE(commission_bps, o);
+    o = buf.writeUInt32LE(minimum_auction_period, o);
+    o = buf.writeUInt32LE(maximum_auction_period, o);
+    o = buf.writeUInt32LE(minimum_time_extension, o);
+    o = buf.writeUInt32LE(maximum_time_extension, o);
+    o = buf.writeUInt32LE(2, o); // auction_count
+    o = buf.writeUInt8(0b0000_0100, o); // pause_flags
+    buf.writeUInt8(bump, o);
+    return buf;
+}
+
+// Version 1 config, same size as the current one but with a u8 `pause_flags`
+function v1ConfigSnapshot(program: anchor.Program<any>, bump: number): Buffer {
+    const buf = Buffer.alloc(program.account.auctionConfig.size);
+    let o = 0;
+    o += discriminator(program, "auctionConfig").copy(buf, o);
+    o = buf.writeUInt8(1, o); // version
+    o += auction_owner.publicKey.toBuffer().copy(buf, o);
+    o += auction_admin.publicKey.toBuffer().copy(buf, o);
+    o = buf.writeBigUInt64LE(BigInt(creation_fee_lamports), o);
+    o = buf.writeUInt16LE(commission_bps, o);
+    o = buf.writeUInt32LE(minimum_auction_period, o);
+    o = buf.writeUInt32LE(maximum_auction_period, o);
+    o = buf.writeUInt32LE(minimum_time_extension, o);
+    o = buf.writeUInt32LE(maximum_time_extension, o);
+    o = buf.writeUInt32LE(4, o); // auction_count
+    o = buf.writeUInt8(0b0010_0001, o); // pause_flags
+    o = buf.writeUInt8(bump, o);
+    o = buf.writeUInt8(0, o); // pending_owner = None
+    o = buf.writeUInt32LE(minimum_config_change_delay, o);
+    buf.writeUInt8(0, o); // pending_config_data = None
+    return buf;
+}
+
+// Active SOL auction holding one bid
+function legacyAuctionSnapshot(
+    program: anchor.Program<any>,
+    prizeMint: PublicKey,
+    bidder: PublicKey,
+    bump: number
+): Buffer {
+    const buf = Buffer.alloc(LEGACY_AUCTION_LEN);
+    let o = 0;
+    o += discriminator(program, "auction").copy(buf, o);
+    o = buf.writeUInt32LE(1, o); // auction_id
+    o += auction_1_creator.publicKey.toBuffer().copy(buf, o);
+    o += prizeMint.toBuffer().copy(buf, o);
+    o = buf.writeBigInt64LE(1_000n, o); // start_time
+    o = buf.writeBigInt64LE(5_000n, o); // end_time
+    o = buf.writeUInt8(0, o); // bid_mint = None
+    o += 32; // unused Option payload
+    o = buf.writeBigUInt64LE(100_000_000n, o); // base_bid
+    o = buf.writeBigUInt64LE(10_000_000n, o); // min_increment
+    o = buf.writeUInt32LE(600, o); // time_extension
+    o = buf.writeBigUInt64LE(250_000_000n, o); // highest_bid_amount
+    o += bidder.toBuffer().copy(buf, o);
+    o = buf.writeUInt8(1, o); // has_any_bid
+    o = buf.writeUInt8(2, o); // status = Active
+    buf.writeUInt8(bump, o);
+    return buf;
+}
+
+// Version 1 auction, created before anti-sniping limits and fungible lots, so both decode as 0.
+// Its reserved space was 126 bytes shorter than the current one
+function v1AuctionSnapshot(program: anchor.Program<any>, prizeMint: PublicKey, bump: number): Buffer {
+    const buf = Buffer.alloc(program.account.auction.size - 126);
+    let o = 0;
+    o += discriminator(program, "auction").copy(buf, o);
+    o = buf.writeUInt8(1, o); // version
+    o = buf.writeUInt32LE(3, o); // auction_id
+    o += auction_1_creator.publicKey.toBuffer().copy(buf, o);
+    o += prizeMint.toBuffer().copy(buf, o);
+    o = buf.writeBigInt64LE(1_000n, o); // start_time
+    o = buf.writeBigInt64LE(5_000n, o); // end_time
+    o = buf.writeUInt8(0, o); // bid_mint = None
+    o += 32; // unused Option payload
+    o = buf.writeBigUInt64LE(100_000_000n, o); // base_bid
+    o = buf.writeBigUInt64LE(10_000_000n, o); // min_increment
+    o = buf.writeUInt32LE(600, o); // time_extension
+    o = buf.writeBigUInt64LE(100_000_000n, o); // highest_bid_amount
+    o += 32; // no highest_bidder
+    o = buf.writeUInt8(0, o); // has_any_bid
+    o = buf.writeUInt8(1, o); // status = Initialized
+    o = buf.writeUInt8(bump, o);
+    buf.writeUInt16LE(250, o); // commission_bps
+    return buf;
+}
+
+describe("Account migration – legacy snapshots", () => {
+    let context: any;
+    let provider: BankrunProvider;
+    let program: anchor.Program<any>;
+
+    let prizeMint: PublicKey;
+    let prizeEscrow: PublicKey;
+    let creatorPrizeAta: PublicKey;
+    const bidder = Keypair.generate().publicKey;
+
+    before(async () => {
+        context = await startAnchor("", [], []);
+        provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+
+        program = anchor.workspace.Auction as anchor.Program<any>;
+        setProgram(program);
+
+        for (const kp of [auction_owner, auction_admin, auction_1_creator]) {
+            await context.setAccount(kp.publicKey, {
+                lamports: 10_000_000_000,
+                owner: SystemProgram.programId,
+                executable: false,
+                data: Buffer.alloc(0),
+            });
+        }
+
+        const [, configBump] = PublicKey.findProgramAddressSync(
+            [Buffer.from("auction")],
+            program.programId
+        );
+        await context.setAccount(auctionConfigPda(), {
+            lamports: 1_000_000_000,
+            owner: program.programId,
+            executable: false,
+            data: legacyConfigSnapshot(program, configBump),
+        });
+
+        // the prize sits in the auction's escrow as it did before the migration
+        prizeMint = await createNftMint();
+        prizeEscrow = await createAta(prizeMint, auctionPda(1));
+        creatorPrizeAta = await createAta(prizeMint, auction_1_creator.publicKey);
+        await mintTokens(prizeMint, prizeEscrow, 1);
+
+        const [, auctionBump] = PublicKey.findProgramAddressSync(
+            [Buffer.from("auction"), new anchor.BN(1).toArrayLike(Buffer, "le", 4)],
+            program.programId
+        );
+        await context.setAccount(auctionPda(1), {
+            lamports: 250_000_000 + 5_000_000, // highest bid + rent
+            owner: program.programId,
+            executable: false,
+            data: legacyAuctionSnapshot(program, prizeMint, bidder, auctionBump),
+        });
+    });
+
+    it("Rejects config migration from a non-owner", async () => {
+        const fake_owner = Keypair.generate();
+        await context.setAccount(fake_owner.publicKey, {
+            lamports: 1_000_000_000,
+            owner: SystemProgram.programId,
+            executable: false,
+            data: Buffer.alloc(0),
+        });
+
+        await assert.rejects(
+            program.methods
+                .migrateAuctionConfig()
+                .accounts({
+                    auctionConfig: auctionConfigPda(),
+                    auctionOwner: fake_owner.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([fake_owner])
+                .rpc()
+        );
+    });
+
+    it("Migrates the legacy config and keeps its data", async () => {
+        await program.methods
+            .migrateAuctionConfig()
+            .accounts({
+                auctionConfig: auctionConfigPda(),
+                auctionOwner: auction_owner.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([auction_owner])
+            .rpc();
+
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        assert.equal(cfg.version, 2);
+        assert.equal(cfg.auctionOwner.toString(), auction_owner.publicKey.toString());
+        assert.equal(cfg.auctionAdmin.toString(), auction_admin.publicKey.toString());
+        assert.equal(cfg.creationFeeLamports.toNumber(), creation_fee_lamports);
+        assert.equal(cfg.commissionBps, commission_bps);
+        assert.equal(cfg.minimumAuctionPeriod, minimum_auction_period);
+        assert.equal(cfg.maximumAuctionPeriod, maximum_auction_period);
+        assert.equal(cfg.minimumTimeExtension, minimum_time_extension);
+        assert.equal(cfg.maximumTimeExtension, maximum_time_extension);
+        assert.equal(cfg.auctionCount, 2);
+        assert.equal(cfg.pauseFlags.toNumber(), 0b0000_0100);
+        assert.equal(cfg.pendingOwner, null);
+        assert.equal(cfg.configChangeDelay, minimum_config_change_delay);
+
+        const info = await context.banksClient.getAccount(auctionConfigPda());
+        assert.ok(info.data.length > LEGACY_CONFIG_LEN);
+    });
+
+    it("Fails to migrate the config twice", async () => {
+        await assert.rejects(
+            program.methods
+                .migrateAuctionConfig()
+                .accounts({
+                    auctionConfig: auctionConfigPda(),
+                    auctionOwner: auction_owner.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([auction_owner])
+                .rpc()
+        );
+    });
+
+    it("Refuses to migrate a legacy auction holding a bid without the bidder's escrow", async () => {
+        await assert.rejects(
+            program.methods
+                .migrateAuction(1)
+                .accounts(migrateAuctionAccounts(1))
+                .signers([auction_owner])
+                .rpc(),
+            /MissingLegacyBidAccounts/
+        );
+    });
+
+    it("Migrates a live legacy auction and moves its bid into the bidder's escrow", async () => {
+        const before = await context.banksClient.getAccount(auctionPda(1));
+
+        await program.methods
+            .migrateAuction(1)
+            .accounts(migrateAuctionAccounts(1, bidEscrowPda(1, bidder)))
+            .signers([auction_owner])
+            .rpc();
+
+        const auction = await program.account.auction.fetch(auctionPda(1));
+        assert.equal(auction.version, 2);
+        assert.equal(auction.auctionId, 1);
+        assert.equal(auction.creator.toString(), auction_1_creator.publicKey.toString());
+        assert.equal(auction.prizeMint.toString(), prizeMint.toString());
+        assert.equal(auction.bidMint, null);
+        assert.equal(auction.baseBid.toNumber(), 100_000_000);
+        assert.equal(auction.timeExtension, 600);
+        assert.equal(auction.highestBidAmount.toNumber(), 250_000_000);
+        assert.equal(auction.highestBidder.toString(), bidder.toString());
+        assert.equal(auction.hasAnyBid, true);
+        assert.ok("active" in auction.status);
+        // commission frozen from the config at migration time
+        assert.equal(auction.commissionBps, commission_bps);
+
+        // the bid left the auction for the escrow `complete_auction` and `withdraw_outbid` pay out of
+        const escrow = await program.account.bidEscrow.fetch(bidEscrowPda(1, bidder));
+        assert.equal(escrow.auctionId, 1);
+        assert.equal(escrow.bidder.toString(), bidder.toString());
+        assert.equal(escrow.bidMint, null);
+        assert.equal(escrow.amount.toNumber(), 250_000_000);
+        const escrowInfo = await context.banksClient.getAccount(bidEscrowPda(1, bidder));
+        const escrowRent = Number((await context.banksClient.getRent()).minimumBalance(BigInt(escrowInfo.data.length)));
+        assert.equal(Number(escrowInfo.lamports), escrowRent + 250_000_000);
+
+        // rent for the extra bytes comes from the payer
+        const after = await context.banksClient.getAccount(auctionPda(1));
+        assert.ok(BigInt(after.lamports) >= BigInt(before.lamports) - 250_000_000n);
+    });
+
+    it("Fails to migrate the auction twice", async () => {
+        await assert.rejects(
+            program.methods
+                .migrateAuction(1)
+                .accounts(migrateAuctionAccounts(1, bidEscrowPda(1, bidder)))
+                .signers([auction_owner])
+                .rpc()
+        );
+    });
+
+    it("Settles the migrated auction out of the moved bid", async () => {
+        // the snapshot ended at t = 5000
+        const now = await getCurrentTimestamp();
+        if (now <= 5_000) await warpForward(5_001 - now);
+
+        const creatorBefore = await getSolBalance(auction_1_creator.publicKey);
+        await completeAuction(program, {
+            auctionId: 1,
+            keeper: auction_admin,
+            creator: auction_1_creator.publicKey,
+            winner: bidder,
+            prizeMint,
+            bidMint: prizeMint,
+            prizeEscrow,
+            winnerBidEscrowAta: prizeEscrow,
+            creatorPrizeAta,
+            winnerPrizeAta: getAssociatedTokenAddressSync(prizeMint, bidder),
+            bidFeeTreasuryAta: prizeEscrow,
+            creatorBidAta: prizeEscrow,
+        });
+
+        assert.equal(await getTokenBalance(getAssociatedTokenAddressSync(prizeMint, bidder)), 1);
+        const fee = (250_000_000 * commission_bps) / 10_000;
+        assert.ok((await getSolBalance(auction_1_creator.publicKey)) - creatorBefore >= 250_000_000 - fee);
+        assert.equal(await context.banksClient.getAccount(bidEscrowPda(1, bidder)), null);
+        assert.equal(await context.banksClient.getAccount(auctionPda(1)), null);
+    });
+});
+
+describe("Account migration – version 1 config", () => {
+    let context: any;
+    let program: anchor.Program<any>;
+
+    const prizeMint = Keypair.generate().publicKey;
+
+    before(async () => {
+        context = await startAnchor("", [], []);
+        const provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+
+        program = anchor.workspace.Auction as anchor.Program<any>;
+        setProgram(program);
+
+        await context.setAccount(auction_owner.publicKey, {
+            lamports: 10_000_000_000,
+            owner: SystemProgram.programId,
+            executable: false,
+            data: Buffer.alloc(0),
+        });
+
+        const [, configBump] = PublicKey.findProgramAddressSync(
+            [Buffer.from("auction")],
+            program.programId
+        );
+        await context.setAccount(auctionConfigPda(), {
+            lamports: 1_000_000_000,
+            owner: program.programId,
+            executable: false,
+            data: v1ConfigSnapshot(program, configBump),
+        });
+
+        const [, auctionBump] = PublicKey.findProgramAddressSync(
+            [Buffer.from("auction"), new anchor.BN(3).toArrayLike(Buffer, "le", 4)],
+            program.programId
+        );
+        await context.setAccount(auctionPda(3), {
+            lamports: 5_000_000,
+            owner: program.programId,
+            executable: false,
+            data: v1AuctionSnapshot(program, prizeMint, auctionBump),
+        });
+    });
+
+    it("Widens the pause flags in place", async () => {
+        const before = await context.banksClient.getAccount(auctionConfigPda());
+
+        await program.methods
+            .migrateAuctionConfig()
+            .accounts({
+                auctionConfig: auctionConfigPda(),
+                auctionOwner: auction_owner.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([auction_owner])
+            .rpc();
+
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        assert.equal(cfg.version, 2);
+        assert.equal(cfg.auctionOwner.toString(), auction_owner.publicKey.toString());
+        assert.equal(cfg.auctionCount, 4);
+        assert.equal(cfg.pauseFlags.toNumber(), 0b0010_0001);
+        assert.equal(cfg.configChangeDelay, minimum_config_change_delay);
+
+        const after = await context.banksClient.getAccount(auctionConfigPda());
+        assert.equal(after.data.length, before.data.length);
+    });
+
+    it("Grows a version 1 auction and keeps its commission", async () => {
+        // the config was migrated just above
+        await program.methods
+            .migrateAuction(3)
+            .accounts(migrateAuctionAccounts(3))
+            .signers([auction_owner])
+            .rpc();
+
+        const auction = await program.account.auction.fetch(auctionPda(3));
+        assert.equal(auction.version, 2);
+        assert.equal(auction.auctionId, 3);
+        assert.equal(auction.prizeMint.toString(), prizeMint.toString());
+        assert.equal(auction.baseBid.toNumber(), 100_000_000);
+        assert.ok("initialized" in auction.status);
+        assert.equal(auction.commissionBps, 250);
+        // fields carved out after the auction was created get the defaults a version 0 auction gets
+        assert.equal(auction.lotAmount.toNumber(), 1);
+        assert.equal(auction.maxEndTime.toString(), "9223372036854775807");
+        assert.equal(auction.maxExtensions, 65535);
+
+        const after = await context.banksClient.getAccount(auctionPda(3));
+        assert.equal(after.data.length, program.account.auction.size);
+    });
+});
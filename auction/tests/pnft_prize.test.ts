@@ -0,0 +1,120 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { SystemProgram } from "@solana/web3.js";
+import { AccountLayout, getAssociatedTokenAddressSync } from "@solana/spl-token";
+
+import {
+    createAuctionConfig,
+    createAuction,
+    cancelAuction,
+    createPnftMint,
+    createAta,
+    getTokenBalance,
+    getCurrentTimestamp,
+} from "./helpers";
+
+import {
+    auction_owner,
+    auction_admin,
+    auction_1_creator,
+    creation_fee_lamports,
+    commission_bps,
+    minimum_auction_period,
+    maximum_auction_period,
+    minimum_time_extension,
+    maximum_time_extension,
+    setProgram,
+    setProvider,
+    auctionConfigPda,
+    auctionPda,
+    tokenRecordPda,
+    TOKEN_METADATA_PROGRAM_ID,
+} from "./values";
+
+import type { Auction } from "../target/types/auction";
+
+// Token Metadata is loaded from tests/fixtures/mpl_token_metadata.so, dumped from mainnet with
+// `solana program dump -u m metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s tests/fixtures/mpl_token_metadata.so`
+describe("Programmable NFT prizes", () => {
+    let context: any;
+    let provider: BankrunProvider;
+    let program: anchor.Program<Auction>;
+
+    before(async () => {
+        context = await startAnchor("", [{ name: "mpl_token_metadata", programId: TOKEN_METADATA_PROGRAM_ID }], []);
+        provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+
+        program = anchor.workspace.Auction as anchor.Program<Auction>;
+        setProgram(program);
+
+        for (const kp of [auction_owner, auction_admin, auction_1_creator]) {
+            await context.setAccount(kp.publicKey, {
+                lamports: 20_000_000_000,
+                owner: SystemProgram.programId,
+                executable: false,
+                data: Buffer.alloc(0),
+            });
+        }
+
+        await createAuctionConfig(program, auction_owner, auction_admin.publicKey, {
+            creationFeeLamports: creation_fee_lamports,
+            commision_bps: commission_bps,
+            minPeriod: minimum_auction_period,
+            maxPeriod: maximum_auction_period,
+            minTimeExtension: minimum_time_extension,
+            maxTimeExtension: maximum_time_extension,
+        });
+    });
+
+    // pNFT token accounts sit frozen between transfers
+    async function isFrozen(ata: anchor.web3.PublicKey) {
+        const info = await provider.connection.getAccountInfo(ata);
+        return AccountLayout.decode(info!.data).state === 2;
+    }
+
+    it("escrows a pNFT prize and hands it back on cancel", async () => {
+        const prizeMint = await createPnftMint(auction_1_creator.publicKey);
+        const creatorPrizeAta = getAssociatedTokenAddressSync(prizeMint, auction_1_creator.publicKey);
+
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        const prizeEscrow = await createAta(prizeMint, auctionPda(cfg.auctionCount));
+
+        const start = await getCurrentTimestamp();
+        const { auctionId } = await createAuction(program, {
+            creator: auction_1_creator,
+            auctionAdmin: auction_admin,
+            prizeMint,
+            bidMint: null,
+            creatorPrizeAta,
+            prizeEscrow,
+            startTime: start,
+            endTime: start + minimum_auction_period,
+            startImmediately: true,
+            baseBid: 1_000_000_000,
+            minIncrement: 500_000_000,
+            timeExtension: minimum_time_extension,
+        });
+
+        // the move went through Token Metadata: the escrow holds the pNFT frozen, under its own token record
+        assert.equal(await getTokenBalance(prizeEscrow), 1);
+        assert.equal(await getTokenBalance(creatorPrizeAta), 0);
+        assert.ok(await isFrozen(prizeEscrow));
+        assert.ok(await provider.connection.getAccountInfo(tokenRecordPda(prizeMint, prizeEscrow)));
+
+        await cancelAuction(program, {
+            auctionId,
+            creator: auction_1_creator,
+            auctionAdmin: auction_admin,
+            prizeMint,
+            prizeEscrow,
+            creatorPrizeAta,
+        });
+
+        assert.equal(await getTokenBalance(creatorPrizeAta), 1);
+        assert.ok(await isFrozen(creatorPrizeAta));
+    });
+});
@@ -0,0 +1,236 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { Keypair, SystemProgram } from "@solana/web3.js";
+
+import {
+    createAuctionConfig,
+    createAuction,
+    placeBid,
+    withdrawOutbid,
+    completeAuction,
+    createNftMint,
+    createAta,
+    mintTokens,
+    getSolBalance,
+    getTokenBalance,
+    getCurrentTimestamp,
+    warpForward,
+} from "./helpers";
+
+import {
+    auction_owner,
+    auction_admin,
+    auction_1_creator,
+    creation_fee_lamports,
+    commission_bps,
+    minimum_auction_period,
+    maximum_auction_period,
+    minimum_time_extension,
+    maximum_time_extension,
+    setProgram,
+    setProvider,
+    auctionConfigPda,
+    auctionPda,
+} from "./values";
+
+import type { Auction } from "../target/types/auction";
+
+describe("Proxy bids", () => {
+    let context: any;
+    let provider: BankrunProvider;
+    let program: anchor.Program<Auction>;
+
+    const baseBid = 1_000_000_000;
+    const minIncrement = 500_000_000;
+
+    before(async () => {
+        context = await startAnchor("", [], []);
+        provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+
+        program = anchor.workspace.Auction as anchor.Program<Auction>;
+        setProgram(program);
+
+        for (const kp of [auction_owner, auction_admin, auction_1_creator]) {
+            await context.setAccount(kp.publicKey, {
+                lamports: 20_000_000_000,
+                owner: SystemProgram.programId,
+                executable: false,
+                data: Buffer.alloc(0),
+            });
+        }
+
+        await createAuctionConfig(program, auction_owner, auction_admin.publicKey, {
+            creationFeeLamports: creation_fee_lamports,
+            commision_bps: commission_bps,
+            minPeriod: minimum_auction_period,
+            maxPeriod: maximum_auction_period,
+            minTimeExtension: minimum_time_extension,
+            maxTimeExtension: maximum_time_extension,
+        });
+    });
+
+    async function fundedKeypair() {
+        const kp = Keypair.generate();
+        await context.setAccount(kp.publicKey, {
+            lamports: 20_000_000_000,
+            owner: SystemProgram.programId,
+            executable: false,
+            data: Buffer.alloc(0),
+        });
+        return kp;
+    }
+
+    // live SOL auction
+    async function liveAuction(reservePrice?: number, minIncrementBps?: number) {
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        const prizeMint = await createNftMint();
+        const creatorPrizeAta = await createAta(prizeMint, auction_1_creator.publicKey);
+        const prizeEscrow = await createAta(prizeMint, auctionPda(cfg.auctionCount));
+        await mintTokens(prizeMint, creatorPrizeAta, 1);
+
+        const start = await getCurrentTimestamp();
+        const { auctionId } = await createAuction(program, {
+            creator: auction_1_creator,
+            auctionAdmin: auction_admin,
+            prizeMint,
+            bidMint: null,
+            creatorPrizeAta,
+            prizeEscrow,
+            startTime: start,
+            endTime: start + minimum_auction_period + 100,
+            startImmediately: true,
+            baseBid,
+            minIncrement,
+            minIncrementBps,
+            timeExtension: minimum_time_extension,
+            reservePrice,
+        });
+
+        // SOL bids never touch the token accounts, the prize escrow stands in for them
+        const bid = async (bidder: Keypair, bidAmount: number, proxy = true) => {
+            await placeBid(program, {
+                auctionId,
+                bidder,
+                auctionAdmin: auction_admin,
+                bidAmount,
+                proxy,
+                bidMint: prizeMint,
+                bidderAta: prizeEscrow,
+                bidEscrowAta: prizeEscrow,
+            });
+            return program.account.auction.fetch(auctionPda(auctionId));
+        };
+
+        const withdraw = (bidder: Keypair) =>
+            withdrawOutbid(program, {
+                auctionId,
+                bidder,
+                bidMint: prizeMint,
+                bidEscrowAta: prizeEscrow,
+                bidderAta: prizeEscrow,
+            });
+
+        const complete = async (winner: Keypair) => {
+            const winnerPrizeAta = await createAta(prizeMint, winner.publicKey);
+            await completeAuction(program, {
+                auctionId,
+                keeper: auction_admin,
+                creator: auction_1_creator.publicKey,
+                winner: winner.publicKey,
+                prizeMint,
+                bidMint: prizeMint,
+                prizeEscrow,
+                winnerBidEscrowAta: prizeEscrow,
+                creatorPrizeAta,
+                winnerPrizeAta,
+                bidFeeTreasuryAta: creatorPrizeAta,
+                creatorBidAta: creatorPrizeAta,
+            });
+            return winnerPrizeAta;
+        };
+
+        return { bid, withdraw, complete };
+    }
+
+    it("leads at one increment over the runner-up's maximum", async () => {
+        const { bid, withdraw, complete } = await liveAuction();
+        const alice = await fundedKeypair();
+        const bob = await fundedKeypair();
+        const carol = await fundedKeypair();
+
+        // the first proxy only bids the opening price
+        let auction = await bid(alice, 5_000_000_000);
+        assert.equal(auction.highestBidder.toString(), alice.publicKey.toString());
+        assert.equal(auction.highestBidAmount.toNumber(), baseBid + minIncrement);
+        assert.equal(auction.highestBidMax.toNumber(), 5_000_000_000);
+
+        // a lower proxy is answered by alice's, one increment above it
+        auction = await bid(bob, 3_000_000_000);
+        assert.equal(auction.highestBidder.toString(), alice.publicKey.toString());
+        assert.equal(auction.highestBidAmount.toNumber(), 3_500_000_000);
+
+        // a plain bid matching alice's maximum loses the tie to her
+        auction = await bid(carol, 5_000_000_000, false);
+        assert.equal(auction.highestBidder.toString(), alice.publicKey.toString());
+        assert.equal(auction.highestBidAmount.toNumber(), 5_000_000_000);
+
+        // a higher proxy takes over one increment past alice's maximum
+        auction = await bid(bob, 8_000_000_000);
+        assert.equal(auction.highestBidder.toString(), bob.publicKey.toString());
+        assert.equal(auction.highestBidAmount.toNumber(), 5_500_000_000);
+        assert.equal(auction.highestBidMax.toNumber(), 8_000_000_000);
+
+        await withdraw(alice);
+        await withdraw(carol);
+        await warpForward(minimum_auction_period + 200);
+
+        // bob pays the visible price, the rest of his maximum comes back with the closed escrow
+        const bobBefore = Number(await getSolBalance(bob.publicKey));
+        const winnerPrizeAta = await complete(bob);
+        assert.equal(await getTokenBalance(winnerPrizeAta), 1);
+        assert.ok(Number(await getSolBalance(bob.publicKey)) - bobBefore >= 8_000_000_000 - 5_500_000_000 - 10_000_000);
+    });
+
+    it("prices each proxy answer one percentage increment above the bid it beats", async () => {
+        const { bid } = await liveAuction(undefined, 2_000); // 20%, `minIncrement` as its floor
+        const alice = await fundedKeypair();
+        const bob = await fundedKeypair();
+
+        let auction = await bid(alice, 5_000_000_000);
+        assert.equal(auction.highestBidAmount.toNumber(), baseBid + minIncrement);
+
+        // alice's proxy answers 20% above bob's 3 SOL, not above the 1.5 SOL shown before it
+        auction = await bid(bob, 3_000_000_000);
+        assert.equal(auction.highestBidder.toString(), alice.publicKey.toString());
+        assert.equal(auction.highestBidAmount.toNumber(), 3_600_000_000);
+
+        // bob's higher proxy goes 20% past alice's 5 SOL maximum
+        auction = await bid(bob, 8_000_000_000);
+        assert.equal(auction.highestBidder.toString(), bob.publicKey.toString());
+        assert.equal(auction.highestBidAmount.toNumber(), 6_000_000_000);
+        assert.equal(auction.highestBidMax.toNumber(), 8_000_000_000);
+    });
+
+    it("raises the price to the reserve when the maximum covers it", async () => {
+        const reservePrice = 4_000_000_000;
+        const { bid, complete } = await liveAuction(reservePrice);
+        const alice = await fundedKeypair();
+
+        const auction = await bid(alice, 6_000_000_000);
+        assert.equal(auction.highestBidAmount.toNumber(), baseBid + minIncrement);
+        await warpForward(minimum_auction_period + 200);
+
+        const creatorBefore = Number(await getSolBalance(auction_1_creator.publicKey));
+        const aliceBefore = Number(await getSolBalance(alice.publicKey));
+        const winnerPrizeAta = await complete(alice);
+
+        const fee = (reservePrice * commission_bps) / 10_000;
+        assert.equal(await getTokenBalance(winnerPrizeAta), 1);
+        assert.ok(Number(await getSolBalance(auction_1_creator.publicKey)) - creatorBefore >= reservePrice - fee);
+        assert.ok(Number(await getSolBalance(alice.publicKey)) - aliceBefore >= 6_000_000_000 - reservePrice - 10_000_000);
+    });
+});
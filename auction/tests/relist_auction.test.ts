@@ -0,0 +1,211 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { Keypair, SystemProgram } from "@solana/web3.js";
+
+import {
+    createAuctionConfig,
+    createAuction,
+    relistAuction,
+    placeBid,
+    completeAuction,
+    createNftMint,
+    createAta,
+    mintTokens,
+    getSolBalance,
+    getTokenBalance,
+    getCurrentTimestamp,
+    warpForward,
+} from "./helpers";
+
+import {
+    auction_owner,
+    auction_admin,
+    auction_1_creator,
+    creation_fee_lamports,
+    commission_bps,
+    minimum_auction_period,
+    maximum_auction_period,
+    minimum_time_extension,
+    maximum_time_extension,
+    setProgram,
+    setProvider,
+    auctionConfigPda,
+    auctionPda,
+} from "./values";
+
+import type { Auction } from "../target/types/auction";
+
+describe("Relisting an auction", () => {
+    let context: any;
+    let provider: BankrunProvider;
+    let program: anchor.Program<Auction>;
+
+    before(async () => {
+        context = await startAnchor("", [], []);
+        provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+
+        program = anchor.workspace.Auction as anchor.Program<Auction>;
+        setProgram(program);
+
+        for (const kp of [auction_owner, auction_admin, auction_1_creator]) {
+            await context.setAccount(kp.publicKey, {
+                lamports: 20_000_000_000,
+                owner: SystemProgram.programId,
+                executable: false,
+                data: Buffer.alloc(0),
+            });
+        }
+
+        await createAuctionConfig(program, auction_owner, auction_admin.publicKey, {
+            creationFeeLamports: creation_fee_lamports,
+            commision_bps: commission_bps,
+            minPeriod: minimum_auction_period,
+            maxPeriod: maximum_auction_period,
+            minTimeExtension: minimum_time_extension,
+            maxTimeExtension: maximum_time_extension,
+        });
+    });
+
+    async function fundedKeypair() {
+        const kp = Keypair.generate();
+        await context.setAccount(kp.publicKey, {
+            lamports: 20_000_000_000,
+            owner: SystemProgram.programId,
+            executable: false,
+            data: Buffer.alloc(0),
+        });
+        return kp;
+    }
+
+    // live SOL auction opening at 1 SOL
+    async function liveAuction() {
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        const prizeMint = await createNftMint();
+        const creatorPrizeAta = await createAta(prizeMint, auction_1_creator.publicKey);
+        const prizeEscrow = await createAta(prizeMint, auctionPda(cfg.auctionCount));
+        await mintTokens(prizeMint, creatorPrizeAta, 1);
+
+        const start = await getCurrentTimestamp();
+        const { auctionId } = await createAuction(program, {
+            creator: auction_1_creator,
+            auctionAdmin: auction_admin,
+            prizeMint,
+            bidMint: null,
+            creatorPrizeAta,
+            prizeEscrow,
+            startTime: start,
+            endTime: start + minimum_auction_period,
+            startImmediately: true,
+            baseBid: 1_000_000_000,
+            minIncrement: 100_000_000,
+            timeExtension: minimum_time_extension,
+        });
+
+        return { auctionId, prizeMint, creatorPrizeAta, prizeEscrow };
+    }
+
+    async function relist(auctionId: number, startIn = 0, extra: { reservePrice?: number; buyNowPrice?: number } = {}) {
+        const start = (await getCurrentTimestamp()) + startIn;
+        await relistAuction(program, {
+            auctionId,
+            creator: auction_1_creator,
+            auctionAdmin: auction_admin,
+            startTime: start,
+            endTime: start + minimum_auction_period,
+            startImmediately: startIn === 0,
+            baseBid: 500_000_000,
+            minIncrement: 50_000_000,
+            timeExtension: minimum_time_extension,
+            ...extra,
+        });
+        return { start, auction: await program.account.auction.fetch(auctionPda(auctionId)) };
+    }
+
+    it("reopens an auction that ended without bids, keeping the prize in escrow", async () => {
+        const { auctionId, prizeEscrow } = await liveAuction();
+
+        // still running
+        await assert.rejects(relist(auctionId), /EndTimeNotReached/);
+
+        await warpForward(minimum_auction_period + 1);
+        const creatorBefore = await getSolBalance(auction_1_creator.publicKey);
+        const { start, auction } = await relist(auctionId, 0, { reservePrice: 800_000_000 });
+
+        assert.ok("active" in auction.status);
+        assert.equal(auction.startTime.toNumber(), start);
+        assert.equal(auction.endTime.toNumber(), start + minimum_auction_period);
+        assert.equal(auction.baseBid.toNumber(), 500_000_000);
+        assert.equal(auction.highestBidAmount.toNumber(), 500_000_000);
+        assert.equal(auction.reservePrice.toNumber(), 800_000_000);
+        assert.equal(auction.extensionCount, 0);
+        assert.equal(await getTokenBalance(prizeEscrow), 1);
+        // no creation fee the second time, only the transaction fee
+        assert.ok(creatorBefore - (await getSolBalance(auction_1_creator.publicKey)) < 1_000_000);
+
+        // and it takes bids again
+        await placeBid(program, {
+            auctionId,
+            bidder: await fundedKeypair(),
+            auctionAdmin: auction_admin,
+            bidAmount: 600_000_000,
+            bidMint: auction.prizeMint,
+            bidderAta: prizeEscrow,
+            bidEscrowAta: prizeEscrow,
+        });
+    });
+
+    it("cannot relist once complete_auction has returned the prize", async () => {
+        const { auctionId, prizeMint, creatorPrizeAta, prizeEscrow } = await liveAuction();
+        await warpForward(minimum_auction_period + 1);
+
+        const nobody = Keypair.generate().publicKey;
+        await completeAuction(program, {
+            auctionId,
+            keeper: auction_admin,
+            creator: auction_1_creator.publicKey,
+            winner: nobody,
+            prizeMint,
+            bidMint: prizeMint,
+            prizeEscrow,
+            winnerBidEscrowAta: prizeEscrow,
+            creatorPrizeAta,
+            winnerPrizeAta: await createAta(prizeMint, nobody),
+            bidFeeTreasuryAta: creatorPrizeAta,
+            creatorBidAta: creatorPrizeAta,
+        });
+
+        // settling without bids hands the prize back and closes the auction
+        assert.equal(await getTokenBalance(creatorPrizeAta), 1);
+        assert.equal(await context.banksClient.getAccount(auctionPda(auctionId)), null);
+        await assert.rejects(relist(auctionId));
+    });
+
+    it("can schedule the new run for later", async () => {
+        const { auctionId } = await liveAuction();
+        await warpForward(minimum_auction_period + 1);
+
+        const { start, auction } = await relist(auctionId, 600);
+        assert.ok("initialized" in auction.status);
+        assert.equal(auction.startTime.toNumber(), start);
+    });
+
+    it("refuses an auction that received a bid", async () => {
+        const { auctionId, prizeMint, prizeEscrow } = await liveAuction();
+        await placeBid(program, {
+            auctionId,
+            bidder: await fundedKeypair(),
+            auctionAdmin: auction_admin,
+            bidAmount: 1_000_000_000,
+            bidMint: prizeMint,
+            bidderAta: prizeEscrow,
+            bidEscrowAta: prizeEscrow,
+        });
+        await warpForward(minimum_auction_period + 1);
+
+        await assert.rejects(relist(auctionId), /AuctionNotRelistable/);
+    });
+});
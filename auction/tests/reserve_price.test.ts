@@ -0,0 +1,201 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { Keypair, SystemProgram } from "@solana/web3.js";
+
+import {
+    createAuctionConfig,
+    createAuction,
+    placeBid,
+    createNftMint,
+    createAta,
+    mintTokens,
+    getSolBalance,
+    getTokenBalance,
+    warpForward,
+    getCurrentTimestamp,
+    completeAuction,
+} from "./helpers";
+
+import {
+    auction_owner,
+    auction_admin,
+    auction_1_creator,
+    creation_fee_lamports,
+    commission_bps,
+    minimum_auction_period,
+    maximum_auction_period,
+    minimum_time_extension,
+    maximum_time_extension,
+    setProgram,
+    setProvider,
+    auctionConfigPda,
+    auctionPda,
+    bidEscrowPda,
+    reserveCommitment,
+} from "./values";
+
+import type { Auction } from "../target/types/auction";
+
+describe("Reserve price", () => {
+    let context: any;
+    let provider: BankrunProvider;
+    let program: anchor.Program<Auction>;
+
+    const reserve = 5_000_000_000; // 5 SOL
+    const salt = Array.from(Keypair.generate().publicKey.toBytes());
+
+    before(async () => {
+        context = await startAnchor("", [], []);
+        provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+
+        program = anchor.workspace.Auction as anchor.Program<Auction>;
+        setProgram(program);
+
+        for (const kp of [auction_owner, auction_admin, auction_1_creator]) {
+            await context.setAccount(kp.publicKey, {
+                lamports: 20_000_000_000,
+                owner: SystemProgram.programId,
+                executable: false,
+                data: Buffer.alloc(0),
+            });
+        }
+
+        await createAuctionConfig(program, auction_owner, auction_admin.publicKey, {
+            creationFeeLamports: creation_fee_lamports,
+            commision_bps: commission_bps,
+            minPeriod: minimum_auction_period,
+            maxPeriod: maximum_auction_period,
+            minTimeExtension: minimum_time_extension,
+            maxTimeExtension: maximum_time_extension,
+        });
+    });
+
+    // SOL auction with a single bid of `bid`, ended and ready to complete
+    async function endedAuction(bid: number, reserveOpts: { reservePrice?: number; reserveCommitment?: number[] }) {
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        const prizeMint = await createNftMint();
+        const creatorPrizeAta = await createAta(prizeMint, auction_1_creator.publicKey);
+        const prizeEscrow = await createAta(prizeMint, auctionPda(cfg.auctionCount));
+        await mintTokens(prizeMint, creatorPrizeAta, 1);
+
+        const start = await getCurrentTimestamp();
+        const { auctionId } = await createAuction(program, {
+            creator: auction_1_creator,
+            auctionAdmin: auction_admin,
+            prizeMint,
+            bidMint: null,
+            creatorPrizeAta,
+            prizeEscrow,
+            startTime: start,
+            endTime: start + minimum_auction_period + 100,
+            startImmediately: true,
+            baseBid: 1_000_000_000,
+            minIncrement: 500_000_000,
+            timeExtension: minimum_time_extension,
+            ...reserveOpts,
+        });
+
+        const bidder = Keypair.generate();
+        await context.setAccount(bidder.publicKey, {
+            lamports: 20_000_000_000,
+            owner: SystemProgram.programId,
+            executable: false,
+            data: Buffer.alloc(0),
+        });
+
+        // SOL bids never touch the token accounts, the prize escrow stands in for them
+        await placeBid(program, {
+            auctionId,
+            bidder,
+            auctionAdmin: auction_admin,
+            bidAmount: bid,
+            bidMint: prizeMint,
+            bidderAta: prizeEscrow,
+            bidEscrowAta: prizeEscrow,
+        });
+        await warpForward(minimum_auction_period + 200);
+
+        const winnerPrizeAta = await createAta(prizeMint, bidder.publicKey);
+        const complete = (reserveReveal?: { price: number; salt: number[] }) =>
+            completeAuction(program, {
+                auctionId,
+                keeper: auction_admin,
+                creator: auction_1_creator.publicKey,
+                winner: bidder.publicKey,
+                prizeMint,
+                bidMint: prizeMint,
+                prizeEscrow,
+                winnerBidEscrowAta: prizeEscrow,
+                creatorPrizeAta,
+                winnerPrizeAta,
+                bidFeeTreasuryAta: creatorPrizeAta,
+                creatorBidAta: creatorPrizeAta,
+                reserveReveal,
+            });
+
+        return { auctionId, bidder, creatorPrizeAta, winnerPrizeAta, complete };
+    }
+
+    it("rejects an auction with both a public and a hidden reserve", async () => {
+        await assert.rejects(
+            endedAuction(6_000_000_000, { reservePrice: reserve, reserveCommitment: reserveCommitment(reserve, salt) }),
+            /InvalidReserve/
+        );
+    });
+
+    it("refunds the top bid and returns the prize when the reserve is not met", async () => {
+        const bid = 2_000_000_000;
+        const { auctionId, bidder, creatorPrizeAta, winnerPrizeAta, complete } = await endedAuction(bid, {
+            reservePrice: reserve,
+        });
+
+        const bidderBefore = Number(await getSolBalance(bidder.publicKey));
+        await complete();
+
+        assert.equal(await getTokenBalance(creatorPrizeAta), 1);
+        assert.equal(await getTokenBalance(winnerPrizeAta), 0);
+        assert.equal(await context.banksClient.getAccount(bidEscrowPda(auctionId, bidder.publicKey)), null);
+        assert.equal(await context.banksClient.getAccount(auctionPda(auctionId)), null);
+
+        // the whole bid comes back, along with the escrow's rent
+        assert.ok(Number(await getSolBalance(bidder.publicKey)) - bidderBefore > bid);
+    });
+
+    it("sells once the top bid reaches the reserve", async () => {
+        const { creatorPrizeAta, winnerPrizeAta, complete } = await endedAuction(reserve, { reservePrice: reserve });
+
+        await complete();
+
+        assert.equal(await getTokenBalance(winnerPrizeAta), 1);
+        assert.equal(await getTokenBalance(creatorPrizeAta), 0);
+    });
+
+    it("only sells above a hidden reserve once it is revealed", async () => {
+        const { auctionId, winnerPrizeAta, complete } = await endedAuction(6_000_000_000, {
+            reserveCommitment: reserveCommitment(reserve, salt),
+        });
+
+        const auction = await program.account.auction.fetch(auctionPda(auctionId));
+        assert.equal(auction.reservePrice.toNumber(), 0);
+
+        await assert.rejects(complete({ price: reserve - 1, salt }), /InvalidReserveReveal/);
+
+        await complete({ price: reserve, salt });
+        assert.equal(await getTokenBalance(winnerPrizeAta), 1);
+    });
+
+    it("fails the sale when a hidden reserve is never revealed", async () => {
+        const { creatorPrizeAta, winnerPrizeAta, complete } = await endedAuction(6_000_000_000, {
+            reserveCommitment: reserveCommitment(reserve, salt),
+        });
+
+        await complete();
+
+        assert.equal(await getTokenBalance(creatorPrizeAta), 1);
+        assert.equal(await getTokenBalance(winnerPrizeAta), 0);
+    });
+});
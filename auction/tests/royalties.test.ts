@@ -0,0 +1,213 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { AccountMeta, Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+
+import {
+    createAuctionConfig,
+    createAuction,
+    placeBid,
+    createSplMint,
+    createAta,
+    mintTokens,
+    getSolBalance,
+    warpForward,
+    getCurrentTimestamp,
+    completeAuction,
+    setNftMetadata,
+} from "./helpers";
+
+import {
+    auction_owner,
+    auction_admin,
+    auction_1_creator,
+    creation_fee_lamports,
+    commission_bps,
+    minimum_auction_period,
+    maximum_auction_period,
+    minimum_time_extension,
+    maximum_time_extension,
+    setProgram,
+    setProvider,
+    auctionConfigPda,
+    auctionPda,
+} from "./values";
+
+import type { Auction } from "../target/types/auction";
+
+describe("Creator royalties on settlement", () => {
+    let context: any;
+    let provider: BankrunProvider;
+    let program: anchor.Program<Auction>;
+
+    const price = 10_000_000_000; // 10 SOL
+    const sellerFeeBps = 500; // 5%
+
+    before(async () => {
+        context = await startAnchor("", [], []);
+        provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+
+        program = anchor.workspace.Auction as anchor.Program<Auction>;
+        setProgram(program);
+
+        for (const kp of [auction_owner, auction_admin, auction_1_creator]) {
+            await context.setAccount(kp.publicKey, {
+                lamports: 20_000_000_000,
+                owner: SystemProgram.programId,
+                executable: false,
+                data: Buffer.alloc(0),
+            });
+        }
+
+        await createAuctionConfig(program, auction_owner, auction_admin.publicKey, {
+            creationFeeLamports: creation_fee_lamports,
+            commision_bps: commission_bps,
+            minPeriod: minimum_auction_period,
+            maxPeriod: maximum_auction_period,
+            minTimeExtension: minimum_time_extension,
+            maxTimeExtension: maximum_time_extension,
+        });
+    });
+
+    // SOL auction for an NFT listing `creators`, won at `price` and ready to settle
+    async function wonAuction(
+        creators: { address: PublicKey; verified: boolean; share: number }[],
+        sellerFeeBasisPoints = sellerFeeBps
+    ) {
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        const prizeMint = await createSplMint(0);
+        await setNftMetadata(prizeMint, { sellerFeeBasisPoints, creators });
+
+        const creatorPrizeAta = await createAta(prizeMint, auction_1_creator.publicKey);
+        const prizeEscrow = await createAta(prizeMint, auctionPda(cfg.auctionCount));
+        await mintTokens(prizeMint, creatorPrizeAta, 1);
+
+        const start = await getCurrentTimestamp();
+        const { auctionId } = await createAuction(program, {
+            creator: auction_1_creator,
+            auctionAdmin: auction_admin,
+            prizeMint,
+            bidMint: null,
+            creatorPrizeAta,
+            prizeEscrow,
+            startTime: start,
+            endTime: start + minimum_auction_period + 100,
+            startImmediately: true,
+            baseBid: 1_000_000_000,
+            minIncrement: 500_000_000,
+            timeExtension: minimum_time_extension,
+        });
+
+        const winner = Keypair.generate();
+        await context.setAccount(winner.publicKey, {
+            lamports: 20_000_000_000,
+            owner: SystemProgram.programId,
+            executable: false,
+            data: Buffer.alloc(0),
+        });
+
+        // SOL bids never touch the token accounts, the prize escrow stands in for them
+        await placeBid(program, {
+            auctionId,
+            bidder: winner,
+            auctionAdmin: auction_admin,
+            bidAmount: price,
+            bidMint: prizeMint,
+            bidderAta: prizeEscrow,
+            bidEscrowAta: prizeEscrow,
+        });
+        await warpForward(minimum_auction_period + 200);
+
+        const winnerPrizeAta = await createAta(prizeMint, winner.publicKey);
+        const settle = (remainingAccounts: AccountMeta[]) =>
+            completeAuction(program, {
+                auctionId,
+                keeper: auction_admin,
+                creator: auction_1_creator.publicKey,
+                winner: winner.publicKey,
+                prizeMint,
+                bidMint: prizeMint,
+                prizeEscrow,
+                winnerBidEscrowAta: prizeEscrow,
+                creatorPrizeAta,
+                winnerPrizeAta,
+                bidFeeTreasuryAta: creatorPrizeAta,
+                creatorBidAta: creatorPrizeAta,
+                remainingAccounts,
+            });
+
+        return { settle };
+    }
+
+    const royaltyAccount = (pubkey: PublicKey): AccountMeta => ({ pubkey, isSigner: false, isWritable: true });
+
+    it("pays each verified creator its share of the royalty in SOL", async () => {
+        const artist = Keypair.generate().publicKey;
+        const studio = Keypair.generate().publicKey;
+        const unverified = Keypair.generate().publicKey;
+
+        const { settle } = await wonAuction([
+            { address: artist, verified: true, share: 60 },
+            { address: studio, verified: true, share: 30 },
+            { address: unverified, verified: false, share: 10 },
+        ]);
+
+        const sellerBefore = await getSolBalance(auction_1_creator.publicKey);
+        await settle([royaltyAccount(artist), royaltyAccount(studio)]);
+
+        const royalty = (price * sellerFeeBps) / 10_000;
+        const fee = (price * commission_bps) / 10_000;
+        assert.equal(await getSolBalance(artist), (royalty * 60) / 100);
+        assert.equal(await getSolBalance(studio), (royalty * 30) / 100);
+        assert.equal(await getSolBalance(unverified), 0);
+
+        // the unverified creator's share stays with the seller, who also gets the closed accounts' rent
+        const sellerGain = (await getSolBalance(auction_1_creator.publicKey)) - sellerBefore;
+        assert.ok(sellerGain >= price - fee - (royalty * 90) / 100);
+    });
+
+    it("rejects settlement without a matching account for every verified creator", async () => {
+        const artist = Keypair.generate().publicKey;
+        const { settle } = await wonAuction([{ address: artist, verified: true, share: 100 }]);
+
+        await assert.rejects(settle([]), /MissingRoyaltyAccounts/);
+        await assert.rejects(settle([royaltyAccount(Keypair.generate().publicKey)]), /InvalidRoyaltyRecipient/);
+
+        await settle([royaltyAccount(artist)]);
+        assert.equal(await getSolBalance(artist), (price * sellerFeeBps) / 10_000);
+    });
+
+    it("leaves a royalty too small to open a new wallet with the seller", async () => {
+        // 0.01% of the price split in two, each half below the rent of an empty account
+        const royalty = price / 10_000;
+        const fresh = Keypair.generate().publicKey;
+        const funded = Keypair.generate().publicKey;
+        await context.setAccount(funded, {
+            lamports: 1_000_000_000,
+            owner: SystemProgram.programId,
+            executable: false,
+            data: Buffer.alloc(0),
+        });
+
+        const { settle } = await wonAuction(
+            [
+                { address: fresh, verified: true, share: 50 },
+                { address: funded, verified: true, share: 50 },
+            ],
+            1
+        );
+
+        const sellerBefore = await getSolBalance(auction_1_creator.publicKey);
+        await settle([royaltyAccount(fresh), royaltyAccount(funded)]);
+
+        assert.equal(await getSolBalance(fresh), 0);
+        assert.equal(await getSolBalance(funded), 1_000_000_000 + royalty / 2);
+
+        const fee = (price * commission_bps) / 10_000;
+        const sellerGain = (await getSolBalance(auction_1_creator.publicKey)) - sellerBefore;
+        assert.ok(sellerGain >= price - fee - royalty / 2);
+    });
+});
@@ -0,0 +1,258 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { Keypair, SystemProgram } from "@solana/web3.js";
+
+import {
+    createAuctionConfig,
+    createAuction,
+    placeBid,
+    commitSealedBid,
+    revealSealedBid,
+    withdrawOutbid,
+    completeAuction,
+    setSealedBidPolicy,
+    setAuctionFrozen,
+    createNftMint,
+    createAta,
+    mintTokens,
+    getSolBalance,
+    getTokenBalance,
+    getCurrentTimestamp,
+    warpForward,
+} from "./helpers";
+
+import {
+    auction_owner,
+    auction_admin,
+    auction_1_creator,
+    creation_fee_lamports,
+    commission_bps,
+    minimum_auction_period,
+    maximum_auction_period,
+    minimum_time_extension,
+    maximum_time_extension,
+    setProgram,
+    setProvider,
+    auctionConfigPda,
+    auctionPda,
+    sealedBidCommitment,
+    bidEscrowPda,
+} from "./values";
+
+import type { Auction } from "../target/types/auction";
+
+describe("Sealed-bid auctions", () => {
+    let context: any;
+    let provider: BankrunProvider;
+    let program: anchor.Program<Auction>;
+
+    const revealPeriod = 600;
+    const penaltyBps = 1_000; // 10%
+
+    before(async () => {
+        context = await startAnchor("", [], []);
+        provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+
+        program = anchor.workspace.Auction as anchor.Program<Auction>;
+        setProgram(program);
+
+        for (const kp of [auction_owner, auction_admin, auction_1_creator]) {
+            await context.setAccount(kp.publicKey, {
+                lamports: 20_000_000_000,
+                owner: SystemProgram.programId,
+                executable: false,
+                data: Buffer.alloc(0),
+            });
+        }
+
+        await createAuctionConfig(program, auction_owner, auction_admin.publicKey, {
+            creationFeeLamports: creation_fee_lamports,
+            commision_bps: commission_bps,
+            minPeriod: minimum_auction_period,
+            maxPeriod: maximum_auction_period,
+            minTimeExtension: minimum_time_extension,
+            maxTimeExtension: maximum_time_extension,
+        });
+    });
+
+    async function fundedKeypair() {
+        const kp = Keypair.generate();
+        await context.setAccount(kp.publicKey, {
+            lamports: 20_000_000_000,
+            owner: SystemProgram.programId,
+            executable: false,
+            data: Buffer.alloc(0),
+        });
+        return kp;
+    }
+
+    // live sealed SOL auction
+    async function sealedAuction() {
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        const prizeMint = await createNftMint();
+        const creatorPrizeAta = await createAta(prizeMint, auction_1_creator.publicKey);
+        const prizeEscrow = await createAta(prizeMint, auctionPda(cfg.auctionCount));
+        await mintTokens(prizeMint, creatorPrizeAta, 1);
+
+        const start = await getCurrentTimestamp();
+        const { auctionId } = await createAuction(program, {
+            creator: auction_1_creator,
+            auctionAdmin: auction_admin,
+            prizeMint,
+            bidMint: null,
+            creatorPrizeAta,
+            prizeEscrow,
+            startTime: start,
+            endTime: start + minimum_auction_period + 100,
+            startImmediately: true,
+            baseBid: 1_000_000_000,
+            minIncrement: 500_000_000,
+            timeExtension: minimum_time_extension,
+            sealed: true,
+        });
+
+        // SOL bids never touch the token accounts, the prize escrow stands in for them
+        const commit = (bidder: Keypair, amount: number, salt: number[], deposit: number) =>
+            commitSealedBid(program, {
+                auctionId,
+                bidder,
+                auctionAdmin: auction_admin,
+                commitment: sealedBidCommitment(auctionId, bidder.publicKey, amount, salt),
+                deposit,
+                bidMint: prizeMint,
+                bidderAta: prizeEscrow,
+                bidEscrowAta: prizeEscrow,
+            });
+
+        const withdraw = (bidder: Keypair) =>
+            withdrawOutbid(program, {
+                auctionId,
+                bidder,
+                bidMint: prizeMint,
+                bidEscrowAta: prizeEscrow,
+                bidderAta: prizeEscrow,
+            });
+
+        const complete = async (winner: Keypair) => {
+            const winnerPrizeAta = await createAta(prizeMint, winner.publicKey);
+            await completeAuction(program, {
+                auctionId,
+                keeper: auction_admin,
+                creator: auction_1_creator.publicKey,
+                winner: winner.publicKey,
+                prizeMint,
+                bidMint: prizeMint,
+                prizeEscrow,
+                winnerBidEscrowAta: prizeEscrow,
+                creatorPrizeAta,
+                winnerPrizeAta,
+                bidFeeTreasuryAta: creatorPrizeAta,
+                creatorBidAta: creatorPrizeAta,
+            });
+            return winnerPrizeAta;
+        };
+
+        const placeOpenBid = (bidder: Keypair) =>
+            placeBid(program, {
+                auctionId,
+                bidder,
+                auctionAdmin: auction_admin,
+                bidAmount: 2_000_000_000,
+                bidMint: prizeMint,
+                bidderAta: prizeEscrow,
+                bidEscrowAta: prizeEscrow,
+            });
+
+        return { auctionId, commit, withdraw, complete, placeOpenBid };
+    }
+
+    const salt = () => Array.from(Keypair.generate().publicKey.toBytes());
+
+    it("only creates sealed auctions once the owner enables them", async () => {
+        await assert.rejects(sealedAuction(), /SealedAuctionsDisabled/);
+
+        await assert.rejects(setSealedBidPolicy(program, auction_owner, revealPeriod, 10_001), /InvalidSealedPenalty/);
+        await assert.rejects(setSealedBidPolicy(program, auction_1_creator, revealPeriod, penaltyBps), /InvalidAuctionOwner/);
+
+        await setSealedBidPolicy(program, auction_owner, revealPeriod, penaltyBps);
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        assert.equal(cfg.sealedRevealPeriod, revealPeriod);
+        assert.equal(cfg.sealedPenaltyBps, penaltyBps);
+    });
+
+    it("sells to the highest revealed bid at the second highest price", async () => {
+        const { auctionId, commit, withdraw, complete, placeOpenBid } = await sealedAuction();
+        const alice = await fundedKeypair();
+        const bob = await fundedKeypair();
+        const carol = await fundedKeypair();
+        const [aliceSalt, bobSalt, carolSalt] = [salt(), salt(), salt()];
+
+        await assert.rejects(placeOpenBid(alice), /SealedAuction/);
+
+        // alice over-deposits to hide her bid, carol never reveals
+        await commit(alice, 5_000_000_000, aliceSalt, 6_000_000_000);
+        await commit(bob, 4_000_000_000, bobSalt, 4_000_000_000);
+        await commit(carol, 7_000_000_000, carolSalt, 7_000_000_000);
+
+        await assert.rejects(revealSealedBid(program, auctionId, alice, 5_000_000_000, aliceSalt), /NotInRevealPeriod/);
+        await warpForward(minimum_auction_period + 200);
+
+        await revealSealedBid(program, auctionId, alice, 5_000_000_000, aliceSalt);
+        await assert.rejects(revealSealedBid(program, auctionId, bob, 4_000_000_000, aliceSalt), /InvalidBidReveal/);
+        await revealSealedBid(program, auctionId, bob, 4_000_000_000, bobSalt);
+
+        const auction = await program.account.auction.fetch(auctionPda(auctionId));
+        assert.equal(auction.highestBidder.toString(), alice.publicKey.toString());
+        assert.equal(auction.highestBidAmount.toNumber(), 5_000_000_000);
+        assert.equal(auction.secondBidAmount.toNumber(), 4_000_000_000);
+
+        await assert.rejects(withdraw(bob), /RevealPeriodNotOver/);
+        await assert.rejects(complete(alice), /EndTimeNotReached/);
+        await warpForward(revealPeriod);
+
+        const creatorBefore = Number(await getSolBalance(auction_1_creator.publicKey));
+        const aliceBefore = Number(await getSolBalance(alice.publicKey));
+        const winnerPrizeAta = await complete(alice);
+
+        const price = 4_000_000_000;
+        const fee = (price * commission_bps) / 10_000;
+        assert.equal(await getTokenBalance(winnerPrizeAta), 1);
+        assert.ok(Number(await getSolBalance(auction_1_creator.publicKey)) - creatorBefore >= price - fee);
+
+        // the deposit above the price comes back with the closed escrow
+        assert.ok(Number(await getSolBalance(alice.publicKey)) - aliceBefore >= 6_000_000_000 - price);
+
+        // a revealed loser gets everything back, an unrevealed bid forfeits the penalty
+        const bobBefore = Number(await getSolBalance(bob.publicKey));
+        await withdraw(bob);
+        assert.ok(Number(await getSolBalance(bob.publicKey)) - bobBefore >= 4_000_000_000);
+
+        const configBefore = Number(await getSolBalance(auctionConfigPda()));
+        const carolBefore = Number(await getSolBalance(carol.publicKey));
+        await withdraw(carol);
+
+        const penalty = (7_000_000_000 * penaltyBps) / 10_000;
+        assert.equal(Number(await getSolBalance(auctionConfigPda())) - configBefore, penalty);
+        assert.ok(Number(await getSolBalance(carol.publicKey)) - carolBefore >= 7_000_000_000 - penalty);
+    });
+
+    it("still takes reveals while the auction is frozen", async () => {
+        const { auctionId, commit } = await sealedAuction();
+        const alice = await fundedKeypair();
+        const aliceSalt = salt();
+
+        await commit(alice, 3_000_000_000, aliceSalt, 3_000_000_000);
+        await warpForward(minimum_auction_period + 200);
+
+        // a freeze in the reveal period must not cost anyone the unrevealed-bid penalty
+        await setAuctionFrozen(program, { auctionId, frozen: true, auctionOwner: auction_owner });
+        await revealSealedBid(program, auctionId, alice, 3_000_000_000, aliceSalt);
+
+        const escrow = await program.account.bidEscrow.fetch(bidEscrowPda(auctionId, alice.publicKey));
+        assert.equal(escrow.revealed, true);
+    });
+});
@@ -0,0 +1,145 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+
+import {
+    createAuctionConfig,
+    createAuction,
+    cancelAuction,
+    createTransferHookMint,
+    transferHookAccounts,
+    createAta,
+    mintTokens,
+    getTokenBalance,
+    getCurrentTimestamp,
+    allowMint,
+} from "./helpers";
+
+import {
+    auction_owner,
+    auction_admin,
+    auction_1_creator,
+    creation_fee_lamports,
+    commission_bps,
+    minimum_auction_period,
+    maximum_auction_period,
+    minimum_time_extension,
+    maximum_time_extension,
+    setProgram,
+    setProvider,
+    auctionConfigPda,
+    auctionPda,
+} from "./values";
+
+import type { Auction } from "../target/types/auction";
+
+describe("Transfer hook mints", () => {
+    let context: any;
+    let provider: BankrunProvider;
+    let program: anchor.Program<Auction>;
+    let hookProgram: anchor.Program<any>;
+    let prizeMint: PublicKey;
+
+    const lotAmount = 1_000_000_000;
+
+    before(async () => {
+        context = await startAnchor("", [], []);
+        provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+        program = anchor.workspace.Auction as anchor.Program<Auction>;
+        hookProgram = anchor.workspace.TransferHookCounter as anchor.Program<any>;
+        setProgram(program);
+
+        for (const kp of [auction_owner, auction_admin, auction_1_creator]) {
+            await context.setAccount(kp.publicKey, {
+                lamports: 20_000_000_000,
+                owner: SystemProgram.programId,
+                executable: false,
+                data: Buffer.alloc(0),
+            });
+        }
+
+        await createAuctionConfig(program, auction_owner, auction_admin.publicKey, {
+            creationFeeLamports: creation_fee_lamports,
+            commision_bps: commission_bps,
+            minPeriod: minimum_auction_period,
+            maxPeriod: maximum_auction_period,
+            minTimeExtension: minimum_time_extension,
+            maxTimeExtension: maximum_time_extension,
+        });
+
+        prizeMint = await createTransferHookMint(hookProgram.programId);
+        await hookProgram.methods
+            .initializeExtraAccountMetaList()
+            .accounts({ payer: provider.wallet.publicKey, mint: prizeMint })
+            .rpc();
+
+        // hooked mints are rejected by the mint policy unless vetted
+        await allowMint(program, auction_owner, prizeMint);
+    });
+
+    async function hookTransfers(): Promise<number> {
+        const counter = transferHookAccounts(hookProgram.programId, prizeMint)[2].pubkey;
+        const state = await hookProgram.account.transferCounter.fetch(counter);
+        return state.transfers.toNumber();
+    }
+
+    // SOL bids, a lot of the hooked mint as the prize
+    async function createHookedAuction(withHookAccounts: boolean) {
+        const cfg = await program.account.auctionConfig.fetch(auctionConfigPda());
+        const creatorPrizeAta = await createAta(prizeMint, auction_1_creator.publicKey, TOKEN_2022_PROGRAM_ID);
+        const prizeEscrow = await createAta(prizeMint, auctionPda(cfg.auctionCount), TOKEN_2022_PROGRAM_ID);
+        await mintTokens(prizeMint, creatorPrizeAta, lotAmount, TOKEN_2022_PROGRAM_ID);
+
+        const start = await getCurrentTimestamp();
+        const { auctionId } = await createAuction(program, {
+            creator: auction_1_creator,
+            auctionAdmin: auction_admin,
+            prizeMint,
+            bidMint: null,
+            creatorPrizeAta,
+            prizeEscrow,
+            startTime: start,
+            endTime: start + minimum_auction_period + 100,
+            startImmediately: true,
+            baseBid: 1_000_000_000,
+            minIncrement: 500_000_000,
+            timeExtension: minimum_time_extension,
+            lotAmount,
+            prizeTokenProgram: TOKEN_2022_PROGRAM_ID,
+            remainingAccounts: withHookAccounts ? transferHookAccounts(hookProgram.programId, prizeMint) : [],
+        });
+
+        return { auctionId, creatorPrizeAta, prizeEscrow };
+    }
+
+    it("fails with a clear error when the hook accounts are not forwarded", async () => {
+        await assert.rejects(createHookedAuction(false), /MissingTransferHookAccounts/);
+    });
+
+    it("runs the hook when escrowing the lot and when the auction PDA returns it", async () => {
+        const before = await hookTransfers();
+
+        const { auctionId, creatorPrizeAta, prizeEscrow } = await createHookedAuction(true);
+        assert.equal(await getTokenBalance(prizeEscrow), lotAmount);
+        assert.equal(await hookTransfers(), before + 1);
+
+        // PDA-signed transfer out of the escrow
+        await cancelAuction(program, {
+            auctionId,
+            creator: auction_1_creator,
+            auctionAdmin: auction_admin,
+            prizeMint,
+            prizeEscrow,
+            creatorPrizeAta,
+            prizeTokenProgram: TOKEN_2022_PROGRAM_ID,
+            remainingAccounts: transferHookAccounts(hookProgram.programId, prizeMint),
+        });
+        assert.equal(await getTokenBalance(creatorPrizeAta), 2 * lotAmount);
+        assert.equal(await hookTransfers(), before + 2);
+    });
+});
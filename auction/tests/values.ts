@@ -1,6 +1,7 @@
 // tests/values.ts
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
+import { createHash } from "crypto";
 import type { Auction } from "../target/types/auction";
 
 let program: Program<Auction>;
@@ -25,6 +26,22 @@ export const minimum_auction_period = 60 * 60;  // 1 hour
 export const maximum_auction_period = 24 * 60 * 60;  // 24 hours
 export const minimum_time_extension = 10 * 60;  // 10 minutues
 export const maximum_time_extension = 60 * 60;  // 1 hour
+export const minimum_config_change_delay = 24 * 60 * 60;  // MINIMUM_CONFIG_CHANGE_DELAY on chain
+
+// pause bit indexes, mirrors constants.rs
+export const WITHDRAW_FEES_PAUSE = 6;
+export const UPDATE_CONFIG_PAUSE = 7;
+
+// risky Token-2022 extension bit indexes, mirrors constants.rs
+export const PERMANENT_DELEGATE_EXTENSION = 0;
+export const NON_TRANSFERABLE_EXTENSION = 1;
+export const DEFAULT_FROZEN_EXTENSION = 2;
+export const TRANSFER_HOOK_EXTENSION = 3;
+export const PAUSABLE_EXTENSION = 4;
+
+// Metaplex programs used for programmable NFT prizes
+export const TOKEN_METADATA_PROGRAM_ID = new anchor.web3.PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+export const AUTH_RULES_PROGRAM_ID = new anchor.web3.PublicKey("auth9SigNpDKz4sJJ1DfCTuZrZNSAgh9sFD3rboVmgg");
 
 export function getProgram() {
   if (!program) throw new Error("Program not set");
@@ -48,4 +65,84 @@ export function auctionPda(auctionId: number) {
     [Buffer.from("auction"), new anchor.BN(auctionId).toArrayLike(Buffer, "le", 4)],
     getProgram().programId
   )[0];
+}
+
+export function mintAllowlistPda(mint: anchor.web3.PublicKey) {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("auction"), Buffer.from("mint_allowlist"), mint.toBuffer()],
+    getProgram().programId
+  )[0];
+}
+
+export function collectionAllowlistPda(collection: anchor.web3.PublicKey) {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("auction"), Buffer.from("collection_allowlist"), collection.toBuffer()],
+    getProgram().programId
+  )[0];
+}
+
+export function nftMetadataPda(mint: anchor.web3.PublicKey) {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+    TOKEN_METADATA_PROGRAM_ID
+  )[0];
+}
+
+export function nftEditionPda(mint: anchor.web3.PublicKey) {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer(), Buffer.from("edition")],
+    TOKEN_METADATA_PROGRAM_ID
+  )[0];
+}
+
+export function tokenRecordPda(mint: anchor.web3.PublicKey, token: anchor.web3.PublicKey) {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer(), Buffer.from("token_record"), token.toBuffer()],
+    TOKEN_METADATA_PROGRAM_ID
+  )[0];
+}
+
+export function bidEscrowPda(auctionId: number, bidder: anchor.web3.PublicKey) {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("auction"), new anchor.BN(auctionId).toArrayLike(Buffer, "le", 4), Buffer.from("bid"), bidder.toBuffer()],
+    getProgram().programId
+  )[0];
+}
+
+export function listingPda(mint: anchor.web3.PublicKey) {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("auction"), Buffer.from("listing"), mint.toBuffer()],
+    getProgram().programId
+  )[0];
+}
+
+export function offerPda(collection: anchor.web3.PublicKey, buyer: anchor.web3.PublicKey) {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("auction"), Buffer.from("offer"), collection.toBuffer(), buyer.toBuffer()],
+    getProgram().programId
+  )[0];
+}
+
+export function auctionBundlePda(auctionId: number) {
+  return anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("auction"), new anchor.BN(auctionId).toArrayLike(Buffer, "le", 4), Buffer.from("bundle")],
+    getProgram().programId
+  )[0];
+}
+
+// sha256 of `price (le u64) | salt`, as checked by `complete_auction`
+export function reserveCommitment(price: number, salt: number[]) {
+  const data = Buffer.concat([new anchor.BN(price).toArrayLike(Buffer, "le", 8), Buffer.from(salt)]);
+  return Array.from(createHash("sha256").update(data).digest());
+}
+
+// sha256 of `auction id (le u32) | bidder | amount (le u64) | salt`, as checked by `reveal_sealed_bid`
+export function sealedBidCommitment(auctionId: number, bidder: anchor.web3.PublicKey, amount: number, salt: number[]) {
+  const data = Buffer.concat([
+    new anchor.BN(auctionId).toArrayLike(Buffer, "le", 4),
+    bidder.toBuffer(),
+    new anchor.BN(amount).toArrayLike(Buffer, "le", 8),
+    Buffer.from(salt),
+  ]);
+  return Array.from(createHash("sha256").update(data).digest());
 }
\ No newline at end of file
@@ -5,6 +5,25 @@ pub const FEE_MANTISSA: u16 = 10_000;
 
 pub const MINIMUM_TICKETS: u16 = 3; // minimum tickets have to set in a GUMBALL
 pub const MAXIMUM_TICKETS: u16 = 1_000; // number of prizes == number of tickets
+pub const MINIMUM_CONFIG_CHANGE_DELAY: u32 = 24 * 60 * 60; // fee and period changes are queued for at least 1 day
+
+// current account layout versions, stored in the leading `version` byte
+pub const GUMBALL_CONFIG_VERSION: u8 = 2; // 2: `pause_flags` widened to u64
+pub const GUMBALL_VERSION: u8 = 2; // 2: `ticket_revenue` tracked, older machines fall back to price * sold
+
+// MINT EXTENSIONS (bit indexes into `GumballConfig::allowed_mint_extensions`)
+pub const PERMANENT_DELEGATE_EXTENSION: u8 = 0; // the delegate can move tokens out of any account, escrows included
+pub const NON_TRANSFERABLE_EXTENSION: u8 = 1; // tokens can never leave the escrow again
+pub const DEFAULT_FROZEN_EXTENSION: u8 = 2; // new accounts start frozen, only the freeze authority can thaw the escrow
+pub const TRANSFER_HOOK_EXTENSION: u8 = 3; // every transfer runs an arbitrary program
+pub const PAUSABLE_EXTENSION: u8 = 4; // the pause authority can halt every transfer
+pub const MINT_EXTENSION_COUNT: u8 = 5;
+
+// PROGRAMMABLE NFTS
+pub const AUTH_RULES_PROGRAM_ID: Pubkey = pubkey!("auth9SigNpDKz4sJJ1DfCTuZrZNSAgh9sFD3rboVmgg"); // Metaplex Token Auth Rules
+
+// PERMITS
+pub const PERMIT_MESSAGE_LEN: usize = 32 + 4 + 2 + 32 + 8 + 8 + 8; // program | gumball id | prize index | user | max amount | expiry | nonce
 
 pub const CREATE_GUMBALL_PAUSE: u8 = 0;
 pub const ACTIVATE_GUMBALL_PAUSE: u8 = 1; 
@@ -14,3 +33,5 @@ pub const CLAIM_PRIZES_BACK_PAUSE: u8 = 4;
 pub const END_GUMBALL_PAUSE: u8 = 5;
 pub const SPIN_GUMBALL_PAUSE: u8 = 6;
 pub const UPDATE_GUMBALL_PAUSE: u8 = 7;
+pub const WITHDRAW_FEES_PAUSE: u8 = 8; // both SOL and SPL fee withdrawals
+pub const UPDATE_CONFIG_PAUSE: u8 = 9; // queuing and applying fee/period changes
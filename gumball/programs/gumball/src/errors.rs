@@ -79,6 +79,9 @@ pub enum GumballStateErrors {
 
     #[msg("Invalid prize quantity")]
     InvalidPrizeQuantity,
+
+    #[msg("Gumball is frozen")]
+    GumballFrozen,
 }
 
 #[error_code]
@@ -137,6 +140,93 @@ pub enum ConfigStateErrors {
 
     #[msg("Gumball should be between min & max periods")]
     InvalidGumballPeriod,
+
+    #[msg("No ownership transfer is pending")]
+    NoPendingOwner,
+
+    #[msg("Signer is not the pending owner")]
+    InvalidPendingOwner,
+
+    #[msg("Config change delay is below the minimum")]
+    InvalidConfigChangeDelay,
+
+    #[msg("No config change is queued")]
+    NoPendingConfigChange,
+
+    #[msg("Queued config change is not effective yet")]
+    ConfigChangeNotReady,
+
+    #[msg("Ticket fee is above 100%")]
+    InvalidTicketFee,
+}
+
+#[error_code]
+pub enum PermitErrors {
+    #[msg("Permit must be verified by an Ed25519 instruction right before this one")]
+    MissingPermitInstruction,
+
+    #[msg("Ed25519 instruction must verify exactly one inline signature")]
+    InvalidPermitInstruction,
+
+    #[msg("Signed permit does not match this spin")]
+    PermitMessageMismatch,
+
+    #[msg("Permit has expired")]
+    PermitExpired,
+
+    #[msg("Amount exceeds the permit maximum")]
+    PermitAmountExceeded,
+
+    #[msg("Permit is not signed by the gumball admin")]
+    InvalidPermitSigner,
+}
+
+#[error_code]
+pub enum MintPolicyErrors {
+    #[msg("Mint has a permanent delegate")]
+    PermanentDelegateNotAllowed,
+
+    #[msg("Mint is non-transferable")]
+    NonTransferableNotAllowed,
+
+    #[msg("Mint freezes new token accounts by default")]
+    DefaultFrozenNotAllowed,
+
+    #[msg("Mint has a transfer hook")]
+    TransferHookNotAllowed,
+
+    #[msg("Mint is pausable")]
+    PausableNotAllowed,
+
+    #[msg("Invalid mint extension index")]
+    InvalidMintExtension,
+}
+
+#[error_code]
+pub enum NftVerificationErrors {
+    #[msg("NFT prize has no Metaplex metadata")]
+    MissingNftMetadata,
+
+    #[msg("NFT prize has no master edition")]
+    InvalidMasterEdition,
+
+    #[msg("NFT prize is not part of a verified collection")]
+    UnverifiedCollection,
+
+    #[msg("NFT prize collection is not allowlisted")]
+    CollectionNotAllowed,
+}
+
+#[error_code]
+pub enum MigrationErrors {
+    #[msg("Account is not owned by this program")]
+    InvalidAccountOwner,
+
+    #[msg("Account discriminator does not match")]
+    InvalidDiscriminator,
+
+    #[msg("Account is already on the current layout")]
+    AlreadyMigrated,
 }
 
 #[error_code]
@@ -152,4 +242,19 @@ pub enum TransferErrors {
 
     #[msg("Insufficient SOL Token Balance")]
     InsufficientSolBalance,
+
+    #[msg("Transfer fee could not be calculated")]
+    TransferFeeCalculationFailed,
+
+    #[msg("Escrow received less than required after the transfer fee")]
+    InsufficientAmountReceived,
+
+    #[msg("Transfer hook program or its extra accounts are missing from the remaining accounts")]
+    MissingTransferHookAccounts,
+
+    #[msg("Prize metadata account could not be read")]
+    InvalidPrizeMetadata,
+
+    #[msg("Authorization rules do not match the rule set of the programmable NFT")]
+    InvalidAuthorizationRules,
 }
@@ -1,5 +1,11 @@
 use anchor_lang::prelude::*;
 use anchor_lang::system_program::{self};
+use anchor_spl::metadata::mpl_token_metadata::{
+    self,
+    accounts::{MasterEdition, Metadata},
+    instructions::TransferV1CpiBuilder,
+    types::{Key as MetadataKey, ProgrammableConfig, TokenStandard},
+};
 use anchor_spl::token_interface::{
     Mint,
     TokenAccount,
@@ -7,15 +13,31 @@ use anchor_spl::token_interface::{
     TransferChecked,
     transfer_checked,
 };
-use crate::errors::TransferErrors;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        default_account_state::DefaultAccountState, transfer_fee::TransferFeeConfig,
+        transfer_hook, BaseStateWithExtensions, ExtensionType, StateWithExtensions,
+    },
+    onchain,
+    state::AccountState,
+};
+use crate::constants::{
+    DEFAULT_FROZEN_EXTENSION, NON_TRANSFERABLE_EXTENSION, PAUSABLE_EXTENSION,
+    PERMANENT_DELEGATE_EXTENSION, TRANSFER_HOOK_EXTENSION,
+};
+use crate::errors::{MintPolicyErrors, NftVerificationErrors, TransferErrors};
+use crate::states::CollectionAllowlist;
 
 // Transfer SPL Tokens With PDA Seeds — SAFE (uses transfer_checked)
+#[allow(clippy::too_many_arguments)]
 pub fn transfer_tokens_with_seeds<'info>(
     from: &InterfaceAccount<'info, TokenAccount>,
     to: &InterfaceAccount<'info, TokenAccount>,
     authority: &AccountInfo<'info>,
     token_program: &Interface<'info, TokenInterface>,
     mint: &InterfaceAccount<'info, Mint>,
+    remaining_accounts: &[AccountInfo<'info>],
     signer_seeds: &[&[&[u8]]],
     amount: u64,
 ) -> Result<()> {
@@ -24,38 +46,87 @@ pub fn transfer_tokens_with_seeds<'info>(
         TransferErrors::InsufficientTokenBalance
     );
 
-    transfer_checked(
-        CpiContext::new_with_signer(
-            token_program.to_account_info(),
-            TransferChecked {
-                from: from.to_account_info(),
-                to: to.to_account_info(),
-                mint: mint.to_account_info(),
-                authority: authority.to_account_info(),
-            },
-            signer_seeds,
-        ),
+    invoke_transfer_checked(
+        from,
+        to,
+        authority,
+        token_program,
+        mint,
+        remaining_accounts,
+        signer_seeds,
         amount,
-        mint.decimals,
-    ).map_err(|_| TransferErrors::TokenTransferFailed.into())
+    )
 }
 
-// Transfer SPL Tokens (normal signer authority) — SAFE
+// Transfer SPL Tokens (normal signer authority) — SAFE, returns what `to` actually received
 pub fn transfer_tokens<'info>(
     from: &InterfaceAccount<'info, TokenAccount>,
     to: &InterfaceAccount<'info, TokenAccount>,
     authority: &Signer<'info>,
     token_program: &Interface<'info, TokenInterface>,
     mint: &InterfaceAccount<'info, Mint>,
+    remaining_accounts: &[AccountInfo<'info>],
     amount: u64,
-) -> Result<()> {
+) -> Result<u64> {
     require!(
         from.amount >= amount,
         TransferErrors::InsufficientTokenBalance
     );
 
+    // the token program withholds the fee in `to`, it never counts towards the escrowed amount
+    let received = amount
+        .checked_sub(transfer_fee_amount(mint, amount)?)
+        .ok_or(TransferErrors::TransferFeeCalculationFailed)?;
+
+    invoke_transfer_checked(
+        from,
+        to,
+        &authority.to_account_info(),
+        token_program,
+        mint,
+        remaining_accounts,
+        &[],
+        amount,
+    )?;
+
+    Ok(received)
+}
+
+// Token-2022 runs the mint's transfer hook inside transfer_checked, so a hooked mint goes through the
+// hook-aware CPI that resolves the hook's extra account metas from `remaining_accounts`
+#[allow(clippy::too_many_arguments)]
+fn invoke_transfer_checked<'info>(
+    from: &InterfaceAccount<'info, TokenAccount>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    authority: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    mint: &InterfaceAccount<'info, Mint>,
+    remaining_accounts: &[AccountInfo<'info>],
+    signer_seeds: &[&[&[u8]]],
+    amount: u64,
+) -> Result<()> {
+    if let Some(hook_program_id) = transfer_hook_program_id(mint)? {
+        require!(
+            remaining_accounts.iter().any(|account| account.key == &hook_program_id),
+            TransferErrors::MissingTransferHookAccounts
+        );
+
+        return onchain::invoke_transfer_checked(
+            token_program.key,
+            from.to_account_info(),
+            mint.to_account_info(),
+            to.to_account_info(),
+            authority.to_account_info(),
+            remaining_accounts,
+            amount,
+            mint.decimals,
+            signer_seeds,
+        )
+        .map_err(|_| TransferErrors::TokenTransferFailed.into());
+    }
+
     transfer_checked(
-        CpiContext::new(
+        CpiContext::new_with_signer(
             token_program.to_account_info(),
             TransferChecked {
                 from: from.to_account_info(),
@@ -63,10 +134,253 @@ pub fn transfer_tokens<'info>(
                 mint: mint.to_account_info(),
                 authority: authority.to_account_info(),
             },
+            signer_seeds,
         ),
         amount,
         mint.decimals,
-    ).map_err(|_| TransferErrors::TokenTransferFailed.into())
+    )
+    .map_err(|_| TransferErrors::TokenTransferFailed.into())
+}
+
+// Program a Token-2022 `TransferHook` mint calls on every transfer, none for every other mint
+fn transfer_hook_program_id<'info>(mint: &InterfaceAccount<'info, Mint>) -> Result<Option<Pubkey>> {
+    let mint_ai = mint.to_account_info();
+    if *mint_ai.owner != spl_token_2022::ID {
+        return Ok(None);
+    }
+
+    let data = mint_ai.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(transfer_hook::get_program_id(&mint_state))
+}
+
+fn transfer_fee_config<'info>(mint: &InterfaceAccount<'info, Mint>) -> Result<Option<TransferFeeConfig>> {
+    let mint_ai = mint.to_account_info();
+    if *mint_ai.owner != spl_token_2022::ID {
+        return Ok(None);
+    }
+
+    let data = mint_ai.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    Ok(mint_state.get_extension::<TransferFeeConfig>().ok().copied())
+}
+
+// Fee a Token-2022 `TransferFee` mint withholds from a transfer of `amount`, zero for every other mint
+pub fn transfer_fee_amount<'info>(mint: &InterfaceAccount<'info, Mint>, amount: u64) -> Result<u64> {
+    match transfer_fee_config(mint)? {
+        Some(fee_config) => fee_config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(TransferErrors::TransferFeeCalculationFailed.into()),
+        None => Ok(0),
+    }
+}
+
+// Amount to send so that exactly `net_amount` lands in the escrow once the transfer fee is withheld
+pub fn amount_with_transfer_fee<'info>(mint: &InterfaceAccount<'info, Mint>, net_amount: u64) -> Result<u64> {
+    let fee = match transfer_fee_config(mint)? {
+        Some(fee_config) => fee_config
+            .calculate_inverse_epoch_fee(Clock::get()?.epoch, net_amount)
+            .ok_or(TransferErrors::TransferFeeCalculationFailed)?,
+        None => 0,
+    };
+
+    net_amount
+        .checked_add(fee)
+        .ok_or(TransferErrors::TransferFeeCalculationFailed.into())
+}
+
+// Token-2022 extensions that can drain or lock a gumball escrow pass only if the config allows them or the mint is allowlisted
+pub fn check_mint_policy<'info>(
+    mint: &InterfaceAccount<'info, Mint>,
+    allowed_extensions: u8,
+    mint_allowlist: &AccountInfo<'info>,
+) -> Result<()> {
+    let mint_ai = mint.to_account_info();
+    if *mint_ai.owner != spl_token_2022::ID {
+        return Ok(());
+    }
+
+    // the allowlist PDA can only be created through `allow_mint`, existing is enough
+    if *mint_allowlist.owner == crate::ID && !mint_allowlist.data_is_empty() {
+        return Ok(());
+    }
+
+    let data = mint_ai.try_borrow_data()?;
+    let mint_state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+    for extension in mint_state.get_extension_types()? {
+        let (index, error) = match extension {
+            ExtensionType::PermanentDelegate => (
+                PERMANENT_DELEGATE_EXTENSION,
+                MintPolicyErrors::PermanentDelegateNotAllowed,
+            ),
+            ExtensionType::NonTransferable => (
+                NON_TRANSFERABLE_EXTENSION,
+                MintPolicyErrors::NonTransferableNotAllowed,
+            ),
+            ExtensionType::DefaultAccountState => {
+                // an initialized default is harmless, only a frozen one locks the escrow
+                let default_state = mint_state.get_extension::<DefaultAccountState>()?;
+                if default_state.state != AccountState::Frozen as u8 {
+                    continue;
+                }
+                (
+                    DEFAULT_FROZEN_EXTENSION,
+                    MintPolicyErrors::DefaultFrozenNotAllowed,
+                )
+            }
+            ExtensionType::TransferHook => (
+                TRANSFER_HOOK_EXTENSION,
+                MintPolicyErrors::TransferHookNotAllowed,
+            ),
+            ExtensionType::Pausable => (PAUSABLE_EXTENSION, MintPolicyErrors::PausableNotAllowed),
+            _ => continue,
+        };
+
+        if allowed_extensions & (1u8 << index) == 0 {
+            return Err(error.into());
+        }
+    }
+
+    Ok(())
+}
+
+// Token Metadata accounts a programmable NFT transfer needs besides the token accounts
+pub struct ProgrammableNftAccounts<'a, 'info> {
+    pub metadata: &'a AccountInfo<'info>,
+    pub edition: &'a AccountInfo<'info>,
+    pub token_record: &'a AccountInfo<'info>,
+    pub destination_token_record: &'a AccountInfo<'info>,
+    pub authorization_rules: &'a AccountInfo<'info>,
+    pub authorization_rules_program: &'a AccountInfo<'info>,
+    pub token_metadata_program: &'a AccountInfo<'info>,
+    pub sysvar_instructions: &'a AccountInfo<'info>,
+    pub associated_token_program: &'a AccountInfo<'info>,
+    pub system_program: &'a AccountInfo<'info>,
+}
+
+fn read_prize_metadata(metadata: &AccountInfo) -> Result<Option<Metadata>> {
+    if *metadata.owner != mpl_token_metadata::ID || metadata.data_is_empty() {
+        return Ok(None);
+    }
+
+    Metadata::try_from(metadata)
+        .map(Some)
+        .map_err(|_| TransferErrors::InvalidPrizeMetadata.into())
+}
+
+// True for a Metaplex programmable NFT, false for plain NFTs, SPL tokens and mints without metadata
+pub fn is_programmable_nft(metadata: &AccountInfo) -> Result<bool> {
+    Ok(matches!(
+        read_prize_metadata(metadata)?.and_then(|metadata| metadata.token_standard),
+        Some(TokenStandard::ProgrammableNonFungible | TokenStandard::ProgrammableNonFungibleEdition)
+    ))
+}
+
+// only `allow_collection` can create a program owned `CollectionAllowlist`
+fn read_collection_allowlist(entry: &AccountInfo) -> Option<CollectionAllowlist> {
+    if *entry.owner != crate::ID {
+        return None;
+    }
+
+    let data = entry.try_borrow_data().ok()?;
+    CollectionAllowlist::try_deserialize(&mut &data[..]).ok()
+}
+
+// An NFT prize needs Metaplex metadata and a master edition, returns its verified collection if any
+pub fn verify_nft_prize<'info>(
+    mint: &InterfaceAccount<'info, Mint>,
+    metadata: &AccountInfo<'info>,
+    edition: &AccountInfo<'info>,
+    collection_allowlist: Option<&AccountInfo<'info>>,
+    require_verified_collection: bool,
+) -> Result<Option<Pubkey>> {
+    let metadata = read_prize_metadata(metadata)?.ok_or(NftVerificationErrors::MissingNftMetadata)?;
+    require_keys_eq!(metadata.mint, mint.key(), NftVerificationErrors::MissingNftMetadata);
+
+    // the edition PDA is pinned by the accounts constraints, a print edition lives at the same address
+    let is_master_edition = *edition.owner == mpl_token_metadata::ID
+        && !edition.data_is_empty()
+        && MasterEdition::try_from(edition).is_ok_and(|edition| {
+            matches!(edition.key, MetadataKey::MasterEditionV1 | MetadataKey::MasterEditionV2)
+        });
+    require!(is_master_edition, NftVerificationErrors::InvalidMasterEdition);
+
+    let collection = metadata
+        .collection
+        .filter(|collection| collection.verified)
+        .map(|collection| collection.key);
+
+    if require_verified_collection {
+        let collection = collection.ok_or(NftVerificationErrors::UnverifiedCollection)?;
+
+        let allowlisted = collection_allowlist
+            .and_then(read_collection_allowlist)
+            .is_some_and(|entry| entry.collection == collection);
+        require!(allowlisted, NftVerificationErrors::CollectionNotAllowed);
+    }
+
+    Ok(collection)
+}
+
+// pNFT token accounts stay frozen between transfers, so the token program rejects a plain
+// transfer_checked and the move has to go through Token Metadata's TransferV1
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_programmable_nft<'info>(
+    from: &InterfaceAccount<'info, TokenAccount>,
+    from_owner: &AccountInfo<'info>,
+    to: &InterfaceAccount<'info, TokenAccount>,
+    to_owner: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    payer: &AccountInfo<'info>,
+    token_program: &Interface<'info, TokenInterface>,
+    pnft: &ProgrammableNftAccounts<'_, 'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    require!(from.amount >= 1, TransferErrors::InsufficientTokenBalance);
+
+    // rule set enforced by the pNFT, if any; the rules account is only forwarded when there is one
+    let rule_set = match read_prize_metadata(pnft.metadata)?.and_then(|m| m.programmable_config) {
+        Some(ProgrammableConfig::V1 { rule_set }) => rule_set,
+        None => None,
+    };
+    if let Some(rule_set) = rule_set {
+        require_keys_eq!(
+            pnft.authorization_rules.key(),
+            rule_set,
+            TransferErrors::InvalidAuthorizationRules
+        );
+    }
+    let (authorization_rules_program, authorization_rules) = match rule_set {
+        Some(_) => (Some(pnft.authorization_rules_program), Some(pnft.authorization_rules)),
+        None => (None, None),
+    };
+
+    let from_ai = from.to_account_info();
+    let to_ai = to.to_account_info();
+    let mint_ai = mint.to_account_info();
+    let token_program_ai = token_program.to_account_info();
+
+    TransferV1CpiBuilder::new(pnft.token_metadata_program)
+        .token(&from_ai)
+        .token_owner(from_owner)
+        .destination_token(&to_ai)
+        .destination_owner(to_owner)
+        .mint(&mint_ai)
+        .metadata(pnft.metadata)
+        .edition(Some(pnft.edition))
+        .token_record(Some(pnft.token_record))
+        .destination_token_record(Some(pnft.destination_token_record))
+        .authority(from_owner)
+        .payer(payer)
+        .system_program(pnft.system_program)
+        .sysvar_instructions(pnft.sysvar_instructions)
+        .spl_token_program(&token_program_ai)
+        .spl_ata_program(pnft.associated_token_program)
+        .authorization_rules_program(authorization_rules_program)
+        .authorization_rules(authorization_rules)
+        .amount(1)
+        .invoke_signed(signer_seeds)
+        .map_err(|_| TransferErrors::TokenTransferFailed.into())
 }
 
 // Transfer SOL from normal signer
@@ -93,3 +407,27 @@ pub fn transfer_sol<'info>(
         amount,
     ).map_err(|_| TransferErrors::SolTransferFailed.into())
 }
+
+// Grow a program-owned account to `new_len`, topping up rent from the payer first
+pub fn resize_account<'info>(
+    account: &AccountInfo<'info>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    new_len: usize,
+) -> Result<()> {
+    let required_lamports = Rent::get()?.minimum_balance(new_len);
+    let current_lamports = account.lamports();
+
+    if required_lamports > current_lamports {
+        transfer_sol(
+            payer,
+            account,
+            system_program,
+            required_lamports - current_lamports,
+        )?;
+    }
+
+    account.resize(new_len)?;
+
+    Ok(())
+}
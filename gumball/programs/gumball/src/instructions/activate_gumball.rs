@@ -61,7 +61,8 @@ pub struct ActivateGumball<'info> {
         mut,
         seeds = [b"gumball", gumball_id.to_le_bytes().as_ref()],
         bump = gumball.gumball_bump,
-        constraint = gumball.gumball_id == gumball_id @ GumballStateErrors::InvalidGumballId
+        constraint = gumball.gumball_id == gumball_id @ GumballStateErrors::InvalidGumballId,
+        constraint = !gumball.frozen @ GumballStateErrors::GumballFrozen,
     )]
     pub gumball: Box<Account<'info, GumballMachine>>,
 
@@ -1,8 +1,9 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::mpl_token_metadata;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::constants::*;
-use crate::errors::{ConfigStateErrors, GumballStateErrors};
+use crate::errors::{ConfigStateErrors, GumballStateErrors, TransferErrors};
 use crate::helpers::*;
 use crate::states::*;
 use crate::utils::*;
@@ -12,13 +13,14 @@ pub struct PrizeAdded {
     pub gumball_id: u32,
     pub prize_index: u16,
     pub prize_mint: Pubkey,
+    pub collection: Option<Pubkey>, // verified collection of an NFT prize
     pub prize_amount: u64,
     pub quantitiy: u16,
     pub added_at: i64,
 }
 
-pub fn add_prize(
-    ctx: Context<AddPrize>,
+pub fn add_prize<'info>(
+    ctx: Context<'_, '_, '_, 'info, AddPrize<'info>>,
     gumball_id: u32,
     prize_index: u16,
     prize_amount: u64,
@@ -51,6 +53,7 @@ pub fn add_prize(
 
     // Validate mint if NFT
     let is_nft = validate_nft(prize_mint);
+    let mut collection = None;
     if is_nft {
         // For NFTs: prize_amount must equal 1 and quantity must be 1 (single NFT prize)
         require_eq!(
@@ -59,6 +62,14 @@ pub fn add_prize(
             GumballStateErrors::InvalidNftPrizeAmount
         );
         require_eq!(quantity, 1u16, GumballStateErrors::InvalidNftPrizeQuantity);
+
+        collection = verify_nft_prize(
+            prize_mint,
+            &ctx.accounts.prize_metadata,
+            &ctx.accounts.prize_edition,
+            ctx.accounts.prize_collection_allowlist.as_deref(),
+            ctx.accounts.gumball_config.require_verified_collection,
+        )?;
     } else {
         // For SPL: prize_amount must be > 0 and quantity > 0
         require_gt!(prize_amount, 0, GumballStateErrors::InvalidPrizeAmount);
@@ -139,21 +150,60 @@ pub fn add_prize(
         .checked_add(quantity)
         .ok_or(GumballStateErrors::Overflow)?;
 
-    // Transfer from creator ATA to escrow ATA
-    transfer_tokens(
-        &ctx.accounts.creator_prize_ata,
-        &ctx.accounts.prize_escrow,
-        creator,
-        &ctx.accounts.prize_token_program,
+    check_mint_policy(
         prize_mint,
-        add_amount,
+        ctx.accounts.gumball_config.allowed_mint_extensions,
+        &ctx.accounts.prize_mint_allowlist,
     )?;
 
+    // Transfer from creator ATA to escrow ATA, the creator covers any Token-2022 transfer fee
+    // so every spin can still pay out the full `prize_amount`
+    if is_programmable_nft(&ctx.accounts.prize_metadata)? {
+        transfer_programmable_nft(
+            &ctx.accounts.creator_prize_ata,
+            creator,
+            &ctx.accounts.prize_escrow,
+            &gumball.to_account_info(),
+            prize_mint,
+            creator,
+            &ctx.accounts.prize_token_program,
+            &ProgrammableNftAccounts {
+                metadata: &ctx.accounts.prize_metadata,
+                edition: &ctx.accounts.prize_edition,
+                token_record: &ctx.accounts.creator_token_record,
+                destination_token_record: &ctx.accounts.escrow_token_record,
+                authorization_rules: &ctx.accounts.authorization_rules,
+                authorization_rules_program: &ctx.accounts.authorization_rules_program,
+                token_metadata_program: &ctx.accounts.token_metadata_program,
+                sysvar_instructions: &ctx.accounts.instructions_sysvar,
+                associated_token_program: &ctx.accounts.associated_token_program,
+                system_program: &ctx.accounts.system_program,
+            },
+            &[],
+        )?;
+    } else {
+        let received = transfer_tokens(
+            &ctx.accounts.creator_prize_ata,
+            &ctx.accounts.prize_escrow,
+            creator,
+            &ctx.accounts.prize_token_program,
+            prize_mint,
+            ctx.remaining_accounts,
+            amount_with_transfer_fee(prize_mint, add_amount)?,
+        )?;
+        require_gte!(
+            received,
+            add_amount,
+            TransferErrors::InsufficientAmountReceived
+        );
+    }
+
     // Emit event
     emit!(PrizeAdded {
         gumball_id,
         prize_index,
         prize_mint: prize_mint.key(),
+        collection,
         prize_amount,
         quantitiy: quantity,
         added_at: now,
@@ -176,7 +226,8 @@ pub struct AddPrize<'info> {
         mut,
         seeds = [b"gumball", gumball_id.to_le_bytes().as_ref()],
         bump = gumball.gumball_bump,
-        constraint = gumball.gumball_id == gumball_id @ GumballStateErrors::InvalidGumballId
+        constraint = gumball.gumball_id == gumball_id @ GumballStateErrors::InvalidGumballId,
+        constraint = !gumball.frozen @ GumballStateErrors::GumballFrozen,
     )]
     pub gumball: Box<Account<'info, GumballMachine>>,
 
@@ -199,6 +250,13 @@ pub struct AddPrize<'info> {
 
     pub prize_mint: InterfaceAccount<'info, Mint>,
 
+    /// CHECK: only has to exist when the owner allowlisted the prize mint, see `check_mint_policy`
+    #[account(
+        seeds = [b"gumball".as_ref(), b"mint_allowlist".as_ref(), prize_mint.key().as_ref()],
+        bump
+    )]
+    pub prize_mint_allowlist: UncheckedAccount<'info>,
+
     // Ticket escrow ATA (create ATA to store the tickets amount from the buyers and owner of the ATA is the gumball account, if ticket mint != sol)
     #[account(
         init_if_needed,
@@ -219,6 +277,80 @@ pub struct AddPrize<'info> {
 
     pub prize_token_program: Interface<'info, TokenInterface>,
  
+    /// CHECK: Token Metadata PDA of the prize mint, verified for NFT prizes and read to pick the pNFT transfer path
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub prize_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: master edition PDA of the prize mint, verified for NFT prizes and used by pNFT transfers
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub prize_edition: UncheckedAccount<'info>,
+
+    /// CHECK: `CollectionAllowlist` entry of the prize's verified collection, only read when the config requires one
+    pub prize_collection_allowlist: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: pNFT token record of `creator_prize_ata`, only used by pNFT transfers
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"token_record".as_ref(),
+            creator_prize_ata.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub creator_token_record: UncheckedAccount<'info>,
+
+    /// CHECK: pNFT token record of `prize_escrow`, only used by pNFT transfers
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"token_record".as_ref(),
+            prize_escrow.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub escrow_token_record: UncheckedAccount<'info>,
+
+    /// CHECK: only forwarded when the pNFT has a rule set, checked in `transfer_programmable_nft`
+    pub authorization_rules: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to Metaplex Token Auth Rules
+    #[account(address = AUTH_RULES_PROGRAM_ID)]
+    pub authorization_rules_program: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to Token Metadata, only invoked for pNFT prizes
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to the instructions sysvar, read by Token Metadata
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
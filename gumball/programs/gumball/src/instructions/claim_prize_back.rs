@@ -1,7 +1,7 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{close_account, CloseAccount};
-use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use anchor_spl::metadata::mpl_token_metadata;
+use anchor_spl::token_interface::{close_account, CloseAccount, Mint, TokenAccount, TokenInterface};
 use crate::constants::*;
 use crate::errors::{ConfigStateErrors, GumballStateErrors};
 use crate::helpers::*;
@@ -21,8 +21,8 @@ pub struct PrizeClaimed {
 // Claim remaining tokens for a prize back to the creator.
 // This will transfer all `remaining_quantity * prize_amount` from the prize escrow to the creator ATA,
 // then close the prize account and the prize escrow ATA (rent goes to creator).
-pub fn claim_prize_back(
-    ctx: Context<ClaimPrizeBack>,
+pub fn claim_prize_back<'info>(
+    ctx: Context<'_, '_, '_, 'info, ClaimPrizeBack<'info>>,
     gumball_id: u32,
     prize_index: u16,
 ) -> Result<()> {
@@ -75,15 +75,41 @@ pub fn claim_prize_back(
     // Build signer seeds for gumball PDA (authority over prize_escrow ATA)
     if claimable_amount > 0 {
         // Transfer tokens from prize_escrow -> creator_prize_ata using gumball PDA as signer
-        transfer_tokens_with_seeds(
-            &ctx.accounts.prize_escrow,
-            &ctx.accounts.creator_prize_ata,
-            &gumball_ai,
-            &ctx.accounts.prize_token_program,
-            &ctx.accounts.prize_mint,
-            signer_seeds,
-            claimable_amount,
-        )?;
+        if is_programmable_nft(&ctx.accounts.prize_metadata)? {
+            transfer_programmable_nft(
+                &ctx.accounts.prize_escrow,
+                &gumball_ai,
+                &ctx.accounts.creator_prize_ata,
+                &ctx.accounts.creator,
+                &ctx.accounts.prize_mint,
+                &ctx.accounts.creator,
+                &ctx.accounts.prize_token_program,
+                &ProgrammableNftAccounts {
+                    metadata: &ctx.accounts.prize_metadata,
+                    edition: &ctx.accounts.prize_edition,
+                    token_record: &ctx.accounts.escrow_token_record,
+                    destination_token_record: &ctx.accounts.creator_token_record,
+                    authorization_rules: &ctx.accounts.authorization_rules,
+                    authorization_rules_program: &ctx.accounts.authorization_rules_program,
+                    token_metadata_program: &ctx.accounts.token_metadata_program,
+                    sysvar_instructions: &ctx.accounts.instructions_sysvar,
+                    associated_token_program: &ctx.accounts.associated_token_program,
+                    system_program: &ctx.accounts.system_program,
+                },
+                signer_seeds,
+            )?;
+        } else {
+            transfer_tokens_with_seeds(
+                &ctx.accounts.prize_escrow,
+                &ctx.accounts.creator_prize_ata,
+                &gumball_ai,
+                &ctx.accounts.prize_token_program,
+                &ctx.accounts.prize_mint,
+                ctx.remaining_accounts,
+                signer_seeds,
+                claimable_amount,
+            )?;
+        }
     }
 
     // Set remaining to zero
@@ -128,7 +154,8 @@ pub struct ClaimPrizeBack<'info> {
         mut,
         seeds = [b"gumball", gumball_id.to_le_bytes().as_ref()],
         bump = gumball.gumball_bump,
-        constraint = gumball.gumball_id == gumball_id @ GumballStateErrors::InvalidGumballId
+        constraint = gumball.gumball_id == gumball_id @ GumballStateErrors::InvalidGumballId,
+        constraint = !gumball.frozen @ GumballStateErrors::GumballFrozen,
     )]
     pub gumball: Box<Account<'info, GumballMachine>>,
 
@@ -176,6 +203,77 @@ pub struct ClaimPrizeBack<'info> {
     // Token program
     pub prize_token_program: Interface<'info, TokenInterface>,
 
+    /// CHECK: Token Metadata PDA of the prize mint, read to pick the pNFT transfer path
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub prize_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: master edition PDA of the prize mint, only used by pNFT transfers
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub prize_edition: UncheckedAccount<'info>,
+
+    /// CHECK: pNFT token record of `prize_escrow`, only used by pNFT transfers
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"token_record".as_ref(),
+            prize_escrow.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub escrow_token_record: UncheckedAccount<'info>,
+
+    /// CHECK: pNFT token record of `creator_prize_ata`, only used by pNFT transfers
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"token_record".as_ref(),
+            creator_prize_ata.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub creator_token_record: UncheckedAccount<'info>,
+
+    /// CHECK: only forwarded when the pNFT has a rule set, checked in `transfer_programmable_nft`
+    pub authorization_rules: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to Metaplex Token Auth Rules
+    #[account(address = AUTH_RULES_PROGRAM_ID)]
+    pub authorization_rules_program: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to Token Metadata, only invoked for pNFT prizes
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to the instructions sysvar, read by Token Metadata
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
     // Programs
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -58,6 +58,7 @@ pub fn create_gumball(
     );
 
     // Set gumball metadata
+    gumball.version = GUMBALL_VERSION;
     gumball.gumball_id = config.gumball_count;
     gumball.creator = creator.key();
     gumball.start_time = start_time;
@@ -70,9 +71,15 @@ pub fn create_gumball(
         GumballState::Initialized
     };
     gumball.gumball_bump = ctx.bumps.gumball;
+    gumball.ticket_fee_bps = config.ticket_fee_bps;
 
     // Ticket escrow (only if SPL tickets)
     if !is_ticket_sol {
+        check_mint_policy(
+            &ctx.accounts.ticket_mint,
+            config.allowed_mint_extensions,
+            &ctx.accounts.ticket_mint_allowlist,
+        )?;
         gumball.ticket_mint = Some(ctx.accounts.ticket_mint.key());
     } else {
         gumball.ticket_mint = None;
@@ -132,5 +139,12 @@ pub struct CreateGumball<'info> {
     // Mint used for tickets (must be a valid SPL mint if `is_ticket_sol == false`)
     pub ticket_mint: InterfaceAccount<'info, Mint>,
 
+    /// CHECK: only has to exist when the owner allowlisted the ticket mint, see `check_mint_policy`
+    #[account(
+        seeds = [b"gumball".as_ref(), b"mint_allowlist".as_ref(), ticket_mint.key().as_ref()],
+        bump
+    )]
+    pub ticket_mint_allowlist: UncheckedAccount<'info>,
+
     pub system_program: Program<'info, System>,
 }
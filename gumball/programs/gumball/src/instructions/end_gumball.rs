@@ -1,6 +1,5 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{close_account, CloseAccount};
-use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use anchor_spl::token_interface::{close_account, CloseAccount, Mint, TokenAccount, TokenInterface};
 use crate::constants::*;
 use crate::errors::{ConfigStateErrors, GumballStateErrors, KeysMismatchErrors};
 use crate::helpers::*;
@@ -16,7 +15,10 @@ pub struct GumballEnded {
     pub ended_at: i64,
 }
 
-pub fn end_gumball(ctx: Context<EndGumball>, gumball_id: u32) -> Result<()> {
+pub fn end_gumball<'info>(
+    ctx: Context<'_, '_, '_, 'info, EndGumball<'info>>,
+    gumball_id: u32,
+) -> Result<()> {
     let gumball_ai = ctx.accounts.gumball.to_account_info();
     let config = &ctx.accounts.gumball_config;
     let gumball = &mut ctx.accounts.gumball;
@@ -55,14 +57,19 @@ pub fn end_gumball(ctx: Context<EndGumball>, gumball_id: u32) -> Result<()> {
         return Ok(());
     }
 
-    let total_amount = gumball
-        .ticket_price
-        .checked_mul(tickets_sold as u64)
-        .ok_or(GumballStateErrors::Overflow)?;
+    // version 1 machines never tracked `ticket_revenue`, every spin there paid the full price
+    let total_amount = if gumball.version >= 2 {
+        gumball.ticket_revenue
+    } else {
+        gumball
+            .ticket_price
+            .checked_mul(tickets_sold as u64)
+            .ok_or(GumballStateErrors::Overflow)?
+    };
 
     let fee_amount = get_pct_amount(
         total_amount,
-        config.ticket_fee_bps as u64,
+        gumball.ticket_fee_bps as u64,
         FEE_MANTISSA as u64,
     )?;
     let mut creator_amount = total_amount
@@ -124,6 +131,7 @@ pub fn end_gumball(ctx: Context<EndGumball>, gumball_id: u32) -> Result<()> {
                 &gumball_ai,
                 &ctx.accounts.ticket_token_program,
                 &ctx.accounts.ticket_mint,
+                ctx.remaining_accounts,
                 signer_seeds,
                 fee_amount,
             )?;
@@ -135,6 +143,7 @@ pub fn end_gumball(ctx: Context<EndGumball>, gumball_id: u32) -> Result<()> {
                 &gumball_ai,
                 &ctx.accounts.ticket_token_program,
                 &ctx.accounts.ticket_mint,
+                ctx.remaining_accounts,
                 signer_seeds,
                 creator_amount,
             )?;
@@ -182,7 +191,8 @@ pub struct EndGumball<'info> {
         mut,
         seeds = [b"gumball", gumball_id.to_le_bytes().as_ref()],
         bump = gumball.gumball_bump,
-        constraint = gumball.gumball_id == gumball_id @ GumballStateErrors::InvalidGumballId
+        constraint = gumball.gumball_id == gumball_id @ GumballStateErrors::InvalidGumballId,
+        constraint = !gumball.frozen @ GumballStateErrors::GumballFrozen,
     )]
     pub gumball: Box<Account<'info, GumballMachine>>,
 
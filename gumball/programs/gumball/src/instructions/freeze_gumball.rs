@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+use crate::errors::{ConfigStateErrors, GumballStateErrors};
+use crate::states::{GumballConfig, GumballMachine};
+
+#[event]
+pub struct GumballFrozenUpdated {
+    pub gumball_id: u32,
+    pub frozen: bool,
+    pub updated_at: i64,
+}
+
+// Halts (or resumes) a single gumball without touching the program wide pause flags
+pub fn set_gumball_frozen(ctx: Context<SetGumballFrozen>, gumball_id: u32, frozen: bool) -> Result<()> {
+    let gumball = &mut ctx.accounts.gumball;
+
+    gumball.frozen = frozen;
+
+    emit!(GumballFrozenUpdated {
+        gumball_id,
+        frozen,
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(gumball_id: u32)]
+pub struct SetGumballFrozen<'info> {
+    #[account(
+        seeds = [b"gumball"],
+        bump = gumball_config.config_bump,
+        constraint = gumball_owner.key() == gumball_config.gumball_owner @ ConfigStateErrors::InvalidGumballOwner
+    )]
+    pub gumball_config: Box<Account<'info, GumballConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"gumball", gumball_id.to_le_bytes().as_ref()],
+        bump = gumball.gumball_bump,
+        constraint = gumball.gumball_id == gumball_id @ GumballStateErrors::InvalidGumballId,
+    )]
+    pub gumball: Box<Account<'info, GumballMachine>>,
+
+    pub gumball_owner: Signer<'info>,
+}
@@ -0,0 +1,231 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use crate::constants::{GUMBALL_CONFIG_VERSION, GUMBALL_VERSION, MINIMUM_CONFIG_CHANGE_DELAY};
+use crate::errors::{ConfigStateErrors, GumballStateErrors, MigrationErrors};
+use crate::helpers::resize_account;
+use crate::states::*;
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub version: u8,
+    pub migrated_at: i64,
+}
+
+fn check_owner_and_discriminator(account: &AccountInfo, discriminator: &[u8]) -> Result<()> {
+    require_keys_eq!(
+        *account.owner,
+        crate::ID,
+        MigrationErrors::InvalidAccountOwner
+    );
+
+    let data = account.try_borrow_data()?;
+    require!(
+        data.len() >= 8 && &data[..8] == discriminator,
+        MigrationErrors::InvalidDiscriminator
+    );
+
+    Ok(())
+}
+
+// Decode a legacy account body once its owner, discriminator and legacy size are confirmed
+fn read_legacy<T: AnchorDeserialize>(
+    account: &AccountInfo,
+    discriminator: &[u8],
+    legacy_len: usize,
+) -> Result<T> {
+    check_owner_and_discriminator(account, discriminator)?;
+
+    let data = account.try_borrow_data()?;
+    // anything other than the legacy size is already versioned
+    require_eq!(data.len(), legacy_len, MigrationErrors::AlreadyMigrated);
+
+    T::deserialize(&mut &data[8..]).map_err(|_| error!(ErrorCode::AccountDidNotDeserialize))
+}
+
+// Decode a versioned account body that is still on layout `version`
+fn read_versioned<T: AnchorDeserialize>(
+    account: &AccountInfo,
+    discriminator: &[u8],
+    version: u8,
+) -> Result<T> {
+    check_owner_and_discriminator(account, discriminator)?;
+
+    let data = account.try_borrow_data()?;
+    require!(
+        data.get(8) == Some(&version),
+        MigrationErrors::AlreadyMigrated
+    );
+
+    T::deserialize(&mut &data[8..]).map_err(|_| error!(ErrorCode::AccountDidNotDeserialize))
+}
+
+fn write_migrated<T: AccountSerialize>(account: &AccountInfo, migrated: &T) -> Result<()> {
+    let mut data = account.try_borrow_mut_data()?;
+    migrated.try_serialize(&mut &mut data[..])
+}
+
+pub fn migrate_gumball_config(ctx: Context<MigrateGumballConfig>) -> Result<()> {
+    let config_ai = ctx.accounts.gumball_config.to_account_info();
+
+    // version 0 is only recognisable by its size, later versions carry the `version` byte
+    let legacy: GumballConfigV1 = if config_ai.data_len() == 8 + GumballConfigV0::INIT_SPACE {
+        let v0: GumballConfigV0 = read_legacy(
+            &config_ai,
+            GumballConfig::DISCRIMINATOR,
+            8 + GumballConfigV0::INIT_SPACE,
+        )?;
+
+        GumballConfigV1 {
+            version: 1,
+            gumball_owner: v0.gumball_owner,
+            gumball_admin: v0.gumball_admin,
+            creation_fee_lamports: v0.creation_fee_lamports,
+            ticket_fee_bps: v0.ticket_fee_bps,
+            minimum_gumball_period: v0.minimum_gumball_period,
+            maximum_gumball_period: v0.maximum_gumball_period,
+            gumball_count: v0.gumball_count,
+            pause_flags: v0.pause_flags,
+            config_bump: v0.config_bump,
+            pending_owner: None,
+            config_change_delay: MINIMUM_CONFIG_CHANGE_DELAY,
+            pending_config_data: None,
+            reserved: [0u8; 60],
+        }
+    } else {
+        read_versioned(&config_ai, GumballConfig::DISCRIMINATOR, 1)?
+    };
+
+    let migrated = GumballConfig {
+        version: GUMBALL_CONFIG_VERSION,
+        gumball_owner: legacy.gumball_owner,
+        gumball_admin: legacy.gumball_admin,
+        creation_fee_lamports: legacy.creation_fee_lamports,
+        ticket_fee_bps: legacy.ticket_fee_bps,
+        minimum_gumball_period: legacy.minimum_gumball_period,
+        maximum_gumball_period: legacy.maximum_gumball_period,
+        gumball_count: legacy.gumball_count,
+        // the old eight bits keep their indexes, the new ones start unpaused
+        pause_flags: legacy.pause_flags as u64,
+        config_bump: legacy.config_bump,
+        pending_owner: legacy.pending_owner,
+        config_change_delay: legacy.config_change_delay,
+        pending_config_data: legacy.pending_config_data,
+        allowed_mint_extensions: 0,
+        require_verified_collection: false,
+        reserved: [0u8; 51],
+    };
+
+    require_keys_eq!(
+        migrated.gumball_owner,
+        ctx.accounts.gumball_owner.key(),
+        ConfigStateErrors::InvalidGumballOwner
+    );
+
+    resize_account(
+        &config_ai,
+        &ctx.accounts.gumball_owner,
+        &ctx.accounts.system_program,
+        8 + GumballConfig::INIT_SPACE,
+    )?;
+    write_migrated(&config_ai, &migrated)?;
+
+    emit!(AccountMigrated {
+        account: config_ai.key(),
+        version: GUMBALL_CONFIG_VERSION,
+        migrated_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Permissionless: the new layout only adds defaults, the payer just covers the extra rent.
+// The config has to be migrated first, its current fee becomes the machine's snapshot.
+pub fn migrate_gumball(ctx: Context<MigrateGumball>, _gumball_id: u32) -> Result<()> {
+    let gumball_ai = ctx.accounts.gumball.to_account_info();
+
+    let legacy: GumballMachineV0 = read_legacy(
+        &gumball_ai,
+        GumballMachine::DISCRIMINATOR,
+        8 + GumballMachineV0::INIT_SPACE,
+    )?;
+
+    let migrated = GumballMachine {
+        version: GUMBALL_VERSION,
+        gumball_id: legacy.gumball_id,
+        creator: legacy.creator,
+        start_time: legacy.start_time,
+        end_time: legacy.end_time,
+        total_tickets: legacy.total_tickets,
+        tickets_sold: legacy.tickets_sold,
+        prizes_added: legacy.prizes_added,
+        ticket_mint: legacy.ticket_mint,
+        ticket_price: legacy.ticket_price,
+        status: legacy.status,
+        gumball_bump: legacy.gumball_bump,
+        // legacy machines charged whatever the config held, freeze that value now
+        ticket_fee_bps: ctx.accounts.gumball_config.ticket_fee_bps,
+        frozen: false,
+        // legacy escrows always received the full price
+        ticket_revenue: legacy
+            .ticket_price
+            .checked_mul(legacy.tickets_sold as u64)
+            .ok_or(GumballStateErrors::Overflow)?,
+        reserved: [0u8; 117],
+    };
+
+    resize_account(
+        &gumball_ai,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        8 + GumballMachine::INIT_SPACE,
+    )?;
+    write_migrated(&gumball_ai, &migrated)?;
+
+    emit!(AccountMigrated {
+        account: gumball_ai.key(),
+        version: GUMBALL_VERSION,
+        migrated_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateGumballConfig<'info> {
+    /// CHECK: still on the legacy layout, owner and discriminator are checked in the handler
+    #[account(
+        mut,
+        seeds = [b"gumball"],
+        bump
+    )]
+    pub gumball_config: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub gumball_owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(gumball_id: u32)]
+pub struct MigrateGumball<'info> {
+    /// CHECK: still on the legacy layout, owner and discriminator are checked in the handler
+    #[account(
+        mut,
+        seeds = [b"gumball", gumball_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub gumball: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"gumball"],
+        bump = gumball_config.config_bump,
+    )]
+    pub gumball_config: Box<Account<'info, GumballConfig>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
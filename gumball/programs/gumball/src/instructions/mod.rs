@@ -1,9 +1,12 @@
 pub mod activate_gumball;
 pub mod add_prize;
+pub mod allowlist;
 pub mod cancel_gumball;
 pub mod claim_prize_back;
 pub mod create_gumball;
 pub mod end_gumball;
+pub mod freeze_gumball;
+pub mod migrate_accounts;
 pub mod process_gumball_config;
 pub mod spin_gumball;
 pub mod update_gumball;
@@ -12,10 +15,13 @@ pub mod withdraw_spl_fees;
 
 pub use activate_gumball::*;
 pub use add_prize::*;
+pub use allowlist::*;
 pub use cancel_gumball::*;
 pub use claim_prize_back::*;
 pub use create_gumball::*;
 pub use end_gumball::*;
+pub use freeze_gumball::*;
+pub use migrate_accounts::*;
 pub use process_gumball_config::*;
 pub use spin_gumball::*;
 pub use update_gumball::*;
@@ -1,6 +1,69 @@
 use anchor_lang::prelude::*;
-use crate::errors::ConfigStateErrors;
-use crate::states::GumballConfig;
+use crate::constants::{
+    FEE_MANTISSA, GUMBALL_CONFIG_VERSION, MINIMUM_CONFIG_CHANGE_DELAY, MINT_EXTENSION_COUNT, UPDATE_CONFIG_PAUSE,
+};
+use crate::errors::{ConfigStateErrors, GumballStateErrors, MintPolicyErrors};
+use crate::states::{GumballConfig, PendingGumballConfigData};
+use crate::utils::is_paused;
+
+#[event]
+pub struct OwnershipProposed {
+    pub current_owner: Pubkey,
+    pub proposed_owner: Pubkey,
+    pub proposed_at: i64,
+}
+
+#[event]
+pub struct OwnershipTransferred {
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub transferred_at: i64,
+}
+
+#[event]
+pub struct OwnershipProposalCancelled {
+    pub owner: Pubkey,
+    pub cancelled_owner: Pubkey,
+    pub cancelled_at: i64,
+}
+
+#[event]
+pub struct ConfigChangeQueued {
+    pub creation_fee_lamports: u64,
+    pub ticket_fee_bps: u16,
+    pub minimum_gumball_period: u32,
+    pub maximum_gumball_period: u32,
+    pub config_change_delay: u32,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct ConfigChangeApplied {
+    pub creation_fee_lamports: u64,
+    pub ticket_fee_bps: u16,
+    pub minimum_gumball_period: u32,
+    pub maximum_gumball_period: u32,
+    pub config_change_delay: u32,
+    pub applied_at: i64,
+}
+
+#[event]
+pub struct ConfigChangeCancelled {
+    pub effective_at: i64,
+    pub cancelled_at: i64,
+}
+
+#[event]
+pub struct MintExtensionPolicyUpdated {
+    pub allowed_mint_extensions: u8,
+    pub updated_at: i64,
+}
+
+#[event]
+pub struct CollectionPolicyUpdated {
+    pub require_verified_collection: bool,
+    pub updated_at: i64,
+}
 
 pub fn initialize_gumball_config(
     ctx: Context<InitializeGumballConfig>,
@@ -11,6 +74,10 @@ pub fn initialize_gumball_config(
     minimum_gumball_period: u32, // must be > 0
     maximum_gumball_period: u32, // must be > minimum
 ) -> Result<()> {
+    require!(
+        !is_paused(ctx.accounts.gumball_config.pause_flags, UPDATE_CONFIG_PAUSE),
+        GumballStateErrors::FunctionPaused
+    );
     require!(
         minimum_gumball_period > 0 && maximum_gumball_period > minimum_gumball_period,
         ConfigStateErrors::InvalidGumballPeriod
@@ -18,6 +85,7 @@ pub fn initialize_gumball_config(
 
     let cfg = &mut ctx.accounts.gumball_config;
 
+    cfg.version = GUMBALL_CONFIG_VERSION;
     cfg.gumball_owner = gumball_owner;
     cfg.gumball_admin = gumball_admin;
     cfg.creation_fee_lamports = creation_fee_lamports;
@@ -25,15 +93,66 @@ pub fn initialize_gumball_config(
     cfg.minimum_gumball_period = minimum_gumball_period;
     cfg.maximum_gumball_period = maximum_gumball_period;
     cfg.gumball_count = 1; // first gumball id will be 1
+    cfg.config_change_delay = MINIMUM_CONFIG_CHANGE_DELAY;
     cfg.config_bump = ctx.bumps.gumball_config;
 
     Ok(())
 }
 
-pub fn update_gumball_owner(ctx: Context<UpdateGumballConfig>, new_owner: Pubkey) -> Result<()> {
+// Ownership moves in two steps, so a mistyped key can never take over the config
+pub fn propose_owner(ctx: Context<UpdateGumballConfig>, new_owner: Pubkey) -> Result<()> {
     let cfg = &mut ctx.accounts.gumball_config;
 
+    cfg.pending_owner = Some(new_owner);
+
+    emit!(OwnershipProposed {
+        current_owner: cfg.gumball_owner,
+        proposed_owner: new_owner,
+        proposed_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn accept_owner(ctx: Context<AcceptGumballOwner>) -> Result<()> {
+    let cfg = &mut ctx.accounts.gumball_config;
+    let new_owner = ctx.accounts.new_gumball_owner.key();
+
+    let pending_owner = cfg
+        .pending_owner
+        .ok_or(ConfigStateErrors::NoPendingOwner)?;
+    require_keys_eq!(
+        pending_owner,
+        new_owner,
+        ConfigStateErrors::InvalidPendingOwner
+    );
+
+    let previous_owner = cfg.gumball_owner;
     cfg.gumball_owner = new_owner;
+    cfg.pending_owner = None;
+
+    emit!(OwnershipTransferred {
+        previous_owner,
+        new_owner,
+        transferred_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn cancel_owner_proposal(ctx: Context<UpdateGumballConfig>) -> Result<()> {
+    let cfg = &mut ctx.accounts.gumball_config;
+
+    let cancelled_owner = cfg
+        .pending_owner
+        .take()
+        .ok_or(ConfigStateErrors::NoPendingOwner)?;
+
+    emit!(OwnershipProposalCancelled {
+        owner: cfg.gumball_owner,
+        cancelled_owner,
+        cancelled_at: Clock::get()?.unix_timestamp,
+    });
 
     Ok(())
 }
@@ -46,29 +165,115 @@ pub fn update_gumball_admin(ctx: Context<UpdateGumballConfig>, new_admin: Pubkey
     Ok(())
 }
 
-pub fn update_gumball_config_data(
+// Fee and period changes are queued, so creators always get `config_change_delay` of notice
+pub fn queue_gumball_config_data(
     ctx: Context<UpdateGumballConfig>,
     creation_fee_lamports: u64,
     ticket_fee_bps: u16,
     minimum_gumball_period: u32,
     maximum_gumball_period: u32,
+    config_change_delay: u32,
 ) -> Result<()> {
     require!(
         minimum_gumball_period > 0 && maximum_gumball_period > minimum_gumball_period,
         ConfigStateErrors::InvalidGumballPeriod
     );
+    require_gte!(
+        FEE_MANTISSA,
+        ticket_fee_bps,
+        ConfigStateErrors::InvalidTicketFee
+    );
+    require_gte!(
+        config_change_delay,
+        MINIMUM_CONFIG_CHANGE_DELAY,
+        ConfigStateErrors::InvalidConfigChangeDelay
+    );
 
     let cfg = &mut ctx.accounts.gumball_config;
 
-    cfg.creation_fee_lamports = creation_fee_lamports;
-    cfg.ticket_fee_bps = ticket_fee_bps;
-    cfg.minimum_gumball_period = minimum_gumball_period;
-    cfg.maximum_gumball_period = maximum_gumball_period;
+    // a zeroed delay (freshly carved out of `reserved`) still waits the minimum
+    let current_delay = cfg.config_change_delay.max(MINIMUM_CONFIG_CHANGE_DELAY);
+    let effective_at = Clock::get()?
+        .unix_timestamp
+        .checked_add(current_delay as i64)
+        .ok_or(GumballStateErrors::Overflow)?;
+
+    // queuing again replaces the previous change and restarts the delay
+    cfg.pending_config_data = Some(PendingGumballConfigData {
+        creation_fee_lamports,
+        ticket_fee_bps,
+        minimum_gumball_period,
+        maximum_gumball_period,
+        config_change_delay,
+        effective_at,
+    });
+
+    emit!(ConfigChangeQueued {
+        creation_fee_lamports,
+        ticket_fee_bps,
+        minimum_gumball_period,
+        maximum_gumball_period,
+        config_change_delay,
+        effective_at,
+    });
 
     Ok(())
 }
 
-pub fn update_pause_flags(ctx: Context<UpdateGumballConfig>, new_pause_flags: u8) -> Result<()> {
+pub fn apply_gumball_config_data(ctx: Context<UpdateGumballConfig>) -> Result<()> {
+    require!(
+        !is_paused(ctx.accounts.gumball_config.pause_flags, UPDATE_CONFIG_PAUSE),
+        GumballStateErrors::FunctionPaused
+    );
+
+    let cfg = &mut ctx.accounts.gumball_config;
+    let now = Clock::get()?.unix_timestamp;
+
+    let pending = cfg
+        .pending_config_data
+        .ok_or(ConfigStateErrors::NoPendingConfigChange)?;
+    require_gte!(
+        now,
+        pending.effective_at,
+        ConfigStateErrors::ConfigChangeNotReady
+    );
+
+    cfg.creation_fee_lamports = pending.creation_fee_lamports;
+    cfg.ticket_fee_bps = pending.ticket_fee_bps;
+    cfg.minimum_gumball_period = pending.minimum_gumball_period;
+    cfg.maximum_gumball_period = pending.maximum_gumball_period;
+    cfg.config_change_delay = pending.config_change_delay;
+    cfg.pending_config_data = None;
+
+    emit!(ConfigChangeApplied {
+        creation_fee_lamports: pending.creation_fee_lamports,
+        ticket_fee_bps: pending.ticket_fee_bps,
+        minimum_gumball_period: pending.minimum_gumball_period,
+        maximum_gumball_period: pending.maximum_gumball_period,
+        config_change_delay: pending.config_change_delay,
+        applied_at: now,
+    });
+
+    Ok(())
+}
+
+pub fn cancel_gumball_config_data(ctx: Context<UpdateGumballConfig>) -> Result<()> {
+    let cfg = &mut ctx.accounts.gumball_config;
+
+    let pending = cfg
+        .pending_config_data
+        .take()
+        .ok_or(ConfigStateErrors::NoPendingConfigChange)?;
+
+    emit!(ConfigChangeCancelled {
+        effective_at: pending.effective_at,
+        cancelled_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn update_pause_flags(ctx: Context<UpdateGumballConfig>, new_pause_flags: u64) -> Result<()> {
     let cfg = &mut ctx.accounts.gumball_config;
 
     cfg.pause_flags = new_pause_flags;
@@ -76,6 +281,45 @@ pub fn update_pause_flags(ctx: Context<UpdateGumballConfig>, new_pause_flags: u8
     Ok(())
 }
 
+// Only checked when a gumball is created or a prize is added, earlier prizes stay in place
+pub fn set_allowed_mint_extensions(
+    ctx: Context<UpdateGumballConfig>,
+    allowed_mint_extensions: u8,
+) -> Result<()> {
+    require!(
+        allowed_mint_extensions >> MINT_EXTENSION_COUNT == 0,
+        MintPolicyErrors::InvalidMintExtension
+    );
+
+    let cfg = &mut ctx.accounts.gumball_config;
+
+    cfg.allowed_mint_extensions = allowed_mint_extensions;
+
+    emit!(MintExtensionPolicyUpdated {
+        allowed_mint_extensions,
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Only checked for prizes added from now on, prizes already added stay in place
+pub fn set_collection_policy(
+    ctx: Context<UpdateGumballConfig>,
+    require_verified_collection: bool,
+) -> Result<()> {
+    let cfg = &mut ctx.accounts.gumball_config;
+
+    cfg.require_verified_collection = require_verified_collection;
+
+    emit!(CollectionPolicyUpdated {
+        require_verified_collection,
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
 #[derive(Accounts)]
 pub struct InitializeGumballConfig<'info> {
     #[account(
@@ -106,3 +350,15 @@ pub struct UpdateGumballConfig<'info> {
     #[account(mut)]
     pub gumball_owner: Signer<'info>,
 }
+
+#[derive(Accounts)]
+pub struct AcceptGumballOwner<'info> {
+    #[account(
+        mut,
+        seeds = [b"gumball"],
+        bump = gumball_config.config_bump,
+    )]
+    pub gumball_config: Box<Account<'info, GumballConfig>>,
+
+    pub new_gumball_owner: Signer<'info>,
+}
@@ -1,12 +1,12 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{close_account, CloseAccount};
-use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use anchor_spl::metadata::mpl_token_metadata;
+use anchor_spl::token_interface::{close_account, CloseAccount, Mint, TokenAccount, TokenInterface};
 use crate::constants::*;
-use crate::errors::{ConfigStateErrors, GumballStateErrors, KeysMismatchErrors};
+use crate::errors::{GumballStateErrors, KeysMismatchErrors, PermitErrors};
 use crate::helpers::*;
 use crate::states::*; 
-use crate::utils::is_paused;
+use crate::utils::{is_paused, verify_permit};
 
 #[event]
 pub struct PrizeSpinned {
@@ -18,7 +18,12 @@ pub struct PrizeSpinned {
     pub spun_at: i64,
 }
 
-pub fn spin_gumball(ctx: Context<SpinGumball>, gumball_id: u32, prize_index: u16) -> Result<()> {
+pub fn spin_gumball<'info>(
+    ctx: Context<'_, '_, '_, 'info, SpinGumball<'info>>,
+    gumball_id: u32,
+    prize_index: u16,
+    permit: Permit,
+) -> Result<()> {
     // Pause check
     require!(
         !is_paused(ctx.accounts.gumball_config.pause_flags, SPIN_GUMBALL_PAUSE),
@@ -43,6 +48,28 @@ pub fn spin_gumball(ctx: Context<SpinGumball>, gumball_id: u32, prize_index: u16
     // Time window check
     require_gte!(gumball.end_time, now, GumballStateErrors::EndTimeIsReached);
 
+    // The admin signs the spin off-chain instead of co-signing the transaction, the prize it drew included
+    let permit_signer = verify_permit(
+        &ctx.accounts.instructions_sysvar,
+        gumball_id,
+        prize_index,
+        ctx.accounts.spinner.key(),
+        gumball.ticket_price,
+        &permit,
+    )?;
+    require_keys_eq!(
+        permit_signer,
+        ctx.accounts.gumball_config.gumball_admin,
+        PermitErrors::InvalidPermitSigner
+    );
+
+    // Spend the nonce, `init` already rejected a replay
+    let permit_nonce = &mut ctx.accounts.permit_nonce;
+    permit_nonce.user = ctx.accounts.spinner.key();
+    permit_nonce.nonce = permit.nonce;
+    permit_nonce.used_at = now;
+    permit_nonce.nonce_bump = ctx.bumps.permit_nonce;
+
     // Ensure there are prizes available relative to sold tickets
     require_gt!(
         gumball.prizes_added,
@@ -91,20 +118,46 @@ pub fn spin_gumball(ctx: Context<SpinGumball>, gumball_id: u32, prize_index: u16
     let signer_seeds: &[&[&[u8]]] = &[seeds];
 
     // Perform token transfer: prize_escrow (owned by gumball PDA) -> spinner_ata
-    transfer_tokens_with_seeds(
-        &ctx.accounts.prize_escrow,
-        &ctx.accounts.spinner_prize_ata,
-        &gumball_ai, // authority is gumball PDA
-        &ctx.accounts.prize_token_program,
-        &ctx.accounts.prize_mint,
-        signer_seeds,
-        transfer_amount,
-    )?;
+    if is_programmable_nft(&ctx.accounts.prize_metadata)? {
+        transfer_programmable_nft(
+            &ctx.accounts.prize_escrow,
+            &gumball_ai, // authority is gumball PDA,
+            &ctx.accounts.spinner_prize_ata,
+            &ctx.accounts.spinner,
+            &ctx.accounts.prize_mint,
+            &ctx.accounts.spinner,
+            &ctx.accounts.prize_token_program,
+            &ProgrammableNftAccounts {
+                metadata: &ctx.accounts.prize_metadata,
+                edition: &ctx.accounts.prize_edition,
+                token_record: &ctx.accounts.escrow_token_record,
+                destination_token_record: &ctx.accounts.spinner_token_record,
+                authorization_rules: &ctx.accounts.authorization_rules,
+                authorization_rules_program: &ctx.accounts.authorization_rules_program,
+                token_metadata_program: &ctx.accounts.token_metadata_program,
+                sysvar_instructions: &ctx.accounts.instructions_sysvar,
+                associated_token_program: &ctx.accounts.associated_token_program,
+                system_program: &ctx.accounts.system_program,
+            },
+            signer_seeds,
+        )?;
+    } else {
+        transfer_tokens_with_seeds(
+            &ctx.accounts.prize_escrow,
+            &ctx.accounts.spinner_prize_ata,
+            &gumball_ai, // authority is gumball PDA
+            &ctx.accounts.prize_token_program,
+            &ctx.accounts.prize_mint,
+            ctx.remaining_accounts,
+            signer_seeds,
+            transfer_amount,
+        )?;
+    }
 
     let ticket_price = gumball.ticket_price;
 
     // collect ticket prize and send to escrow if SPL , or it ticket mint is None then transfer sol to gumball struct directely for singel ticket
-    match gumball.ticket_mint {
+    let received = match gumball.ticket_mint {
         None => {
             // SOL ticket
             transfer_sol(
@@ -113,6 +166,8 @@ pub fn spin_gumball(ctx: Context<SpinGumball>, gumball_id: u32, prize_index: u16
                 &ctx.accounts.system_program,
                 ticket_price,
             )?;
+
+            ticket_price
         }
 
         Some(stored_mint) => {
@@ -145,10 +200,17 @@ pub fn spin_gumball(ctx: Context<SpinGumball>, gumball_id: u32, prize_index: u16
                 &ctx.accounts.spinner,
                 &ctx.accounts.ticket_token_program,
                 &ctx.accounts.ticket_mint,
+                ctx.remaining_accounts,
                 ticket_price,
-            )?;
+            )?
         }
-    }
+    };
+
+    // revenue is settled from what the escrow holds, not from `ticket_price`
+    gumball.ticket_revenue = gumball
+        .ticket_revenue
+        .checked_add(received)
+        .ok_or(GumballStateErrors::Overflow)?;
 
     if prize_acc.quantity == 0 {
         let cpi_ctx = CpiContext::new_with_signer(
@@ -177,12 +239,11 @@ pub fn spin_gumball(ctx: Context<SpinGumball>, gumball_id: u32, prize_index: u16
 }
 
 #[derive(Accounts)]
-#[instruction(gumball_id: u32, prize_index: u16)]
+#[instruction(gumball_id: u32, prize_index: u16, permit: Permit)]
 pub struct SpinGumball<'info> {
     #[account(
         seeds = [b"gumball"],
         bump = gumball_config.config_bump,
-        constraint = gumball_config.gumball_admin == gumball_admin.key() @ ConfigStateErrors::InvalidGumballAdmin
     )]
     pub gumball_config: Box<Account<'info, GumballConfig>>,
 
@@ -190,7 +251,8 @@ pub struct SpinGumball<'info> {
         mut,
         seeds = [b"gumball", gumball_id.to_le_bytes().as_ref()],
         bump = gumball.gumball_bump,
-        constraint = gumball.gumball_id == gumball_id @ GumballStateErrors::InvalidGumballId
+        constraint = gumball.gumball_id == gumball_id @ GumballStateErrors::InvalidGumballId,
+        constraint = !gumball.frozen @ GumballStateErrors::GumballFrozen,
     )]
     pub gumball: Box<Account<'info, GumballMachine>>,
 
@@ -207,7 +269,23 @@ pub struct SpinGumball<'info> {
     #[account(mut)]
     pub spinner: Signer<'info>,
 
-    pub gumball_admin: Signer<'info>,
+    #[account(
+        init,
+        payer = spinner,
+        space = 8 + PermitNonce::INIT_SPACE,
+        seeds = [
+            b"gumball".as_ref(),
+            b"permit".as_ref(),
+            spinner.key().as_ref(),
+            permit.nonce.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub permit_nonce: Box<Account<'info, PermitNonce>>,
+
+    /// CHECK: address is pinned to the instructions sysvar, read in `verify_permit`
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
 
     pub prize_mint: InterfaceAccount<'info, Mint>,
 
@@ -240,6 +318,73 @@ pub struct SpinGumball<'info> {
     pub prize_token_program: Interface<'info, TokenInterface>,
     pub ticket_token_program: Interface<'info, TokenInterface>,
 
+    /// CHECK: Token Metadata PDA of the prize mint, read to pick the pNFT transfer path
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub prize_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: master edition PDA of the prize mint, only used by pNFT transfers
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub prize_edition: UncheckedAccount<'info>,
+
+    /// CHECK: pNFT token record of `prize_escrow`, only used by pNFT transfers
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"token_record".as_ref(),
+            prize_escrow.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub escrow_token_record: UncheckedAccount<'info>,
+
+    /// CHECK: pNFT token record of `spinner_prize_ata`, only used by pNFT transfers
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"token_record".as_ref(),
+            spinner_prize_ata.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub spinner_token_record: UncheckedAccount<'info>,
+
+    /// CHECK: only forwarded when the pNFT has a rule set, checked in `transfer_programmable_nft`
+    pub authorization_rules: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to Metaplex Token Auth Rules
+    #[account(address = AUTH_RULES_PROGRAM_ID)]
+    pub authorization_rules_program: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to Token Metadata, only invoked for pNFT prizes
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
     /// system Programs
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
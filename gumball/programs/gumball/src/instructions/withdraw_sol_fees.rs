@@ -1,5 +1,7 @@
-use crate::errors::{ConfigStateErrors, TransferErrors};
+use crate::constants::WITHDRAW_FEES_PAUSE;
+use crate::errors::{ConfigStateErrors, GumballStateErrors, TransferErrors};
 use crate::states::GumballConfig;
+use crate::utils::is_paused;
 use anchor_lang::prelude::*;
 use anchor_lang::system_program::System;
 
@@ -10,6 +12,11 @@ pub struct FeesWithdrawn {
 }
 
 pub fn withdraw_sol_fees(ctx: Context<WithdrawSolFees>, amount: u64) -> Result<()> {
+    require!(
+        !is_paused(ctx.accounts.gumball_config.pause_flags, WITHDRAW_FEES_PAUSE),
+        GumballStateErrors::FunctionPaused
+    );
+
     let config = &ctx.accounts.gumball_config;
     let pda_ai = config.to_account_info();
 
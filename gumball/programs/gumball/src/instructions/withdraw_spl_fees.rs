@@ -1,8 +1,10 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
-use crate::errors::ConfigStateErrors;
+use crate::constants::WITHDRAW_FEES_PAUSE;
+use crate::errors::{ConfigStateErrors, GumballStateErrors};
 use crate::helpers::transfer_tokens_with_seeds;
 use crate::states::GumballConfig;
+use crate::utils::is_paused;
 
 #[event]
 pub struct SplFeesWithdrawn {
@@ -12,7 +14,15 @@ pub struct SplFeesWithdrawn {
 }
 
 // Withdraw accumulated SPL fees from the treasury ATA, Only the gumball owner can withdraw
-pub fn withdraw_spl_fees(ctx: Context<WithdrawSplFees>, amount: u64) -> Result<()> {
+pub fn withdraw_spl_fees<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawSplFees<'info>>,
+    amount: u64,
+) -> Result<()> {
+    require!(
+        !is_paused(ctx.accounts.gumball_config.pause_flags, WITHDRAW_FEES_PAUSE),
+        GumballStateErrors::FunctionPaused
+    );
+
     let config = &ctx.accounts.gumball_config;
 
     let signer_seeds: &[&[&[u8]]] = &[&[b"gumball", &[config.config_bump]]];
@@ -23,6 +33,7 @@ pub fn withdraw_spl_fees(ctx: Context<WithdrawSplFees>, amount: u64) -> Result<(
         &ctx.accounts.gumball_config.to_account_info(),
         &ctx.accounts.token_program,
         &ctx.accounts.fee_mint,
+        ctx.remaining_accounts,
         signer_seeds,
         amount,
     )?;
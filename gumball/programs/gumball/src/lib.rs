@@ -5,7 +5,7 @@ pub mod instructions;
 pub mod states;
 pub mod utils;
 
-use {anchor_lang::prelude::*, instructions::*};
+use {anchor_lang::prelude::*, instructions::*, states::Permit};
 
 declare_id!("6WjtxBErVmFVqndkV3J79rQ7qgBtwxcdSbBjMKHfUrGE");
 
@@ -33,11 +33,16 @@ pub mod gumball {
         )
     }
 
-    pub fn update_gumball_owner(
-        ctx: Context<UpdateGumballConfig>,
-        new_owner: Pubkey,
-    ) -> Result<()> {
-        process_gumball_config::update_gumball_owner(ctx, new_owner)
+    pub fn propose_owner(ctx: Context<UpdateGumballConfig>, new_owner: Pubkey) -> Result<()> {
+        process_gumball_config::propose_owner(ctx, new_owner)
+    }
+
+    pub fn accept_owner(ctx: Context<AcceptGumballOwner>) -> Result<()> {
+        process_gumball_config::accept_owner(ctx)
+    }
+
+    pub fn cancel_owner_proposal(ctx: Context<UpdateGumballConfig>) -> Result<()> {
+        process_gumball_config::cancel_owner_proposal(ctx)
     }
 
     pub fn update_gumball_admin(
@@ -49,27 +54,76 @@ pub mod gumball {
 
     pub fn update_pause_flags(
         ctx: Context<UpdateGumballConfig>,
-        new_pause_flags: u8,
+        new_pause_flags: u64,
     ) -> Result<()> {
         process_gumball_config::update_pause_flags(ctx, new_pause_flags)
     }
 
-    pub fn update_gumball_config_data(
+    pub fn set_allowed_mint_extensions(
+        ctx: Context<UpdateGumballConfig>,
+        allowed_mint_extensions: u8,
+    ) -> Result<()> {
+        process_gumball_config::set_allowed_mint_extensions(ctx, allowed_mint_extensions)
+    }
+
+    pub fn allow_mint(ctx: Context<AllowMint>) -> Result<()> {
+        allowlist::allow_mint(ctx)
+    }
+
+    pub fn remove_allowed_mint(ctx: Context<RemoveAllowedMint>) -> Result<()> {
+        allowlist::remove_allowed_mint(ctx)
+    }
+
+    pub fn set_collection_policy(
+        ctx: Context<UpdateGumballConfig>,
+        require_verified_collection: bool,
+    ) -> Result<()> {
+        process_gumball_config::set_collection_policy(ctx, require_verified_collection)
+    }
+
+    pub fn allow_collection(ctx: Context<AllowCollection>) -> Result<()> {
+        allowlist::allow_collection(ctx)
+    }
+
+    pub fn remove_allowed_collection(ctx: Context<RemoveAllowedCollection>) -> Result<()> {
+        allowlist::remove_allowed_collection(ctx)
+    }
+
+    pub fn queue_gumball_config_data(
         ctx: Context<UpdateGumballConfig>,
         creation_fee_lamports: u64,
         ticket_fee_bps: u16,
         minimum_gumball_period: u32,
         maximum_gumball_period: u32,
+        config_change_delay: u32,
     ) -> Result<()> {
-        process_gumball_config::update_gumball_config_data(
+        process_gumball_config::queue_gumball_config_data(
             ctx,
             creation_fee_lamports,
             ticket_fee_bps,
             minimum_gumball_period,
             maximum_gumball_period,
+            config_change_delay,
         )
     }
 
+    pub fn apply_gumball_config_data(ctx: Context<UpdateGumballConfig>) -> Result<()> {
+        process_gumball_config::apply_gumball_config_data(ctx)
+    }
+
+    pub fn cancel_gumball_config_data(ctx: Context<UpdateGumballConfig>) -> Result<()> {
+        process_gumball_config::cancel_gumball_config_data(ctx)
+    }
+
+    // Migration
+    pub fn migrate_gumball_config(ctx: Context<MigrateGumballConfig>) -> Result<()> {
+        migrate_accounts::migrate_gumball_config(ctx)
+    }
+
+    pub fn migrate_gumball(ctx: Context<MigrateGumball>, gumball_id: u32) -> Result<()> {
+        migrate_accounts::migrate_gumball(ctx, gumball_id)
+    }
+
     pub fn create_gumball(
         ctx: Context<CreateGumball>,
         start_time: i64,
@@ -94,8 +148,8 @@ pub mod gumball {
         activate_gumball::activate_gumball(ctx, gumball_id)
     }
 
-    pub fn add_prize(
-        ctx: Context<AddPrize>,
+    pub fn add_prize<'info>(
+        ctx: Context<'_, '_, '_, 'info, AddPrize<'info>>,
         gumball_id: u32,
         prize_index: u16,
         prize_amount: u64,
@@ -108,10 +162,21 @@ pub mod gumball {
         cancel_gumball::cancel_gumball(ctx, gumball_id)
     }
 
-    pub fn end_gumball(ctx: Context<EndGumball>, gumball_id: u32) -> Result<()> {
+    pub fn end_gumball<'info>(
+        ctx: Context<'_, '_, '_, 'info, EndGumball<'info>>,
+        gumball_id: u32,
+    ) -> Result<()> {
         end_gumball::end_gumball(ctx, gumball_id)
     }
 
+    pub fn set_gumball_frozen(
+        ctx: Context<SetGumballFrozen>,
+        gumball_id: u32,
+        frozen: bool,
+    ) -> Result<()> {
+        freeze_gumball::set_gumball_frozen(ctx, gumball_id, frozen)
+    }
+
     pub fn update_gumball_time(
         ctx: Context<UpdateGumball>,
         gumball_id: u32,
@@ -137,16 +202,17 @@ pub mod gumball {
         update_gumball::update_gumball_data(ctx, gumball_id, new_ticket_price, new_total_tickets)
     }
 
-    pub fn spin_gumball(
-        ctx: Context<SpinGumball>,
+    pub fn spin_gumball<'info>(
+        ctx: Context<'_, '_, '_, 'info, SpinGumball<'info>>,
         gumball_id: u32,
         prize_index: u16,
+        permit: Permit,
     ) -> Result<()> {
-        spin_gumball::spin_gumball(ctx, gumball_id, prize_index)
+        spin_gumball::spin_gumball(ctx, gumball_id, prize_index, permit)
     }
 
-    pub fn claim_prize_back(
-        ctx: Context<ClaimPrizeBack>,
+    pub fn claim_prize_back<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimPrizeBack<'info>>,
         gumball_id: u32,
         prize_index: u16,
     ) -> Result<()> {
@@ -157,7 +223,10 @@ pub mod gumball {
         withdraw_sol_fees::withdraw_sol_fees(ctx, amount)
     }
 
-    pub fn withdraw_spl_fees(ctx: Context<WithdrawSplFees>, amount: u64) -> Result<()> {
+    pub fn withdraw_spl_fees<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawSplFees<'info>>,
+        amount: u64,
+    ) -> Result<()> {
         withdraw_spl_fees::withdraw_spl_fees(ctx, amount)
     }
 }
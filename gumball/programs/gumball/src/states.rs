@@ -3,6 +3,7 @@ use anchor_lang::prelude::*;
 #[account] // seed = "gumball"
 #[derive(InitSpace)]
 pub struct GumballConfig {
+    pub version: u8, // account layout version, bumped whenever the layout changes
     pub gumball_owner: Pubkey,
     pub gumball_admin: Pubkey,
     pub creation_fee_lamports: u64, // while creating the gumball the user have to pay fees Native SOL
@@ -10,13 +11,30 @@ pub struct GumballConfig {
     pub minimum_gumball_period: u32, // minimum period the gumball should be active
     pub maximum_gumball_period: u32, // maximum period the gumball can be set
     pub gumball_count: u32,  // use the latest gumball count for seed, start from `1`
-    pub pause_flags: u8,     // pause the function using bit masking
+    pub pause_flags: u64,    // pause the function using bit masking, see the `*_PAUSE` indexes
     pub config_bump: u8,
+    pub pending_owner: Option<Pubkey>, // proposed owner, only takes over once it calls `accept_owner`
+    pub config_change_delay: u32, // seconds a queued fee/period change waits, never below `MINIMUM_CONFIG_CHANGE_DELAY`
+    pub pending_config_data: Option<PendingGumballConfigData>, // queued change, applied only after its `effective_at`
+    pub allowed_mint_extensions: u8, // risky Token-2022 extensions accepted on any mint, see the `*_EXTENSION` indexes
+    pub require_verified_collection: bool, // NFT prizes must belong to a verified, allowlisted collection
+    pub reserved: [u8; 51], // zeroed space for future fields, new fields must decode `0` as their default
+}
+
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct PendingGumballConfigData {
+    pub creation_fee_lamports: u64,
+    pub ticket_fee_bps: u16,
+    pub minimum_gumball_period: u32,
+    pub maximum_gumball_period: u32,
+    pub config_change_delay: u32,
+    pub effective_at: i64, // unix timestamp from which the change can be applied
 }
 
 #[account] // seed = "gumball" + `gumball_id`
 #[derive(InitSpace)]
 pub struct GumballMachine {
+    pub version: u8, // layout version, see `GUMBALL_VERSION`
     pub gumball_id: u32,
     pub creator: Pubkey,
     pub start_time: i64,
@@ -28,6 +46,10 @@ pub struct GumballMachine {
     pub ticket_price: u64,
     pub status: GumballState,
     pub gumball_bump: u8,
+    pub ticket_fee_bps: u16, // config fee in effect at `create_gumball`, later config changes never reach a live gumball
+    pub frozen: bool, // halts this gumball alone, every instruction on it fails until it is unfrozen
+    pub ticket_revenue: u64, // ticket payments that actually reached the escrow, net of any Token-2022 transfer fee
+    pub reserved: [u8; 117], // same rules as `GumballConfig::reserved`
 }
 
 #[account] // seeds = "gumball" + `gumball_id` + `prize_index`
@@ -42,6 +64,39 @@ pub struct Prize {
     pub quantity: u16,
 }
 
+#[account] // seeds = "gumball" + "permit" + `user address` + `nonce`
+#[derive(InitSpace)]
+pub struct PermitNonce {
+    pub user: Pubkey,
+    pub nonce: u64,
+    pub used_at: i64, // existing at all marks the nonce as spent, so a permit can never be replayed
+    pub nonce_bump: u8,
+}
+
+#[account] // seeds = "gumball" + "mint_allowlist" + `mint address`
+#[derive(InitSpace)]
+pub struct MintAllowlist {
+    pub mint: Pubkey,
+    pub allowed_at: i64, // existing at all means the owner vetted the mint, it skips the extension policy
+    pub allowlist_bump: u8,
+}
+
+#[account] // seeds = "gumball" + "collection_allowlist" + `collection mint address`
+#[derive(InitSpace)]
+pub struct CollectionAllowlist {
+    pub collection: Pubkey,
+    pub allowed_at: i64, // only read when `require_verified_collection` is set
+    pub allowlist_bump: u8,
+}
+
+// Signed off-chain by the gumball admin over `utils::permit_message`, verified via the Ed25519 program
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Permit {
+    pub max_amount: u64, // upper bound on the ticket price paid with this permit
+    pub expiry: i64,     // unix timestamp after which the permit is rejected
+    pub nonce: u64,      // unique per user, spent through `PermitNonce`
+}
+
 #[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(u8)]
 pub enum GumballState {
@@ -52,3 +107,52 @@ pub enum GumballState {
     CompletedSuccessfully = 4,
     CompletedFailed = 5,
 }
+
+// Legacy layouts, as deployed before the `version` byte and reserved space were added.
+// Only read by the `migrate_*` instructions, never written.
+#[derive(InitSpace, AnchorDeserialize)]
+pub struct GumballConfigV0 {
+    pub gumball_owner: Pubkey,
+    pub gumball_admin: Pubkey,
+    pub creation_fee_lamports: u64,
+    pub ticket_fee_bps: u16,
+    pub minimum_gumball_period: u32,
+    pub maximum_gumball_period: u32,
+    pub gumball_count: u32,
+    pub pause_flags: u8,
+    pub config_bump: u8,
+}
+
+// Version 1 config, before `pause_flags` was widened to u64. Same total size as version 2.
+#[derive(InitSpace, AnchorDeserialize)]
+pub struct GumballConfigV1 {
+    pub version: u8,
+    pub gumball_owner: Pubkey,
+    pub gumball_admin: Pubkey,
+    pub creation_fee_lamports: u64,
+    pub ticket_fee_bps: u16,
+    pub minimum_gumball_period: u32,
+    pub maximum_gumball_period: u32,
+    pub gumball_count: u32,
+    pub pause_flags: u8,
+    pub config_bump: u8,
+    pub pending_owner: Option<Pubkey>,
+    pub config_change_delay: u32,
+    pub pending_config_data: Option<PendingGumballConfigData>,
+    pub reserved: [u8; 60],
+}
+
+#[derive(InitSpace, AnchorDeserialize)]
+pub struct GumballMachineV0 {
+    pub gumball_id: u32,
+    pub creator: Pubkey,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub total_tickets: u16,
+    pub tickets_sold: u16,
+    pub prizes_added: u16,
+    pub ticket_mint: Option<Pubkey>,
+    pub ticket_price: u64,
+    pub status: GumballState,
+    pub gumball_bump: u8,
+}
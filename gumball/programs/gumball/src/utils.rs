@@ -1,6 +1,9 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::Mint;
-use crate::errors::GumballStateErrors;
+use solana_instructions_sysvar::{load_current_index_checked, load_instruction_at_checked};
+use crate::constants::PERMIT_MESSAGE_LEN;
+use crate::errors::{GumballStateErrors, PermitErrors};
+use crate::states::Permit;
 
 pub fn get_pct_amount(amount: u64, pct: u64, base: u64) -> Result<u64> {
     let mul = amount
@@ -15,7 +18,86 @@ pub fn validate_nft(mint: &InterfaceAccount<Mint>) -> bool {
 }
 
 // weather the fucntion is paused or not
-pub fn is_paused(pause_flags: u8, index: u8) -> bool {
-    let mask = 1u8 << index; // set the bit at `index`
+pub fn is_paused(pause_flags: u64, index: u8) -> bool {
+    let mask = 1u64 << index; // set the bit at `index`
     (pause_flags & mask) != 0 // check if it's active
 }
+
+// Bytes the gumball admin signs off-chain to let `user` spin prize `prize_index` of gumball `gumball_id`
+pub fn permit_message(gumball_id: u32, prize_index: u16, user: Pubkey, permit: &Permit) -> Vec<u8> {
+    let mut message = Vec::with_capacity(PERMIT_MESSAGE_LEN);
+    message.extend_from_slice(crate::ID.as_ref());
+    message.extend_from_slice(&gumball_id.to_le_bytes());
+    message.extend_from_slice(&prize_index.to_le_bytes());
+    message.extend_from_slice(user.as_ref());
+    message.extend_from_slice(&permit.max_amount.to_le_bytes());
+    message.extend_from_slice(&permit.expiry.to_le_bytes());
+    message.extend_from_slice(&permit.nonce.to_le_bytes());
+    message
+}
+
+// Check the permit against the Ed25519 instruction placed right before the current one
+// and return the key that signed it. The Ed25519 program has already verified the signature
+// by the time we run, so we only need to bind its key and message to this spin.
+pub fn verify_permit(
+    instructions_sysvar: &AccountInfo,
+    gumball_id: u32,
+    prize_index: u16,
+    user: Pubkey,
+    amount: u64,
+    permit: &Permit,
+) -> Result<Pubkey> {
+    require_gte!(
+        permit.expiry,
+        Clock::get()?.unix_timestamp,
+        PermitErrors::PermitExpired
+    );
+    require_gte!(
+        permit.max_amount,
+        amount,
+        PermitErrors::PermitAmountExceeded
+    );
+
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require_gt!(current_index, 0, PermitErrors::MissingPermitInstruction);
+    let ed25519_ix = load_instruction_at_checked(current_index as usize - 1, instructions_sysvar)?;
+    require_keys_eq!(
+        ed25519_ix.program_id,
+        solana_sdk_ids::ed25519_program::ID,
+        PermitErrors::MissingPermitInstruction
+    );
+
+    // layout: [num signatures, padding, offsets (7 x u16), ...inline data]
+    let data = &ed25519_ix.data;
+    require!(
+        data.len() >= 16 && data[0] == 1,
+        PermitErrors::InvalidPermitInstruction
+    );
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]) as usize;
+
+    // signature, key and message must all live in the Ed25519 instruction itself
+    require!(
+        read_u16(4) == u16::MAX as usize
+            && read_u16(8) == u16::MAX as usize
+            && read_u16(14) == u16::MAX as usize,
+        PermitErrors::InvalidPermitInstruction
+    );
+
+    let public_key_offset = read_u16(6);
+    let message_offset = read_u16(10);
+    let message_size = read_u16(12);
+
+    let signer = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(PermitErrors::InvalidPermitInstruction)?;
+    let message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(PermitErrors::InvalidPermitInstruction)?;
+
+    require!(
+        message == permit_message(gumball_id, prize_index, user, permit).as_slice(),
+        PermitErrors::PermitMessageMismatch
+    );
+
+    Pubkey::try_from(signer).map_err(|_| error!(PermitErrors::InvalidPermitInstruction))
+}
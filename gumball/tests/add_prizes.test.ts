@@ -7,6 +7,10 @@ import {
     createSplMint,
     createNftMint,
     createAta,
+    createTransferFeeMint,
+    createPermanentDelegateMint,
+    allowMint,
+    removeAllowedMint,
     initializeGumballConfig,
     createGumball,
     activateGumball,
@@ -19,6 +23,7 @@ import {
     warpForward,
     claimPrizeBack,
     spinGumball,
+    signPermit,
     endGumball,
 } from "./helpers";
 
@@ -36,10 +41,12 @@ import {
     minimum_Gumball_period,
     maximum_Gumball_period,
     gumballPrizePda,
+    getProgram,
 } from "./values";
 
 import { Gumball } from "../target/types/gumball";
 import { PublicKey } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
 import { generateKeyPair } from "crypto";
 
 describe("Add prizes", () => {
@@ -193,6 +200,60 @@ describe("Add prizes", () => {
         console.log(label, "creator:", c, "escrow:", e);
     }
 
+    it("grosses up a transfer-fee prize so the escrow holds every payout", async () => {
+        const feeMint = await createTransferFeeMint(100, BigInt(1_000_000_000)); // 1%
+        const creatorPrizeAta = await createAta(feeMint, gumball_2_creator.publicKey, TOKEN_2022_PROGRAM_ID);
+        const prizeEscrow = await createAta(feeMint, gumballB_pda, TOKEN_2022_PROGRAM_ID);
+        await mintTokens(feeMint, creatorPrizeAta, 100_000_000_000, TOKEN_2022_PROGRAM_ID);
+
+        const prizeAmount = 1_000_000_000;
+        const quantity = 2;
+        const creatorBefore = await getTokenBalance(creatorPrizeAta);
+
+        await addPrize(
+            gumballB_pda, gumballB_id, 0, prizeAmount, quantity,
+            gumball_2_creator, gumball_admin, feeMint, prizeEscrow, creatorPrizeAta,
+            TOKEN_2022_PROGRAM_ID
+        );
+
+        // the fee is withheld on top, the escrow keeps exactly what the spins will pay out
+        assert.strictEqual(await getTokenBalance(prizeEscrow), prizeAmount * quantity);
+        assert.ok(creatorBefore - (await getTokenBalance(creatorPrizeAta)) > prizeAmount * quantity);
+
+        const prize = await program.account.prize.fetch(gumballPrizePda(gumballB_id, 0));
+        assert.strictEqual(prize.totalAmount.toNumber(), prizeAmount * quantity);
+    });
+
+    it("checks every add_prize call, top-ups included, against the mint allowlist", async () => {
+        const mint = await createPermanentDelegateMint(anchor.web3.Keypair.generate().publicKey);
+        const creatorPrizeAta = await createAta(mint, gumball_2_creator.publicKey, TOKEN_2022_PROGRAM_ID);
+        const prizeEscrow = await createAta(mint, gumballB_pda, TOKEN_2022_PROGRAM_ID);
+        await mintTokens(mint, creatorPrizeAta, 10_000_000_000, TOKEN_2022_PROGRAM_ID);
+
+        const add = (prizeIndex: number) =>
+            addPrize(
+                gumballB_pda, gumballB_id, prizeIndex, 1_000_000_000, 1,
+                gumball_2_creator, gumball_admin, mint, prizeEscrow, creatorPrizeAta,
+                TOKEN_2022_PROGRAM_ID
+            );
+
+        await assert.rejects(add(1), /PermanentDelegateNotAllowed/);
+
+        await assert.rejects(allowMint(gumball_2_creator, mint), /InvalidGumballOwner/);
+        await allowMint(gumball_owner, mint);
+        await add(1);
+
+        // topping up the same prize is a new add, so it is checked again
+        await removeAllowedMint(gumball_owner, mint);
+        await assert.rejects(add(1), /PermanentDelegateNotAllowed/);
+
+        // the prize already in the machine stays
+        const prize = await program.account.prize.fetch(gumballPrizePda(gumballB_id, 1));
+        assert.strictEqual(prize.mint.toString(), mint.toString());
+        assert.strictEqual(prize.totalAmount.toNumber(), 1_000_000_000);
+        assert.strictEqual(await getTokenBalance(prizeEscrow), 1_000_000_000);
+    });
+
     it("adds multiple SPL prizes + spins + end game", async () => {
 
         const spl1 = await createSplMint();
@@ -256,9 +317,47 @@ describe("Add prizes", () => {
             spl1, esc1, spinner_1_ata,
             spl1, spinner_1_ata, spinner_1_ata);
 
+        // second spin with a permit signed up front, so it can be replayed below
+        const gumballA = await getProgram().account.gumballMachine.fetch(gumballA_pda);
+        const ticketPrice = gumballA.ticketPrice.toNumber();
+        const signed = await signPermit(getProgram(), gumballA_id, 2, spinner1.publicKey, ticketPrice, gumball_admin);
         await spinGumball(gumballA_pda, gumballA_id, 2, spinner1, gumball_admin,
             spl1, esc1, spinner_1_ata,
-            spl1, spinner_1_ata, spinner_1_ata);
+            spl1, spinner_1_ata, spinner_1_ata, signed);
+
+        // a spent permit cannot be replayed
+        await assert.rejects(
+            spinGumball(gumballA_pda, gumballA_id, 2, spinner1, gumball_admin,
+                spl1, esc1, spinner_1_ata,
+                spl1, spinner_1_ata, spinner_1_ata, signed)
+        );
+
+        // only the gumball admin can issue permits
+        const forged = await signPermit(getProgram(), gumballA_id, 2, spinner1.publicKey, ticketPrice, anchor.web3.Keypair.generate());
+        await assert.rejects(
+            spinGumball(gumballA_pda, gumballA_id, 2, spinner1, gumball_admin,
+                spl1, esc1, spinner_1_ata,
+                spl1, spinner_1_ata, spinner_1_ata, forged),
+            /InvalidPermitSigner/
+        );
+
+        // nor cover less than the ticket price
+        const underpriced = await signPermit(getProgram(), gumballA_id, 2, spinner1.publicKey, ticketPrice - 1, gumball_admin);
+        await assert.rejects(
+            spinGumball(gumballA_pda, gumballA_id, 2, spinner1, gumball_admin,
+                spl1, esc1, spinner_1_ata,
+                spl1, spinner_1_ata, spinner_1_ata, underpriced),
+            /PermitAmountExceeded/
+        );
+
+        // the admin draws the prize, a permit for one index cannot spin another
+        const otherPrize = await signPermit(getProgram(), gumballA_id, 3, spinner1.publicKey, ticketPrice, gumball_admin);
+        await assert.rejects(
+            spinGumball(gumballA_pda, gumballA_id, 2, spinner1, gumball_admin,
+                spl1, esc1, spinner_1_ata,
+                spl1, spinner_1_ata, spinner_1_ata, otherPrize),
+            /PermitMessageMismatch/
+        );
 
         // ---------- AFTER SPIN ----------
         console.log("--------- AFTER SPIN ---------");
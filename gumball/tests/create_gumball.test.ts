@@ -19,6 +19,8 @@ import {
     updateGumballTime,
     updateGumballData,
     endGumball,
+    setGumballFrozen,
+    updatePauseFlags,
 } from "./helpers";
 import {
     setProgram,
@@ -33,6 +35,7 @@ import {
     ticket_fee_bps,
     minimum_Gumball_period,
     maximum_Gumball_period,
+    WITHDRAW_FEES_PAUSE,
 } from "./values";
 import { Gumball } from "../target/types/gumball";
 
@@ -134,6 +137,14 @@ describe("Create Gumball — Bankrun", () => {
         console.log("Owner SOL :", beforeOwnerBalance);
         console.log("Receiver SOL:", beforeReceiverBalance);
 
+        // withdrawals can be paused on their own
+        await updatePauseFlags(gumball_owner, new anchor.BN(1).shln(WITHDRAW_FEES_PAUSE));
+        await assert.rejects(
+            withdrawSolFees(gumball_owner, gumball_2_creator.publicKey, withdrawAmount),
+            /FunctionPaused/
+        );
+        await updatePauseFlags(gumball_owner, new anchor.BN(0));
+
         await withdrawSolFees(
             gumball_owner,            // must match gumball_owner from config
             gumball_2_creator.publicKey, // receiver
@@ -157,6 +168,19 @@ describe("Create Gumball — Bankrun", () => {
 
         // wrap some time to test cases
         await warpForward(1000);
+
+        // a frozen gumball rejects everything until the owner unfreezes it
+        await setGumballFrozen(gumballPdaAddr, gumballId, true, gumball_owner);
+        await assert.rejects(
+            activateGumball(gumballPdaAddr, gumballId, gumball_admin),
+            /GumballFrozen/
+        );
+        await assert.rejects(
+            setGumballFrozen(gumballPdaAddr, gumballId, false, gumball_1_creator),
+            /InvalidGumballOwner/
+        );
+        await setGumballFrozen(gumballPdaAddr, gumballId, false, gumball_owner);
+
         await activateGumball(gumballPdaAddr, gumballId, gumball_admin);
 
         await updateGumballData(gumballPdaAddr, gumballId, 200_000_000, 3, gumball_1_creator, gumball_admin);
@@ -172,6 +196,7 @@ describe("Create Gumball — Bankrun", () => {
 
         const solGumball = await program.account.gumballMachine.fetch(gumballPdaAddr);
         console.log(solGumball)
+        assert.equal(solGumball.ticketFeeBps, ticket_fee_bps);
 
         // await cancelGumball(gumballPdaAddr, gumballId, gumball_1_creator, gumball_admin);
 
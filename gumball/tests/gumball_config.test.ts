@@ -0,0 +1,208 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { Keypair } from "@solana/web3.js";
+import { initializeGumballConfig, warpForward } from "./helpers";
+import {
+    setProgram,
+    setProvider,
+    gumball_owner,
+    gumball_admin,
+    gumballConfigPda,
+    creation_fee_lamports,
+    ticket_fee_bps,
+    minimum_Gumball_period,
+    maximum_Gumball_period,
+    minimum_config_change_delay,
+    UPDATE_CONFIG_PAUSE,
+} from "./values";
+import { Gumball } from "../target/types/gumball";
+
+describe("Gumball Config — ownership and queued changes", () => {
+    let context: any;
+    let provider: BankrunProvider;
+    let program: anchor.Program<Gumball>;
+
+    const new_owner = Keypair.generate();
+    const stranger = Keypair.generate();
+
+    before(async () => {
+        context = await startAnchor("", [], []);
+        provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+
+        program = anchor.workspace.Gumball as anchor.Program<Gumball>;
+        setProgram(program);
+
+        await context.setAccount(gumball_owner.publicKey, {
+            lamports: 10_000_000_000,
+            owner: anchor.web3.SystemProgram.programId,
+            data: Buffer.alloc(0),
+            executable: false,
+        });
+
+        await initializeGumballConfig(
+            gumball_owner,
+            gumball_owner.publicKey,
+            gumball_admin.publicKey,
+            creation_fee_lamports,
+            ticket_fee_bps,
+            minimum_Gumball_period,
+            maximum_Gumball_period
+        );
+    });
+
+    const propose = (owner: Keypair, proposed: Keypair) =>
+        program.methods
+            .proposeOwner(proposed.publicKey)
+            .accounts({ gumballConfig: gumballConfigPda(), gumballOwner: owner.publicKey })
+            .signers([owner])
+            .rpc();
+
+    const accept = (signer: Keypair) =>
+        program.methods
+            .acceptOwner()
+            .accounts({ gumballConfig: gumballConfigPda(), newGumballOwner: signer.publicKey })
+            .signers([signer])
+            .rpc();
+
+    it("non-owner cannot propose", async () => {
+        await assert.rejects(propose(stranger, stranger));
+    });
+
+    it("accept fails with nothing pending", async () => {
+        await assert.rejects(accept(new_owner));
+    });
+
+    it("cancel clears the pending owner", async () => {
+        await propose(gumball_owner, new_owner);
+
+        await program.methods
+            .cancelOwnerProposal()
+            .accounts({ gumballConfig: gumballConfigPda(), gumballOwner: gumball_owner.publicKey })
+            .signers([gumball_owner])
+            .rpc();
+
+        const cfg = await program.account.gumballConfig.fetch(gumballConfigPda());
+        assert.equal(cfg.pendingOwner, null);
+        await assert.rejects(accept(new_owner));
+    });
+
+    it("only the pending owner can accept", async () => {
+        await propose(gumball_owner, new_owner);
+        await assert.rejects(accept(stranger));
+
+        await accept(new_owner);
+
+        const cfg = await program.account.gumballConfig.fetch(gumballConfigPda());
+        assert.equal(cfg.gumballOwner.toBase58(), new_owner.publicKey.toBase58());
+        assert.equal(cfg.pendingOwner, null);
+
+        // previous owner lost its rights
+        await assert.rejects(propose(gumball_owner, gumball_owner));
+    });
+
+    it("config data changes wait for the delay", async () => {
+        const queue = (bps: number, delay: number) =>
+            program.methods
+                .queueGumballConfigData(
+                    new anchor.BN(creation_fee_lamports),
+                    bps,
+                    minimum_Gumball_period,
+                    maximum_Gumball_period,
+                    delay
+                )
+                .accounts({ gumballConfig: gumballConfigPda(), gumballOwner: new_owner.publicKey })
+                .signers([new_owner])
+                .rpc();
+
+        const apply = () =>
+            program.methods
+                .applyGumballConfigData()
+                .accounts({ gumballConfig: gumballConfigPda(), gumballOwner: new_owner.publicKey })
+                .signers([new_owner])
+                .rpc();
+
+        await assert.rejects(queue(300, minimum_config_change_delay - 1));
+        await assert.rejects(queue(10_001, minimum_config_change_delay), /InvalidTicketFee/);
+
+        await queue(300, minimum_config_change_delay);
+        await assert.rejects(apply());
+
+        let cfg = await program.account.gumballConfig.fetch(gumballConfigPda());
+        assert.equal(cfg.ticketFeeBps, ticket_fee_bps);
+        assert.equal(cfg.pendingConfigData.ticketFeeBps, 300);
+
+        await warpForward(minimum_config_change_delay);
+        await apply();
+
+        cfg = await program.account.gumballConfig.fetch(gumballConfigPda());
+        assert.equal(cfg.ticketFeeBps, 300);
+        assert.equal(cfg.pendingConfigData, null);
+        await assert.rejects(apply());
+    });
+
+    it("config changes are blocked while UPDATE_CONFIG_PAUSE is set", async () => {
+        const setFlags = (flags: anchor.BN) =>
+            program.methods
+                .updatePauseFlags(flags)
+                .accounts({ gumballConfig: gumballConfigPda(), gumballOwner: new_owner.publicKey })
+                .signers([new_owner])
+                .rpc();
+
+        await setFlags(new anchor.BN(1).shln(UPDATE_CONFIG_PAUSE));
+        await assert.rejects(
+            program.methods
+                .queueGumballConfigData(
+                    new anchor.BN(creation_fee_lamports),
+                    500,
+                    minimum_Gumball_period,
+                    maximum_Gumball_period,
+                    minimum_config_change_delay
+                )
+                .accounts({ gumballConfig: gumballConfigPda(), gumballOwner: new_owner.publicKey })
+                .signers([new_owner])
+                .rpc(),
+            /FunctionPaused/
+        );
+
+        await setFlags(new anchor.BN(0));
+        const cfg = await program.account.gumballConfig.fetch(gumballConfigPda());
+        assert.equal(cfg.pauseFlags.toNumber(), 0);
+        assert.equal(cfg.pendingConfigData, null);
+    });
+
+    it("cancelled config changes are never applied", async () => {
+        await program.methods
+            .queueGumballConfigData(
+                new anchor.BN(creation_fee_lamports),
+                1_000,
+                minimum_Gumball_period,
+                maximum_Gumball_period,
+                minimum_config_change_delay
+            )
+            .accounts({ gumballConfig: gumballConfigPda(), gumballOwner: new_owner.publicKey })
+            .signers([new_owner])
+            .rpc();
+
+        await program.methods
+            .cancelGumballConfigData()
+            .accounts({ gumballConfig: gumballConfigPda(), gumballOwner: new_owner.publicKey })
+            .signers([new_owner])
+            .rpc();
+
+        await warpForward(minimum_config_change_delay);
+        await assert.rejects(
+            program.methods
+                .applyGumballConfigData()
+                .accounts({ gumballConfig: gumballConfigPda(), gumballOwner: new_owner.publicKey })
+                .signers([new_owner])
+                .rpc()
+        );
+
+        const cfg = await program.account.gumballConfig.fetch(gumballConfigPda());
+        assert.equal(cfg.ticketFeeBps, 300);
+    });
+});
@@ -1,16 +1,31 @@
 import * as anchor from "@coral-xyz/anchor";
-import { Keypair, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import {
+    AccountMeta,
+    Ed25519Program,
+    Keypair,
+    PublicKey,
+    SYSVAR_INSTRUCTIONS_PUBKEY,
+    SystemProgram,
+    Transaction,
+    TransactionInstruction,
+} from "@solana/web3.js";
 import {
     createInitializeMintInstruction,
     createAssociatedTokenAccountIdempotentInstruction,
     createTransferCheckedInstruction,
     TOKEN_PROGRAM_ID,
+    TOKEN_2022_PROGRAM_ID,
+    ExtensionType,
+    getMintLen,
+    createInitializeTransferFeeConfigInstruction,
+    createInitializePermanentDelegateInstruction,
+    createInitializeTransferHookInstruction,
     getAssociatedTokenAddressSync,
     createMintToInstruction,
     AccountLayout,
     ASSOCIATED_TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
-import { getProgram, getProvider, gumballConfigPda, gumballPda, gumballPrizePda } from "./values";
+import { getProgram, getProvider, gumballConfigPda, gumballPda, gumballPrizePda, mintAllowlistPda, TOKEN_METADATA_PROGRAM_ID, AUTH_RULES_PROGRAM_ID, collectionAllowlistPda, nftMetadataPda, nftEditionPda, tokenRecordPda } from "./values";
 import { Clock } from "solana-bankrun";
 import { Gumball } from "../target/types/gumball";
 
@@ -36,19 +51,263 @@ export async function createSplMint(decimals = 9): Promise<PublicKey> {
 }
 
 export async function createNftMint(): Promise<PublicKey> {
-    return createSplMint(0);
+    const mint = await createSplMint(0);
+    await setNftMetadata(mint);
+    return mint;
+}
+
+// Writes a Token Metadata `Metadata` and `MasterEdition` for `mint` straight into the bank, the
+// Token Metadata program itself is not loaded in these tests
+export async function setNftMetadata(
+    mint: PublicKey,
+    opts: { collection?: PublicKey; verifiedCollection?: boolean; masterEdition?: boolean } = {}
+) {
+    const { collection, verifiedCollection = true, masterEdition = true } = opts;
+    const context = getProvider().context;
+
+    const borshString = (value: string) => {
+        const len = Buffer.alloc(4);
+        len.writeUInt32LE(value.length);
+        return Buffer.concat([len, Buffer.from(value)]);
+    };
+
+    const metadata = Buffer.concat([
+        Buffer.from([4]), // Key::MetadataV1
+        getProvider().wallet.publicKey.toBuffer(), // update authority
+        mint.toBuffer(),
+        borshString("Prize"),
+        borshString("PRZ"),
+        borshString(""),
+        Buffer.from([0, 0]), // seller fee bps
+        Buffer.from([0]), // no creators
+        Buffer.from([0, 1]), // primary sale happened, is mutable
+        Buffer.from([0]), // edition nonce
+        Buffer.from([1, 0]), // TokenStandard::NonFungible
+        collection
+            ? Buffer.concat([Buffer.from([1, verifiedCollection ? 1 : 0]), collection.toBuffer()])
+            : Buffer.from([0]),
+        Buffer.from([0, 0, 0]), // uses, collection details, programmable config
+    ]);
+    await context.setAccount(nftMetadataPda(mint), {
+        lamports: 1_000_000_000,
+        data: metadata,
+        owner: TOKEN_METADATA_PROGRAM_ID,
+        executable: false,
+    });
+
+    if (masterEdition) {
+        // Key::MasterEditionV2, supply 0, max supply Some(0)
+        const edition = Buffer.concat([Buffer.from([6]), Buffer.alloc(8), Buffer.from([1]), Buffer.alloc(8)]);
+        await context.setAccount(nftEditionPda(mint), {
+            lamports: 1_000_000_000,
+            data: edition,
+            owner: TOKEN_METADATA_PROGRAM_ID,
+            executable: false,
+        });
+    }
+}
+
+// Mints a programmable NFT to `owner` through Token Metadata's CreateV1 and MintV1, unlike
+// `setNftMetadata` this needs the real program loaded from tests/fixtures/mpl_token_metadata.so
+export async function createPnftMint(owner: PublicKey): Promise<PublicKey> {
+    const provider = getProvider();
+    const payer = provider.wallet.publicKey;
+    const mint = Keypair.generate();
+    const metadata = nftMetadataPda(mint.publicKey);
+    const edition = nftEditionPda(mint.publicKey);
+    const ownerAta = getAssociatedTokenAddressSync(mint.publicKey, owner, true);
+
+    const borshString = (value: string) => {
+        const len = Buffer.alloc(4);
+        len.writeUInt32LE(value.length);
+        return Buffer.concat([len, Buffer.from(value)]);
+    };
+    const account = (pubkey: PublicKey, isWritable = false, isSigner = false) => ({ pubkey, isSigner, isWritable });
+    // absent optional accounts are passed as the Token Metadata program id
+    const none = account(TOKEN_METADATA_PROGRAM_ID);
+
+    const create = new TransactionInstruction({
+        programId: TOKEN_METADATA_PROGRAM_ID,
+        keys: [
+            account(metadata, true),
+            account(edition, true),
+            account(mint.publicKey, true, true),
+            account(payer, false, true), // mint authority
+            account(payer, true, true),
+            account(payer, false, true), // update authority
+            account(SystemProgram.programId),
+            account(SYSVAR_INSTRUCTIONS_PUBKEY),
+            account(TOKEN_PROGRAM_ID),
+        ],
+        data: Buffer.concat([
+            Buffer.from([42, 0]), // CreateV1
+            borshString("Prize"),
+            borshString("PRZ"),
+            borshString(""),
+            Buffer.from([0, 0]), // seller fee bps
+            Buffer.from([0]), // no creators
+            Buffer.from([0, 1]), // primary sale happened, is mutable
+            Buffer.from([4]), // TokenStandard::ProgrammableNonFungible
+            Buffer.from([0, 0, 0, 0]), // collection, uses, collection details, rule set
+            Buffer.from([1, 0]), // decimals
+            Buffer.from([1, 0]), // PrintSupply::Zero
+        ]),
+    });
+
+    const mintTo = new TransactionInstruction({
+        programId: TOKEN_METADATA_PROGRAM_ID,
+        keys: [
+            account(ownerAta, true),
+            account(owner),
+            account(metadata),
+            account(edition, true),
+            account(tokenRecordPda(mint.publicKey, ownerAta), true),
+            account(mint.publicKey, true),
+            account(payer, false, true),
+            none, // delegate record
+            account(payer, true, true),
+            account(SystemProgram.programId),
+            account(SYSVAR_INSTRUCTIONS_PUBKEY),
+            account(TOKEN_PROGRAM_ID),
+            account(ASSOCIATED_TOKEN_PROGRAM_ID),
+            none, // authorization rules program
+            none, // authorization rules
+        ],
+        data: Buffer.concat([
+            Buffer.from([43, 0]), // MintV1
+            new anchor.BN(1).toArrayLike(Buffer, "le", 8),
+            Buffer.from([0]), // no authorization data
+        ]),
+    });
+
+    await provider.sendAndConfirm(new Transaction().add(create), [mint]);
+    await provider.sendAndConfirm(new Transaction().add(mintTo));
+    return mint.publicKey;
+}
+
+// Token-2022 mint that withholds `feeBps` of every transfer, capped at `maxFee`
+export async function createTransferFeeMint(feeBps: number, maxFee: bigint, decimals = 9): Promise<PublicKey> {
+    const provider = getProvider();
+    const mint = Keypair.generate();
+
+    const space = getMintLen([ExtensionType.TransferFeeConfig]);
+    const lamports = await provider.connection.getMinimumBalanceForRentExemption(space);
+    const tx = new Transaction().add(
+        SystemProgram.createAccount({
+            fromPubkey: provider.wallet.publicKey,
+            newAccountPubkey: mint.publicKey,
+            space,
+            lamports,
+            programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+            mint.publicKey,
+            provider.wallet.publicKey,
+            provider.wallet.publicKey,
+            feeBps,
+            maxFee,
+            TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(mint.publicKey, decimals, provider.wallet.publicKey, null, TOKEN_2022_PROGRAM_ID)
+    );
+
+    await provider.sendAndConfirm(tx, [mint]);
+    return mint.publicKey;
+}
+
+// Token-2022 mint whose `delegate` can move tokens out of any account, escrows included
+export async function createPermanentDelegateMint(delegate: PublicKey, decimals = 9): Promise<PublicKey> {
+    const provider = getProvider();
+    const mint = Keypair.generate();
+
+    const space = getMintLen([ExtensionType.PermanentDelegate]);
+    const lamports = await provider.connection.getMinimumBalanceForRentExemption(space);
+    const tx = new Transaction().add(
+        SystemProgram.createAccount({
+            fromPubkey: provider.wallet.publicKey,
+            newAccountPubkey: mint.publicKey,
+            space,
+            lamports,
+            programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializePermanentDelegateInstruction(mint.publicKey, delegate, TOKEN_2022_PROGRAM_ID),
+        createInitializeMintInstruction(mint.publicKey, decimals, provider.wallet.publicKey, null, TOKEN_2022_PROGRAM_ID)
+    );
+
+    await provider.sendAndConfirm(tx, [mint]);
+    return mint.publicKey;
+}
+
+export async function createTransferHookMint(hookProgramId: PublicKey, decimals = 9): Promise<PublicKey> {
+    const provider = getProvider();
+    const mint = Keypair.generate();
+
+    const space = getMintLen([ExtensionType.TransferHook]);
+    const lamports = await provider.connection.getMinimumBalanceForRentExemption(space);
+    const tx = new Transaction().add(
+        SystemProgram.createAccount({
+            fromPubkey: provider.wallet.publicKey,
+            newAccountPubkey: mint.publicKey,
+            space,
+            lamports,
+            programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferHookInstruction(
+            mint.publicKey,
+            provider.wallet.publicKey,
+            hookProgramId,
+            TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(mint.publicKey, decimals, provider.wallet.publicKey, null, TOKEN_2022_PROGRAM_ID)
+    );
+
+    await provider.sendAndConfirm(tx, [mint]);
+    return mint.publicKey;
 }
 
-export async function createAta(mint: PublicKey, owner: PublicKey): Promise<PublicKey> {
+// Hook program, its validation account and the counter PDA it lists, in the order the hook resolves them
+export function transferHookAccounts(hookProgramId: PublicKey, mint: PublicKey): AccountMeta[] {
+    const [extraAccountMetaList] = PublicKey.findProgramAddressSync(
+        [Buffer.from("extra-account-metas"), mint.toBuffer()],
+        hookProgramId
+    );
+    const [counter] = PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), mint.toBuffer()],
+        hookProgramId
+    );
+
+    return [
+        { pubkey: hookProgramId, isSigner: false, isWritable: false },
+        { pubkey: extraAccountMetaList, isSigner: false, isWritable: false },
+        { pubkey: counter, isSigner: false, isWritable: true },
+    ];
+}
+
+// Token Metadata accounts every prize transfer carries; with no rule set the program id stands in for `authorizationRules`
+export function pnftAccounts(authorizationRules: PublicKey = TOKEN_METADATA_PROGRAM_ID) {
+    return {
+        authorizationRules,
+        authorizationRulesProgram: AUTH_RULES_PROGRAM_ID,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+    };
+}
+
+export async function createAta(
+    mint: PublicKey,
+    owner: PublicKey,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID
+): Promise<PublicKey> {
     const provider = getProvider();
-    const ata = getAssociatedTokenAddressSync(mint, owner, true);
+    const ata = getAssociatedTokenAddressSync(mint, owner, true, tokenProgram);
 
     const tx = new Transaction().add(
         createAssociatedTokenAccountIdempotentInstruction(
             provider.wallet.publicKey, // payer
             ata,
             owner,
-            mint
+            mint,
+            tokenProgram
         )
     );
 
@@ -56,14 +315,21 @@ export async function createAta(mint: PublicKey, owner: PublicKey): Promise<Publ
     return ata;
 }
 
-export async function mintTokens(mint: PublicKey, to: PublicKey, amount: number) {
+export async function mintTokens(
+    mint: PublicKey,
+    to: PublicKey,
+    amount: number,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID
+) {
     const provider = getProvider();
     const tx = new Transaction().add(
         createMintToInstruction(
             mint,
             to,
             provider.wallet.publicKey, // mint authority
-            amount
+            amount,
+            [],
+            tokenProgram
         )
     );
     await provider.sendAndConfirm(tx);
@@ -153,6 +419,91 @@ export async function withdrawSolFees(
         .rpc();
 }
 
+export async function updatePauseFlags(gumballOwner: Keypair, flags: anchor.BN) {
+    await getProgram().methods
+        .updatePauseFlags(flags)
+        .accounts({
+            gumballConfig: gumballConfigPda(),
+            gumballOwner: gumballOwner.publicKey,
+        })
+        .signers([gumballOwner])
+        .rpc();
+}
+
+export async function setAllowedMintExtensions(gumballOwner: Keypair, allowedMintExtensions: number) {
+    await getProgram().methods
+        .setAllowedMintExtensions(allowedMintExtensions)
+        .accounts({
+            gumballConfig: gumballConfigPda(),
+            gumballOwner: gumballOwner.publicKey,
+        })
+        .signers([gumballOwner])
+        .rpc();
+}
+
+export async function allowMint(gumballOwner: Keypair, mint: PublicKey) {
+    await getProgram().methods
+        .allowMint()
+        .accounts({
+            gumballConfig: gumballConfigPda(),
+            mint,
+            mintAllowlist: mintAllowlistPda(mint),
+            gumballOwner: gumballOwner.publicKey,
+            systemProgram: SystemProgram.programId,
+        })
+        .signers([gumballOwner])
+        .rpc();
+}
+
+export async function removeAllowedMint(gumballOwner: Keypair, mint: PublicKey) {
+    await getProgram().methods
+        .removeAllowedMint()
+        .accounts({
+            gumballConfig: gumballConfigPda(),
+            mintAllowlist: mintAllowlistPda(mint),
+            gumballOwner: gumballOwner.publicKey,
+        })
+        .signers([gumballOwner])
+        .rpc();
+}
+
+export async function setCollectionPolicy(gumballOwner: Keypair, requireVerifiedCollection: boolean) {
+    await getProgram().methods
+        .setCollectionPolicy(requireVerifiedCollection)
+        .accounts({
+            gumballConfig: gumballConfigPda(),
+            gumballOwner: gumballOwner.publicKey,
+        })
+        .signers([gumballOwner])
+        .rpc();
+}
+
+export async function allowCollection(gumballOwner: Keypair, collection: PublicKey) {
+    await getProgram().methods
+        .allowCollection()
+        .accounts({
+            gumballConfig: gumballConfigPda(),
+            collectionMint: collection,
+            collectionAllowlist: collectionAllowlistPda(collection),
+            gumballOwner: gumballOwner.publicKey,
+            systemProgram: SystemProgram.programId,
+        })
+        .signers([gumballOwner])
+        .rpc();
+}
+
+export async function removeAllowedCollection(gumballOwner: Keypair, collection: PublicKey) {
+    await getProgram().methods
+        .removeAllowedCollection()
+        .accounts({
+            gumballConfig: gumballConfigPda(),
+            collectionAllowlist: collectionAllowlistPda(collection),
+            gumballOwner: gumballOwner.publicKey,
+        })
+        .signers([gumballOwner])
+        .rpc();
+}
+
 export async function initializeGumballConfig(
     payer: Keypair,
     gumballOwner: PublicKey,
@@ -233,6 +584,23 @@ export async function activateGumball(
         .rpc();
 }
 
+export async function setGumballFrozen(
+    gumballPdaAddr: PublicKey,
+    gumballId: number,
+    frozen: boolean,
+    gumballOwner: Keypair
+) {
+    await getProgram().methods
+        .setGumballFrozen(gumballId, frozen)
+        .accounts({
+            gumballConfig: gumballConfigPda(),
+            gumball: gumballPdaAddr,
+            gumballOwner: gumballOwner.publicKey,
+        })
+        .signers([gumballOwner])
+        .rpc();
+}
+
 export async function cancelGumball(
     gumballPdaAddr: PublicKey,
     gumballId: number,
@@ -289,7 +657,9 @@ export async function addPrize(
     gumballAdmin: Keypair,
     prizeMint: PublicKey,
     prizeEscrow: PublicKey,
-    creatorPrizeAta: PublicKey
+    creatorPrizeAta: PublicKey,
+    prizeTokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+    remainingAccounts: AccountMeta[] = []
 ) {
     const tx = await getProgram().methods
         .addPrize(gumballId, prizeIndex, new anchor.BN(prizeAmount), quantity)
@@ -302,10 +672,12 @@ export async function addPrize(
             prizeMint,
             prizeEscrow,
             creatorPrizeAta,
-            prizeTokenProgram: TOKEN_PROGRAM_ID,
+            prizeTokenProgram,
             associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
+            ...pnftAccounts(),
         })
+        .remainingAccounts(remainingAccounts)
         .signers([creator, gumballAdmin])
         .transaction();
 
@@ -324,7 +696,9 @@ export async function claimPrizeBack(
     gumballAdmin: Keypair,
     prizeMint: PublicKey,
     prizeEscrow: PublicKey,
-    creatorPrizeAta: PublicKey
+    creatorPrizeAta: PublicKey,
+    prizeTokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+    remainingAccounts: AccountMeta[] = []
 ) {
     await getProgram().methods
         .claimPrizeBack(gumballId, prizeIndex)
@@ -337,14 +711,80 @@ export async function claimPrizeBack(
             prizeMint,
             prizeEscrow,
             creatorPrizeAta,
-            prizeTokenProgram: TOKEN_PROGRAM_ID,
+            prizeTokenProgram,
             associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
+            ...pnftAccounts(),
         })
+        .remainingAccounts(remainingAccounts)
         .signers([creator, gumballAdmin])
         .rpc();
 }
 
+// === PERMITS ===
+
+let nextPermitNonce = 1;
+
+// Mirrors `utils::permit_message` on chain
+export function permitMessage(
+    programId: PublicKey,
+    gumballId: number,
+    prizeIndex: number,
+    user: PublicKey,
+    permit: { maxAmount: anchor.BN; expiry: anchor.BN; nonce: anchor.BN }
+): Buffer {
+    return Buffer.concat([
+        programId.toBuffer(),
+        new anchor.BN(gumballId).toArrayLike(Buffer, "le", 4),
+        new anchor.BN(prizeIndex).toArrayLike(Buffer, "le", 2),
+        user.toBuffer(),
+        permit.maxAmount.toArrayLike(Buffer, "le", 8),
+        permit.expiry.toTwos(64).toArrayLike(Buffer, "le", 8),
+        permit.nonce.toArrayLike(Buffer, "le", 8),
+    ]);
+}
+
+// Signs a permit off-chain and returns it with the Ed25519 instruction that must precede the call
+export async function signPermit(
+    program: anchor.Program<Gumball>,
+    gumballId: number,
+    prizeIndex: number,
+    user: PublicKey,
+    maxAmount: number,
+    signer: Keypair,
+    overrides: { expiry?: number; nonce?: number } = {}
+): Promise<{
+    permit: { maxAmount: anchor.BN; expiry: anchor.BN; nonce: anchor.BN };
+    ed25519Ix: TransactionInstruction;
+    permitNonce: PublicKey;
+}> {
+    const expiry = overrides.expiry ?? (await getCurrentTimestamp()) + 60 * 60;
+    const nonce = overrides.nonce ?? nextPermitNonce++;
+
+    const permit = {
+        maxAmount: new anchor.BN(maxAmount),
+        expiry: new anchor.BN(expiry),
+        nonce: new anchor.BN(nonce),
+    };
+
+    const ed25519Ix = Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: signer.secretKey,
+        message: permitMessage(program.programId, gumballId, prizeIndex, user, permit),
+    });
+
+    const permitNonce = PublicKey.findProgramAddressSync(
+        [
+            Buffer.from("gumball"),
+            Buffer.from("permit"),
+            user.toBuffer(),
+            permit.nonce.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+    )[0];
+
+    return { permit, ed25519Ix, permitNonce };
+}
+
 export async function spinGumball(
     gumballPdaAddr: PublicKey,
     gumballId: number,
@@ -356,16 +796,32 @@ export async function spinGumball(
     spinnerPrizeAta: PublicKey,
     ticketMint: PublicKey,
     ticketEscrow: PublicKey,
-    spinnerTicketAta: PublicKey
+    spinnerTicketAta: PublicKey,
+    signed?: Awaited<ReturnType<typeof signPermit>>
 ) {
-    await getProgram().methods
-        .spinGumball(gumballId, prizeIndex)
+    const program = getProgram();
+
+    // the admin only signs the permit, the spinner submits on their own
+    if (!signed) {
+        const gumball = await program.account.gumballMachine.fetch(gumballPdaAddr);
+        signed = await signPermit(
+            program,
+            gumballId,
+            prizeIndex,
+            spinner.publicKey,
+            gumball.ticketPrice.toNumber(),
+            gumballAdmin
+        );
+    }
+
+    await program.methods
+        .spinGumball(gumballId, prizeIndex, signed.permit)
         .accounts({
             gumballConfig: gumballConfigPda(),
             gumball: gumballPdaAddr,
             prize: gumballPrizePda(gumballId, prizeIndex),
             spinner: spinner.publicKey,
-            gumballAdmin: gumballAdmin.publicKey,
+            permitNonce: signed.permitNonce,
             prizeMint,
             ticketMint,
             prizeEscrow,
@@ -376,8 +832,10 @@ export async function spinGumball(
             ticketTokenProgram: TOKEN_PROGRAM_ID,
             associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
+            ...pnftAccounts(),
         })
-        .signers([spinner, gumballAdmin])
+        .preInstructions([signed.ed25519Ix])
+        .signers([spinner])
         .rpc();
 }
 
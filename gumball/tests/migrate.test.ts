@@ -0,0 +1,306 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import {
+    setProgram,
+    setProvider,
+    gumball_owner,
+    gumball_admin,
+    gumball_1_creator,
+    gumballConfigPda,
+    gumballPda,
+    creation_fee_lamports,
+    ticket_fee_bps,
+    minimum_Gumball_period,
+    maximum_Gumball_period,
+    minimum_config_change_delay,
+} from "./values";
+
+// Sizes of the accounts as deployed before the `version` byte and reserved space
+const LEGACY_CONFIG_LEN = 8 + 32 + 32 + 8 + 2 + 4 + 4 + 4 + 1 + 1;
+const LEGACY_GUMBALL_LEN = 8 + 4 + 32 + 8 + 8 + 2 + 2 + 2 + 33 + 8 + 1 + 1;
+
+function discriminator(program: anchor.Program<any>, name: string): Buffer {
+    const account = program.idl.accounts.find((a: any) => a.name === name);
+    return Buffer.from(account.discriminator);
+}
+
+function legacyConfigSnapshot(program: anchor.Program<any>, bump: number): Buffer {
+    const buf = Buffer.alloc(LEGACY_CONFIG_LEN);
+    let o = 0;
+    o += discriminator(program, "gumballConfig").copy(buf, o);
+    o += gumball_owner.publicKey.toBuffer().copy(buf, o);
+    o += gumball_admin.publicKey.toBuffer().copy(buf, o);
+    o = buf.writeBigUInt64LE(BigInt(creation_fee_lamports), o);
+    o = buf.writeUInt16LE(ticket_fee_bps, o);
+    o = buf.writeUInt32LE(minimum_Gumball_period, o);
+    o = buf.writeUInt32LE(maximum_Gumball_period, o);
+    o = buf.writeUInt32LE(2, o); // gumball_count
+    o = buf.writeUInt8(0b0100_0000, o); // pause_flags
+    buf.writeUInt8(bump, o);
+    return buf;
+}
+
+// Version 1 config, same size as the current one but with a u8 `pause_flags`
+function v1ConfigSnapshot(program: anchor.Program<any>, bump: number): Buffer {
+    const buf = Buffer.alloc(program.account.gumballConfig.size);
+    let o = 0;
+    o += discriminator(program, "gumballConfig").copy(buf, o);
+    o = buf.writeUInt8(1, o); // version
+    o += gumball_owner.publicKey.toBuffer().copy(buf, o);
+    o += gumball_admin.publicKey.toBuffer().copy(buf, o);
+    o = buf.writeBigUInt64LE(BigInt(creation_fee_lamports), o);
+    o = buf.writeUInt16LE(ticket_fee_bps, o);
+    o = buf.writeUInt32LE(minimum_Gumball_period, o);
+    o = buf.writeUInt32LE(maximum_Gumball_period, o);
+    o = buf.writeUInt32LE(5, o); // gumball_count
+    o = buf.writeUInt8(0b1000_0010, o); // pause_flags
+    o = buf.writeUInt8(bump, o);
+    o = buf.writeUInt8(0, o); // pending_owner = None
+    o = buf.writeUInt32LE(minimum_config_change_delay, o);
+    buf.writeUInt8(0, o); // pending_config_data = None
+    return buf;
+}
+
+// Active SPL-ticket gumball with 4 of 6 prizes spun
+function legacyGumballSnapshot(
+    program: anchor.Program<any>,
+    ticketMint: PublicKey,
+    bump: number
+): Buffer {
+    const buf = Buffer.alloc(LEGACY_GUMBALL_LEN);
+    let o = 0;
+    o += discriminator(program, "gumballMachine").copy(buf, o);
+    o = buf.writeUInt32LE(1, o); // gumball_id
+    o += gumball_1_creator.publicKey.toBuffer().copy(buf, o);
+    o = buf.writeBigInt64LE(1_000n, o); // start_time
+    o = buf.writeBigInt64LE(5_000n, o); // end_time
+    o = buf.writeUInt16LE(10, o); // total_tickets
+    o = buf.writeUInt16LE(4, o); // tickets_sold
+    o = buf.writeUInt16LE(6, o); // prizes_added
+    o = buf.writeUInt8(1, o); // ticket_mint = Some
+    o += ticketMint.toBuffer().copy(buf, o);
+    o = buf.writeBigUInt64LE(50_000_000n, o); // ticket_price
+    o = buf.writeUInt8(2, o); // status = Active
+    buf.writeUInt8(bump, o);
+    return buf;
+}
+
+describe("Account migration — legacy snapshots", () => {
+    let context: any;
+    let provider: BankrunProvider;
+    let program: anchor.Program<any>;
+
+    const ticketMint = Keypair.generate().publicKey;
+
+    before(async () => {
+        context = await startAnchor("", [], []);
+        provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+
+        program = anchor.workspace.Gumball as anchor.Program<any>;
+        setProgram(program);
+
+        await context.setAccount(gumball_owner.publicKey, {
+            lamports: 10_000_000_000,
+            owner: SystemProgram.programId,
+            executable: false,
+            data: Buffer.alloc(0),
+        });
+
+        const [, configBump] = PublicKey.findProgramAddressSync(
+            [Buffer.from("gumball")],
+            program.programId
+        );
+        await context.setAccount(gumballConfigPda(), {
+            lamports: 1_000_000_000,
+            owner: program.programId,
+            executable: false,
+            data: legacyConfigSnapshot(program, configBump),
+        });
+
+        const [, gumballBump] = PublicKey.findProgramAddressSync(
+            [Buffer.from("gumball"), new anchor.BN(1).toArrayLike(Buffer, "le", 4)],
+            program.programId
+        );
+        await context.setAccount(gumballPda(1), {
+            lamports: 5_000_000,
+            owner: program.programId,
+            executable: false,
+            data: legacyGumballSnapshot(program, ticketMint, gumballBump),
+        });
+    });
+
+    it("rejects config migration from a non-owner", async () => {
+        const fake_owner = Keypair.generate();
+        await context.setAccount(fake_owner.publicKey, {
+            lamports: 1_000_000_000,
+            owner: SystemProgram.programId,
+            executable: false,
+            data: Buffer.alloc(0),
+        });
+
+        await assert.rejects(
+            program.methods
+                .migrateGumballConfig()
+                .accounts({
+                    gumballConfig: gumballConfigPda(),
+                    gumballOwner: fake_owner.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([fake_owner])
+                .rpc()
+        );
+    });
+
+    it("migrates the legacy config and keeps its data", async () => {
+        await program.methods
+            .migrateGumballConfig()
+            .accounts({
+                gumballConfig: gumballConfigPda(),
+                gumballOwner: gumball_owner.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([gumball_owner])
+            .rpc();
+
+        const cfg = await program.account.gumballConfig.fetch(gumballConfigPda());
+        assert.equal(cfg.version, 2);
+        assert.equal(cfg.gumballOwner.toString(), gumball_owner.publicKey.toString());
+        assert.equal(cfg.gumballAdmin.toString(), gumball_admin.publicKey.toString());
+        assert.equal(cfg.creationFeeLamports.toNumber(), creation_fee_lamports);
+        assert.equal(cfg.ticketFeeBps, ticket_fee_bps);
+        assert.equal(cfg.minimumGumballPeriod, minimum_Gumball_period);
+        assert.equal(cfg.maximumGumballPeriod, maximum_Gumball_period);
+        assert.equal(cfg.gumballCount, 2);
+        assert.equal(cfg.pauseFlags.toNumber(), 0b0100_0000);
+        assert.equal(cfg.pendingOwner, null);
+        assert.equal(cfg.configChangeDelay, minimum_config_change_delay);
+
+        const info = await context.banksClient.getAccount(gumballConfigPda());
+        assert.ok(info.data.length > LEGACY_CONFIG_LEN);
+    });
+
+    it("fails to migrate the config twice", async () => {
+        await assert.rejects(
+            program.methods
+                .migrateGumballConfig()
+                .accounts({
+                    gumballConfig: gumballConfigPda(),
+                    gumballOwner: gumball_owner.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([gumball_owner])
+                .rpc()
+        );
+    });
+
+    it("migrates a live legacy gumball machine", async () => {
+        const before = await context.banksClient.getAccount(gumballPda(1));
+
+        await program.methods
+            .migrateGumball(1)
+            .accounts({
+                gumball: gumballPda(1),
+                gumballConfig: gumballConfigPda(),
+                payer: gumball_owner.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([gumball_owner])
+            .rpc();
+
+        const gumball = await program.account.gumballMachine.fetch(gumballPda(1));
+        assert.equal(gumball.version, 2);
+        assert.equal(gumball.gumballId, 1);
+        assert.equal(gumball.creator.toString(), gumball_1_creator.publicKey.toString());
+        assert.equal(gumball.totalTickets, 10);
+        assert.equal(gumball.ticketsSold, 4);
+        assert.equal(gumball.prizesAdded, 6);
+        assert.equal(gumball.ticketMint.toString(), ticketMint.toString());
+        assert.equal(gumball.ticketPrice.toNumber(), 50_000_000);
+        assert.ok("active" in gumball.status);
+        // fee frozen from the config at migration time
+        assert.equal(gumball.ticketFeeBps, ticket_fee_bps);
+        // legacy escrows were paid in full, revenue is reconstructed from the price
+        assert.equal(gumball.ticketRevenue.toNumber(), 4 * 50_000_000);
+
+        const after = await context.banksClient.getAccount(gumballPda(1));
+        assert.ok(BigInt(after.lamports) >= BigInt(before.lamports));
+        assert.ok(after.data.length > LEGACY_GUMBALL_LEN);
+    });
+
+    it("fails to migrate the gumball machine twice", async () => {
+        await assert.rejects(
+            program.methods
+                .migrateGumball(1)
+                .accounts({
+                    gumball: gumballPda(1),
+                    gumballConfig: gumballConfigPda(),
+                    payer: gumball_owner.publicKey,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([gumball_owner])
+                .rpc()
+        );
+    });
+});
+
+describe("Account migration — version 1 config", () => {
+    let context: any;
+    let program: anchor.Program<any>;
+
+    before(async () => {
+        context = await startAnchor("", [], []);
+        const provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+
+        program = anchor.workspace.Gumball as anchor.Program<any>;
+        setProgram(program);
+
+        await context.setAccount(gumball_owner.publicKey, {
+            lamports: 10_000_000_000,
+            owner: SystemProgram.programId,
+            executable: false,
+            data: Buffer.alloc(0),
+        });
+
+        const [, configBump] = PublicKey.findProgramAddressSync(
+            [Buffer.from("gumball")],
+            program.programId
+        );
+        await context.setAccount(gumballConfigPda(), {
+            lamports: 1_000_000_000,
+            owner: program.programId,
+            executable: false,
+            data: v1ConfigSnapshot(program, configBump),
+        });
+    });
+
+    it("widens the pause flags in place", async () => {
+        const before = await context.banksClient.getAccount(gumballConfigPda());
+
+        await program.methods
+            .migrateGumballConfig()
+            .accounts({
+                gumballConfig: gumballConfigPda(),
+                gumballOwner: gumball_owner.publicKey,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([gumball_owner])
+            .rpc();
+
+        const cfg = await program.account.gumballConfig.fetch(gumballConfigPda());
+        assert.equal(cfg.version, 2);
+        assert.equal(cfg.gumballOwner.toString(), gumball_owner.publicKey.toString());
+        assert.equal(cfg.gumballCount, 5);
+        assert.equal(cfg.pauseFlags.toNumber(), 0b1000_0010);
+        assert.equal(cfg.configChangeDelay, minimum_config_change_delay);
+
+        const after = await context.banksClient.getAccount(gumballConfigPda());
+        assert.equal(after.data.length, before.data.length);
+    });
+});
@@ -0,0 +1,120 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { AccountLayout, getAssociatedTokenAddressSync } from "@solana/spl-token";
+
+import {
+    initializeGumballConfig,
+    createGumball,
+    cancelGumball,
+    addPrize,
+    claimPrizeBack,
+    createPnftMint,
+    createSplMint,
+    createAta,
+    getTokenBalance,
+    getCurrentTimestamp,
+} from "./helpers";
+import {
+    gumball_owner,
+    gumball_admin,
+    gumball_1_creator,
+    setProgram,
+    setProvider,
+    gumballConfigPda,
+    gumballPda,
+    tokenRecordPda,
+    creation_fee_lamports,
+    ticket_fee_bps,
+    minimum_Gumball_period,
+    maximum_Gumball_period,
+    TOKEN_METADATA_PROGRAM_ID,
+} from "./values";
+import type { Gumball } from "../target/types/gumball";
+
+// Token Metadata is loaded from tests/fixtures/mpl_token_metadata.so, dumped from mainnet with
+// `solana program dump -u m metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s tests/fixtures/mpl_token_metadata.so`
+describe("Programmable NFT prizes", () => {
+    let context: any;
+    let provider: BankrunProvider;
+    let program: anchor.Program<Gumball>;
+
+    before(async () => {
+        context = await startAnchor("", [{ name: "mpl_token_metadata", programId: TOKEN_METADATA_PROGRAM_ID }], []);
+        provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+
+        program = anchor.workspace.Gumball as anchor.Program<Gumball>;
+        setProgram(program);
+
+        for (const kp of [gumball_owner, gumball_1_creator]) {
+            await context.setAccount(kp.publicKey, {
+                lamports: 50_000_000_000,
+                owner: anchor.web3.SystemProgram.programId,
+                data: Buffer.alloc(0),
+                executable: false,
+            });
+        }
+
+        await initializeGumballConfig(
+            gumball_owner,
+            gumball_owner.publicKey,
+            gumball_admin.publicKey,
+            creation_fee_lamports,
+            ticket_fee_bps,
+            minimum_Gumball_period,
+            maximum_Gumball_period
+        );
+    });
+
+    // pNFT token accounts sit frozen between transfers
+    async function isFrozen(ata: anchor.web3.PublicKey) {
+        const info = await provider.connection.getAccountInfo(ata);
+        return AccountLayout.decode(info!.data).state === 2;
+    }
+
+    it("escrows a pNFT prize and hands it back once cancelled", async () => {
+        const cfg = await program.account.gumballConfig.fetch(gumballConfigPda());
+        const gumballId = cfg.gumballCount;
+        const gumball = gumballPda(gumballId);
+
+        const now = await getCurrentTimestamp();
+        await createGumball(
+            {
+                startTime: now + 100,
+                endTime: now + 100 + minimum_Gumball_period,
+                totalTickets: 1,
+                ticketPrice: 1_000_000_000,
+                isTicketSol: true,
+                startGumball: false,
+            },
+            {
+                gumballPda: gumball,
+                creator: gumball_1_creator,
+                gumballAdmin: gumball_admin,
+                ticketMint: await createSplMint(9),
+            }
+        );
+
+        const prizeMint = await createPnftMint(gumball_1_creator.publicKey);
+        const creatorPrizeAta = getAssociatedTokenAddressSync(prizeMint, gumball_1_creator.publicKey);
+        const prizeEscrow = await createAta(prizeMint, gumball);
+
+        await addPrize(gumball, gumballId, 0, 1, 1, gumball_1_creator, gumball_admin, prizeMint, prizeEscrow, creatorPrizeAta);
+
+        // the move went through Token Metadata: the escrow holds the pNFT frozen, under its own token record
+        assert.equal(await getTokenBalance(prizeEscrow), 1);
+        assert.equal(await getTokenBalance(creatorPrizeAta), 0);
+        assert.ok(await isFrozen(prizeEscrow));
+        assert.ok(await provider.connection.getAccountInfo(tokenRecordPda(prizeMint, prizeEscrow)));
+
+        await cancelGumball(gumball, gumballId, gumball_1_creator, gumball_admin);
+        await claimPrizeBack(gumball, gumballId, 0, gumball_1_creator, gumball_admin, prizeMint, prizeEscrow, creatorPrizeAta);
+
+        assert.equal(await getTokenBalance(creatorPrizeAta), 1);
+        assert.ok(await isFrozen(creatorPrizeAta));
+        assert.equal(await provider.connection.getAccountInfo(prizeEscrow), null);
+    });
+});
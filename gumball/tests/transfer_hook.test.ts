@@ -0,0 +1,148 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { PublicKey } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+
+import {
+    initializeGumballConfig,
+    createGumball,
+    cancelGumball,
+    addPrize,
+    claimPrizeBack,
+    createSplMint,
+    createTransferHookMint,
+    transferHookAccounts,
+    createAta,
+    mintTokens,
+    getTokenBalance,
+    getCurrentTimestamp,
+    allowMint,
+} from "./helpers";
+import {
+    gumball_owner,
+    gumball_admin,
+    gumball_1_creator,
+    setProgram,
+    setProvider,
+    gumballConfigPda,
+    gumballPda,
+    creation_fee_lamports,
+    ticket_fee_bps,
+    minimum_Gumball_period,
+    maximum_Gumball_period,
+} from "./values";
+import type { Gumball } from "../target/types/gumball";
+
+describe("Transfer hook mints", () => {
+    let context: any;
+    let provider: BankrunProvider;
+    let program: anchor.Program<Gumball>;
+    let hookProgram: anchor.Program<any>;
+    let prizeMint: PublicKey;
+
+    const prizeAmount = 1_000_000_000;
+    const quantity = 2;
+
+    before(async () => {
+        context = await startAnchor("", [], []);
+        provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+        program = anchor.workspace.Gumball as anchor.Program<Gumball>;
+        hookProgram = anchor.workspace.TransferHookCounter as anchor.Program<any>;
+        setProgram(program);
+
+        for (const kp of [gumball_owner, gumball_1_creator]) {
+            await context.setAccount(kp.publicKey, {
+                lamports: 50_000_000_000,
+                owner: anchor.web3.SystemProgram.programId,
+                data: Buffer.alloc(0),
+                executable: false,
+            });
+        }
+
+        await initializeGumballConfig(
+            gumball_owner,
+            gumball_owner.publicKey,
+            gumball_admin.publicKey,
+            creation_fee_lamports,
+            ticket_fee_bps,
+            minimum_Gumball_period,
+            maximum_Gumball_period
+        );
+
+        prizeMint = await createTransferHookMint(hookProgram.programId);
+        await hookProgram.methods
+            .initializeExtraAccountMetaList()
+            .accounts({ payer: provider.wallet.publicKey, mint: prizeMint })
+            .rpc();
+
+        // hooked mints are rejected by the mint policy unless vetted
+        await allowMint(gumball_owner, prizeMint);
+    });
+
+    async function hookTransfers(): Promise<number> {
+        const counter = transferHookAccounts(hookProgram.programId, prizeMint)[2].pubkey;
+        const state = await hookProgram.account.transferCounter.fetch(counter);
+        return state.transfers.toNumber();
+    }
+
+    // SOL tickets, the hooked mint as an SPL prize
+    async function createHookedGumball() {
+        const cfg = await program.account.gumballConfig.fetch(gumballConfigPda());
+        const gumballId = cfg.gumballCount;
+        const gumball = gumballPda(gumballId);
+
+        const now = await getCurrentTimestamp();
+        await createGumball(
+            {
+                startTime: now + 100,
+                endTime: now + 100 + minimum_Gumball_period,
+                totalTickets: 10,
+                ticketPrice: 100_000_000,
+                isTicketSol: true,
+                startGumball: false,
+            },
+            {
+                gumballPda: gumball,
+                creator: gumball_1_creator,
+                gumballAdmin: gumball_admin,
+                ticketMint: await createSplMint(9),
+            }
+        );
+
+        const creatorPrizeAta = await createAta(prizeMint, gumball_1_creator.publicKey, TOKEN_2022_PROGRAM_ID);
+        const prizeEscrow = await createAta(prizeMint, gumball, TOKEN_2022_PROGRAM_ID);
+        await mintTokens(prizeMint, creatorPrizeAta, prizeAmount * quantity, TOKEN_2022_PROGRAM_ID);
+
+        return { gumball, gumballId, creatorPrizeAta, prizeEscrow };
+    }
+
+    it("fails with a clear error when the hook accounts are not forwarded", async () => {
+        const { gumball, gumballId, creatorPrizeAta, prizeEscrow } = await createHookedGumball();
+
+        await assert.rejects(
+            addPrize(gumball, gumballId, 0, prizeAmount, quantity, gumball_1_creator, gumball_admin, prizeMint, prizeEscrow, creatorPrizeAta, TOKEN_2022_PROGRAM_ID),
+            /MissingTransferHookAccounts/
+        );
+    });
+
+    it("runs the hook when escrowing the prize and when the gumball PDA returns it", async () => {
+        const before = await hookTransfers();
+        const hookAccounts = transferHookAccounts(hookProgram.programId, prizeMint);
+
+        const { gumball, gumballId, creatorPrizeAta, prizeEscrow } = await createHookedGumball();
+        const creatorBefore = await getTokenBalance(creatorPrizeAta);
+        await addPrize(gumball, gumballId, 0, prizeAmount, quantity, gumball_1_creator, gumball_admin, prizeMint, prizeEscrow, creatorPrizeAta, TOKEN_2022_PROGRAM_ID, hookAccounts);
+        assert.equal(await getTokenBalance(prizeEscrow), prizeAmount * quantity);
+        assert.equal(await hookTransfers(), before + 1);
+
+        // PDA-signed transfer out of the escrow
+        await cancelGumball(gumball, gumballId, gumball_1_creator, gumball_admin);
+        await claimPrizeBack(gumball, gumballId, 0, gumball_1_creator, gumball_admin, prizeMint, prizeEscrow, creatorPrizeAta, TOKEN_2022_PROGRAM_ID, hookAccounts);
+        assert.equal(await getTokenBalance(creatorPrizeAta), creatorBefore);
+        assert.equal(await hookTransfers(), before + 2);
+    });
+});
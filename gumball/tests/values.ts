@@ -23,6 +23,22 @@ export const creation_fee_lamports = 100_000_000;
 export const ticket_fee_bps = 100;  // 1% 
 export const minimum_Gumball_period = 60 * 60;  // 1 hour
 export const maximum_Gumball_period = 24 * 60 * 60;  // 24 hours
+export const minimum_config_change_delay = 24 * 60 * 60;  // MINIMUM_CONFIG_CHANGE_DELAY on chain
+
+// pause bit indexes, mirrors constants.rs
+export const WITHDRAW_FEES_PAUSE = 8;
+export const UPDATE_CONFIG_PAUSE = 9;
+
+// risky Token-2022 extension bit indexes, mirrors constants.rs
+export const PERMANENT_DELEGATE_EXTENSION = 0;
+export const NON_TRANSFERABLE_EXTENSION = 1;
+export const DEFAULT_FROZEN_EXTENSION = 2;
+export const TRANSFER_HOOK_EXTENSION = 3;
+export const PAUSABLE_EXTENSION = 4;
+
+// Metaplex programs used for programmable NFT prizes
+export const TOKEN_METADATA_PROGRAM_ID = new anchor.web3.PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+export const AUTH_RULES_PROGRAM_ID = new anchor.web3.PublicKey("auth9SigNpDKz4sJJ1DfCTuZrZNSAgh9sFD3rboVmgg");
 
 export function getProgram() {
     if (!program) throw new Error("Program not set");
@@ -53,4 +69,39 @@ export function gumballPrizePda(gumballId: number, prizeIndex: number) {
         [Buffer.from("gumball"), new anchor.BN(gumballId).toArrayLike(Buffer, "le", 4), new anchor.BN(prizeIndex).toArrayLike(Buffer, "le", 2)],
         getProgram().programId
     )[0];
+}
+
+export function mintAllowlistPda(mint: anchor.web3.PublicKey) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("gumball"), Buffer.from("mint_allowlist"), mint.toBuffer()],
+        getProgram().programId
+    )[0];
+}
+
+export function collectionAllowlistPda(collection: anchor.web3.PublicKey) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("gumball"), Buffer.from("collection_allowlist"), collection.toBuffer()],
+        getProgram().programId
+    )[0];
+}
+
+export function nftMetadataPda(mint: anchor.web3.PublicKey) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+        TOKEN_METADATA_PROGRAM_ID
+    )[0];
+}
+
+export function nftEditionPda(mint: anchor.web3.PublicKey) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer(), Buffer.from("edition")],
+        TOKEN_METADATA_PROGRAM_ID
+    )[0];
+}
+
+export function tokenRecordPda(mint: anchor.web3.PublicKey, token: anchor.web3.PublicKey) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer(), Buffer.from("token_record"), token.toBuffer()],
+        TOKEN_METADATA_PROGRAM_ID
+    )[0];
 }
\ No newline at end of file
@@ -7,6 +7,35 @@ pub const MINIMUM_TICKETS: u16 = 3; // minimum tickets have to set in a raffle
 pub const MAXIMUM_TICKETS: u16 = 10_000; // maximum tickets can able to set is 10,000
 pub const MAXIMUM_WALLET_PCT: u8 = 40; // maximum percentage of tickets can able to buy for a single buyer in a single raffle
 pub const MAXIMUM_WINNERS_COUNT: u8 = 10; // maximum winners can be set in a raffle [1-10 max]
+pub const MINIMUM_CONFIG_CHANGE_DELAY: u32 = 24 * 60 * 60; // fee and period changes are queued for at least 1 day
+
+// current account layout versions, stored in the leading `version` byte
+pub const RAFFLE_CONFIG_VERSION: u8 = 2; // 2: `pause_flags` widened to u64
+pub const RAFFLE_VERSION: u8 = 2; // 2: `ticket_revenue` tracked, older raffles fall back to price * sold
+pub const ROLE_REGISTRY_VERSION: u8 = 1;
+
+// ROLES (bit indexes into `RoleMember::roles`)
+pub const CREATOR_APPROVER_ROLE: u8 = 0; // co-signs create, update, activate, cancel and creator claims
+pub const PURCHASE_COSIGNER_ROLE: u8 = 1; // co-signs ticket purchases and buyer prize claims
+pub const ANNOUNCER_ROLE: u8 = 2; // announces winners
+pub const PAUSER_ROLE: u8 = 3; // updates the pause flags
+pub const FEE_WITHDRAWER_ROLE: u8 = 4; // withdraws SOL and SPL fees
+pub const ROLE_COUNT: u8 = 5;
+pub const MAXIMUM_ROLE_MEMBERS: usize = 10; // keep in sync with `RoleRegistry::members` max_len
+
+// MINT EXTENSIONS (bit indexes into `RaffleConfig::allowed_mint_extensions`)
+pub const PERMANENT_DELEGATE_EXTENSION: u8 = 0; // the delegate can move tokens out of any account, escrows included
+pub const NON_TRANSFERABLE_EXTENSION: u8 = 1; // tokens can never leave the escrow again
+pub const DEFAULT_FROZEN_EXTENSION: u8 = 2; // new accounts start frozen, only the freeze authority can thaw the escrow
+pub const TRANSFER_HOOK_EXTENSION: u8 = 3; // every transfer runs an arbitrary program
+pub const PAUSABLE_EXTENSION: u8 = 4; // the pause authority can halt every transfer
+pub const MINT_EXTENSION_COUNT: u8 = 5;
+
+// PROGRAMMABLE NFTS
+pub const AUTH_RULES_PROGRAM_ID: Pubkey = pubkey!("auth9SigNpDKz4sJJ1DfCTuZrZNSAgh9sFD3rboVmgg"); // Metaplex Token Auth Rules
+
+// PERMITS
+pub const PERMIT_MESSAGE_LEN: usize = 32 + 4 + 32 + 8 + 8 + 8; // program | raffle id | user | max amount | expiry | nonce
   
 pub const CREATE_RAFFLE_PAUSE: u8 = 0;
 pub const ACTIVATE_RAFFLE_PAUSE: u8 = 1;
@@ -16,4 +45,6 @@ pub const BUYER_CLAIM_PRIZE_PAUSE: u8 = 4;
 pub const CANCEL_RAFFLE_PAUSE: u8 = 5;
 pub const CLAIM_AMOUNT_BACK_PAUSE: u8 = 6;
 pub const UPDATE_RAFFLE_PAUSE: u8 = 7;
+pub const WITHDRAW_FEES_PAUSE: u8 = 8; // both SOL and SPL fee withdrawals
+pub const UPDATE_CONFIG_PAUSE: u8 = 9; // queuing and applying fee/period changes
  
\ No newline at end of file
@@ -4,7 +4,7 @@ use crate::constants::*;
 use crate::errors::*;
 use crate::helpers::{transfer_tokens_with_seeds};
 use crate::states::*;
-use crate::utils::{get_pct_amount, has_duplicate_pubkeys, is_paused};
+use crate::utils::{get_pct_amount, has_duplicate_pubkeys, has_role, is_paused};
 
 #[event]
 pub struct ColledtedTicketRevenue {
@@ -29,8 +29,8 @@ pub struct RaffleFailed {
     pub announce_time: i64,
 }
 
-pub fn announce_winners(
-    mut ctx: Context<AnnounceWinners>,
+pub fn announce_winners<'info>(
+    mut ctx: Context<'_, '_, '_, 'info, AnnounceWinners<'info>>,
     raffle_id: u32,
     winners: Vec<Pubkey>,
 ) -> Result<()> {
@@ -152,18 +152,26 @@ pub fn announce_winners(
 }
 
 // Fixed signature + fixed temporary borrow in seeds
-fn process_ticket_revenue(ctx: &mut Context<AnnounceWinners>, tickets_sold: u16) -> Result<()> {
+fn process_ticket_revenue<'info>(
+    ctx: &mut Context<'_, '_, '_, 'info, AnnounceWinners<'info>>,
+    tickets_sold: u16,
+) -> Result<()> {
     let raffle = &mut ctx.accounts.raffle;
     let raffle_config = &ctx.accounts.raffle_config;
 
-    let total_revenue = raffle
-        .ticket_price
-        .checked_mul(tickets_sold as u64)
-        .ok_or(RaffleStateErrors::Overflow)?;
+    // version 1 raffles never tracked `ticket_revenue`, every sale there paid the full price
+    let total_revenue = if raffle.version >= 2 {
+        raffle.ticket_revenue
+    } else {
+        raffle
+            .ticket_price
+            .checked_mul(tickets_sold as u64)
+            .ok_or(RaffleStateErrors::Overflow)?
+    };
 
     let fee_amount = get_pct_amount(
         total_revenue,
-        raffle_config.ticket_fee_bps as u64,
+        raffle.ticket_fee_bps as u64,
         FEE_MANTISSA as u64,
     )?;
 
@@ -221,6 +229,7 @@ fn process_ticket_revenue(ctx: &mut Context<AnnounceWinners>, tickets_sold: u16)
                 &raffle_ai,
                 &ctx.accounts.ticket_token_program,
                 mint,
+                ctx.remaining_accounts,
                 seeds,
                 fee_amount,
             )?;
@@ -244,15 +253,22 @@ pub struct AnnounceWinners<'info> {
         mut,
         seeds = [b"raffle"],
         bump = raffle_config.config_bump,
-        constraint = raffle_config.raffle_admin == raffle_admin.key() @ ConfigStateErrors::InvalidRaffleAdmin,
     )]
     pub raffle_config: Box<Account<'info, RaffleConfig>>,
 
+    #[account(
+        seeds = [b"raffle".as_ref(), b"roles".as_ref()],
+        bump = role_registry.registry_bump,
+        constraint = has_role(&role_registry, raffle_admin.key(), ANNOUNCER_ROLE) @ RoleErrors::MissingRole
+    )]
+    pub role_registry: Box<Account<'info, RoleRegistry>>,
+
     #[account(
         mut,
         seeds = [b"raffle", raffle_id.to_le_bytes().as_ref()],
         bump = raffle.raffle_bump,
         constraint = raffle.raffle_id == raffle_id @ RaffleStateErrors::InvalidRaffleId,
+        constraint = !raffle.frozen @ RaffleStateErrors::RaffleFrozen,
     )]
     pub raffle: Box<Account<'info, Raffle>>,
 
@@ -3,8 +3,8 @@ use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 use crate::constants::*;
 use crate::errors::*;
 use crate::helpers::{transfer_sol, transfer_tokens};
-use crate::states::{Buyer, Raffle, RaffleConfig, RaffleState};
-use crate::utils::{calculate_max_tickets, is_paused};
+use crate::states::{Buyer, Permit, PermitNonce, Raffle, RaffleConfig, RaffleState, RoleRegistry};
+use crate::utils::{calculate_max_tickets, has_role, is_paused, verify_permit};
 
 #[event]
 pub struct TicketPurchased {
@@ -15,7 +15,12 @@ pub struct TicketPurchased {
     pub bought_time: i64, 
 }
 
-pub fn buy_ticket(ctx: Context<BuyTicket>, raffle_id: u32, tickets_to_buy: u16) -> Result<()> {
+pub fn buy_ticket<'info>(
+    ctx: Context<'_, '_, '_, 'info, BuyTicket<'info>>,
+    raffle_id: u32,
+    tickets_to_buy: u16,
+    permit: Permit,
+) -> Result<()> {
     require!(
         !is_paused(ctx.accounts.raffle_config.pause_flags, BUY_TICKET_PAUSE),
         RaffleStateErrors::FunctionPaused
@@ -101,14 +106,36 @@ pub fn buy_ticket(ctx: Context<BuyTicket>, raffle_id: u32, tickets_to_buy: u16)
         .checked_mul(tickets_to_buy as u128)
         .ok_or(RaffleStateErrors::Overflow)? as u64;
 
+    // a purchase co-signer signed this purchase off-chain instead of co-signing the transaction
+    let permit_signer = verify_permit(
+        &ctx.accounts.instructions_sysvar,
+        raffle_id,
+        buyer.key(),
+        price_to_pay,
+        &permit,
+    )?;
+    require!(
+        has_role(&ctx.accounts.role_registry, permit_signer, PURCHASE_COSIGNER_ROLE),
+        RoleErrors::MissingRole
+    );
+
+    // spend the nonce, `init` already rejected a replay
+    let permit_nonce = &mut ctx.accounts.permit_nonce;
+    permit_nonce.user = buyer.key();
+    permit_nonce.nonce = permit.nonce;
+    permit_nonce.used_at = now;
+    permit_nonce.nonce_bump = ctx.bumps.permit_nonce;
+
     // SOL ticket (ticket_mint == None) => pay to raffle PDA lamports
-    if raffle.ticket_mint.is_none() {
+    let received = if raffle.ticket_mint.is_none() {
         transfer_sol(
             buyer,
             &raffle.to_account_info(),
             &ctx.accounts.system_program,
             price_to_pay,
         )?;
+
+        price_to_pay
     } else {
         // SPL ticket => transfer from buyer ATA to ticket_escrow ATA
         let stored_ticket_mint = raffle
@@ -146,9 +173,16 @@ pub fn buy_ticket(ctx: Context<BuyTicket>, raffle_id: u32, tickets_to_buy: u16)
             buyer,
             &ctx.accounts.ticket_token_program,
             ticket_mint,
+            ctx.remaining_accounts,
             price_to_pay,
-        )?;
-    }
+        )?
+    };
+
+    // revenue is settled from what the escrow holds, not from `ticket_price`
+    raffle.ticket_revenue = raffle
+        .ticket_revenue
+        .checked_add(received)
+        .ok_or(RaffleStateErrors::Overflow)?;
 
     // Emit event
     emit!(TicketPurchased {
@@ -163,21 +197,27 @@ pub fn buy_ticket(ctx: Context<BuyTicket>, raffle_id: u32, tickets_to_buy: u16)
 }
 
 #[derive(Accounts)]
-#[instruction(raffle_id: u32)]
+#[instruction(raffle_id: u32, tickets_to_buy: u16, permit: Permit)]
 pub struct BuyTicket<'info> {
     #[account(
         mut,
         seeds = [b"raffle"],
         bump = raffle_config.config_bump,
-        constraint = raffle_config.raffle_admin == raffle_admin.key() @ ConfigStateErrors::InvalidRaffleAdmin,
     )]
     pub raffle_config: Box<Account<'info, RaffleConfig>>,
 
+    #[account(
+        seeds = [b"raffle".as_ref(), b"roles".as_ref()],
+        bump = role_registry.registry_bump,
+    )]
+    pub role_registry: Box<Account<'info, RoleRegistry>>,
+
     #[account(
         mut,
         seeds = [b"raffle", raffle_id.to_le_bytes().as_ref()],
         bump = raffle.raffle_bump,
         constraint = raffle.status == RaffleState::Active @ RaffleStateErrors::RaffleNotActive,
+        constraint = !raffle.frozen @ RaffleStateErrors::RaffleFrozen,
     )]
     pub raffle: Box<Account<'info, Raffle>>,
 
@@ -197,7 +237,23 @@ pub struct BuyTicket<'info> {
     #[account(mut)]
     pub buyer: Signer<'info>,
 
-    pub raffle_admin: Signer<'info>,
+    #[account(
+        init,
+        payer = buyer,
+        space = 8 + PermitNonce::INIT_SPACE,
+        seeds = [
+            b"raffle".as_ref(),
+            b"permit".as_ref(),
+            buyer.key().as_ref(),
+            permit.nonce.to_le_bytes().as_ref(),
+        ],
+        bump
+    )]
+    pub permit_nonce: Box<Account<'info, PermitNonce>>,
+
+    /// CHECK: address is pinned to the instructions sysvar, read in `verify_permit`
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
 
     // buyer pays the amount with this mint and this mint hsould be match with raffle stored ticket_mint key
     pub ticket_mint: InterfaceAccount<'info, Mint>,
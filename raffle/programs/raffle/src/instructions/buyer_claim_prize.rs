@@ -1,10 +1,14 @@
 use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::mpl_token_metadata;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
-use crate::constants::{BUYER_CLAIM_PRIZE_PAUSE, TOTAL_PCT};
+use crate::constants::{
+    AUTH_RULES_PROGRAM_ID, BUYER_CLAIM_PRIZE_PAUSE, PURCHASE_COSIGNER_ROLE, TOTAL_PCT,
+};
 use crate::errors::*;
 use crate::helpers::*;
 use crate::states::*;
-use crate::utils::{get_pct_amount, is_paused};
+use crate::utils::{get_pct_amount, has_role, is_paused};
 
 #[event]
 pub struct PrizeClaimed {
@@ -16,7 +20,10 @@ pub struct PrizeClaimed {
     pub claimed_time: i64,
 }
 
-pub fn buyer_claim_prize(ctx: Context<BuyerClaimPrize>, raffle_id: u32) -> Result<()> {
+pub fn buyer_claim_prize<'info>(
+    ctx: Context<'_, '_, '_, 'info, BuyerClaimPrize<'info>>,
+    raffle_id: u32,
+) -> Result<()> {
     require!(
         !is_paused(
             ctx.accounts.raffle_config.pause_flags,
@@ -158,15 +165,41 @@ pub fn buyer_claim_prize(ctx: Context<BuyerClaimPrize>, raffle_id: u32) -> Resul
             }
 
             // Transfer once
-            transfer_tokens_with_seeds(
-                prize_escrow,
-                winner_prize_ata,
-                &raffle.to_account_info(),
-                &ctx.accounts.prize_token_program,
-                prize_mint,
-                signer_seeds,
-                total_tokens,
-            )?;
+            if is_programmable_nft(&ctx.accounts.prize_metadata)? {
+                transfer_programmable_nft(
+                    prize_escrow,
+                    &raffle.to_account_info(),
+                    winner_prize_ata,
+                    winner,
+                    prize_mint,
+                    winner,
+                    &ctx.accounts.prize_token_program,
+                    &ProgrammableNftAccounts {
+                        metadata: &ctx.accounts.prize_metadata,
+                        edition: &ctx.accounts.prize_edition,
+                        token_record: &ctx.accounts.escrow_token_record,
+                        destination_token_record: &ctx.accounts.winner_token_record,
+                        authorization_rules: &ctx.accounts.authorization_rules,
+                        authorization_rules_program: &ctx.accounts.authorization_rules_program,
+                        token_metadata_program: &ctx.accounts.token_metadata_program,
+                        sysvar_instructions: &ctx.accounts.instructions_sysvar,
+                        associated_token_program: &ctx.accounts.associated_token_program,
+                        system_program: &ctx.accounts.system_program,
+                    },
+                    signer_seeds,
+                )?;
+            } else {
+                transfer_tokens_with_seeds(
+                    prize_escrow,
+                    winner_prize_ata,
+                    &raffle.to_account_info(),
+                    &ctx.accounts.prize_token_program,
+                    prize_mint,
+                    ctx.remaining_accounts,
+                    signer_seeds,
+                    total_tokens,
+                )?;
+            }
 
             // Emit events
             for (i, &idx) in claim_indices.iter().enumerate() {
@@ -191,15 +224,22 @@ pub struct BuyerClaimPrize<'info> {
     #[account(
         seeds = [b"raffle"],
         bump = raffle_config.config_bump,
-        constraint = raffle_config.raffle_admin == raffle_admin.key() @ ConfigStateErrors::InvalidRaffleAdmin,
     )]
     pub raffle_config: Box<Account<'info, RaffleConfig>>,
 
+    #[account(
+        seeds = [b"raffle".as_ref(), b"roles".as_ref()],
+        bump = role_registry.registry_bump,
+        constraint = has_role(&role_registry, raffle_admin.key(), PURCHASE_COSIGNER_ROLE) @ RoleErrors::MissingRole
+    )]
+    pub role_registry: Box<Account<'info, RoleRegistry>>,
+
     #[account(
         mut,
         seeds = [b"raffle", raffle_id.to_le_bytes().as_ref()],
         bump = raffle.raffle_bump,
         constraint = raffle.raffle_id == raffle_id @ RaffleStateErrors::InvalidRaffleId,
+        constraint = !raffle.frozen @ RaffleStateErrors::RaffleFrozen,
     )]
     pub raffle: Box<Account<'info, Raffle>>,
 
@@ -237,5 +277,78 @@ pub struct BuyerClaimPrize<'info> {
 
     pub prize_token_program: Interface<'info, TokenInterface>,
 
+    /// CHECK: Token Metadata PDA of the prize mint, read to pick the pNFT transfer path
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub prize_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: master edition PDA of the prize mint, only used by pNFT transfers
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub prize_edition: UncheckedAccount<'info>,
+
+    /// CHECK: pNFT token record of `prize_escrow`, only used by pNFT transfers
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"token_record".as_ref(),
+            prize_escrow.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub escrow_token_record: UncheckedAccount<'info>,
+
+    /// CHECK: pNFT token record of `winner_prize_ata`, only used by pNFT transfers
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"token_record".as_ref(),
+            winner_prize_ata.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub winner_token_record: UncheckedAccount<'info>,
+
+    /// CHECK: only forwarded when the pNFT has a rule set, checked in `transfer_programmable_nft`
+    pub authorization_rules: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to Metaplex Token Auth Rules
+    #[account(address = AUTH_RULES_PROGRAM_ID)]
+    pub authorization_rules_program: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to Token Metadata, only invoked for pNFT prizes
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to the instructions sysvar, read by Token Metadata
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
     pub system_program: Program<'info, System>,
 }
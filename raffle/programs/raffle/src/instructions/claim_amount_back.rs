@@ -1,12 +1,14 @@
 use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::mpl_token_metadata;
 use anchor_spl::token_interface::{
     close_account, CloseAccount, Mint, TokenAccount, TokenInterface,
 };
-use crate::constants::CLAIM_AMOUNT_BACK_PAUSE;
+use crate::constants::{AUTH_RULES_PROGRAM_ID, CLAIM_AMOUNT_BACK_PAUSE, CREATOR_APPROVER_ROLE};
 use crate::errors::*;
 use crate::helpers::*;
 use crate::states::*;
-use crate::utils::is_paused;
+use crate::utils::{has_role, is_paused};
 
 #[event]
 pub struct AmountClaimBack {
@@ -17,7 +19,10 @@ pub struct AmountClaimBack {
     pub claimed_time: i64,
 }
 
-pub fn claim_amount_back(ctx: Context<ClaimAmountBack>, raffle_id: u32) -> Result<()> {
+pub fn claim_amount_back<'info>(
+    ctx: Context<'_, '_, '_, 'info, ClaimAmountBack<'info>>,
+    raffle_id: u32,
+) -> Result<()> {
     require!(
         !is_paused(
             ctx.accounts.raffle_config.pause_flags,
@@ -99,15 +104,41 @@ pub fn claim_amount_back(ctx: Context<ClaimAmountBack>, raffle_id: u32) -> Resul
                     prize_amount_claimable
                 };
 
-                transfer_tokens_with_seeds(
-                    prize_escrow,
-                    creator_prize_ata,
-                    &raffle.to_account_info(),
-                    &ctx.accounts.prize_token_program,
-                    prize_mint,
-                    signer_seeds,
-                    amount,
-                )?;
+                if is_programmable_nft(&ctx.accounts.prize_metadata)? {
+                    transfer_programmable_nft(
+                        prize_escrow,
+                        &raffle.to_account_info(),
+                        creator_prize_ata,
+                        creator,
+                        prize_mint,
+                        creator,
+                        &ctx.accounts.prize_token_program,
+                        &ProgrammableNftAccounts {
+                            metadata: &ctx.accounts.prize_metadata,
+                            edition: &ctx.accounts.prize_edition,
+                            token_record: &ctx.accounts.escrow_token_record,
+                            destination_token_record: &ctx.accounts.creator_token_record,
+                            authorization_rules: &ctx.accounts.authorization_rules,
+                            authorization_rules_program: &ctx.accounts.authorization_rules_program,
+                            token_metadata_program: &ctx.accounts.token_metadata_program,
+                            sysvar_instructions: &ctx.accounts.instructions_sysvar,
+                            associated_token_program: &ctx.accounts.associated_token_program,
+                            system_program: &ctx.accounts.system_program,
+                        },
+                        signer_seeds,
+                    )?;
+                } else {
+                    transfer_tokens_with_seeds(
+                        prize_escrow,
+                        creator_prize_ata,
+                        &raffle.to_account_info(),
+                        &ctx.accounts.prize_token_program,
+                        prize_mint,
+                        ctx.remaining_accounts,
+                        signer_seeds,
+                        amount,
+                    )?;
+                }
             }
         }
     }
@@ -153,6 +184,7 @@ pub fn claim_amount_back(ctx: Context<ClaimAmountBack>, raffle_id: u32) -> Resul
                 &raffle.to_account_info(),
                 &ctx.accounts.ticket_token_program,
                 ticket_mint,
+                ctx.remaining_accounts,
                 signer_seeds,
                 ticket_amount_claimable,
             )?;
@@ -194,15 +226,22 @@ pub struct ClaimAmountBack<'info> {
     #[account(
         seeds = [b"raffle"],
         bump = raffle_config.config_bump,
-        constraint = raffle_config.raffle_admin == raffle_admin.key() @ ConfigStateErrors::InvalidRaffleAdmin,
     )]
     pub raffle_config: Box<Account<'info, RaffleConfig>>,
 
+    #[account(
+        seeds = [b"raffle".as_ref(), b"roles".as_ref()],
+        bump = role_registry.registry_bump,
+        constraint = has_role(&role_registry, raffle_admin.key(), CREATOR_APPROVER_ROLE) @ RoleErrors::MissingRole
+    )]
+    pub role_registry: Box<Account<'info, RoleRegistry>>,
+
     #[account(
         mut,
         seeds = [b"raffle", raffle_id.to_le_bytes().as_ref()],
         bump = raffle.raffle_bump,
         constraint = raffle.raffle_id == raffle_id @ RaffleStateErrors::InvalidRaffleId,
+        constraint = !raffle.frozen @ RaffleStateErrors::RaffleFrozen,
     )]
     pub raffle: Box<Account<'info, Raffle>>,
 
@@ -239,5 +278,78 @@ pub struct ClaimAmountBack<'info> {
     pub prize_token_program: Interface<'info, TokenInterface>,
     pub ticket_token_program: Interface<'info, TokenInterface>,
 
+    /// CHECK: Token Metadata PDA of the prize mint, read to pick the pNFT transfer path
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub prize_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: master edition PDA of the prize mint, only used by pNFT transfers
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub prize_edition: UncheckedAccount<'info>,
+
+    /// CHECK: pNFT token record of `prize_escrow`, only used by pNFT transfers
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"token_record".as_ref(),
+            prize_escrow.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub escrow_token_record: UncheckedAccount<'info>,
+
+    /// CHECK: pNFT token record of `creator_prize_ata`, only used by pNFT transfers
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"token_record".as_ref(),
+            creator_prize_ata.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub creator_token_record: UncheckedAccount<'info>,
+
+    /// CHECK: only forwarded when the pNFT has a rule set, checked in `transfer_programmable_nft`
+    pub authorization_rules: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to Metaplex Token Auth Rules
+    #[account(address = AUTH_RULES_PROGRAM_ID)]
+    pub authorization_rules_program: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to Token Metadata, only invoked for pNFT prizes
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to the instructions sysvar, read by Token Metadata
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
     pub system_program: Program<'info, System>,
 }
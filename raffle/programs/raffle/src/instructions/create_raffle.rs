@@ -1,22 +1,27 @@
 use crate::constants::*;
-use crate::errors::{ConfigStateErrors, KeysMismatchErrors, RaffleStateErrors};
+use crate::errors::{
+    ConfigStateErrors, KeysMismatchErrors, RaffleStateErrors, RoleErrors, TransferErrors,
+};
 use crate::helpers::*;
 use crate::states::*;
-use crate::utils::{is_paused, validate_win_shares};
+use crate::utils::{has_role, is_paused, validate_win_shares};
 use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::mpl_token_metadata;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 
 #[event]
 pub struct RaffleCreated {
     pub raffle_id: u32,
     pub creator: Pubkey,
+    pub collection: Option<Pubkey>, // verified collection of an NFT prize
     pub start_time: i64,
     pub end_time: i64,
     pub created_at: i64,
 }
  
-pub fn create_raffle(
-    ctx: Context<CreateRaffle>,
+pub fn create_raffle<'info>(
+    ctx: Context<'_, '_, '_, 'info, CreateRaffle<'info>>,
     mut start_time: i64,
     end_time: i64,
     total_tickets: u16,
@@ -107,6 +112,7 @@ pub fn create_raffle(
     );
 
     // --- Set raffle metadata ---
+    raffle.version = RAFFLE_VERSION;
     raffle.raffle_id = config.raffle_count;
     raffle.creator = creator.key();
     raffle.start_time = start_time;
@@ -127,6 +133,7 @@ pub fn create_raffle(
         RaffleState::Initialized
     };
     raffle.raffle_bump = ctx.bumps.raffle;
+    raffle.ticket_fee_bps = config.ticket_fee_bps;
 
     // Pay creation fee separately
     if config.creation_fee_lamports > 0 {
@@ -139,6 +146,7 @@ pub fn create_raffle(
     }
 
     // --- Prize Handling ---
+    let mut collection = None;
     match prize_type {
         PrizeType::Sol => {
             raffle.prize_mint = None;
@@ -159,6 +167,16 @@ pub fn create_raffle(
 
             require!(prize_nft == is_nft, RaffleStateErrors::InvalidNFT);
 
+            if is_nft {
+                collection = verify_nft_prize(
+                    prize_mint,
+                    &ctx.accounts.prize_metadata,
+                    &ctx.accounts.prize_edition,
+                    ctx.accounts.prize_collection_allowlist.as_deref(),
+                    config.require_verified_collection,
+                )?;
+            }
+
             // Validate creator's prize ATA
             let creator_ata = &ctx.accounts.creator_prize_ata;
             require_keys_eq!(
@@ -184,16 +202,55 @@ pub fn create_raffle(
                 KeysMismatchErrors::InvalidPrizeMint
             );
 
-            // Transfer prize tokens
-            transfer_tokens(
-                creator_ata,
-                prize_escrow,
-                creator,
-                &ctx.accounts.prize_token_program,
+            check_mint_policy(
                 prize_mint,
-                prize_amount,
+                config.allowed_mint_extensions,
+                &ctx.accounts.prize_mint_allowlist,
             )?;
 
+            // Transfer prize tokens, winners are paid out of what actually reached the escrow
+            let received = if is_programmable_nft(&ctx.accounts.prize_metadata)? {
+                transfer_programmable_nft(
+                    creator_ata,
+                    creator,
+                    prize_escrow,
+                    &raffle.to_account_info(),
+                    prize_mint,
+                    creator,
+                    &ctx.accounts.prize_token_program,
+                    &ProgrammableNftAccounts {
+                        metadata: &ctx.accounts.prize_metadata,
+                        edition: &ctx.accounts.prize_edition,
+                        token_record: &ctx.accounts.creator_token_record,
+                        destination_token_record: &ctx.accounts.escrow_token_record,
+                        authorization_rules: &ctx.accounts.authorization_rules,
+                        authorization_rules_program: &ctx.accounts.authorization_rules_program,
+                        token_metadata_program: &ctx.accounts.token_metadata_program,
+                        sysvar_instructions: &ctx.accounts.instructions_sysvar,
+                        associated_token_program: &ctx.accounts.associated_token_program,
+                        system_program: &ctx.accounts.system_program,
+                    },
+                    &[],
+                )?;
+                prize_amount
+            } else {
+                transfer_tokens(
+                    creator_ata,
+                    prize_escrow,
+                    creator,
+                    &ctx.accounts.prize_token_program,
+                    prize_mint,
+                    ctx.remaining_accounts,
+                    prize_amount,
+                )?
+            };
+            require_gte!(
+                received,
+                num_winners as u64,
+                TransferErrors::InsufficientAmountReceived
+            );
+
+            raffle.prize_amount = received;
             raffle.prize_mint = Some(prize_mint_key);
         }
     }
@@ -217,6 +274,12 @@ pub fn create_raffle(
             KeysMismatchErrors::InvalidTicketMint
         );
 
+        check_mint_policy(
+            ticket_mint,
+            config.allowed_mint_extensions,
+            &ctx.accounts.ticket_mint_allowlist,
+        )?;
+
         raffle.ticket_mint = Some(ticket_mint_key);
     }
 
@@ -229,6 +292,7 @@ pub fn create_raffle(
     emit!(RaffleCreated {
         raffle_id: raffle.raffle_id,
         creator: creator.key(),
+        collection,
         start_time: raffle.start_time,
         end_time: raffle.end_time,
         created_at: now,
@@ -243,10 +307,16 @@ pub struct CreateRaffle<'info> {
         mut,
         seeds = [b"raffle"],
         bump = raffle_config.config_bump,
-        constraint = raffle_admin.key() == raffle_config.raffle_admin @ ConfigStateErrors::InvalidRaffleAdmin,
     )]
     pub raffle_config: Box<Account<'info, RaffleConfig>>,
 
+    #[account(
+        seeds = [b"raffle".as_ref(), b"roles".as_ref()],
+        bump = role_registry.registry_bump,
+        constraint = has_role(&role_registry, raffle_admin.key(), CREATOR_APPROVER_ROLE) @ RoleErrors::MissingRole
+    )]
+    pub role_registry: Box<Account<'info, RoleRegistry>>,
+
     #[account( 
         init,
         payer = creator,
@@ -267,6 +337,20 @@ pub struct CreateRaffle<'info> {
     // Mint used for prize (must be a valid mint(SPL or NFT) if `prize_type != PrizeType::Sol`)
     pub prize_mint: InterfaceAccount<'info, Mint>,
 
+    /// CHECK: only has to exist when the owner allowlisted the ticket mint, see `check_mint_policy`
+    #[account(
+        seeds = [b"raffle".as_ref(), b"mint_allowlist".as_ref(), ticket_mint.key().as_ref()],
+        bump
+    )]
+    pub ticket_mint_allowlist: UncheckedAccount<'info>,
+
+    /// CHECK: only has to exist when the owner allowlisted the prize mint, see `check_mint_policy`
+    #[account(
+        seeds = [b"raffle".as_ref(), b"mint_allowlist".as_ref(), prize_mint.key().as_ref()],
+        bump
+    )]
+    pub prize_mint_allowlist: UncheckedAccount<'info>,
+
     // Ticket escrow ATA (create ATA to store the tickets amount from the buyers and the owner of the ATA is raffle account, if ticket mint != sol)
     #[account(mut)]
     pub ticket_escrow: InterfaceAccount<'info, TokenAccount>,
@@ -282,5 +366,81 @@ pub struct CreateRaffle<'info> {
     pub ticket_token_program: Interface<'info, TokenInterface>,
     pub prize_token_program: Interface<'info, TokenInterface>,
 
+    /// CHECK: Token Metadata PDA of the prize mint, verified for NFT prizes and read to pick the pNFT transfer path
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub prize_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: master edition PDA of the prize mint, verified for NFT prizes and used by pNFT transfers
+    #[account(
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"edition".as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub prize_edition: UncheckedAccount<'info>,
+
+    /// CHECK: `CollectionAllowlist` entry of the prize's verified collection, only read when the config requires one
+    pub prize_collection_allowlist: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: pNFT token record of `creator_prize_ata`, only used by pNFT transfers
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"token_record".as_ref(),
+            creator_prize_ata.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub creator_token_record: UncheckedAccount<'info>,
+
+    /// CHECK: pNFT token record of `prize_escrow`, only used by pNFT transfers
+    #[account(
+        mut,
+        seeds = [
+            b"metadata".as_ref(),
+            token_metadata_program.key().as_ref(),
+            prize_mint.key().as_ref(),
+            b"token_record".as_ref(),
+            prize_escrow.key().as_ref(),
+        ],
+        seeds::program = token_metadata_program.key(),
+        bump
+    )]
+    pub escrow_token_record: UncheckedAccount<'info>,
+
+    /// CHECK: only forwarded when the pNFT has a rule set, checked in `transfer_programmable_nft`
+    pub authorization_rules: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to Metaplex Token Auth Rules
+    #[account(address = AUTH_RULES_PROGRAM_ID)]
+    pub authorization_rules_program: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to Token Metadata, only invoked for pNFT prizes
+    #[account(address = mpl_token_metadata::ID)]
+    pub token_metadata_program: UncheckedAccount<'info>,
+
+    /// CHECK: address is pinned to the instructions sysvar, read by Token Metadata
+    #[account(address = solana_sdk_ids::sysvar::instructions::ID)]
+    pub instructions_sysvar: UncheckedAccount<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
     pub system_program: Program<'info, System>,
 }
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+use crate::constants::PAUSER_ROLE;
+use crate::errors::{RaffleStateErrors, RoleErrors};
+use crate::states::{Raffle, RoleRegistry};
+use crate::utils::has_role;
+
+#[event]
+pub struct RaffleFrozenUpdated {
+    pub raffle_id: u32,
+    pub frozen: bool,
+    pub updated_at: i64,
+}
+
+// Halts (or resumes) a single raffle without touching the program wide pause flags
+pub fn set_raffle_frozen(ctx: Context<SetRaffleFrozen>, raffle_id: u32, frozen: bool) -> Result<()> {
+    let raffle = &mut ctx.accounts.raffle;
+
+    raffle.frozen = frozen;
+
+    emit!(RaffleFrozenUpdated {
+        raffle_id,
+        frozen,
+        updated_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(raffle_id: u32)]
+pub struct SetRaffleFrozen<'info> {
+    #[account(
+        seeds = [b"raffle".as_ref(), b"roles".as_ref()],
+        bump = role_registry.registry_bump,
+        constraint = has_role(&role_registry, pauser.key(), PAUSER_ROLE) @ RoleErrors::MissingRole
+    )]
+    pub role_registry: Box<Account<'info, RoleRegistry>>,
+
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle_id.to_le_bytes().as_ref()],
+        bump = raffle.raffle_bump,
+        constraint = raffle.raffle_id == raffle_id @ RaffleStateErrors::InvalidRaffleId,
+    )]
+    pub raffle: Box<Account<'info, Raffle>>,
+
+    pub pauser: Signer<'info>,
+}
@@ -0,0 +1,149 @@
+use anchor_lang::prelude::*;
+use crate::constants::{
+    FEE_WITHDRAWER_ROLE, MAXIMUM_ROLE_MEMBERS, PAUSER_ROLE, ROLE_COUNT, ROLE_REGISTRY_VERSION,
+};
+use crate::errors::{ConfigStateErrors, RoleErrors};
+use crate::states::{RaffleConfig, RoleMember, RoleRegistry};
+
+#[event]
+pub struct RoleGranted {
+    pub member: Pubkey,
+    pub role: u8,
+}
+
+#[event]
+pub struct RoleRevoked {
+    pub member: Pubkey,
+    pub role: u8,
+}
+
+// Roles start with whoever held them before the registry: the owner pauses and withdraws fees,
+// the `raffle_admin` keeps co-signing and announcing
+pub fn initialize_role_registry(ctx: Context<InitializeRoleRegistry>) -> Result<()> {
+    let registry = &mut ctx.accounts.role_registry;
+    let raffle_owner = ctx.accounts.raffle_config.raffle_owner;
+    let raffle_admin = ctx.accounts.raffle_config.raffle_admin;
+    let owner_roles = (1u8 << PAUSER_ROLE) | (1u8 << FEE_WITHDRAWER_ROLE);
+    let admin_roles = ((1u8 << ROLE_COUNT) - 1) & !owner_roles;
+
+    registry.version = ROLE_REGISTRY_VERSION;
+    registry.members = if raffle_owner == raffle_admin {
+        vec![RoleMember {
+            member: raffle_owner,
+            roles: owner_roles | admin_roles,
+        }]
+    } else {
+        vec![
+            RoleMember {
+                member: raffle_owner,
+                roles: owner_roles,
+            },
+            RoleMember {
+                member: raffle_admin,
+                roles: admin_roles,
+            },
+        ]
+    };
+    registry.registry_bump = ctx.bumps.role_registry;
+
+    for role in 0..ROLE_COUNT {
+        let member = if owner_roles & (1u8 << role) != 0 {
+            raffle_owner
+        } else {
+            raffle_admin
+        };
+        emit!(RoleGranted { member, role });
+    }
+
+    Ok(())
+}
+
+pub fn grant_role(ctx: Context<UpdateRoles>, member: Pubkey, role: u8) -> Result<()> {
+    require_gt!(ROLE_COUNT, role, RoleErrors::InvalidRole);
+
+    let registry = &mut ctx.accounts.role_registry;
+    let mask = 1u8 << role;
+
+    match registry.members.iter_mut().find(|m| m.member == member) {
+        Some(existing) => existing.roles |= mask,
+        None => {
+            require_gt!(
+                MAXIMUM_ROLE_MEMBERS,
+                registry.members.len(),
+                RoleErrors::TooManyRoleMembers
+            );
+            registry.members.push(RoleMember {
+                member,
+                roles: mask,
+            });
+        }
+    }
+
+    emit!(RoleGranted { member, role });
+
+    Ok(())
+}
+
+pub fn revoke_role(ctx: Context<UpdateRoles>, member: Pubkey, role: u8) -> Result<()> {
+    require_gt!(ROLE_COUNT, role, RoleErrors::InvalidRole);
+
+    let registry = &mut ctx.accounts.role_registry;
+    let mask = 1u8 << role;
+
+    let existing = registry
+        .members
+        .iter_mut()
+        .find(|m| m.member == member && (m.roles & mask) != 0)
+        .ok_or(RoleErrors::RoleNotGranted)?;
+    existing.roles &= !mask;
+
+    // drop keys without any role left so their slot can be reused
+    registry.members.retain(|m| m.roles != 0);
+
+    emit!(RoleRevoked { member, role });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeRoleRegistry<'info> {
+    #[account(
+        seeds = [b"raffle"],
+        bump = raffle_config.config_bump,
+        constraint = raffle_owner.key() == raffle_config.raffle_owner @ ConfigStateErrors::InvalidRaffleOwner
+    )]
+    pub raffle_config: Box<Account<'info, RaffleConfig>>,
+
+    #[account(
+        init,
+        payer = raffle_owner,
+        space = 8 + RoleRegistry::INIT_SPACE,
+        seeds = [b"raffle".as_ref(), b"roles".as_ref()],
+        bump
+    )]
+    pub role_registry: Box<Account<'info, RoleRegistry>>,
+
+    #[account(mut)]
+    pub raffle_owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRoles<'info> {
+    #[account(
+        seeds = [b"raffle"],
+        bump = raffle_config.config_bump,
+        constraint = raffle_owner.key() == raffle_config.raffle_owner @ ConfigStateErrors::InvalidRaffleOwner
+    )]
+    pub raffle_config: Box<Account<'info, RaffleConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"raffle".as_ref(), b"roles".as_ref()],
+        bump = role_registry.registry_bump,
+    )]
+    pub role_registry: Box<Account<'info, RoleRegistry>>,
+
+    pub raffle_owner: Signer<'info>,
+}
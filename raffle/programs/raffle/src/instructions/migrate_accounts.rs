@@ -0,0 +1,242 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use crate::constants::{MINIMUM_CONFIG_CHANGE_DELAY, RAFFLE_CONFIG_VERSION, RAFFLE_VERSION};
+use crate::errors::{ConfigStateErrors, MigrationErrors, RaffleStateErrors};
+use crate::helpers::resize_account;
+use crate::states::*;
+
+#[event]
+pub struct AccountMigrated {
+    pub account: Pubkey,
+    pub version: u8,
+    pub migrated_at: i64,
+}
+
+fn check_owner_and_discriminator(account: &AccountInfo, discriminator: &[u8]) -> Result<()> {
+    require_keys_eq!(
+        *account.owner,
+        crate::ID,
+        MigrationErrors::InvalidAccountOwner
+    );
+
+    let data = account.try_borrow_data()?;
+    require!(
+        data.len() >= 8 && &data[..8] == discriminator,
+        MigrationErrors::InvalidDiscriminator
+    );
+
+    Ok(())
+}
+
+// Decode a legacy account body once its owner, discriminator and legacy size are confirmed
+fn read_legacy<T: AnchorDeserialize>(
+    account: &AccountInfo,
+    discriminator: &[u8],
+    legacy_len: usize,
+) -> Result<T> {
+    check_owner_and_discriminator(account, discriminator)?;
+
+    let data = account.try_borrow_data()?;
+    // anything other than the legacy size is already versioned
+    require_eq!(data.len(), legacy_len, MigrationErrors::AlreadyMigrated);
+
+    T::deserialize(&mut &data[8..]).map_err(|_| error!(ErrorCode::AccountDidNotDeserialize))
+}
+
+// Decode a versioned account body that is still on layout `version`
+fn read_versioned<T: AnchorDeserialize>(
+    account: &AccountInfo,
+    discriminator: &[u8],
+    version: u8,
+) -> Result<T> {
+    check_owner_and_discriminator(account, discriminator)?;
+
+    let data = account.try_borrow_data()?;
+    require!(
+        data.get(8) == Some(&version),
+        MigrationErrors::AlreadyMigrated
+    );
+
+    T::deserialize(&mut &data[8..]).map_err(|_| error!(ErrorCode::AccountDidNotDeserialize))
+}
+
+fn write_migrated<T: AccountSerialize>(account: &AccountInfo, migrated: &T) -> Result<()> {
+    let mut data = account.try_borrow_mut_data()?;
+    migrated.try_serialize(&mut &mut data[..])
+}
+
+pub fn migrate_raffle_config(ctx: Context<MigrateRaffleConfig>) -> Result<()> {
+    let config_ai = ctx.accounts.raffle_config.to_account_info();
+
+    // version 0 is only recognisable by its size, later versions carry the `version` byte
+    let legacy: RaffleConfigV1 = if config_ai.data_len() == 8 + RaffleConfigV0::INIT_SPACE {
+        let v0: RaffleConfigV0 = read_legacy(
+            &config_ai,
+            RaffleConfig::DISCRIMINATOR,
+            8 + RaffleConfigV0::INIT_SPACE,
+        )?;
+
+        RaffleConfigV1 {
+            version: 1,
+            raffle_owner: v0.raffle_owner,
+            raffle_admin: v0.raffle_admin,
+            creation_fee_lamports: v0.creation_fee_lamports,
+            ticket_fee_bps: v0.ticket_fee_bps,
+            minimum_raffle_period: v0.minimum_raffle_period,
+            maximum_raffle_period: v0.maximum_raffle_period,
+            raffle_count: v0.raffle_count,
+            pause_flags: v0.pause_flags,
+            config_bump: v0.config_bump,
+            pending_owner: None,
+            config_change_delay: MINIMUM_CONFIG_CHANGE_DELAY,
+            pending_config_data: None,
+            reserved: [0u8; 60],
+        }
+    } else {
+        read_versioned(&config_ai, RaffleConfig::DISCRIMINATOR, 1)?
+    };
+
+    let migrated = RaffleConfig {
+        version: RAFFLE_CONFIG_VERSION,
+        raffle_owner: legacy.raffle_owner,
+        raffle_admin: legacy.raffle_admin,
+        creation_fee_lamports: legacy.creation_fee_lamports,
+        ticket_fee_bps: legacy.ticket_fee_bps,
+        minimum_raffle_period: legacy.minimum_raffle_period,
+        maximum_raffle_period: legacy.maximum_raffle_period,
+        raffle_count: legacy.raffle_count,
+        // the old eight bits keep their indexes, the new ones start unpaused
+        pause_flags: legacy.pause_flags as u64,
+        config_bump: legacy.config_bump,
+        pending_owner: legacy.pending_owner,
+        config_change_delay: legacy.config_change_delay,
+        pending_config_data: legacy.pending_config_data,
+        allowed_mint_extensions: 0,
+        require_verified_collection: false,
+        reserved: [0u8; 51],
+    };
+
+    require_keys_eq!(
+        migrated.raffle_owner,
+        ctx.accounts.raffle_owner.key(),
+        ConfigStateErrors::InvalidRaffleOwner
+    );
+
+    resize_account(
+        &config_ai,
+        &ctx.accounts.raffle_owner,
+        &ctx.accounts.system_program,
+        8 + RaffleConfig::INIT_SPACE,
+    )?;
+    write_migrated(&config_ai, &migrated)?;
+
+    emit!(AccountMigrated {
+        account: config_ai.key(),
+        version: RAFFLE_CONFIG_VERSION,
+        migrated_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+// Permissionless: the new layout only adds defaults, the payer just covers the extra rent.
+// The config has to be migrated first, its current fee becomes the raffle's snapshot.
+pub fn migrate_raffle(ctx: Context<MigrateRaffle>, _raffle_id: u32) -> Result<()> {
+    let raffle_ai = ctx.accounts.raffle.to_account_info();
+
+    let legacy: RaffleV0 = read_legacy(
+        &raffle_ai,
+        Raffle::DISCRIMINATOR,
+        8 + RaffleV0::INIT_SPACE,
+    )?;
+
+    let migrated = Raffle {
+        version: RAFFLE_VERSION,
+        raffle_id: legacy.raffle_id,
+        creator: legacy.creator,
+        start_time: legacy.start_time,
+        end_time: legacy.end_time,
+        total_tickets: legacy.total_tickets,
+        tickets_sold: legacy.tickets_sold,
+        buyers_count: legacy.buyers_count,
+        ticket_price: legacy.ticket_price,
+        ticket_mint: legacy.ticket_mint,
+        max_per_wallet_pct: legacy.max_per_wallet_pct,
+        prize_type: legacy.prize_type,
+        prize_amount: legacy.prize_amount,
+        prize_mint: legacy.prize_mint,
+        num_winners: legacy.num_winners,
+        is_unique_winners: legacy.is_unique_winners,
+        status: legacy.status,
+        win_shares: legacy.win_shares,
+        winners: legacy.winners,
+        is_win_claimed: legacy.is_win_claimed,
+        claimable_ticket_amount: legacy.claimable_ticket_amount,
+        claimable_prize_back: legacy.claimable_prize_back,
+        raffle_bump: legacy.raffle_bump,
+        // legacy raffles charged whatever the config held, freeze that value now
+        ticket_fee_bps: ctx.accounts.raffle_config.ticket_fee_bps,
+        frozen: false,
+        // legacy escrows always received the full price
+        ticket_revenue: legacy
+            .ticket_price
+            .checked_mul(legacy.tickets_sold as u64)
+            .ok_or(RaffleStateErrors::Overflow)?,
+        reserved: [0u8; 117],
+    };
+
+    resize_account(
+        &raffle_ai,
+        &ctx.accounts.payer,
+        &ctx.accounts.system_program,
+        8 + Raffle::INIT_SPACE,
+    )?;
+    write_migrated(&raffle_ai, &migrated)?;
+
+    emit!(AccountMigrated {
+        account: raffle_ai.key(),
+        version: RAFFLE_VERSION,
+        migrated_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct MigrateRaffleConfig<'info> {
+    /// CHECK: still on the legacy layout, owner and discriminator are checked in the handler
+    #[account(
+        mut,
+        seeds = [b"raffle"],
+        bump
+    )]
+    pub raffle_config: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub raffle_owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(raffle_id: u32)]
+pub struct MigrateRaffle<'info> {
+    /// CHECK: still on the legacy layout, owner and discriminator are checked in the handler
+    #[account(
+        mut,
+        seeds = [b"raffle", raffle_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub raffle: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"raffle"],
+        bump = raffle_config.config_bump,
+    )]
+    pub raffle_config: Box<Account<'info, RaffleConfig>>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
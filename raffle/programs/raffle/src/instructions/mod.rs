@@ -1,10 +1,14 @@
 pub mod activate_raffle;
+pub mod allowlist;
 pub mod announce_winners;
 pub mod buy_ticket;
 pub mod buyer_claim_prize;
 pub mod cancel_raffle;
 pub mod claim_amount_back;
 pub mod create_raffle;
+pub mod freeze_raffle;
+pub mod manage_roles;
+pub mod migrate_accounts;
 pub mod process_raffle_config;
 pub mod update_raffle_ticketing;
 pub mod update_raffle_time;
@@ -13,12 +17,16 @@ pub mod withdraw_sol_fees;
 pub mod withdraw_spl_fees;
 
 pub use activate_raffle::*;
+pub use allowlist::*;
 pub use announce_winners::*;
 pub use buy_ticket::*;
 pub use buyer_claim_prize::*;
 pub use cancel_raffle::*;
 pub use claim_amount_back::*;
 pub use create_raffle::*;
+pub use freeze_raffle::*;
+pub use manage_roles::*;
+pub use migrate_accounts::*;
 pub use process_raffle_config::*;
 pub use update_raffle_ticketing::*;
 pub use update_raffle_time::*;
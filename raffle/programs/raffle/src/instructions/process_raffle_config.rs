@@ -1,6 +1,70 @@
 use anchor_lang::prelude::*;
-use crate::errors::ConfigStateErrors;
-use crate::states::RaffleConfig;
+use crate::constants::{
+    FEE_MANTISSA, MINIMUM_CONFIG_CHANGE_DELAY, MINT_EXTENSION_COUNT, PAUSER_ROLE, RAFFLE_CONFIG_VERSION,
+    UPDATE_CONFIG_PAUSE,
+};
+use crate::errors::{ConfigStateErrors, MintPolicyErrors, RaffleStateErrors, RoleErrors};
+use crate::states::{PendingRaffleConfigData, RaffleConfig, RoleRegistry};
+use crate::utils::{has_role, is_paused};
+
+#[event]
+pub struct OwnershipProposed {
+    pub current_owner: Pubkey,
+    pub proposed_owner: Pubkey,
+    pub proposed_at: i64,
+}
+
+#[event]
+pub struct OwnershipTransferred {
+    pub previous_owner: Pubkey,
+    pub new_owner: Pubkey,
+    pub transferred_at: i64,
+}
+
+#[event]
+pub struct OwnershipProposalCancelled {
+    pub owner: Pubkey,
+    pub cancelled_owner: Pubkey,
+    pub cancelled_at: i64,
+}
+
+#[event]
+pub struct ConfigChangeQueued {
+    pub creation_fee_lamports: u64,
+    pub ticket_fee_bps: u16,
+    pub minimum_raffle_period: u32,
+    pub maximum_raffle_period: u32,
+    pub config_change_delay: u32,
+    pub effective_at: i64,
+}
+
+#[event]
+pub struct ConfigChangeApplied {
+    pub creation_fee_lamports: u64,
+    pub ticket_fee_bps: u16,
+    pub minimum_raffle_period: u32,
+    pub maximum_raffle_period: u32,
+    pub config_change_delay: u32,
+    pub applied_at: i64,
+}
+
+#[event]
+pub struct ConfigChangeCancelled {
+    pub effective_at: i64,
+    pub cancelled_at: i64,
+}
+
+#[event]
+pub struct MintExtensionPolicyUpdated {
+    pub allowed_mint_extensions: u8,
+    pub updated_at: i64,
+}
+
+#[event]
+pub struct CollectionPolicyUpdated {
+    pub require_verified_collection: bool,
+    pub updated_at: i64,
+}
 
 pub fn initialize_raffle_config(
     ctx: Context<InitializeRaffleConfig>,
@@ -18,6 +82,7 @@ pub fn initialize_raffle_config(
  
     let raffle_config = &mut ctx.accounts.raffle_config;
 
+    raffle_config.version = RAFFLE_CONFIG_VERSION;
     raffle_config.raffle_owner = raffle_owner;
     raffle_config.raffle_admin = raffle_admin;
     raffle_config.creation_fee_lamports = creation_fee_lamports;
@@ -25,62 +90,228 @@ pub fn initialize_raffle_config(
     raffle_config.minimum_raffle_period = minimum_raffle_period;
     raffle_config.maximum_raffle_period = maximum_raffle_period;
     raffle_config.raffle_count = 1;
+    raffle_config.config_change_delay = MINIMUM_CONFIG_CHANGE_DELAY;
     raffle_config.config_bump = ctx.bumps.raffle_config;
 
     Ok(())
 }
 
-pub fn update_raffle_config_owner(
-    ctx: Context<UpdateRaffleConfig>,
-    new_raffle_owner: Pubkey,
-) -> Result<()> {
+// Ownership moves in two steps, so a mistyped key can never take over the config
+pub fn propose_owner(ctx: Context<UpdateRaffleConfig>, new_raffle_owner: Pubkey) -> Result<()> {
     let raffle_config = &mut ctx.accounts.raffle_config;
 
+    raffle_config.pending_owner = Some(new_raffle_owner);
+
+    emit!(OwnershipProposed {
+        current_owner: raffle_config.raffle_owner,
+        proposed_owner: new_raffle_owner,
+        proposed_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn accept_owner(ctx: Context<AcceptRaffleOwner>) -> Result<()> {
+    let raffle_config = &mut ctx.accounts.raffle_config;
+    let new_raffle_owner = ctx.accounts.new_raffle_owner.key();
+
+    let pending_owner = raffle_config
+        .pending_owner
+        .ok_or(ConfigStateErrors::NoPendingOwner)?;
+    require_keys_eq!(
+        pending_owner,
+        new_raffle_owner,
+        ConfigStateErrors::InvalidPendingOwner
+    );
+
+    let previous_owner = raffle_config.raffle_owner;
     raffle_config.raffle_owner = new_raffle_owner;
+    raffle_config.pending_owner = None;
+
+    emit!(OwnershipTransferred {
+        previous_owner,
+        new_owner: new_raffle_owner,
+        transferred_at: Clock::get()?.unix_timestamp,
+    });
 
     Ok(())
 }
 
-pub fn update_raffle_config_admin(
+pub fn cancel_owner_proposal(ctx: Context<UpdateRaffleConfig>) -> Result<()> {
+    let raffle_config = &mut ctx.accounts.raffle_config;
+
+    let cancelled_owner = raffle_config
+        .pending_owner
+        .take()
+        .ok_or(ConfigStateErrors::NoPendingOwner)?;
+
+    emit!(OwnershipProposalCancelled {
+        owner: raffle_config.raffle_owner,
+        cancelled_owner,
+        cancelled_at: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+pub fn update_pause_and_unpause(
+    ctx: Context<UpdatePauseFlags>,
+    new_pause_flags: u64,
+) -> Result<()> {
+    let raffle_config = &mut ctx.accounts.raffle_config;
+
+    raffle_config.pause_flags = new_pause_flags;
+
+    Ok(())
+}
+
+// Only checked when a raffle is created, live raffles keep whatever mints they were created with
+pub fn set_allowed_mint_extensions(
     ctx: Context<UpdateRaffleConfig>,
-    new_raffle_admin: Pubkey,
+    allowed_mint_extensions: u8,
 ) -> Result<()> {
+    require!(
+        allowed_mint_extensions >> MINT_EXTENSION_COUNT == 0,
+        MintPolicyErrors::InvalidMintExtension
+    );
+
     let raffle_config = &mut ctx.accounts.raffle_config;
 
-    raffle_config.raffle_admin = new_raffle_admin;
+    raffle_config.allowed_mint_extensions = allowed_mint_extensions;
+
+    emit!(MintExtensionPolicyUpdated {
+        allowed_mint_extensions,
+        updated_at: Clock::get()?.unix_timestamp,
+    });
 
     Ok(())
 }
 
-pub fn update_pause_and_unpause(
+// Only checked for raffles created from now on, live ones keep the prize they were created with
+pub fn set_collection_policy(
     ctx: Context<UpdateRaffleConfig>,
-    new_pause_flags: u8,
+    require_verified_collection: bool,
 ) -> Result<()> {
     let raffle_config = &mut ctx.accounts.raffle_config;
 
-    raffle_config.pause_flags = new_pause_flags;
+    raffle_config.require_verified_collection = require_verified_collection;
+
+    emit!(CollectionPolicyUpdated {
+        require_verified_collection,
+        updated_at: Clock::get()?.unix_timestamp,
+    });
 
     Ok(())
 }
 
-pub fn update_raffle_config_data(
+// Fee and period changes are queued, so creators always get `config_change_delay` of notice
+pub fn queue_raffle_config_data(
     ctx: Context<UpdateRaffleConfig>,
     creation_fee_lamports: u64,
     ticket_fee_bps: u16,
     minimum_raffle_period: u32,
     maximum_raffle_period: u32,
+    config_change_delay: u32,
 ) -> Result<()> {
+    require!(
+        !is_paused(ctx.accounts.raffle_config.pause_flags, UPDATE_CONFIG_PAUSE),
+        RaffleStateErrors::FunctionPaused
+    );
     require!(
         minimum_raffle_period > 0 && maximum_raffle_period > minimum_raffle_period,
         ConfigStateErrors::InvalidRafflePeriod
     );
+    require_gte!(
+        FEE_MANTISSA,
+        ticket_fee_bps,
+        ConfigStateErrors::InvalidTicketFee
+    );
+    require_gte!(
+        config_change_delay,
+        MINIMUM_CONFIG_CHANGE_DELAY,
+        ConfigStateErrors::InvalidConfigChangeDelay
+    );
 
     let raffle_config = &mut ctx.accounts.raffle_config;
 
-    raffle_config.creation_fee_lamports = creation_fee_lamports;
-    raffle_config.ticket_fee_bps = ticket_fee_bps;
-    raffle_config.minimum_raffle_period = minimum_raffle_period;
-    raffle_config.maximum_raffle_period = maximum_raffle_period;
+    // a zeroed delay (freshly carved out of `reserved`) still waits the minimum
+    let current_delay = raffle_config.config_change_delay.max(MINIMUM_CONFIG_CHANGE_DELAY);
+    let effective_at = Clock::get()?
+        .unix_timestamp
+        .checked_add(current_delay as i64)
+        .ok_or(RaffleStateErrors::Overflow)?;
+
+    // queuing again replaces the previous change and restarts the delay
+    raffle_config.pending_config_data = Some(PendingRaffleConfigData {
+        creation_fee_lamports,
+        ticket_fee_bps,
+        minimum_raffle_period,
+        maximum_raffle_period,
+        config_change_delay,
+        effective_at,
+    });
+
+    emit!(ConfigChangeQueued {
+        creation_fee_lamports,
+        ticket_fee_bps,
+        minimum_raffle_period,
+        maximum_raffle_period,
+        config_change_delay,
+        effective_at,
+    });
+
+    Ok(())
+}
+
+pub fn apply_raffle_config_data(ctx: Context<UpdateRaffleConfig>) -> Result<()> {
+    require!(
+        !is_paused(ctx.accounts.raffle_config.pause_flags, UPDATE_CONFIG_PAUSE),
+        RaffleStateErrors::FunctionPaused
+    );
+
+    let raffle_config = &mut ctx.accounts.raffle_config;
+    let now = Clock::get()?.unix_timestamp;
+
+    let pending = raffle_config
+        .pending_config_data
+        .ok_or(ConfigStateErrors::NoPendingConfigChange)?;
+    require_gte!(
+        now,
+        pending.effective_at,
+        ConfigStateErrors::ConfigChangeNotReady
+    );
+
+    raffle_config.creation_fee_lamports = pending.creation_fee_lamports;
+    raffle_config.ticket_fee_bps = pending.ticket_fee_bps;
+    raffle_config.minimum_raffle_period = pending.minimum_raffle_period;
+    raffle_config.maximum_raffle_period = pending.maximum_raffle_period;
+    raffle_config.config_change_delay = pending.config_change_delay;
+    raffle_config.pending_config_data = None;
+
+    emit!(ConfigChangeApplied {
+        creation_fee_lamports: pending.creation_fee_lamports,
+        ticket_fee_bps: pending.ticket_fee_bps,
+        minimum_raffle_period: pending.minimum_raffle_period,
+        maximum_raffle_period: pending.maximum_raffle_period,
+        config_change_delay: pending.config_change_delay,
+        applied_at: now,
+    });
+
+    Ok(())
+}
+
+pub fn cancel_raffle_config_data(ctx: Context<UpdateRaffleConfig>) -> Result<()> {
+    let raffle_config = &mut ctx.accounts.raffle_config;
+
+    let pending = raffle_config
+        .pending_config_data
+        .take()
+        .ok_or(ConfigStateErrors::NoPendingConfigChange)?;
+
+    emit!(ConfigChangeCancelled {
+        effective_at: pending.effective_at,
+        cancelled_at: Clock::get()?.unix_timestamp,
+    });
 
     Ok(())
 }
@@ -115,3 +346,33 @@ pub struct UpdateRaffleConfig<'info> {
     #[account(mut)]
     pub raffle_owner: Signer<'info>,
 }
+
+#[derive(Accounts)]
+pub struct AcceptRaffleOwner<'info> {
+    #[account(
+        mut,
+        seeds = [b"raffle"],
+        bump = raffle_config.config_bump,
+    )]
+    pub raffle_config: Box<Account<'info, RaffleConfig>>,
+
+    pub new_raffle_owner: Signer<'info>,
+}
+#[derive(Accounts)]
+pub struct UpdatePauseFlags<'info> {
+    #[account(
+        mut,
+        seeds = [b"raffle"],
+        bump = raffle_config.config_bump,
+    )]
+    pub raffle_config: Box<Account<'info, RaffleConfig>>,
+
+    #[account(
+        seeds = [b"raffle".as_ref(), b"roles".as_ref()],
+        bump = role_registry.registry_bump,
+        constraint = has_role(&role_registry, pauser.key(), PAUSER_ROLE) @ RoleErrors::MissingRole
+    )]
+    pub role_registry: Box<Account<'info, RoleRegistry>>,
+
+    pub pauser: Signer<'info>,
+}
@@ -1,8 +1,8 @@
 use anchor_lang::prelude::*;
-use crate::constants::UPDATE_RAFFLE_PAUSE;
-use crate::errors::{ConfigStateErrors, RaffleStateErrors};
+use crate::constants::{CREATOR_APPROVER_ROLE, UPDATE_RAFFLE_PAUSE};
+use crate::errors::{RaffleStateErrors, RoleErrors};
 use crate::states::*;
-use crate::utils::{is_paused, validate_win_shares};
+use crate::utils::{has_role, is_paused, validate_win_shares};
 
 #[event]
 pub struct RaffleWinnersUpdated {
@@ -66,15 +66,22 @@ pub struct UpdateRaffleWinners<'info> {
     #[account(
         seeds = [b"raffle"],
         bump = raffle_config.config_bump,
-        constraint = raffle_config.raffle_admin == raffle_admin.key() @ ConfigStateErrors::InvalidRaffleAdmin,
     )]
     pub raffle_config: Box<Account<'info, RaffleConfig>>,
 
+    #[account(
+        seeds = [b"raffle".as_ref(), b"roles".as_ref()],
+        bump = role_registry.registry_bump,
+        constraint = has_role(&role_registry, raffle_admin.key(), CREATOR_APPROVER_ROLE) @ RoleErrors::MissingRole
+    )]
+    pub role_registry: Box<Account<'info, RoleRegistry>>,
+
     #[account(
         mut,
         seeds = [b"raffle", raffle_id.to_le_bytes().as_ref()],
         bump = raffle.raffle_bump,
         constraint = raffle.raffle_id == raffle_id @ RaffleStateErrors::InvalidRaffleId,
+        constraint = !raffle.frozen @ RaffleStateErrors::RaffleFrozen,
     )]
     pub raffle: Box<Account<'info, Raffle>>,
 
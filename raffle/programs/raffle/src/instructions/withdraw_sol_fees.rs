@@ -1,5 +1,7 @@
-use crate::errors::{ConfigStateErrors, TransferErrors};
-use crate::states::RaffleConfig;
+use crate::constants::{FEE_WITHDRAWER_ROLE, WITHDRAW_FEES_PAUSE};
+use crate::errors::{RaffleStateErrors, RoleErrors, TransferErrors};
+use crate::states::{RaffleConfig, RoleRegistry};
+use crate::utils::{has_role, is_paused};
 use anchor_lang::prelude::*;
 use anchor_lang::system_program::System;
 
@@ -10,6 +12,11 @@ pub struct FeesWithdrawn {
 }
 
 pub fn withdraw_sol_fees(ctx: Context<WithdrawSolFees>, amount: u64) -> Result<()> {
+    require!(
+        !is_paused(ctx.accounts.raffle_config.pause_flags, WITHDRAW_FEES_PAUSE),
+        RaffleStateErrors::FunctionPaused
+    );
+
     let raffle_config = &ctx.accounts.raffle_config;
     let from = raffle_config.to_account_info();
     let to = ctx.accounts.receiver.to_account_info();
@@ -41,13 +48,18 @@ pub struct WithdrawSolFees<'info> {
         mut,
         seeds = [b"raffle"],
         bump = raffle_config.config_bump,
-        constraint = raffle_config.raffle_owner == owner.key() @ ConfigStateErrors::InvalidRaffleOwner,
     )]
     pub raffle_config: Account<'info, RaffleConfig>,
 
-    // Must be the config owner (not admin)
+    #[account(
+        seeds = [b"raffle".as_ref(), b"roles".as_ref()],
+        bump = role_registry.registry_bump,
+        constraint = has_role(&role_registry, fee_withdrawer.key(), FEE_WITHDRAWER_ROLE) @ RoleErrors::MissingRole
+    )]
+    pub role_registry: Box<Account<'info, RoleRegistry>>,
+
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub fee_withdrawer: Signer<'info>,
 
     #[account(mut)]
     /// CHECK: receiver may be PDA or wallet address
@@ -1,8 +1,10 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
-use crate::errors::ConfigStateErrors;
+use crate::constants::{FEE_WITHDRAWER_ROLE, WITHDRAW_FEES_PAUSE};
+use crate::errors::{RaffleStateErrors, RoleErrors};
 use crate::helpers::transfer_tokens_with_seeds;
-use crate::states::RaffleConfig;
+use crate::states::{RaffleConfig, RoleRegistry};
+use crate::utils::{has_role, is_paused};
 
 #[event]
 pub struct SplFeesWithdrawn {
@@ -11,8 +13,16 @@ pub struct SplFeesWithdrawn {
     pub receiver: Pubkey,
 }
 
-// Withdraw accumulated SPL fees from the treasury ATA, Only the raffle owner can withdraw
-pub fn withdraw_spl_fees(ctx: Context<WithdrawSplFees>, amount: u64) -> Result<()> {
+// Withdraw accumulated SPL fees from the treasury ATA, Only a fee withdrawer can withdraw
+pub fn withdraw_spl_fees<'info>(
+    ctx: Context<'_, '_, '_, 'info, WithdrawSplFees<'info>>,
+    amount: u64,
+) -> Result<()> {
+    require!(
+        !is_paused(ctx.accounts.raffle_config.pause_flags, WITHDRAW_FEES_PAUSE),
+        RaffleStateErrors::FunctionPaused
+    );
+
     let config = &ctx.accounts.raffle_config;
 
     let signer_seeds: &[&[&[u8]]] = &[&[b"raffle", &[config.config_bump]]];
@@ -23,6 +33,7 @@ pub fn withdraw_spl_fees(ctx: Context<WithdrawSplFees>, amount: u64) -> Result<(
         &ctx.accounts.raffle_config.to_account_info(),
         &ctx.accounts.token_program,
         &ctx.accounts.fee_mint,
+        ctx.remaining_accounts,
         signer_seeds,
         amount,
     )?;
@@ -30,7 +41,7 @@ pub fn withdraw_spl_fees(ctx: Context<WithdrawSplFees>, amount: u64) -> Result<(
     emit!(SplFeesWithdrawn {
         amount,
         mint: ctx.accounts.fee_mint.key(),
-        receiver: ctx.accounts.owner.key(),
+        receiver: ctx.accounts.fee_withdrawer.key(),
     });
 
     Ok(())
@@ -42,12 +53,18 @@ pub struct WithdrawSplFees<'info> {
         mut,
         seeds = [b"raffle"],
         bump = raffle_config.config_bump,
-        constraint = raffle_config.raffle_owner == owner.key() @ ConfigStateErrors::InvalidRaffleOwner
     )]
     pub raffle_config: Account<'info, RaffleConfig>,
 
+    #[account(
+        seeds = [b"raffle".as_ref(), b"roles".as_ref()],
+        bump = role_registry.registry_bump,
+        constraint = has_role(&role_registry, fee_withdrawer.key(), FEE_WITHDRAWER_ROLE) @ RoleErrors::MissingRole
+    )]
+    pub role_registry: Box<Account<'info, RoleRegistry>>,
+
     #[account(mut)]
-    pub owner: Signer<'info>,
+    pub fee_withdrawer: Signer<'info>,
 
     // The mint of the fee token being withdrawn
     pub fee_mint: InterfaceAccount<'info, Mint>,
@@ -34,45 +34,113 @@ pub mod raffle {
         )
     }
 
-    pub fn update_raffle_config_owner(
-        ctx: Context<UpdateRaffleConfig>,
-        new_raffle_owner: Pubkey,
-    ) -> Result<()> {
-        process_raffle_config::update_raffle_config_owner(ctx, new_raffle_owner)
+    pub fn propose_owner(ctx: Context<UpdateRaffleConfig>, new_raffle_owner: Pubkey) -> Result<()> {
+        process_raffle_config::propose_owner(ctx, new_raffle_owner)
+    }
+
+    pub fn accept_owner(ctx: Context<AcceptRaffleOwner>) -> Result<()> {
+        process_raffle_config::accept_owner(ctx)
+    }
+
+    pub fn cancel_owner_proposal(ctx: Context<UpdateRaffleConfig>) -> Result<()> {
+        process_raffle_config::cancel_owner_proposal(ctx)
     }
 
     pub fn update_pause_and_unpause(
-        ctx: Context<UpdateRaffleConfig>,
-        new_pause_flags: u8,
+        ctx: Context<UpdatePauseFlags>,
+        new_pause_flags: u64,
     ) -> Result<()> {
         process_raffle_config::update_pause_and_unpause(ctx, new_pause_flags)
     }
 
-    pub fn update_raffle_config_admin(
+    pub fn set_raffle_frozen(
+        ctx: Context<SetRaffleFrozen>,
+        raffle_id: u32,
+        frozen: bool,
+    ) -> Result<()> {
+        freeze_raffle::set_raffle_frozen(ctx, raffle_id, frozen)
+    }
+
+    pub fn set_allowed_mint_extensions(
         ctx: Context<UpdateRaffleConfig>,
-        new_raffle_admin: Pubkey,
+        allowed_mint_extensions: u8,
     ) -> Result<()> {
-        process_raffle_config::update_raffle_config_admin(ctx, new_raffle_admin)
+        process_raffle_config::set_allowed_mint_extensions(ctx, allowed_mint_extensions)
+    }
+
+    pub fn allow_mint(ctx: Context<AllowMint>) -> Result<()> {
+        allowlist::allow_mint(ctx)
+    }
+
+    pub fn remove_allowed_mint(ctx: Context<RemoveAllowedMint>) -> Result<()> {
+        allowlist::remove_allowed_mint(ctx)
+    }
+
+    pub fn set_collection_policy(
+        ctx: Context<UpdateRaffleConfig>,
+        require_verified_collection: bool,
+    ) -> Result<()> {
+        process_raffle_config::set_collection_policy(ctx, require_verified_collection)
+    }
+
+    pub fn allow_collection(ctx: Context<AllowCollection>) -> Result<()> {
+        allowlist::allow_collection(ctx)
+    }
+
+    pub fn remove_allowed_collection(ctx: Context<RemoveAllowedCollection>) -> Result<()> {
+        allowlist::remove_allowed_collection(ctx)
     }
 
-    pub fn update_raffle_config_data(
+    // Roles
+    pub fn initialize_role_registry(ctx: Context<InitializeRoleRegistry>) -> Result<()> {
+        manage_roles::initialize_role_registry(ctx)
+    }
+
+    pub fn grant_role(ctx: Context<UpdateRoles>, member: Pubkey, role: u8) -> Result<()> {
+        manage_roles::grant_role(ctx, member, role)
+    }
+
+    pub fn revoke_role(ctx: Context<UpdateRoles>, member: Pubkey, role: u8) -> Result<()> {
+        manage_roles::revoke_role(ctx, member, role)
+    }
+
+    pub fn queue_raffle_config_data(
         ctx: Context<UpdateRaffleConfig>,
         creation_fee_lamports: u64,
         ticket_fee_bps: u16,
         minimum_raffle_period: u32,
         maximum_raffle_period: u32,
+        config_change_delay: u32,
     ) -> Result<()> {
-        process_raffle_config::update_raffle_config_data(
+        process_raffle_config::queue_raffle_config_data(
             ctx,
             creation_fee_lamports,
             ticket_fee_bps,
             minimum_raffle_period,
             maximum_raffle_period,
+            config_change_delay,
         )
     }
 
-    pub fn create_raffle(
-        ctx: Context<CreateRaffle>,
+    pub fn apply_raffle_config_data(ctx: Context<UpdateRaffleConfig>) -> Result<()> {
+        process_raffle_config::apply_raffle_config_data(ctx)
+    }
+
+    pub fn cancel_raffle_config_data(ctx: Context<UpdateRaffleConfig>) -> Result<()> {
+        process_raffle_config::cancel_raffle_config_data(ctx)
+    }
+
+    // Migration
+    pub fn migrate_raffle_config(ctx: Context<MigrateRaffleConfig>) -> Result<()> {
+        migrate_accounts::migrate_raffle_config(ctx)
+    }
+
+    pub fn migrate_raffle(ctx: Context<MigrateRaffle>, raffle_id: u32) -> Result<()> {
+        migrate_accounts::migrate_raffle(ctx, raffle_id)
+    }
+
+    pub fn create_raffle<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateRaffle<'info>>,
         start_time: i64,
         end_time: i64,
         total_tickets: u16,
@@ -107,27 +175,41 @@ pub mod raffle {
         activate_raffle::activate_raffle(ctx, raffle_id)
     }
 
-    pub fn announce_winners(
-        ctx: Context<AnnounceWinners>,
+    pub fn announce_winners<'info>(
+        ctx: Context<'_, '_, '_, 'info, AnnounceWinners<'info>>,
         raffle_id: u32,
         winners: Vec<Pubkey>,
     ) -> Result<()> {
         announce_winners::announce_winners(ctx, raffle_id, winners)
     }
 
-    pub fn buy_ticket(ctx: Context<BuyTicket>, raffle_id: u32, tickets_to_buy: u16) -> Result<()> {
-        buy_ticket::buy_ticket(ctx, raffle_id, tickets_to_buy)
+    pub fn buy_ticket<'info>(
+        ctx: Context<'_, '_, '_, 'info, BuyTicket<'info>>,
+        raffle_id: u32,
+        tickets_to_buy: u16,
+        permit: Permit,
+    ) -> Result<()> {
+        buy_ticket::buy_ticket(ctx, raffle_id, tickets_to_buy, permit)
     }
 
-    pub fn buyer_claim_prize(ctx: Context<BuyerClaimPrize>, raffle_id: u32) -> Result<()> {
+    pub fn buyer_claim_prize<'info>(
+        ctx: Context<'_, '_, '_, 'info, BuyerClaimPrize<'info>>,
+        raffle_id: u32,
+    ) -> Result<()> {
         buyer_claim_prize::buyer_claim_prize(ctx, raffle_id)
     }
 
-    pub fn cancel_raffle(ctx: Context<CancelRaffle>, raffle_id: u32) -> Result<()> {
+    pub fn cancel_raffle<'info>(
+        ctx: Context<'_, '_, '_, 'info, CancelRaffle<'info>>,
+        raffle_id: u32,
+    ) -> Result<()> {
         cancel_raffle::cancel_raffle(ctx, raffle_id)
     }
 
-    pub fn claim_amount_back(ctx: Context<ClaimAmountBack>, raffle_id: u32) -> Result<()> {
+    pub fn claim_amount_back<'info>(
+        ctx: Context<'_, '_, '_, 'info, ClaimAmountBack<'info>>,
+        raffle_id: u32,
+    ) -> Result<()> {
         claim_amount_back::claim_amount_back(ctx, raffle_id)
     }
 
@@ -174,7 +256,10 @@ pub mod raffle {
         withdraw_sol_fees::withdraw_sol_fees(ctx, amount)
     }
 
-    pub fn withdraw_spl_fees(ctx: Context<WithdrawSplFees>, amount: u64) -> Result<()> {
+    pub fn withdraw_spl_fees<'info>(
+        ctx: Context<'_, '_, '_, 'info, WithdrawSplFees<'info>>,
+        amount: u64,
+    ) -> Result<()> {
         withdraw_spl_fees::withdraw_spl_fees(ctx, amount)
     }
 }
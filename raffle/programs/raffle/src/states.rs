@@ -4,8 +4,10 @@ use anchor_lang::prelude::*;
 #[account]
 #[derive(InitSpace)]
 pub struct RaffleConfig {
+    pub version: u8, // account layout version, bumped whenever the layout changes
+
     pub raffle_owner: Pubkey, // owner of all of the data, fees(full access control)
-    pub raffle_admin: Pubkey, // the frontend manager key
+    pub raffle_admin: Pubkey, // the frontend manager key, only seeds the `RoleRegistry` which is checked from then on
 
     pub creation_fee_lamports: u64, // while creating the raffle the user have to pay fees Native SOL
     pub ticket_fee_bps: u16, // 100 = 1%, for every sale of ticket this % of fees is sent to owner + stakers
@@ -14,14 +16,27 @@ pub struct RaffleConfig {
     pub maximum_raffle_period: u32, // maximum peroid the raffle can be set
 
     pub raffle_count: u32, // use the latest raffle count for seed, start from `1`
-    pub pause_flags: u8,   // pause the function using bit masking
+    pub pause_flags: u64,  // pause the function using bit masking, see the `*_PAUSE` indexes
     pub config_bump: u8,
+
+    pub pending_owner: Option<Pubkey>, // proposed owner, only takes over once it calls `accept_owner`
+
+    pub config_change_delay: u32, // seconds a queued fee/period change waits, never below `MINIMUM_CONFIG_CHANGE_DELAY`
+    pub pending_config_data: Option<PendingRaffleConfigData>, // queued change, applied only after its `effective_at`
+
+    pub allowed_mint_extensions: u8, // risky Token-2022 extensions accepted on any mint, see the `*_EXTENSION` indexes
+
+    pub require_verified_collection: bool, // NFT prizes must belong to a verified collection on the allowlist
+
+    pub reserved: [u8; 51], // zeroed space for future fields, new fields must decode `0` as their default
 }
 
 // seeds = "raffle" + `latest raffle count`
 #[account]
 #[derive(InitSpace)]
 pub struct Raffle {
+    pub version: u8, // layout version, see `RAFFLE_VERSION`
+
     pub raffle_id: u32,  // A unique ID for every Raffle
     pub creator: Pubkey, // creator of the raffle
 
@@ -56,6 +71,44 @@ pub struct Raffle {
     pub claimable_prize_back: u64, // if the total tickets solded is less than the winners count and if we call anounce winner then the remaining amounts can be claim back by the creator. and if the raffle failed to sold tickets then all amount is claimed back to creator
 
     pub raffle_bump: u8,
+
+    pub ticket_fee_bps: u16, // config fee in effect at `create_raffle`, later config changes never reach a live raffle
+
+    pub frozen: bool, // halts this raffle alone, every instruction on it fails until it is unfrozen
+
+    pub ticket_revenue: u64, // ticket payments that actually reached the escrow, net of any Token-2022 transfer fee
+
+    pub reserved: [u8; 117], // same rules as `RaffleConfig::reserved`
+}
+
+// seeds = "raffle" + "roles"
+#[account]
+#[derive(InitSpace)]
+pub struct RoleRegistry {
+    pub version: u8, // layout version, see `ROLE_REGISTRY_VERSION`
+
+    #[max_len(10)]
+    pub members: Vec<RoleMember>, // every key holding at least one role
+
+    pub registry_bump: u8,
+
+    pub reserved: [u8; 64], // same rules as `RaffleConfig::reserved`
+}
+
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct RoleMember {
+    pub member: Pubkey,
+    pub roles: u8, // bit masking with the `*_ROLE` indexes
+}
+
+#[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct PendingRaffleConfigData {
+    pub creation_fee_lamports: u64,
+    pub ticket_fee_bps: u16,
+    pub minimum_raffle_period: u32,
+    pub maximum_raffle_period: u32,
+    pub config_change_delay: u32,
+    pub effective_at: i64, // unix timestamp from which the change can be applied
 }
 
 // seeds = "raffle" + `raffle id` + `user address`
@@ -67,6 +120,45 @@ pub struct Buyer {
     pub tickets: u16,
 }
 
+// seeds = "raffle" + "permit" + `user address` + `nonce`
+// Existing only marks the nonce as spent, so a permit can never be replayed
+#[account]
+#[derive(InitSpace)]
+pub struct PermitNonce {
+    pub user: Pubkey,
+    pub nonce: u64,
+    pub used_at: i64,
+    pub nonce_bump: u8,
+}
+
+// seeds = "raffle" + "mint_allowlist" + `mint address`
+// Existing means the owner vetted the mint, it skips the extension policy at creation
+#[account]
+#[derive(InitSpace)]
+pub struct MintAllowlist {
+    pub mint: Pubkey,
+    pub allowed_at: i64,
+    pub allowlist_bump: u8,
+}
+
+// seeds = "raffle" + "collection_allowlist" + `collection mint address`
+// Existing lets NFTs of that verified collection in, only checked when the config requires a collection
+#[account]
+#[derive(InitSpace)]
+pub struct CollectionAllowlist {
+    pub collection: Pubkey,
+    pub allowed_at: i64,
+    pub allowlist_bump: u8,
+}
+
+// Signed off-chain by a purchase co-signer over `utils::permit_message`, verified via the Ed25519 program
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct Permit {
+    pub max_amount: u64, // upper bound on what the user pays with this permit
+    pub expiry: i64,     // unix timestamp after which the permit is rejected
+    pub nonce: u64,      // unique per user, spent through `PermitNonce`
+}
+
 #[derive(InitSpace, AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum RaffleState {
@@ -85,3 +177,66 @@ pub enum PrizeType {
     Spl = 1,
     Sol = 2,
 }
+
+// Legacy layouts, as deployed before the `version` byte and reserved space were added.
+// Only read by the `migrate_*` instructions, never written.
+#[derive(InitSpace, AnchorDeserialize)]
+pub struct RaffleConfigV0 {
+    pub raffle_owner: Pubkey,
+    pub raffle_admin: Pubkey,
+    pub creation_fee_lamports: u64,
+    pub ticket_fee_bps: u16,
+    pub minimum_raffle_period: u32,
+    pub maximum_raffle_period: u32,
+    pub raffle_count: u32,
+    pub pause_flags: u8,
+    pub config_bump: u8,
+}
+
+// Version 1 config, before `pause_flags` was widened to u64. Same total size as version 2.
+#[derive(InitSpace, AnchorDeserialize)]
+pub struct RaffleConfigV1 {
+    pub version: u8,
+    pub raffle_owner: Pubkey,
+    pub raffle_admin: Pubkey,
+    pub creation_fee_lamports: u64,
+    pub ticket_fee_bps: u16,
+    pub minimum_raffle_period: u32,
+    pub maximum_raffle_period: u32,
+    pub raffle_count: u32,
+    pub pause_flags: u8,
+    pub config_bump: u8,
+    pub pending_owner: Option<Pubkey>,
+    pub config_change_delay: u32,
+    pub pending_config_data: Option<PendingRaffleConfigData>,
+    pub reserved: [u8; 60],
+}
+
+#[derive(InitSpace, AnchorDeserialize)]
+pub struct RaffleV0 {
+    pub raffle_id: u32,
+    pub creator: Pubkey,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub total_tickets: u16,
+    pub tickets_sold: u16,
+    pub buyers_count: u16,
+    pub ticket_price: u64,
+    pub ticket_mint: Option<Pubkey>,
+    pub max_per_wallet_pct: u8,
+    pub prize_type: PrizeType,
+    pub prize_amount: u64,
+    pub prize_mint: Option<Pubkey>,
+    pub num_winners: u8,
+    pub is_unique_winners: bool,
+    pub status: RaffleState,
+    #[max_len(10)]
+    pub win_shares: Vec<u8>,
+    #[max_len(10)]
+    pub winners: Vec<Pubkey>,
+    #[max_len(10)]
+    pub is_win_claimed: Vec<bool>,
+    pub claimable_ticket_amount: u64,
+    pub claimable_prize_back: u64,
+    pub raffle_bump: u8,
+}
@@ -1,6 +1,8 @@
-use crate::constants::TOTAL_PCT;
-use crate::errors::RaffleStateErrors;
+use crate::constants::{PERMIT_MESSAGE_LEN, TOTAL_PCT};
+use crate::errors::{PermitErrors, RaffleStateErrors};
+use crate::states::{Permit, RoleRegistry};
 use anchor_lang::prelude::*;
+use solana_instructions_sysvar::{load_current_index_checked, load_instruction_at_checked};
 
 /// Check if any duplicate Pubkeys exist
 pub fn has_duplicate_pubkeys(list: &[Pubkey]) -> bool {
@@ -37,11 +39,97 @@ pub fn get_pct_amount(amount: u64, pct: u64, base: u64) -> Result<u64> {
 }
 
 // weather the fucntion is paused or not
-pub fn is_paused(pause_flags: u8, index: u8) -> bool {
-    let mask = 1u8 << index; // set the bit at `index`
+pub fn is_paused(pause_flags: u64, index: u8) -> bool {
+    let mask = 1u64 << index; // set the bit at `index`
     (pause_flags & mask) != 0 // check if it's active
 }
 
+// whether `member` holds the role at `index` in the registry
+pub fn has_role(registry: &RoleRegistry, member: Pubkey, index: u8) -> bool {
+    let mask = 1u8 << index;
+    registry
+        .members
+        .iter()
+        .any(|m| m.member == member && (m.roles & mask) != 0)
+}
+
+/// Bytes a co-signer signs off-chain to authorize `user` on raffle `raffle_id`
+pub fn permit_message(raffle_id: u32, user: Pubkey, permit: &Permit) -> Vec<u8> {
+    let mut message = Vec::with_capacity(PERMIT_MESSAGE_LEN);
+    message.extend_from_slice(crate::ID.as_ref());
+    message.extend_from_slice(&raffle_id.to_le_bytes());
+    message.extend_from_slice(user.as_ref());
+    message.extend_from_slice(&permit.max_amount.to_le_bytes());
+    message.extend_from_slice(&permit.expiry.to_le_bytes());
+    message.extend_from_slice(&permit.nonce.to_le_bytes());
+    message
+}
+
+/// Check the permit against the Ed25519 instruction placed right before the current one
+/// and return the key that signed it. The Ed25519 program has already verified the signature
+/// by the time we run, so we only need to bind its key and message to this purchase.
+pub fn verify_permit(
+    instructions_sysvar: &AccountInfo,
+    raffle_id: u32,
+    user: Pubkey,
+    amount: u64,
+    permit: &Permit,
+) -> Result<Pubkey> {
+    require_gte!(
+        permit.expiry,
+        Clock::get()?.unix_timestamp,
+        PermitErrors::PermitExpired
+    );
+    require_gte!(
+        permit.max_amount,
+        amount,
+        PermitErrors::PermitAmountExceeded
+    );
+
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    require_gt!(current_index, 0, PermitErrors::MissingPermitInstruction);
+    let ed25519_ix = load_instruction_at_checked(current_index as usize - 1, instructions_sysvar)?;
+    require_keys_eq!(
+        ed25519_ix.program_id,
+        solana_sdk_ids::ed25519_program::ID,
+        PermitErrors::MissingPermitInstruction
+    );
+
+    // layout: [num signatures, padding, offsets (7 x u16), ...inline data]
+    let data = &ed25519_ix.data;
+    require!(
+        data.len() >= 16 && data[0] == 1,
+        PermitErrors::InvalidPermitInstruction
+    );
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]) as usize;
+
+    // signature, key and message must all live in the Ed25519 instruction itself
+    require!(
+        read_u16(4) == u16::MAX as usize
+            && read_u16(8) == u16::MAX as usize
+            && read_u16(14) == u16::MAX as usize,
+        PermitErrors::InvalidPermitInstruction
+    );
+
+    let public_key_offset = read_u16(6);
+    let message_offset = read_u16(10);
+    let message_size = read_u16(12);
+
+    let signer = data
+        .get(public_key_offset..public_key_offset + 32)
+        .ok_or(PermitErrors::InvalidPermitInstruction)?;
+    let message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(PermitErrors::InvalidPermitInstruction)?;
+
+    require!(
+        message == permit_message(raffle_id, user, permit).as_slice(),
+        PermitErrors::PermitMessageMismatch
+    );
+
+    Pubkey::try_from(signer).map_err(|_| error!(PermitErrors::InvalidPermitInstruction))
+}
+
 /// Validate win share list:
 /// - non-empty
 /// - max 10 shares
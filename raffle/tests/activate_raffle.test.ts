@@ -10,6 +10,7 @@ import {
     createSplMint,
     buildCreateRaffleAccounts,
     activateRaffle,
+    setRaffleFrozen,
 } from "./helpers";
 import {
     setProgram,
@@ -155,11 +156,28 @@ describe("Activate Raffle - Bankrun", () => {
         //     activateRaffle(program, rafflePdaAddr, raffleId, raffle_admin)
         // );
 
+        // fails while this raffle alone is frozen
+        await setRaffleFrozen(program, rafflePdaAddr, raffleId, true, raffle_owner);
+        assert.equal((await program.account.raffle.fetch(rafflePdaAddr)).frozen, true);
+        await assert.rejects(
+            activateRaffle(program, rafflePdaAddr, raffleId, raffle_admin),
+            /RaffleFrozen/
+        );
+
+        // only a pauser can freeze or unfreeze
+        await assert.rejects(
+            setRaffleFrozen(program, rafflePdaAddr, raffleId, false, raffle_1_creator),
+            /MissingRole/
+        );
+        await setRaffleFrozen(program, rafflePdaAddr, raffleId, false, raffle_owner);
+
         // success: activatd correctely
         await activateRaffle(program, rafflePdaAddr, raffleId, raffle_admin);
 
         const raffle = await program.account.raffle.fetch(rafflePdaAddr);
         assert.deepEqual(raffle.status, { active: {} });
+        // fee snapshotted from the config at creation
+        assert.equal(raffle.ticketFeeBps, 500);
 
         // after activating again it fails
         await assert.rejects(
@@ -18,6 +18,7 @@ import {
     buyTickets,
     warpForward,
     buyerClaimPrize,
+    pnftAccounts,
 } from "./helpers";
 
 import {
@@ -27,6 +28,7 @@ import {
     setProgram,
     setProvider,
     raffleConfigPda,
+    roleRegistryPda,
     rafflePda,
     minimum_raffle_period,
     maximum_raffle_period,
@@ -956,6 +958,7 @@ describe("Announce Winners – setup", () => {
                 prizeTokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
                 ticketTokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
                 systemProgram: anchor.web3.SystemProgram.programId,
+                ...pnftAccounts(),
             })
             .signers([raffle_1_creator, raffle_admin])
             .rpc();
@@ -1031,13 +1034,14 @@ describe("Announce Winners – setup", () => {
 
         // ---------------------------------------------------------------
         // STEP 3: WITHDRAW CALL
-        // Only raffle_owner can call this instruction
+        // Only a fee withdrawer can call this instruction
         // ---------------------------------------------------------------
         await program.methods
             .withdrawSplFees(new anchor.BN(withdrawAmount))
             .accounts({
                 raffleConfig: raffleConfigPda(),
-                owner: raffle_owner.publicKey,
+                roleRegistry: roleRegistryPda(),
+                feeWithdrawer: raffle_owner.publicKey,
                 feeMint: ticketMint,
                 feeTreasuryAta: ticketFeeTreasury,
                 receiverFeeAta: ownerFeeAta,
@@ -13,7 +13,10 @@ import {
     getTokenBalance,
     mintTokens,
     buyTickets,
+    buyTicketsWithPermit,
+    signPermit,
     createAta,
+    createTransferFeeMint,
     getCurrentTimestamp,
     warpForward,
 } from "./helpers";
@@ -34,6 +37,7 @@ import {
     ticket_fee_bps,
 } from "./values";
 import { Keypair, PublicKey } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
 
 describe("Buy raffle tickets", () => {
     let context: any;
@@ -261,6 +265,162 @@ describe("Buy raffle tickets", () => {
         assert.strictEqual(buyerBalanceBefore - buyerBalanceAfter, priceToPay);
     });
 
+    describe("purchase permits", () => {
+        let buyer: Keypair;
+        let buyerTicketAta: PublicKey;
+        let price: number;
+
+        before(async () => {
+            buyer = Keypair.generate();
+            await context.setAccount(buyer.publicKey, {
+                lamports: 20_000_000_000,
+                owner: anchor.web3.SystemProgram.programId,
+                executable: false,
+                data: Buffer.alloc(0),
+            });
+            buyerTicketAta = await createAta(ticketMintSpl, buyer.publicKey);
+            await mintTokens(ticketMintSpl, buyerTicketAta, 100_000_000_000);
+
+            const raffle = await program.account.raffle.fetch(rafflePdaSpl);
+            price = Number(raffle.ticketPrice);
+        });
+
+        const buyOne = (signed: Awaited<ReturnType<typeof signPermit>>) =>
+            buyTicketsWithPermit(
+                program,
+                rafflePdaSpl,
+                raffleIdSpl,
+                buyer,
+                1,
+                ticketMintSpl,
+                ticketEscrowSpl,
+                buyerTicketAta,
+                signed
+            );
+
+        it("rejects a replayed permit", async () => {
+            const signed = await signPermit(program, raffleIdSpl, buyer.publicKey, price, raffle_admin);
+            await buyOne(signed);
+
+            // the nonce PDA already exists, so `init` fails
+            await assert.rejects(buyOne(signed));
+        });
+
+        it("rejects an expired permit", async () => {
+            const now = await getCurrentTimestamp();
+            const signed = await signPermit(program, raffleIdSpl, buyer.publicKey, price, raffle_admin, {
+                expiry: now - 1,
+            });
+
+            await assert.rejects(buyOne(signed), /PermitExpired/);
+        });
+
+        it("rejects a permit below the purchase amount", async () => {
+            const signed = await signPermit(program, raffleIdSpl, buyer.publicKey, price - 1, raffle_admin);
+
+            await assert.rejects(buyOne(signed), /PermitAmountExceeded/);
+        });
+
+        it("rejects a permit signed without the purchase co-signer role", async () => {
+            const signed = await signPermit(program, raffleIdSpl, buyer.publicKey, price, Keypair.generate());
+
+            await assert.rejects(buyOne(signed), /MissingRole/);
+        });
+
+        it("rejects a permit issued to another user", async () => {
+            const other = await signPermit(program, raffleIdSpl, Keypair.generate().publicKey, price, raffle_admin);
+            const own = await signPermit(program, raffleIdSpl, buyer.publicKey, price, raffle_admin, {
+                nonce: other.permit.nonce.toNumber(),
+            });
+
+            // right nonce account for the buyer, but the signed message names someone else
+            await assert.rejects(buyOne({ ...own, ed25519Ix: other.ed25519Ix }), /PermitMessageMismatch/);
+        });
+    });
+
+    describe("transfer-fee ticket mint", () => {
+        const feeBps = 100; // 1%
+        const ticketPrice = 100_000_000;
+
+        let raffleId: number;
+        let raffle: PublicKey;
+        let ticketMint: PublicKey;
+        let ticketEscrow: PublicKey;
+
+        before(async () => {
+            ticketMint = await createTransferFeeMint(feeBps, BigInt(1_000_000_000));
+            const prizeMint = await createSplMint();
+            const cfg = await program.account.raffleConfig.fetch(raffleConfigPda());
+            raffleId = cfg.raffleCount;
+            raffle = rafflePda(raffleId);
+
+            const accounts = await buildCreateRaffleAccounts(raffle, raffle_1_creator, ticketMint, prizeMint, TOKEN_2022_PROGRAM_ID);
+            ticketEscrow = accounts.ticketEscrow;
+            await mintTokens(prizeMint, accounts.creatorPrizeAta, 1_000_000_000);
+
+            const now = await getCurrentTimestamp();
+            await createRaffle(program, {
+                startTime: now,
+                endTime: now + 10000,
+                totalTickets: 10,
+                ticketPrice,
+                isTicketSol: false,
+                maxPct: 100,
+                prizeType: { spl: {} },
+                prizeAmount: 1_000_000_000,
+                numWinners: 1,
+                winShares: [100],
+                unique: false,
+                autoStart: true,
+            }, {
+                raffleConfig: raffleConfigPda(),
+                rafflePda: raffle,
+                creator: raffle_1_creator,
+                raffleAdmin: raffle_admin,
+                ticketMint,
+                prizeMint,
+                ticketEscrow,
+                prizeEscrow: accounts.prizeEscrow,
+                creatorPrizeAta: accounts.creatorPrizeAta,
+                ticketTokenProgram: TOKEN_2022_PROGRAM_ID,
+            });
+        });
+
+        it("records ticket revenue net of the transfer fee", async () => {
+            const buyer = Keypair.generate();
+            await context.setAccount(buyer.publicKey, {
+                lamports: 20_000_000_000,
+                owner: anchor.web3.SystemProgram.programId,
+                executable: false,
+                data: Buffer.alloc(0),
+            });
+            const buyerTicketAta = await createAta(ticketMint, buyer.publicKey, TOKEN_2022_PROGRAM_ID);
+            await mintTokens(ticketMint, buyerTicketAta, 10_000_000_000, TOKEN_2022_PROGRAM_ID);
+
+            const ticketsToBuy = 3;
+            const gross = ticketsToBuy * ticketPrice;
+            const net = gross - (gross * feeBps) / 10_000;
+
+            await buyTickets(
+                program,
+                raffle,
+                raffleId,
+                buyer,
+                ticketsToBuy,
+                ticketMint,
+                ticketEscrow,
+                buyerTicketAta,
+                raffle_admin,
+                TOKEN_2022_PROGRAM_ID
+            );
+
+            const raffleAfter = await program.account.raffle.fetch(raffle);
+            assert.strictEqual(raffleAfter.ticketsSold, ticketsToBuy);
+            assert.strictEqual(raffleAfter.ticketRevenue.toNumber(), net);
+            assert.strictEqual(await getTokenBalance(ticketEscrow), net);
+        });
+    });
+
     // // Fail if function paused (mutate pause_flags)
     // it("fails to buy if function paused", async () => {
     //     const config = await program.account.raffleConfig.fetch(raffleConfigPda());
@@ -25,6 +25,7 @@ import {
     getProgram,
     getProvider,
     raffleConfigPda,
+    roleRegistryPda,
     rafflePda,
     minimum_raffle_period,
     maximum_raffle_period,
@@ -165,7 +166,8 @@ describe("Cancel Raffle", () => {
         await program.methods.withdrawSolFees(new anchor.BN(90_000_000))
             .accounts({
                 raffleConfig: raffleConfigPda(),
-                owner: raffle_owner.publicKey,
+                roleRegistry: roleRegistryPda(),
+                feeWithdrawer: raffle_owner.publicKey,
                 receiver: raffle_admin.publicKey,
                 systemProgram: anchor.web3.SystemProgram.programId,
             })
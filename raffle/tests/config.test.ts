@@ -10,6 +10,7 @@ import {
   ticket_fee_bps,
   minimum_raffle_period,
   maximum_raffle_period,
+  minimum_config_change_delay,
   raffle_owner,
   raffle_admin,
   setProgram,
@@ -17,7 +18,14 @@ import {
   getProgram,
   getProvider,
   raffleConfigPda,
+  roleRegistryPda,
+  CREATOR_APPROVER_ROLE,
+  PAUSER_ROLE,
+  FEE_WITHDRAWER_ROLE,
+  UPDATE_CONFIG_PAUSE,
+  ROLE_COUNT,
 } from "./values";
+import { warpForward } from "./helpers";
 
 describe("Raffle Config – Bankrun", () => {
   let context: any;
@@ -77,27 +85,130 @@ describe("Raffle Config – Bankrun", () => {
     assert.ok(account.configBump >= 0);
   });
 
-  it("updates raffle admin successfully", async () => {
+  it("initializes the role registry with the owner pausing and withdrawing fees", async () => {
     const program = getProgram();
-    const new_admin = web3.Keypair.generate().publicKey;
 
-    const beforeState = await program.account.raffleConfig.fetch(raffleConfigPda());
-    const storedOwner = beforeState.raffleOwner;
+    await program.methods
+      .initializeRoleRegistry()
+      .accounts({
+        raffleConfig: raffleConfigPda(),
+        roleRegistry: roleRegistryPda(),
+        raffleOwner: raffle_owner.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([raffle_owner])
+      .rpc();
+
+    const ownerRoles = (1 << PAUSER_ROLE) | (1 << FEE_WITHDRAWER_ROLE);
+    const registry = await program.account.roleRegistry.fetch(roleRegistryPda());
+    assert.equal(registry.members.length, 2);
+    assert.equal(registry.members[0].member.toString(), raffle_owner.publicKey.toString());
+    assert.equal(registry.members[0].roles, ownerRoles);
+    assert.equal(registry.members[1].member.toString(), raffle_admin.publicKey.toString());
+    assert.equal(registry.members[1].roles, ((1 << ROLE_COUNT) - 1) & ~ownerRoles);
+  });
+
+  it("does not let the admin withdraw fees once the registry exists", async () => {
+    const program = getProgram();
+
+    await assert.rejects(
+      program.methods
+        .withdrawSolFees(new BN(1))
+        .accounts({
+          raffleConfig: raffleConfigPda(),
+          roleRegistry: roleRegistryPda(),
+          feeWithdrawer: raffle_admin.publicKey,
+          receiver: raffle_admin.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .signers([raffle_admin])
+        .rpc(),
+      /MissingRole/
+    );
+  });
+
+  it("grants and revokes a role", async () => {
+    const program = getProgram();
+    const approver = web3.Keypair.generate().publicKey;
 
     await program.methods
-      .updateRaffleConfigAdmin(new_admin)
+      .grantRole(approver, CREATOR_APPROVER_ROLE)
       .accounts({
         raffleConfig: raffleConfigPda(),
-        raffleOwner: storedOwner,
+        roleRegistry: roleRegistryPda(),
+        raffleOwner: raffle_owner.publicKey,
       })
       .signers([raffle_owner])
       .rpc();
 
-    const updated = await program.account.raffleConfig.fetch(raffleConfigPda());
-    assert.equal(updated.raffleAdmin.toString(), new_admin.toString());
+    let registry = await program.account.roleRegistry.fetch(roleRegistryPda());
+    const granted = registry.members.find(
+      (m: any) => m.member.toString() === approver.toString()
+    );
+    assert.equal(granted.roles, 1 << CREATOR_APPROVER_ROLE);
+
+    await program.methods
+      .revokeRole(approver, CREATOR_APPROVER_ROLE)
+      .accounts({
+        raffleConfig: raffleConfigPda(),
+        roleRegistry: roleRegistryPda(),
+        raffleOwner: raffle_owner.publicKey,
+      })
+      .signers([raffle_owner])
+      .rpc();
+
+    // a member without roles is dropped from the registry
+    registry = await program.account.roleRegistry.fetch(roleRegistryPda());
+    assert.equal(registry.members.length, 1);
+
+    // revoking a role that was never granted fails
+    await assert.rejects(
+      program.methods
+        .revokeRole(approver, CREATOR_APPROVER_ROLE)
+        .accounts({
+          raffleConfig: raffleConfigPda(),
+          roleRegistry: roleRegistryPda(),
+          raffleOwner: raffle_owner.publicKey,
+        })
+        .signers([raffle_owner])
+        .rpc()
+    );
+  });
+
+  it("fails when a non-owner grants a role", async () => {
+    const program = getProgram();
+    const fake_owner = web3.Keypair.generate();
+
+    await assert.rejects(
+      program.methods
+        .grantRole(fake_owner.publicKey, PAUSER_ROLE)
+        .accounts({
+          raffleConfig: raffleConfigPda(),
+          roleRegistry: roleRegistryPda(),
+          raffleOwner: fake_owner.publicKey,
+        })
+        .signers([fake_owner])
+        .rpc()
+    );
+  });
+
+  it("fails when granting an unknown role", async () => {
+    const program = getProgram();
+
+    await assert.rejects(
+      program.methods
+        .grantRole(raffle_admin.publicKey, ROLE_COUNT)
+        .accounts({
+          raffleConfig: raffleConfigPda(),
+          roleRegistry: roleRegistryPda(),
+          raffleOwner: raffle_owner.publicKey,
+        })
+        .signers([raffle_owner])
+        .rpc()
+    );
   });
 
-  it("updates config data successfully", async () => {
+  it("queues config data and applies it only after the delay", async () => {
     const program = getProgram();
     const new_min = 7200;
     const new_max = 12000;
@@ -106,13 +217,27 @@ describe("Raffle Config – Bankrun", () => {
 
     const beforeState = await program.account.raffleConfig.fetch(raffleConfigPda());
     const storedOwner = beforeState.raffleOwner;
+    assert.equal(beforeState.configChangeDelay, minimum_config_change_delay);
+
+    await assert.rejects(
+      program.methods
+        .queueRaffleConfigData(new_fee, 10_001, new_min, new_max, minimum_config_change_delay)
+        .accounts({
+          raffleConfig: raffleConfigPda(),
+          raffleOwner: storedOwner,
+        })
+        .signers([raffle_owner])
+        .rpc(),
+      /InvalidTicketFee/
+    );
 
     await program.methods
-      .updateRaffleConfigData(
+      .queueRaffleConfigData(
         new_fee,
         new_bps,
         new_min,
-        new_max
+        new_max,
+        minimum_config_change_delay * 2
       )
       .accounts({
         raffleConfig: raffleConfigPda(),
@@ -121,60 +246,176 @@ describe("Raffle Config – Bankrun", () => {
       .signers([raffle_owner])
       .rpc();
 
+    // nothing changes while the change is queued
+    const queued = await program.account.raffleConfig.fetch(raffleConfigPda());
+    assert.equal(queued.ticketFeeBps, ticket_fee_bps);
+    assert.equal(queued.pendingConfigData.ticketFeeBps, new_bps);
+
+    const applyChange = () =>
+      program.methods
+        .applyRaffleConfigData()
+        .accounts({
+          raffleConfig: raffleConfigPda(),
+          raffleOwner: storedOwner,
+        })
+        .signers([raffle_owner])
+        .rpc();
+
+    await assert.rejects(applyChange());
+
+    await warpForward(minimum_config_change_delay);
+    await applyChange();
+
     const updated = await program.account.raffleConfig.fetch(raffleConfigPda());
 
     assert.equal(updated.minimumRafflePeriod, new_min);
     assert.equal(updated.maximumRafflePeriod, new_max);
     assert.equal(updated.ticketFeeBps, new_bps);
     assert.equal(updated.creationFeeLamports.toNumber(), new_fee.toNumber());
+    assert.equal(updated.configChangeDelay, minimum_config_change_delay * 2);
+    assert.equal(updated.pendingConfigData, null);
+
+    // the queue is empty again
+    await assert.rejects(applyChange());
+  });
+
+  it("cancels a queued config change", async () => {
+    const program = getProgram();
+
+    await program.methods
+      .queueRaffleConfigData(
+        new BN(creation_fee_lamports),
+        5_000,
+        minimum_raffle_period,
+        maximum_raffle_period,
+        minimum_config_change_delay
+      )
+      .accounts({
+        raffleConfig: raffleConfigPda(),
+        raffleOwner: raffle_owner.publicKey,
+      })
+      .signers([raffle_owner])
+      .rpc();
+
+    await program.methods
+      .cancelRaffleConfigData()
+      .accounts({
+        raffleConfig: raffleConfigPda(),
+        raffleOwner: raffle_owner.publicKey,
+      })
+      .signers([raffle_owner])
+      .rpc();
+
+    const cfg = await program.account.raffleConfig.fetch(raffleConfigPda());
+    assert.equal(cfg.pendingConfigData, null);
+    assert.equal(cfg.ticketFeeBps, 200);
+  });
+
+  it("queue fails if the delay is below the minimum", async () => {
+    const program = getProgram();
+
+    await assert.rejects(
+      program.methods
+        .queueRaffleConfigData(
+          new BN(creation_fee_lamports),
+          ticket_fee_bps,
+          minimum_raffle_period,
+          maximum_raffle_period,
+          minimum_config_change_delay - 1
+        )
+        .accounts({
+          raffleConfig: raffleConfigPda(),
+          raffleOwner: raffle_owner.publicKey,
+        })
+        .signers([raffle_owner])
+        .rpc()
+    );
   });
 
   it("updates pause flags", async () => {
     const program = getProgram();
     const new_flags = 5;
 
-    const beforeState = await program.account.raffleConfig.fetch(raffleConfigPda());
-    const storedOwner = beforeState.raffleOwner;
-
     await program.methods
-      .updatePauseAndUnpause(new_flags)
+      .updatePauseAndUnpause(new BN(new_flags))
       .accounts({
         raffleConfig: raffleConfigPda(),
-        raffleOwner: storedOwner,
+        roleRegistry: roleRegistryPda(),
+        pauser: raffle_owner.publicKey,
       })
       .signers([raffle_owner])
       .rpc();
 
     const updated = await program.account.raffleConfig.fetch(raffleConfigPda());
-    assert.equal(updated.pauseFlags, new_flags);
+    assert.equal(updated.pauseFlags.toNumber(), new_flags);
   });
 
-  it("fails when wrong owner tries to update config", async () => {
+  it("blocks config changes while UPDATE_CONFIG_PAUSE is set", async () => {
     const program = getProgram();
-    const fake_owner = web3.Keypair.generate();
+    const setFlags = (flags: BN) =>
+      program.methods
+        .updatePauseAndUnpause(flags)
+        .accounts({
+          raffleConfig: raffleConfigPda(),
+          roleRegistry: roleRegistryPda(),
+          pauser: raffle_owner.publicKey,
+        })
+        .signers([raffle_owner])
+        .rpc();
+
+    const previous = (await program.account.raffleConfig.fetch(raffleConfigPda())).pauseFlags;
+    await setFlags(new BN(1).shln(UPDATE_CONFIG_PAUSE));
 
     await assert.rejects(
       program.methods
-        .updatePauseAndUnpause(77)
+        .queueRaffleConfigData(
+          new BN(creation_fee_lamports),
+          ticket_fee_bps,
+          minimum_raffle_period,
+          maximum_raffle_period,
+          minimum_config_change_delay
+        )
         .accounts({
           raffleConfig: raffleConfigPda(),
-          raffleOwner: fake_owner.publicKey,
+          raffleOwner: raffle_owner.publicKey,
         })
-        .signers([fake_owner])
-        .rpc()
+        .signers([raffle_owner])
+        .rpc(),
+      /FunctionPaused/
+    );
+
+    await setFlags(previous);
+  });
+
+  it("fails when a key without the pauser role updates pause flags", async () => {
+    const program = getProgram();
+    const fake_pauser = web3.Keypair.generate();
+
+    await assert.rejects(
+      program.methods
+        .updatePauseAndUnpause(new BN(77))
+        .accounts({
+          raffleConfig: raffleConfigPda(),
+          roleRegistry: roleRegistryPda(),
+          pauser: fake_pauser.publicKey,
+        })
+        .signers([fake_pauser])
+        .rpc(),
+      /MissingRole/
     );
   });
 
-  it("update fails if minimum period = 0", async () => {
+  it("queue fails if minimum period = 0", async () => {
     const program = getProgram();
 
     await assert.rejects(
       program.methods
-        .updateRaffleConfigData(
+        .queueRaffleConfigData(
           new BN(creation_fee_lamports),
           ticket_fee_bps,
           0,
-          maximum_raffle_period
+          maximum_raffle_period,
+          minimum_config_change_delay
         )
         .accounts({
           raffleConfig: raffleConfigPda(),
@@ -185,16 +426,17 @@ describe("Raffle Config – Bankrun", () => {
     );
   });
 
-  it("update fails if max period < min period", async () => {
+  it("queue fails if max period < min period", async () => {
     const program = getProgram();
 
     await assert.rejects(
       program.methods
-        .updateRaffleConfigData(
+        .queueRaffleConfigData(
           new BN(creation_fee_lamports),
           ticket_fee_bps,
           3600,
-          1000
+          1000,
+          minimum_config_change_delay
         )
         .accounts({
           raffleConfig: raffleConfigPda(),
@@ -204,6 +446,121 @@ describe("Raffle Config – Bankrun", () => {
         .rpc()
     );
   });
+
+  it("propose_owner only records a pending owner", async () => {
+    const program = getProgram();
+    const next_owner = web3.Keypair.generate();
+
+    await program.methods
+      .proposeOwner(next_owner.publicKey)
+      .accounts({
+        raffleConfig: raffleConfigPda(),
+        raffleOwner: raffle_owner.publicKey,
+      })
+      .signers([raffle_owner])
+      .rpc();
+
+    const cfg = await program.account.raffleConfig.fetch(raffleConfigPda());
+    assert.equal(cfg.raffleOwner.toString(), raffle_owner.publicKey.toString());
+    assert.equal(cfg.pendingOwner.toString(), next_owner.publicKey.toString());
+
+    // only the owner may cancel, then nothing is left to accept
+    await program.methods
+      .cancelOwnerProposal()
+      .accounts({
+        raffleConfig: raffleConfigPda(),
+        raffleOwner: raffle_owner.publicKey,
+      })
+      .signers([raffle_owner])
+      .rpc();
+
+    const cancelled = await program.account.raffleConfig.fetch(raffleConfigPda());
+    assert.equal(cancelled.pendingOwner, null);
+
+    await assert.rejects(
+      program.methods
+        .acceptOwner()
+        .accounts({
+          raffleConfig: raffleConfigPda(),
+          newRaffleOwner: next_owner.publicKey,
+        })
+        .signers([next_owner])
+        .rpc()
+    );
+  });
+
+  it("fails when a non-owner proposes a new owner", async () => {
+    const program = getProgram();
+    const fake_owner = web3.Keypair.generate();
+
+    await assert.rejects(
+      program.methods
+        .proposeOwner(fake_owner.publicKey)
+        .accounts({
+          raffleConfig: raffleConfigPda(),
+          raffleOwner: fake_owner.publicKey,
+        })
+        .signers([fake_owner])
+        .rpc()
+    );
+  });
+
+  it("transfers ownership only when the pending owner accepts", async () => {
+    const program = getProgram();
+    const next_owner = web3.Keypair.generate();
+    const stranger = web3.Keypair.generate();
+
+    await program.methods
+      .proposeOwner(next_owner.publicKey)
+      .accounts({
+        raffleConfig: raffleConfigPda(),
+        raffleOwner: raffle_owner.publicKey,
+      })
+      .signers([raffle_owner])
+      .rpc();
+
+    await assert.rejects(
+      program.methods
+        .acceptOwner()
+        .accounts({
+          raffleConfig: raffleConfigPda(),
+          newRaffleOwner: stranger.publicKey,
+        })
+        .signers([stranger])
+        .rpc()
+    );
+
+    await program.methods
+      .acceptOwner()
+      .accounts({
+        raffleConfig: raffleConfigPda(),
+        newRaffleOwner: next_owner.publicKey,
+      })
+      .signers([next_owner])
+      .rpc();
+
+    const cfg = await program.account.raffleConfig.fetch(raffleConfigPda());
+    assert.equal(cfg.raffleOwner.toString(), next_owner.publicKey.toString());
+    assert.equal(cfg.pendingOwner, null);
+
+    // hand it back so the remaining suites keep using `raffle_owner`
+    await program.methods
+      .proposeOwner(raffle_owner.publicKey)
+      .accounts({
+        raffleConfig: raffleConfigPda(),
+        raffleOwner: next_owner.publicKey,
+      })
+      .signers([next_owner])
+      .rpc();
+    await program.methods
+      .acceptOwner()
+      .accounts({
+        raffleConfig: raffleConfigPda(),
+        newRaffleOwner: raffle_owner.publicKey,
+      })
+      .signers([raffle_owner])
+      .rpc();
+  });
 });
 
 describe("Invalid creation tests", () => {
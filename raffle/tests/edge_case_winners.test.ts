@@ -19,6 +19,7 @@ import {
     warpForward,
     buyerClaimPrize,
     buyerPda,
+    pnftAccounts,
 } from "./helpers";
 
 import {
@@ -411,6 +412,7 @@ describe("Edge case Announce Winners – setup", () => {
                 prizeTokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
                 ticketTokenProgram: anchor.utils.token.TOKEN_PROGRAM_ID,
                 systemProgram: anchor.web3.SystemProgram.programId,
+                ...pnftAccounts(),
             })
             .signers([raffle_1_creator, raffle_admin])
             .rpc();
@@ -1,17 +1,33 @@
 // tests/helpers.ts
 import * as anchor from "@coral-xyz/anchor";
-import { Keypair, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import {
+    AccountMeta,
+    Ed25519Program,
+    Keypair,
+    PublicKey,
+    SYSVAR_INSTRUCTIONS_PUBKEY,
+    SystemProgram,
+    Transaction,
+    TransactionInstruction,
+} from "@solana/web3.js";
 import {
     createInitializeMintInstruction,
     createAssociatedTokenAccountIdempotentInstruction,
     createTransferCheckedInstruction,
     TOKEN_PROGRAM_ID,
+    TOKEN_2022_PROGRAM_ID,
+    ExtensionType,
+    getMintLen,
+    createInitializeTransferFeeConfigInstruction,
+    createInitializePermanentDelegateInstruction,
+    createInitializeTransferHookInstruction,
     getAssociatedTokenAddressSync,
     createMintToInstruction,
     AccountLayout,
+    ASSOCIATED_TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 import { PrizeType } from "../target/types/raffle";
-import { getProvider, raffleConfigPda } from "./values";
+import { getProvider, mintAllowlistPda, raffleConfigPda, roleRegistryPda, TOKEN_METADATA_PROGRAM_ID, AUTH_RULES_PROGRAM_ID, collectionAllowlistPda, nftMetadataPda, nftEditionPda, tokenRecordPda } from "./values";
 import { Clock } from "solana-bankrun";
 
 // === CORE HELPERS (Bankrun Compatible) ===
@@ -37,19 +53,263 @@ export async function createSplMint(decimals = 9): Promise<PublicKey> {
 }
 
 export async function createNftMint(): Promise<PublicKey> {
-    return createSplMint(0);
+    const mint = await createSplMint(0);
+    await setNftMetadata(mint);
+    return mint;
+}
+
+// Writes a Token Metadata `Metadata` and `MasterEdition` for `mint` straight into the bank, the
+// Token Metadata program itself is not loaded in these tests
+export async function setNftMetadata(
+    mint: PublicKey,
+    opts: { collection?: PublicKey; verifiedCollection?: boolean; masterEdition?: boolean } = {}
+) {
+    const { collection, verifiedCollection = true, masterEdition = true } = opts;
+    const context = getProvider().context;
+
+    const borshString = (value: string) => {
+        const len = Buffer.alloc(4);
+        len.writeUInt32LE(value.length);
+        return Buffer.concat([len, Buffer.from(value)]);
+    };
+
+    const metadata = Buffer.concat([
+        Buffer.from([4]), // Key::MetadataV1
+        getProvider().wallet.publicKey.toBuffer(), // update authority
+        mint.toBuffer(),
+        borshString("Prize"),
+        borshString("PRZ"),
+        borshString(""),
+        Buffer.from([0, 0]), // seller fee bps
+        Buffer.from([0]), // no creators
+        Buffer.from([0, 1]), // primary sale happened, is mutable
+        Buffer.from([0]), // edition nonce
+        Buffer.from([1, 0]), // TokenStandard::NonFungible
+        collection
+            ? Buffer.concat([Buffer.from([1, verifiedCollection ? 1 : 0]), collection.toBuffer()])
+            : Buffer.from([0]),
+        Buffer.from([0, 0, 0]), // uses, collection details, programmable config
+    ]);
+    await context.setAccount(nftMetadataPda(mint), {
+        lamports: 1_000_000_000,
+        data: metadata,
+        owner: TOKEN_METADATA_PROGRAM_ID,
+        executable: false,
+    });
+
+    if (masterEdition) {
+        // Key::MasterEditionV2, supply 0, max supply Some(0)
+        const edition = Buffer.concat([Buffer.from([6]), Buffer.alloc(8), Buffer.from([1]), Buffer.alloc(8)]);
+        await context.setAccount(nftEditionPda(mint), {
+            lamports: 1_000_000_000,
+            data: edition,
+            owner: TOKEN_METADATA_PROGRAM_ID,
+            executable: false,
+        });
+    }
+}
+
+// Mints a programmable NFT to `owner` through Token Metadata's CreateV1 and MintV1, unlike
+// `setNftMetadata` this needs the real program loaded from tests/fixtures/mpl_token_metadata.so
+export async function createPnftMint(owner: PublicKey): Promise<PublicKey> {
+    const provider = getProvider();
+    const payer = provider.wallet.publicKey;
+    const mint = Keypair.generate();
+    const metadata = nftMetadataPda(mint.publicKey);
+    const edition = nftEditionPda(mint.publicKey);
+    const ownerAta = getAssociatedTokenAddressSync(mint.publicKey, owner, true);
+
+    const borshString = (value: string) => {
+        const len = Buffer.alloc(4);
+        len.writeUInt32LE(value.length);
+        return Buffer.concat([len, Buffer.from(value)]);
+    };
+    const account = (pubkey: PublicKey, isWritable = false, isSigner = false) => ({ pubkey, isSigner, isWritable });
+    // absent optional accounts are passed as the Token Metadata program id
+    const none = account(TOKEN_METADATA_PROGRAM_ID);
+
+    const create = new TransactionInstruction({
+        programId: TOKEN_METADATA_PROGRAM_ID,
+        keys: [
+            account(metadata, true),
+            account(edition, true),
+            account(mint.publicKey, true, true),
+            account(payer, false, true), // mint authority
+            account(payer, true, true),
+            account(payer, false, true), // update authority
+            account(SystemProgram.programId),
+            account(SYSVAR_INSTRUCTIONS_PUBKEY),
+            account(TOKEN_PROGRAM_ID),
+        ],
+        data: Buffer.concat([
+            Buffer.from([42, 0]), // CreateV1
+            borshString("Prize"),
+            borshString("PRZ"),
+            borshString(""),
+            Buffer.from([0, 0]), // seller fee bps
+            Buffer.from([0]), // no creators
+            Buffer.from([0, 1]), // primary sale happened, is mutable
+            Buffer.from([4]), // TokenStandard::ProgrammableNonFungible
+            Buffer.from([0, 0, 0, 0]), // collection, uses, collection details, rule set
+            Buffer.from([1, 0]), // decimals
+            Buffer.from([1, 0]), // PrintSupply::Zero
+        ]),
+    });
+
+    const mintTo = new TransactionInstruction({
+        programId: TOKEN_METADATA_PROGRAM_ID,
+        keys: [
+            account(ownerAta, true),
+            account(owner),
+            account(metadata),
+            account(edition, true),
+            account(tokenRecordPda(mint.publicKey, ownerAta), true),
+            account(mint.publicKey, true),
+            account(payer, false, true),
+            none, // delegate record
+            account(payer, true, true),
+            account(SystemProgram.programId),
+            account(SYSVAR_INSTRUCTIONS_PUBKEY),
+            account(TOKEN_PROGRAM_ID),
+            account(ASSOCIATED_TOKEN_PROGRAM_ID),
+            none, // authorization rules program
+            none, // authorization rules
+        ],
+        data: Buffer.concat([
+            Buffer.from([43, 0]), // MintV1
+            new anchor.BN(1).toArrayLike(Buffer, "le", 8),
+            Buffer.from([0]), // no authorization data
+        ]),
+    });
+
+    await provider.sendAndConfirm(new Transaction().add(create), [mint]);
+    await provider.sendAndConfirm(new Transaction().add(mintTo));
+    return mint.publicKey;
+}
+
+// Token-2022 mint that withholds `feeBps` of every transfer, capped at `maxFee`
+export async function createTransferFeeMint(feeBps: number, maxFee: bigint, decimals = 9): Promise<PublicKey> {
+    const provider = getProvider();
+    const mint = Keypair.generate();
+
+    const space = getMintLen([ExtensionType.TransferFeeConfig]);
+    const lamports = await provider.connection.getMinimumBalanceForRentExemption(space);
+    const tx = new Transaction().add(
+        SystemProgram.createAccount({
+            fromPubkey: provider.wallet.publicKey,
+            newAccountPubkey: mint.publicKey,
+            space,
+            lamports,
+            programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+            mint.publicKey,
+            provider.wallet.publicKey,
+            provider.wallet.publicKey,
+            feeBps,
+            maxFee,
+            TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(mint.publicKey, decimals, provider.wallet.publicKey, null, TOKEN_2022_PROGRAM_ID)
+    );
+
+    await provider.sendAndConfirm(tx, [mint]);
+    return mint.publicKey;
+}
+
+// Token-2022 mint whose `delegate` can move tokens out of any account, escrows included
+export async function createPermanentDelegateMint(delegate: PublicKey, decimals = 9): Promise<PublicKey> {
+    const provider = getProvider();
+    const mint = Keypair.generate();
+
+    const space = getMintLen([ExtensionType.PermanentDelegate]);
+    const lamports = await provider.connection.getMinimumBalanceForRentExemption(space);
+    const tx = new Transaction().add(
+        SystemProgram.createAccount({
+            fromPubkey: provider.wallet.publicKey,
+            newAccountPubkey: mint.publicKey,
+            space,
+            lamports,
+            programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializePermanentDelegateInstruction(mint.publicKey, delegate, TOKEN_2022_PROGRAM_ID),
+        createInitializeMintInstruction(mint.publicKey, decimals, provider.wallet.publicKey, null, TOKEN_2022_PROGRAM_ID)
+    );
+
+    await provider.sendAndConfirm(tx, [mint]);
+    return mint.publicKey;
 }
 
-export async function createAta(mint: PublicKey, owner: PublicKey): Promise<PublicKey> {
+export async function createTransferHookMint(hookProgramId: PublicKey, decimals = 9): Promise<PublicKey> {
     const provider = getProvider();
-    const ata = getAssociatedTokenAddressSync(mint, owner, true);
+    const mint = Keypair.generate();
+
+    const space = getMintLen([ExtensionType.TransferHook]);
+    const lamports = await provider.connection.getMinimumBalanceForRentExemption(space);
+    const tx = new Transaction().add(
+        SystemProgram.createAccount({
+            fromPubkey: provider.wallet.publicKey,
+            newAccountPubkey: mint.publicKey,
+            space,
+            lamports,
+            programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferHookInstruction(
+            mint.publicKey,
+            provider.wallet.publicKey,
+            hookProgramId,
+            TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(mint.publicKey, decimals, provider.wallet.publicKey, null, TOKEN_2022_PROGRAM_ID)
+    );
+
+    await provider.sendAndConfirm(tx, [mint]);
+    return mint.publicKey;
+}
+
+// Hook program, its validation account and the counter PDA it lists, in the order the hook resolves them
+export function transferHookAccounts(hookProgramId: PublicKey, mint: PublicKey): AccountMeta[] {
+    const [extraAccountMetaList] = PublicKey.findProgramAddressSync(
+        [Buffer.from("extra-account-metas"), mint.toBuffer()],
+        hookProgramId
+    );
+    const [counter] = PublicKey.findProgramAddressSync(
+        [Buffer.from("counter"), mint.toBuffer()],
+        hookProgramId
+    );
+
+    return [
+        { pubkey: hookProgramId, isSigner: false, isWritable: false },
+        { pubkey: extraAccountMetaList, isSigner: false, isWritable: false },
+        { pubkey: counter, isSigner: false, isWritable: true },
+    ];
+}
+
+// Token Metadata accounts every prize transfer carries; with no rule set the program id stands in for `authorizationRules`
+export function pnftAccounts(authorizationRules: PublicKey = TOKEN_METADATA_PROGRAM_ID) {
+    return {
+        authorizationRules,
+        authorizationRulesProgram: AUTH_RULES_PROGRAM_ID,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
+    };
+}
+
+export async function createAta(
+    mint: PublicKey,
+    owner: PublicKey,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID
+): Promise<PublicKey> {
+    const provider = getProvider();
+    const ata = getAssociatedTokenAddressSync(mint, owner, true, tokenProgram);
 
     const tx = new Transaction().add(
         createAssociatedTokenAccountIdempotentInstruction(
             provider.wallet.publicKey, // payer
             ata,
             owner,
-            mint
+            mint,
+            tokenProgram
         )
     );
 
@@ -57,14 +317,21 @@ export async function createAta(mint: PublicKey, owner: PublicKey): Promise<Publ
     return ata;
 }
 
-export async function mintTokens(mint: PublicKey, to: PublicKey, amount: number) {
+export async function mintTokens(
+    mint: PublicKey,
+    to: PublicKey,
+    amount: number,
+    tokenProgram: PublicKey = TOKEN_PROGRAM_ID
+) {
     const provider = getProvider();
     const tx = new Transaction().add(
         createMintToInstruction(
             mint,
             to,
             provider.wallet.publicKey, // mint authority
-            amount
+            amount,
+            [],
+            tokenProgram
         )
     );
     await provider.sendAndConfirm(tx);
@@ -114,6 +381,18 @@ export async function createRaffleConfig(
         })
         .signers([owner])
         .rpc();
+
+    // the owner pauses and withdraws fees, the admin holds the other roles
+    await program.methods
+        .initializeRoleRegistry()
+        .accounts({
+            raffleConfig: raffleConfigPda(),
+            roleRegistry: roleRegistryPda(),
+            raffleOwner: owner.publicKey,
+            systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
 }
 
 // === CREATE RAFFLE ===
@@ -122,11 +401,13 @@ export async function buildCreateRaffleAccounts(
     rafflePda: PublicKey,
     creator: Keypair,
     ticketMint: PublicKey,
-    prizeMint: PublicKey
+    prizeMint: PublicKey,
+    ticketTokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+    prizeTokenProgram: PublicKey = TOKEN_PROGRAM_ID
 ) {
-    const creatorPrizeAta = await createAta(prizeMint, creator.publicKey);
-    const prizeEscrow = await createAta(prizeMint, rafflePda);
-    const ticketEscrow = await createAta(ticketMint, rafflePda);
+    const creatorPrizeAta = await createAta(prizeMint, creator.publicKey, prizeTokenProgram);
+    const prizeEscrow = await createAta(prizeMint, rafflePda, prizeTokenProgram);
+    const ticketEscrow = await createAta(ticketMint, rafflePda, ticketTokenProgram);
     return { ticketEscrow, prizeEscrow, creatorPrizeAta };
 }
 
@@ -156,6 +437,9 @@ export async function createRaffle(
         ticketEscrow: PublicKey;
         prizeEscrow: PublicKey;
         creatorPrizeAta: PublicKey;
+        ticketTokenProgram?: PublicKey;
+        prizeTokenProgram?: PublicKey;
+        remainingAccounts?: AccountMeta[];
     }
 ) {
     const response = await program.methods
@@ -183,10 +467,12 @@ export async function createRaffle(
             ticketEscrow: accounts.ticketEscrow,
             prizeEscrow: accounts.prizeEscrow,
             creatorPrizeAta: accounts.creatorPrizeAta,
-            ticketTokenProgram: TOKEN_PROGRAM_ID,
-            prizeTokenProgram: TOKEN_PROGRAM_ID,
+            ticketTokenProgram: accounts.ticketTokenProgram ?? TOKEN_PROGRAM_ID,
+            prizeTokenProgram: accounts.prizeTokenProgram ?? TOKEN_PROGRAM_ID,
             systemProgram: SystemProgram.programId,
+            ...pnftAccounts(),
         })
+        .remainingAccounts(accounts.remainingAccounts ?? [])
         .signers([accounts.creator, accounts.raffleAdmin])
         .rpc();
 
@@ -212,6 +498,108 @@ export async function activateRaffle(
         .rpc();
 }
 
+export async function setRaffleFrozen(
+    program: anchor.Program,
+    rafflePda: PublicKey,
+    raffleId: number,
+    frozen: boolean,
+    pauser: Keypair
+) {
+    await program.methods
+        .setRaffleFrozen(raffleId, frozen)
+        .accounts({
+            roleRegistry: roleRegistryPda(),
+            raffle: rafflePda,
+            pauser: pauser.publicKey,
+        })
+        .signers([pauser])
+        .rpc();
+}
+
+// === MINT POLICY ===
+
+export async function setAllowedMintExtensions(
+    program: anchor.Program,
+    owner: Keypair,
+    allowedMintExtensions: number
+) {
+    await program.methods
+        .setAllowedMintExtensions(allowedMintExtensions)
+        .accounts({
+            raffleConfig: raffleConfigPda(),
+            raffleOwner: owner.publicKey,
+        })
+        .signers([owner])
+        .rpc();
+}
+
+export async function allowMint(program: anchor.Program, owner: Keypair, mint: PublicKey) {
+    await program.methods
+        .allowMint()
+        .accounts({
+            raffleConfig: raffleConfigPda(),
+            mint,
+            mintAllowlist: mintAllowlistPda(mint),
+            raffleOwner: owner.publicKey,
+            systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+}
+
+export async function removeAllowedMint(program: anchor.Program, owner: Keypair, mint: PublicKey) {
+    await program.methods
+        .removeAllowedMint()
+        .accounts({
+            raffleConfig: raffleConfigPda(),
+            mintAllowlist: mintAllowlistPda(mint),
+            raffleOwner: owner.publicKey,
+        })
+        .signers([owner])
+        .rpc();
+}
+
+export async function setCollectionPolicy(
+    program: anchor.Program,
+    owner: Keypair,
+    requireVerifiedCollection: boolean
+) {
+    await program.methods
+        .setCollectionPolicy(requireVerifiedCollection)
+        .accounts({
+            raffleConfig: raffleConfigPda(),
+            raffleOwner: owner.publicKey,
+        })
+        .signers([owner])
+        .rpc();
+}
+
+export async function allowCollection(program: anchor.Program, owner: Keypair, collection: PublicKey) {
+    await program.methods
+        .allowCollection()
+        .accounts({
+            raffleConfig: raffleConfigPda(),
+            collectionMint: collection,
+            collectionAllowlist: collectionAllowlistPda(collection),
+            raffleOwner: owner.publicKey,
+            systemProgram: SystemProgram.programId,
+        })
+        .signers([owner])
+        .rpc();
+}
+
+export async function removeAllowedCollection(program: anchor.Program, owner: Keypair, collection: PublicKey) {
+    await program.methods
+        .removeAllowedCollection()
+        .accounts({
+            raffleConfig: raffleConfigPda(),
+            collectionAllowlist: collectionAllowlistPda(collection),
+            raffleOwner: owner.publicKey,
+        })
+        .signers([owner])
+        .rpc();
+}
+
 // === CANCEL RAFFLE ===
 
 export async function cancelRaffle(
@@ -223,6 +611,8 @@ export async function cancelRaffle(
     prizeMint: PublicKey,
     prizeEscrow: PublicKey,
     creatorPrizeAta: PublicKey,
+    prizeTokenProgram: PublicKey = TOKEN_PROGRAM_ID,
+    remainingAccounts: AccountMeta[] = [],
 ) {
     await program.methods
         .cancelRaffle(raffleId)
@@ -234,14 +624,77 @@ export async function cancelRaffle(
             prizeMint,
             prizeEscrow,
             creatorPrizeAta,
-            prizeTokenProgram: TOKEN_PROGRAM_ID,
+            prizeTokenProgram,
             systemProgram: SystemProgram.programId,
+            ...pnftAccounts(),
         })
+        .remainingAccounts(remainingAccounts)
         .signers([creator, admin])
         .rpc();
 }
 
 
+// === PERMITS ===
+
+let nextPermitNonce = 1;
+
+// Mirrors `utils::permit_message` on chain
+export function permitMessage(
+    programId: PublicKey,
+    raffleId: number,
+    user: PublicKey,
+    permit: { maxAmount: anchor.BN; expiry: anchor.BN; nonce: anchor.BN }
+): Buffer {
+    return Buffer.concat([
+        programId.toBuffer(),
+        new anchor.BN(raffleId).toArrayLike(Buffer, "le", 4),
+        user.toBuffer(),
+        permit.maxAmount.toArrayLike(Buffer, "le", 8),
+        permit.expiry.toTwos(64).toArrayLike(Buffer, "le", 8),
+        permit.nonce.toArrayLike(Buffer, "le", 8),
+    ]);
+}
+
+// Signs a permit off-chain and returns it with the Ed25519 instruction that must precede the purchase
+export async function signPermit(
+    program: anchor.Program,
+    raffleId: number,
+    user: PublicKey,
+    maxAmount: number,
+    signer: Keypair,
+    overrides: { expiry?: number; nonce?: number } = {}
+): Promise<{
+    permit: { maxAmount: anchor.BN; expiry: anchor.BN; nonce: anchor.BN };
+    ed25519Ix: TransactionInstruction;
+    permitNonce: PublicKey;
+}> {
+    const expiry = overrides.expiry ?? (await getCurrentTimestamp()) + 60 * 60;
+    const nonce = overrides.nonce ?? nextPermitNonce++;
+
+    const permit = {
+        maxAmount: new anchor.BN(maxAmount),
+        expiry: new anchor.BN(expiry),
+        nonce: new anchor.BN(nonce),
+    };
+
+    const ed25519Ix = Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: signer.secretKey,
+        message: permitMessage(program.programId, raffleId, user, permit),
+    });
+
+    const permitNonce = PublicKey.findProgramAddressSync(
+        [
+            Buffer.from("raffle"),
+            Buffer.from("permit"),
+            user.toBuffer(),
+            permit.nonce.toArrayLike(Buffer, "le", 8),
+        ],
+        program.programId
+    )[0];
+
+    return { permit, ed25519Ix, permitNonce };
+}
+
 // === BUY TICKETS ===
 
 export async function buyTickets(
@@ -253,7 +706,39 @@ export async function buyTickets(
     ticketMint: PublicKey,
     ticketEscrow: PublicKey,
     buyerTicketAta: PublicKey,
-    raffleAdmin: Keypair
+    raffleAdmin: Keypair,
+    ticketTokenProgram: PublicKey = TOKEN_PROGRAM_ID
+) {
+    // the admin only signs the permit, the buyer submits on their own
+    const raffle = await program.account.raffle.fetch(rafflePda);
+    const price = raffle.ticketPrice.toNumber() * ticketsToBuy;
+    const signed = await signPermit(program, raffleId, buyer.publicKey, price, raffleAdmin);
+
+    await buyTicketsWithPermit(
+        program,
+        rafflePda,
+        raffleId,
+        buyer,
+        ticketsToBuy,
+        ticketMint,
+        ticketEscrow,
+        buyerTicketAta,
+        signed,
+        ticketTokenProgram
+    );
+}
+
+export async function buyTicketsWithPermit(
+    program: anchor.Program,
+    rafflePda: PublicKey,
+    raffleId: number,
+    buyer: Keypair,
+    ticketsToBuy: number,
+    ticketMint: PublicKey,
+    ticketEscrow: PublicKey,
+    buyerTicketAta: PublicKey,
+    signed: Awaited<ReturnType<typeof signPermit>>,
+    ticketTokenProgram: PublicKey = TOKEN_PROGRAM_ID
 ) {
     const buyerAccount = PublicKey.findProgramAddressSync(
         [
@@ -265,20 +750,23 @@ export async function buyTickets(
     )[0];
 
     await program.methods
-        .buyTicket(raffleId, ticketsToBuy)
+        .buyTicket(raffleId, ticketsToBuy, signed.permit)
         .accounts({
             raffleConfig: raffleConfigPda(),
+            roleRegistry: roleRegistryPda(),
             raffle: rafflePda,
             buyerAccount,
             buyer: buyer.publicKey,
-            raffleAdmin: raffleAdmin.publicKey,
+            permitNonce: signed.permitNonce,
+            instructionsSysvar: SYSVAR_INSTRUCTIONS_PUBKEY,
             ticketMint,
             buyerTicketAta,
             ticketEscrow,
-            ticketTokenProgram: TOKEN_PROGRAM_ID,
+            ticketTokenProgram,
             systemProgram: SystemProgram.programId,
         })
-        .signers([buyer, raffleAdmin])
+        .preInstructions([signed.ed25519Ix])
+        .signers([buyer])
         .rpc();
 }
 
@@ -418,7 +906,7 @@ export async function buyerClaimPrize(
 
     return await program.methods
         .buyerClaimPrize(raffleId)
-        .accounts(accounts)
+        .accounts({ ...accounts, ...pnftAccounts() })
         .signers([winner, raffleAdmin])
         .rpc();
 }
@@ -469,7 +957,7 @@ export async function creatorClaimAmountBack(
 
     return await program.methods
         .claimAmountBack(raffleId)
-        .accounts(accounts)
+        .accounts({ ...accounts, ...pnftAccounts() })
         .signers([creator, raffleAdmin])
         .rpc();
 }
@@ -0,0 +1,322 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import * as web3 from "@solana/web3.js";
+
+import {
+  creation_fee_lamports,
+  ticket_fee_bps,
+  minimum_raffle_period,
+  maximum_raffle_period,
+  minimum_config_change_delay,
+  raffle_owner,
+  raffle_admin,
+  raffle_1_creator,
+  setProgram,
+  setProvider,
+  raffleConfigPda,
+  rafflePda,
+} from "./values";
+
+// Sizes of the accounts as deployed before the `version` byte and reserved space
+const LEGACY_CONFIG_LEN = 8 + 32 + 32 + 8 + 2 + 4 + 4 + 4 + 1 + 1;
+const LEGACY_RAFFLE_LEN =
+  8 + 4 + 32 + 8 + 8 + 2 + 2 + 2 + 8 + 33 + 1 + 1 + 8 + 33 + 1 + 1 + 1 +
+  (4 + 10) + (4 + 32 * 10) + (4 + 10) + 8 + 8 + 1;
+
+function discriminator(program: anchor.Program<any>, name: string): Buffer {
+  const account = program.idl.accounts.find((a: any) => a.name === name);
+  return Buffer.from(account.discriminator);
+}
+
+function legacyConfigSnapshot(program: anchor.Program<any>, bump: number): Buffer {
+  const buf = Buffer.alloc(LEGACY_CONFIG_LEN);
+  let o = 0;
+  o += discriminator(program, "raffleConfig").copy(buf, o);
+  o += raffle_owner.publicKey.toBuffer().copy(buf, o);
+  o += raffle_admin.publicKey.toBuffer().copy(buf, o);
+  o = buf.writeBigUInt64LE(BigInt(creation_fee_lamports), o);
+  o = buf.writeUInt16LE(ticket_fee_bps, o);
+  o = buf.writeUInt32LE(minimum_raffle_period, o);
+  o = buf.writeUInt32LE(maximum_raffle_period, o);
+  o = buf.writeUInt32LE(2, o); // raffle_count
+  o = buf.writeUInt8(0b0000_1000, o); // pause_flags
+  buf.writeUInt8(bump, o);
+  return buf;
+}
+
+// Version 1 config, same size as the current one but with a u8 `pause_flags`
+function v1ConfigSnapshot(program: anchor.Program<any>, bump: number): Buffer {
+  const buf = Buffer.alloc(program.account.raffleConfig.size);
+  let o = 0;
+  o += discriminator(program, "raffleConfig").copy(buf, o);
+  o = buf.writeUInt8(1, o); // version
+  o += raffle_owner.publicKey.toBuffer().copy(buf, o);
+  o += raffle_admin.publicKey.toBuffer().copy(buf, o);
+  o = buf.writeBigUInt64LE(BigInt(creation_fee_lamports), o);
+  o = buf.writeUInt16LE(ticket_fee_bps, o);
+  o = buf.writeUInt32LE(minimum_raffle_period, o);
+  o = buf.writeUInt32LE(maximum_raffle_period, o);
+  o = buf.writeUInt32LE(7, o); // raffle_count
+  o = buf.writeUInt8(0b1000_0001, o); // pause_flags
+  o = buf.writeUInt8(bump, o);
+  o = buf.writeUInt8(0, o); // pending_owner = None
+  o = buf.writeUInt32LE(minimum_config_change_delay, o);
+  buf.writeUInt8(0, o); // pending_config_data = None
+  return buf;
+}
+
+// Active SOL-prize raffle with 3 tickets sold, 2 winners pending
+function legacyRaffleSnapshot(program: anchor.Program<any>, bump: number): Buffer {
+  const buf = Buffer.alloc(LEGACY_RAFFLE_LEN);
+  let o = 0;
+  o += discriminator(program, "raffle").copy(buf, o);
+  o = buf.writeUInt32LE(1, o); // raffle_id
+  o += raffle_1_creator.publicKey.toBuffer().copy(buf, o);
+  o = buf.writeBigInt64LE(1_000n, o); // start_time
+  o = buf.writeBigInt64LE(5_000n, o); // end_time
+  o = buf.writeUInt16LE(10, o); // total_tickets
+  o = buf.writeUInt16LE(3, o); // tickets_sold
+  o = buf.writeUInt16LE(2, o); // buyers_count
+  o = buf.writeBigUInt64LE(100_000_000n, o); // ticket_price
+  o = buf.writeUInt8(0, o); // ticket_mint = None
+  o = buf.writeUInt8(40, o); // max_per_wallet_pct
+  o = buf.writeUInt8(2, o); // prize_type = Sol
+  o = buf.writeBigUInt64LE(2_000_000_000n, o); // prize_amount
+  o = buf.writeUInt8(0, o); // prize_mint = None
+  o = buf.writeUInt8(2, o); // num_winners
+  o = buf.writeUInt8(1, o); // is_unique_winners
+  o = buf.writeUInt8(2, o); // status = Active
+  o = buf.writeUInt32LE(2, o); // win_shares
+  o = buf.writeUInt8(70, o);
+  o = buf.writeUInt8(30, o);
+  o = buf.writeUInt32LE(2, o); // winners
+  o += Buffer.alloc(64).copy(buf, o);
+  o = buf.writeUInt32LE(2, o); // is_win_claimed
+  o = buf.writeUInt8(0, o);
+  o = buf.writeUInt8(0, o);
+  o = buf.writeBigUInt64LE(0n, o); // claimable_ticket_amount
+  o = buf.writeBigUInt64LE(0n, o); // claimable_prize_back
+  buf.writeUInt8(bump, o);
+  return buf;
+}
+
+describe("Account migration – legacy snapshots", () => {
+  let context: any;
+  let provider: BankrunProvider;
+  let program: anchor.Program<any>;
+
+  before(async () => {
+    context = await startAnchor("", [], []);
+    provider = new BankrunProvider(context);
+
+    anchor.setProvider(provider);
+    setProvider(provider);
+
+    program = anchor.workspace.Raffle as anchor.Program<any>;
+    setProgram(program);
+
+    await context.setAccount(raffle_owner.publicKey, {
+      lamports: 10_000_000_000n,
+      owner: web3.SystemProgram.programId,
+      data: Buffer.alloc(0),
+      executable: false,
+    });
+
+    const [, configBump] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("raffle")],
+      program.programId
+    );
+    await context.setAccount(raffleConfigPda(), {
+      lamports: 1_000_000_000n,
+      owner: program.programId,
+      data: legacyConfigSnapshot(program, configBump),
+      executable: false,
+    });
+
+    const [, raffleBump] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("raffle"), new anchor.BN(1).toArrayLike(Buffer, "le", 4)],
+      program.programId
+    );
+    await context.setAccount(rafflePda(1), {
+      lamports: 2_300_000_000n + 10_000_000n, // prize + ticket revenue + rent
+      owner: program.programId,
+      data: legacyRaffleSnapshot(program, raffleBump),
+      executable: false,
+    });
+  });
+
+  it("rejects config migration from a non-owner", async () => {
+    const fake_owner = web3.Keypair.generate();
+    await context.setAccount(fake_owner.publicKey, {
+      lamports: 1_000_000_000n,
+      owner: web3.SystemProgram.programId,
+      data: Buffer.alloc(0),
+      executable: false,
+    });
+
+    await assert.rejects(
+      program.methods
+        .migrateRaffleConfig()
+        .accounts({
+          raffleConfig: raffleConfigPda(),
+          raffleOwner: fake_owner.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .signers([fake_owner])
+        .rpc()
+    );
+  });
+
+  it("migrates the legacy config and keeps its data", async () => {
+    await program.methods
+      .migrateRaffleConfig()
+      .accounts({
+        raffleConfig: raffleConfigPda(),
+        raffleOwner: raffle_owner.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([raffle_owner])
+      .rpc();
+
+    const cfg = await program.account.raffleConfig.fetch(raffleConfigPda());
+    assert.equal(cfg.version, 2);
+    assert.equal(cfg.raffleOwner.toString(), raffle_owner.publicKey.toString());
+    assert.equal(cfg.raffleAdmin.toString(), raffle_admin.publicKey.toString());
+    assert.equal(cfg.creationFeeLamports.toNumber(), creation_fee_lamports);
+    assert.equal(cfg.ticketFeeBps, ticket_fee_bps);
+    assert.equal(cfg.minimumRafflePeriod, minimum_raffle_period);
+    assert.equal(cfg.maximumRafflePeriod, maximum_raffle_period);
+    assert.equal(cfg.raffleCount, 2);
+    assert.equal(cfg.pauseFlags.toNumber(), 0b0000_1000);
+    assert.equal(cfg.pendingOwner, null);
+    assert.equal(cfg.configChangeDelay, minimum_config_change_delay);
+
+    const info = await context.banksClient.getAccount(raffleConfigPda());
+    assert.ok(info.data.length > LEGACY_CONFIG_LEN);
+  });
+
+  it("fails to migrate the config twice", async () => {
+    await assert.rejects(
+      program.methods
+        .migrateRaffleConfig()
+        .accounts({
+          raffleConfig: raffleConfigPda(),
+          raffleOwner: raffle_owner.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .signers([raffle_owner])
+        .rpc()
+    );
+  });
+
+  it("migrates a live legacy raffle without touching escrowed SOL", async () => {
+    const before = await context.banksClient.getAccount(rafflePda(1));
+
+    await program.methods
+      .migrateRaffle(1)
+      .accounts({
+        raffle: rafflePda(1),
+        raffleConfig: raffleConfigPda(),
+        payer: raffle_owner.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([raffle_owner])
+      .rpc();
+
+    const raffle = await program.account.raffle.fetch(rafflePda(1));
+    assert.equal(raffle.version, 2);
+    assert.equal(raffle.raffleId, 1);
+    assert.equal(raffle.creator.toString(), raffle_1_creator.publicKey.toString());
+    assert.equal(raffle.totalTickets, 10);
+    assert.equal(raffle.ticketsSold, 3);
+    assert.equal(raffle.buyersCount, 2);
+    assert.equal(raffle.prizeAmount.toNumber(), 2_000_000_000);
+    assert.deepEqual(Array.from(raffle.winShares), [70, 30]);
+    assert.equal(raffle.winners.length, 2);
+    assert.ok("active" in raffle.status);
+    // fee frozen from the config at migration time
+    assert.equal(raffle.ticketFeeBps, ticket_fee_bps);
+    // legacy escrows were paid in full, revenue is reconstructed from the price
+    assert.equal(raffle.ticketRevenue.toNumber(), 3 * 100_000_000);
+
+    // rent for the extra bytes comes from the payer, never from the escrow
+    const after = await context.banksClient.getAccount(rafflePda(1));
+    assert.ok(BigInt(after.lamports) >= BigInt(before.lamports));
+  });
+
+  it("fails to migrate the raffle twice", async () => {
+    await assert.rejects(
+      program.methods
+        .migrateRaffle(1)
+        .accounts({
+          raffle: rafflePda(1),
+          raffleConfig: raffleConfigPda(),
+          payer: raffle_owner.publicKey,
+          systemProgram: web3.SystemProgram.programId,
+        })
+        .signers([raffle_owner])
+        .rpc()
+    );
+  });
+});
+
+describe("Account migration – version 1 config", () => {
+  let context: any;
+  let program: anchor.Program<any>;
+
+  before(async () => {
+    context = await startAnchor("", [], []);
+    const provider = new BankrunProvider(context);
+
+    anchor.setProvider(provider);
+    setProvider(provider);
+
+    program = anchor.workspace.Raffle as anchor.Program<any>;
+    setProgram(program);
+
+    await context.setAccount(raffle_owner.publicKey, {
+      lamports: 10_000_000_000n,
+      owner: web3.SystemProgram.programId,
+      data: Buffer.alloc(0),
+      executable: false,
+    });
+
+    const [, configBump] = web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("raffle")],
+      program.programId
+    );
+    await context.setAccount(raffleConfigPda(), {
+      lamports: 1_000_000_000n,
+      owner: program.programId,
+      data: v1ConfigSnapshot(program, configBump),
+      executable: false,
+    });
+  });
+
+  it("widens the pause flags in place", async () => {
+    const before = await context.banksClient.getAccount(raffleConfigPda());
+
+    await program.methods
+      .migrateRaffleConfig()
+      .accounts({
+        raffleConfig: raffleConfigPda(),
+        raffleOwner: raffle_owner.publicKey,
+        systemProgram: web3.SystemProgram.programId,
+      })
+      .signers([raffle_owner])
+      .rpc();
+
+    const cfg = await program.account.raffleConfig.fetch(raffleConfigPda());
+    assert.equal(cfg.version, 2);
+    assert.equal(cfg.raffleOwner.toString(), raffle_owner.publicKey.toString());
+    assert.equal(cfg.raffleCount, 7);
+    assert.equal(cfg.pauseFlags.toNumber(), 0b1000_0001);
+    assert.equal(cfg.configChangeDelay, minimum_config_change_delay);
+    assert.equal(cfg.pendingConfigData, null);
+
+    const after = await context.banksClient.getAccount(raffleConfigPda());
+    assert.equal(after.data.length, before.data.length);
+  });
+});
@@ -0,0 +1,164 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { Keypair, PublicKey, SystemProgram } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+
+import {
+    createRaffleConfig,
+    createSplMint,
+    createPermanentDelegateMint,
+    createRaffle,
+    buildCreateRaffleAccounts,
+    mintTokens,
+    getCurrentTimestamp,
+    setAllowedMintExtensions,
+    allowMint,
+    removeAllowedMint,
+} from "./helpers";
+import {
+    raffle_owner,
+    raffle_admin,
+    raffle_1_creator,
+    setProgram,
+    setProvider,
+    raffleConfigPda,
+    rafflePda,
+    mintAllowlistPda,
+    minimum_raffle_period,
+    maximum_raffle_period,
+    creation_fee_lamports,
+    ticket_fee_bps,
+    PERMANENT_DELEGATE_EXTENSION,
+    PAUSABLE_EXTENSION,
+} from "./values";
+
+describe("Mint extension policy", () => {
+    let context: any;
+    let provider: BankrunProvider;
+    let program: anchor.Program<any>;
+
+    before(async () => {
+        context = await startAnchor("", [], []);
+        provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+        program = anchor.workspace.Raffle as anchor.Program<any>;
+        setProgram(program);
+
+        for (const kp of [raffle_owner, raffle_1_creator]) {
+            await context.setAccount(kp.publicKey, {
+                lamports: 20_000_000_000,
+                owner: SystemProgram.programId,
+                executable: false,
+                data: Buffer.alloc(0),
+            });
+        }
+
+        await createRaffleConfig(program, raffle_owner, raffle_admin.publicKey, {
+            creationFeeLamports: creation_fee_lamports,
+            ticketFeeBps: ticket_fee_bps,
+            minPeriod: minimum_raffle_period,
+            maxPeriod: maximum_raffle_period,
+        });
+    });
+
+    // SOL tickets, `prizeMint` as a Token-2022 SPL prize
+    async function createWithToken2022Prize(prizeMint: PublicKey) {
+        const cfg = await program.account.raffleConfig.fetch(raffleConfigPda());
+        const raffleId = cfg.raffleCount;
+        const raffle = rafflePda(raffleId);
+        const ticketMint = await createSplMint();
+
+        const accounts = await buildCreateRaffleAccounts(
+            raffle,
+            raffle_1_creator,
+            ticketMint,
+            prizeMint,
+            undefined,
+            TOKEN_2022_PROGRAM_ID
+        );
+        await mintTokens(prizeMint, accounts.creatorPrizeAta, 1_000_000_000, TOKEN_2022_PROGRAM_ID);
+
+        const now = await getCurrentTimestamp();
+        await createRaffle(program, {
+            startTime: now,
+            endTime: now + minimum_raffle_period + 100,
+            totalTickets: 10,
+            ticketPrice: 100_000_000,
+            isTicketSol: true,
+            maxPct: 20,
+            prizeType: { spl: {} },
+            prizeAmount: 1_000_000_000,
+            numWinners: 1,
+            winShares: [100],
+            unique: false,
+            autoStart: true,
+        }, {
+            raffleConfig: raffleConfigPda(),
+            rafflePda: raffle,
+            creator: raffle_1_creator,
+            raffleAdmin: raffle_admin,
+            ticketMint,
+            prizeMint,
+            ticketEscrow: accounts.ticketEscrow,
+            prizeEscrow: accounts.prizeEscrow,
+            creatorPrizeAta: accounts.creatorPrizeAta,
+            prizeTokenProgram: TOKEN_2022_PROGRAM_ID,
+        });
+
+        return raffle;
+    }
+
+    it("rejects a prize mint with a permanent delegate by default", async () => {
+        const mint = await createPermanentDelegateMint(Keypair.generate().publicKey);
+
+        await assert.rejects(createWithToken2022Prize(mint), /PermanentDelegateNotAllowed/);
+    });
+
+    it("accepts the mint once the owner allowlists it", async () => {
+        const mint = await createPermanentDelegateMint(Keypair.generate().publicKey);
+        await allowMint(program, raffle_owner, mint);
+
+        const entry = await program.account.mintAllowlist.fetch(mintAllowlistPda(mint));
+        assert.equal(entry.mint.toString(), mint.toString());
+
+        const raffle = await createWithToken2022Prize(mint);
+        const state = await program.account.raffle.fetch(raffle);
+        assert.equal(state.prizeMint.toString(), mint.toString());
+
+        // removing it only blocks new raffles
+        await removeAllowedMint(program, raffle_owner, mint);
+        assert.equal(await context.banksClient.getAccount(mintAllowlistPda(mint)), null);
+        await assert.rejects(createWithToken2022Prize(mint), /PermanentDelegateNotAllowed/);
+    });
+
+    it("only lets the owner allowlist a mint", async () => {
+        const mint = await createPermanentDelegateMint(Keypair.generate().publicKey);
+
+        await assert.rejects(allowMint(program, raffle_1_creator, mint), /InvalidRaffleOwner/);
+    });
+
+    it("accepts the extension for every mint once the config allows it", async () => {
+        await assert.rejects(
+            setAllowedMintExtensions(program, raffle_owner, 1 << (PAUSABLE_EXTENSION + 1)),
+            /InvalidMintExtension/
+        );
+        await assert.rejects(
+            setAllowedMintExtensions(program, raffle_1_creator, 1 << PERMANENT_DELEGATE_EXTENSION),
+            /InvalidRaffleOwner/
+        );
+
+        await setAllowedMintExtensions(program, raffle_owner, 1 << PERMANENT_DELEGATE_EXTENSION);
+        const cfg = await program.account.raffleConfig.fetch(raffleConfigPda());
+        assert.equal(cfg.allowedMintExtensions, 1 << PERMANENT_DELEGATE_EXTENSION);
+
+        const mint = await createPermanentDelegateMint(Keypair.generate().publicKey);
+        await createWithToken2022Prize(mint);
+
+        await setAllowedMintExtensions(program, raffle_owner, 0);
+        const other = await createPermanentDelegateMint(Keypair.generate().publicKey);
+        await assert.rejects(createWithToken2022Prize(other), /PermanentDelegateNotAllowed/);
+    });
+});
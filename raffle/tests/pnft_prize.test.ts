@@ -0,0 +1,130 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { SystemProgram } from "@solana/web3.js";
+import { AccountLayout, getAssociatedTokenAddressSync } from "@solana/spl-token";
+
+import {
+    createRaffleConfig,
+    createPnftMint,
+    createRaffle,
+    cancelRaffle,
+    createAta,
+    getTokenBalance,
+    getCurrentTimestamp,
+} from "./helpers";
+import {
+    raffle_owner,
+    raffle_admin,
+    raffle_1_creator,
+    setProgram,
+    setProvider,
+    raffleConfigPda,
+    rafflePda,
+    tokenRecordPda,
+    minimum_raffle_period,
+    maximum_raffle_period,
+    creation_fee_lamports,
+    ticket_fee_bps,
+    TOKEN_METADATA_PROGRAM_ID,
+} from "./values";
+
+// Token Metadata is loaded from tests/fixtures/mpl_token_metadata.so, dumped from mainnet with
+// `solana program dump -u m metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s tests/fixtures/mpl_token_metadata.so`
+describe("Programmable NFT prizes", () => {
+    let context: any;
+    let provider: BankrunProvider;
+    let program: anchor.Program<any>;
+
+    before(async () => {
+        context = await startAnchor("", [{ name: "mpl_token_metadata", programId: TOKEN_METADATA_PROGRAM_ID }], []);
+        provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+        program = anchor.workspace.Raffle as anchor.Program<any>;
+        setProgram(program);
+
+        for (const kp of [raffle_owner, raffle_1_creator]) {
+            await context.setAccount(kp.publicKey, {
+                lamports: 20_000_000_000,
+                owner: SystemProgram.programId,
+                executable: false,
+                data: Buffer.alloc(0),
+            });
+        }
+
+        await createRaffleConfig(program, raffle_owner, raffle_admin.publicKey, {
+            creationFeeLamports: creation_fee_lamports,
+            ticketFeeBps: ticket_fee_bps,
+            minPeriod: minimum_raffle_period,
+            maxPeriod: maximum_raffle_period,
+        });
+    });
+
+    // pNFT token accounts sit frozen between transfers
+    async function isFrozen(ata: anchor.web3.PublicKey) {
+        const info = await provider.connection.getAccountInfo(ata);
+        return AccountLayout.decode(info!.data).state === 2;
+    }
+
+    it("escrows a pNFT prize and hands it back on cancel", async () => {
+        const prizeMint = await createPnftMint(raffle_1_creator.publicKey);
+        const creatorPrizeAta = getAssociatedTokenAddressSync(prizeMint, raffle_1_creator.publicKey);
+
+        const cfg = await program.account.raffleConfig.fetch(raffleConfigPda());
+        const raffleId = cfg.raffleCount;
+        const raffle = rafflePda(raffleId);
+        const prizeEscrow = await createAta(prizeMint, raffle);
+
+        const now = await getCurrentTimestamp();
+        await createRaffle(
+            program,
+            {
+                startTime: now + 10,
+                endTime: now + 10 + minimum_raffle_period,
+                totalTickets: 100,
+                ticketPrice: 100_000,
+                isTicketSol: true,
+                maxPct: 30,
+                prizeType: { nft: {} },
+                prizeAmount: 1,
+                numWinners: 1,
+                winShares: [100],
+                unique: false,
+                autoStart: false,
+            },
+            {
+                raffleConfig: raffleConfigPda(),
+                rafflePda: raffle,
+                creator: raffle_1_creator,
+                raffleAdmin: raffle_admin,
+                ticketMint: prizeMint,
+                prizeMint,
+                ticketEscrow: prizeEscrow,
+                prizeEscrow,
+                creatorPrizeAta,
+            }
+        );
+
+        // the move went through Token Metadata: the escrow holds the pNFT frozen, under its own token record
+        assert.equal(await getTokenBalance(prizeEscrow), 1);
+        assert.equal(await getTokenBalance(creatorPrizeAta), 0);
+        assert.ok(await isFrozen(prizeEscrow));
+        assert.ok(await provider.connection.getAccountInfo(tokenRecordPda(prizeMint, prizeEscrow)));
+
+        await cancelRaffle(
+            program,
+            raffle,
+            raffleId,
+            raffle_1_creator,
+            raffle_admin,
+            prizeMint,
+            prizeEscrow,
+            creatorPrizeAta
+        );
+
+        assert.equal(await getTokenBalance(creatorPrizeAta), 1);
+        assert.ok(await isFrozen(creatorPrizeAta));
+    });
+});
@@ -0,0 +1,160 @@
+import assert from "assert";
+import * as anchor from "@coral-xyz/anchor";
+import { startAnchor } from "solana-bankrun";
+import { BankrunProvider } from "anchor-bankrun";
+import { PublicKey, SystemProgram } from "@solana/web3.js";
+import { TOKEN_2022_PROGRAM_ID } from "@solana/spl-token";
+
+import {
+    createRaffleConfig,
+    createSplMint,
+    createTransferHookMint,
+    transferHookAccounts,
+    createRaffle,
+    cancelRaffle,
+    buildCreateRaffleAccounts,
+    mintTokens,
+    getTokenBalance,
+    getCurrentTimestamp,
+    allowMint,
+} from "./helpers";
+import {
+    raffle_owner,
+    raffle_admin,
+    raffle_1_creator,
+    setProgram,
+    setProvider,
+    raffleConfigPda,
+    rafflePda,
+    minimum_raffle_period,
+    maximum_raffle_period,
+    creation_fee_lamports,
+    ticket_fee_bps,
+} from "./values";
+
+describe("Transfer hook mints", () => {
+    let context: any;
+    let provider: BankrunProvider;
+    let program: anchor.Program<any>;
+    let hookProgram: anchor.Program<any>;
+    let prizeMint: PublicKey;
+
+    const prizeAmount = 1_000_000_000;
+
+    before(async () => {
+        context = await startAnchor("", [], []);
+        provider = new BankrunProvider(context);
+        anchor.setProvider(provider);
+        setProvider(provider);
+        program = anchor.workspace.Raffle as anchor.Program<any>;
+        hookProgram = anchor.workspace.TransferHookCounter as anchor.Program<any>;
+        setProgram(program);
+
+        for (const kp of [raffle_owner, raffle_1_creator]) {
+            await context.setAccount(kp.publicKey, {
+                lamports: 20_000_000_000,
+                owner: SystemProgram.programId,
+                executable: false,
+                data: Buffer.alloc(0),
+            });
+        }
+
+        await createRaffleConfig(program, raffle_owner, raffle_admin.publicKey, {
+            creationFeeLamports: creation_fee_lamports,
+            ticketFeeBps: ticket_fee_bps,
+            minPeriod: minimum_raffle_period,
+            maxPeriod: maximum_raffle_period,
+        });
+
+        prizeMint = await createTransferHookMint(hookProgram.programId);
+        await hookProgram.methods
+            .initializeExtraAccountMetaList()
+            .accounts({ payer: provider.wallet.publicKey, mint: prizeMint })
+            .rpc();
+
+        // hooked mints are rejected by the mint policy unless vetted
+        await allowMint(program, raffle_owner, prizeMint);
+    });
+
+    async function hookTransfers(): Promise<number> {
+        const counter = transferHookAccounts(hookProgram.programId, prizeMint)[2].pubkey;
+        const state = await hookProgram.account.transferCounter.fetch(counter);
+        return state.transfers.toNumber();
+    }
+
+    // SOL tickets, the hooked mint as an SPL prize
+    async function createHookedRaffle(withHookAccounts: boolean) {
+        const cfg = await program.account.raffleConfig.fetch(raffleConfigPda());
+        const raffleId = cfg.raffleCount;
+        const raffle = rafflePda(raffleId);
+        const ticketMint = await createSplMint();
+
+        const accounts = await buildCreateRaffleAccounts(
+            raffle,
+            raffle_1_creator,
+            ticketMint,
+            prizeMint,
+            undefined,
+            TOKEN_2022_PROGRAM_ID
+        );
+        await mintTokens(prizeMint, accounts.creatorPrizeAta, prizeAmount, TOKEN_2022_PROGRAM_ID);
+
+        const now = await getCurrentTimestamp();
+        await createRaffle(program, {
+            startTime: now,
+            endTime: now + minimum_raffle_period + 100,
+            totalTickets: 10,
+            ticketPrice: 100_000_000,
+            isTicketSol: true,
+            maxPct: 20,
+            prizeType: { spl: {} },
+            prizeAmount,
+            numWinners: 1,
+            winShares: [100],
+            unique: false,
+            autoStart: true,
+        }, {
+            raffleConfig: raffleConfigPda(),
+            rafflePda: raffle,
+            creator: raffle_1_creator,
+            raffleAdmin: raffle_admin,
+            ticketMint,
+            prizeMint,
+            ticketEscrow: accounts.ticketEscrow,
+            prizeEscrow: accounts.prizeEscrow,
+            creatorPrizeAta: accounts.creatorPrizeAta,
+            prizeTokenProgram: TOKEN_2022_PROGRAM_ID,
+            remainingAccounts: withHookAccounts ? transferHookAccounts(hookProgram.programId, prizeMint) : [],
+        });
+
+        return { raffle, raffleId, ...accounts };
+    }
+
+    it("fails with a clear error when the hook accounts are not forwarded", async () => {
+        await assert.rejects(createHookedRaffle(false), /MissingTransferHookAccounts/);
+    });
+
+    it("runs the hook when escrowing the prize and when the raffle PDA returns it", async () => {
+        const before = await hookTransfers();
+
+        const { raffle, raffleId, prizeEscrow, creatorPrizeAta } = await createHookedRaffle(true);
+        assert.equal(await getTokenBalance(prizeEscrow), prizeAmount);
+        assert.equal(await hookTransfers(), before + 1);
+
+        // PDA-signed transfer out of the escrow
+        await cancelRaffle(
+            program,
+            raffle,
+            raffleId,
+            raffle_1_creator,
+            raffle_admin,
+            prizeMint,
+            prizeEscrow,
+            creatorPrizeAta,
+            TOKEN_2022_PROGRAM_ID,
+            transferHookAccounts(hookProgram.programId, prizeMint)
+        );
+        assert.equal(await getTokenBalance(creatorPrizeAta), prizeAmount);
+        assert.equal(await hookTransfers(), before + 2);
+    });
+});
@@ -24,6 +24,30 @@ export const creation_fee_lamports = 100_000_000;
 export const ticket_fee_bps = 100;
 export const minimum_raffle_period = 60 * 60;
 export const maximum_raffle_period = 24 * 60 * 60;
+export const minimum_config_change_delay = 24 * 60 * 60; // MINIMUM_CONFIG_CHANGE_DELAY on chain
+
+// role bit indexes, mirrors constants.rs
+export const CREATOR_APPROVER_ROLE = 0;
+export const PURCHASE_COSIGNER_ROLE = 1;
+export const ANNOUNCER_ROLE = 2;
+export const PAUSER_ROLE = 3;
+export const FEE_WITHDRAWER_ROLE = 4;
+export const ROLE_COUNT = 5;
+
+// pause bit indexes, mirrors constants.rs
+export const WITHDRAW_FEES_PAUSE = 8;
+export const UPDATE_CONFIG_PAUSE = 9;
+
+// risky Token-2022 extension bit indexes, mirrors constants.rs
+export const PERMANENT_DELEGATE_EXTENSION = 0;
+export const NON_TRANSFERABLE_EXTENSION = 1;
+export const DEFAULT_FROZEN_EXTENSION = 2;
+export const TRANSFER_HOOK_EXTENSION = 3;
+export const PAUSABLE_EXTENSION = 4;
+
+// Metaplex programs used for programmable NFT prizes
+export const TOKEN_METADATA_PROGRAM_ID = new anchor.web3.PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+export const AUTH_RULES_PROGRAM_ID = new anchor.web3.PublicKey("auth9SigNpDKz4sJJ1DfCTuZrZNSAgh9sFD3rboVmgg");
 
 export function getProgram() {
     if (!program) throw new Error("Program not set");
@@ -42,9 +66,51 @@ export function raffleConfigPda() {
     )[0];
 }
 
+export function roleRegistryPda() {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("raffle"), Buffer.from("roles")],
+        getProgram().programId
+    )[0];
+}
+
 export function rafflePda(raffleId: number) {
     return anchor.web3.PublicKey.findProgramAddressSync(
         [Buffer.from("raffle"), new anchor.BN(raffleId).toArrayLike(Buffer, "le", 4)],
         getProgram().programId
     )[0];
+}
+
+export function mintAllowlistPda(mint: anchor.web3.PublicKey) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("raffle"), Buffer.from("mint_allowlist"), mint.toBuffer()],
+        getProgram().programId
+    )[0];
+}
+
+export function collectionAllowlistPda(collection: anchor.web3.PublicKey) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("raffle"), Buffer.from("collection_allowlist"), collection.toBuffer()],
+        getProgram().programId
+    )[0];
+}
+
+export function nftMetadataPda(mint: anchor.web3.PublicKey) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+        TOKEN_METADATA_PROGRAM_ID
+    )[0];
+}
+
+export function nftEditionPda(mint: anchor.web3.PublicKey) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer(), Buffer.from("edition")],
+        TOKEN_METADATA_PROGRAM_ID
+    )[0];
+}
+
+export function tokenRecordPda(mint: anchor.web3.PublicKey, token: anchor.web3.PublicKey) {
+    return anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer(), Buffer.from("token_record"), token.toBuffer()],
+        TOKEN_METADATA_PROGRAM_ID
+    )[0];
 }
\ No newline at end of file